@@ -0,0 +1,89 @@
+/*
+ * Test application for the `disabled` feature
+ *
+ * Built with liblogger's `disabled` feature, so every log_*! call and
+ * attribute macro must compile to the plain code it wraps:
+ * - Log arguments are never evaluated (a panicking Display impl proves it)
+ * - Annotated functions keep their signatures and return values
+ * - The binary doesn't contain any liblogger code
+ *
+ * The logger is deliberately never initialized. Run from this directory
+ * with `cargo run`.
+ */
+
+use liblogger::{log_debug, log_error, log_info, log_info_every, log_warn};
+use liblogger_macros::*;
+use std::fmt;
+
+initialize_logger_attributes!();
+
+struct PanicsWhenFormatted;
+
+impl fmt::Display for PanicsWhenFormatted {
+    fn fmt(&self, _f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        panic!("a disabled log message was formatted");
+    }
+}
+
+#[log_entry_exit]
+#[measure_time]
+fn add(a: i32, b: i32) -> i32 {
+    a + b
+}
+
+#[log_args(input)]
+#[log_response]
+fn shout(input: &str) -> String {
+    input.to_uppercase()
+}
+
+#[log_errors]
+fn parse_port(value: &str) -> Result<u16, String> {
+    value.parse::<u16>().map_err(|e| e.to_string())
+}
+
+#[log_retries(max_attempts = 3)]
+fn fails_once(calls: &mut u32) -> Result<u32, String> {
+    *calls += 1;
+    if *calls == 1 {
+        Err("first attempt fails".to_string())
+    } else {
+        Ok(*calls)
+    }
+}
+
+#[catch_panic]
+fn no_panic() -> &'static str {
+    "unchanged"
+}
+
+fn main() {
+    log_debug!(&format!("never formatted: {}", PanicsWhenFormatted));
+    log_info!(&format!("never formatted: {}", PanicsWhenFormatted));
+    log_warn!("never logged", Some(format!("never formatted: {}", PanicsWhenFormatted)));
+    log_error!("never logged");
+    log_info!("never logged", None);
+    log_info_every!(100, "never logged", None);
+    log_info_every!(100, &format!("never formatted: {}", PanicsWhenFormatted));
+    
+    assert_eq!(add(2, 3), 5);
+    assert_eq!(shout("quiet"), "QUIET");
+    assert_eq!(parse_port("8080"), Ok(8080));
+    assert!(parse_port("not a port").is_err());
+    assert_eq!(no_panic(), "unchanged");
+    
+    // Without the macro the function is called exactly once, with no retry loop
+    let mut calls = 0;
+    assert!(fails_once(&mut calls).is_err());
+    assert_eq!(calls, 1);
+    
+    // Mangled symbols spell the crate as "9liblogger"; the needle is stored
+    // reversed so the string itself doesn't end up in the binary
+    let needle: Vec<u8> = b"reggolbil9".iter().rev().copied().collect();
+    let binary = std::fs::read(std::env::current_exe().expect("Failed to locate test binary"))
+        .expect("Failed to read test binary");
+    assert!(!binary.windows(needle.len()).any(|w| w == needle.as_slice()),
+            "liblogger code was linked into the binary");
+    
+    println!("disabled test completed");
+}
@@ -0,0 +1,58 @@
+/*
+ * Check that `initialize_logger_attributes!()` was called
+ *
+ * The attribute macros call helpers that `initialize_logger_attributes!()`
+ * defines in the calling module. Without it the build used to fail with
+ * "cannot find function `get_trace_id`", so every instrumented function now
+ * starts with a check that fails with a clearer message instead.
+ *
+ * The check is resolved through method priority: the initializer defines a
+ * module-local trait whose by-value `liblogger_attributes` method on `Probe`
+ * returns `Ready`. When that trait is not in scope, the by-reference
+ * `Uninitialized` method is picked instead, and its `Missing` result fails
+ * the `Initialized` bound with the message below.
+ */
+
+/// Receiver of the `liblogger_attributes` check, see the module docs
+pub struct Probe;
+
+/// Result of the check in a module that called `initialize_logger_attributes!()`
+pub struct Ready;
+
+/// Result of the check in a module that didn't
+pub struct Missing;
+
+/// Implemented only by `Ready`
+#[diagnostic::on_unimplemented(
+    message = "call `initialize_logger_attributes!()` in this module before using liblogger's attribute macros",
+    label = "this module has not called `initialize_logger_attributes!()`"
+)]
+pub trait Initialized {}
+
+impl Initialized for Ready {}
+
+/// Fallback check, picked when the initializer's trait is not in scope
+pub trait Uninitialized {
+    fn liblogger_attributes(&self) -> Missing {
+        Missing
+    }
+}
+
+impl Uninitialized for Probe {}
+
+/// Fails to compile unless `check` is `Ready`
+///
+/// Attribute macros compile in a module that called the initializer:
+/// ```
+/// use liblogger_macros::*;
+///
+/// initialize_logger_attributes!();
+///
+/// #[log_entry_exit]
+/// fn handle_request() {}
+/// # fn main() { handle_request(); }
+/// ```
+///
+/// and fail with "call `initialize_logger_attributes!()` in this module"
+/// in one that didn't; tests/ui/missing_initializer.stderr pins the full error.
+pub fn assert_initialized<T: Initialized>(_check: T) {}
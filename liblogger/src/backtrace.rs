@@ -0,0 +1,52 @@
+/*
+ * Backtraces for panics caught by `#[catch_panic(backtrace = true)]`
+ *
+ * By the time `catch_unwind` returns, the panicking frames are gone, so the
+ * backtrace has to be taken inside a panic hook. The hook installed here
+ * chains to the previous one and only captures while a caller on the same
+ * thread is waiting for it. `Backtrace::capture` honours RUST_BACKTRACE and
+ * RUST_LIB_BACKTRACE, so nothing is resolved unless they enable it.
+ */
+
+use std::any::Any;
+use std::backtrace::Backtrace;
+use std::cell::{Cell, RefCell};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Once;
+
+static HOOK: Once = Once::new();
+
+thread_local! {
+    // Calls on this thread currently waiting for a backtrace
+    static ARMED: Cell<u32> = const { Cell::new(0) };
+    static LAST: RefCell<Option<Backtrace>> = const { RefCell::new(None) };
+}
+
+fn install_hook() {
+    HOOK.call_once(|| {
+        let previous_hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if ARMED.try_with(Cell::get).unwrap_or(0) > 0 {
+                let _ = LAST.try_with(|last| *last.borrow_mut() = Some(Backtrace::capture()));
+            }
+            previous_hook(info);
+        }));
+    });
+}
+
+/// Run `f`, returning the panic payload and the backtrace taken when it panicked
+///
+/// The backtrace is `None` when another panic hook replaced ours without
+/// chaining to it.
+pub(crate) fn catch_unwind<F, R>(f: F) -> Result<R, (Box<dyn Any + Send>, Option<Backtrace>)>
+where
+    F: FnOnce() -> R,
+{
+    install_hook();
+    LAST.with(|last| last.borrow_mut().take());
+    ARMED.with(|armed| armed.set(armed.get() + 1));
+    let result = panic::catch_unwind(AssertUnwindSafe(f));
+    ARMED.with(|armed| armed.set(armed.get() - 1));
+
+    result.map_err(|payload| (payload, LAST.with(|last| last.borrow_mut().take())))
+}
@@ -0,0 +1,79 @@
+/*
+ * Per-function duration baselines for `#[log_anomaly_detection]`
+ *
+ * Each instrumented function records its call durations under a key. The
+ * running mean and variance are kept with Welford's online algorithm in a
+ * process-global map, so a call can be compared against every call made
+ * before it without storing the samples.
+ */
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+// Running statistics per key
+static BASELINES: Mutex<Option<HashMap<String, Welford>>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Welford {
+    count: u64,
+    mean: f64,
+    m2: f64,
+}
+
+impl Welford {
+    fn add(&mut self, value: f64) {
+        self.count += 1;
+        let delta = value - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (value - self.mean);
+    }
+}
+
+/// Duration statistics of the calls recorded so far for one function
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DurationBaseline {
+    /// Number of calls recorded
+    pub count: u64,
+    /// Mean duration in milliseconds
+    pub mean_ms: f64,
+    /// Sample standard deviation in milliseconds (0 with fewer than two calls)
+    pub std_dev_ms: f64,
+}
+
+impl DurationBaseline {
+    /// Standard deviations `duration_ms` lies from the mean
+    ///
+    /// The spread is floored at 10% of the mean, so deviations smaller than
+    /// that never look significant for very consistent functions.
+    pub fn z_score(&self, duration_ms: f64) -> f64 {
+        let spread = self.std_dev_ms.max(self.mean_ms * 0.1);
+        if spread > 0.0 {
+            (duration_ms - self.mean_ms).abs() / spread
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Statistics for `key`, all zero when nothing has been recorded yet
+pub(crate) fn baseline(key: &str) -> DurationBaseline {
+    let baselines = BASELINES.lock().unwrap_or_else(|e| e.into_inner());
+    let stats = baselines.as_ref()
+        .and_then(|map| map.get(key).copied())
+        .unwrap_or_default();
+    let variance = if stats.count > 1 { stats.m2 / (stats.count - 1) as f64 } else { 0.0 };
+    DurationBaseline {
+        count: stats.count,
+        mean_ms: stats.mean,
+        std_dev_ms: variance.sqrt(),
+    }
+}
+
+/// Add one call's duration to the statistics for `key`
+pub(crate) fn record(key: &str, duration_ms: f64) {
+    let mut baselines = BASELINES.lock().unwrap_or_else(|e| e.into_inner());
+    baselines.get_or_insert_with(HashMap::new)
+        .entry(key.to_string())
+        .or_default()
+        .add(duration_ms);
+}
@@ -0,0 +1,101 @@
+/*
+ * Named circuit breakers shared by every call site in the process
+ *
+ * A breaker counts consecutive failures of calls to one service. Once they
+ * reach the call site's threshold it opens, and after `OPEN_COOLDOWN` it is
+ * half-open: the next call is a trial, whose success closes the breaker and
+ * whose failure opens it again. `#[log_service_communication]` records
+ * every call into the breaker named by its `service_name`, so all functions
+ * talking to the same service see and log the same state.
+ */
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+// How long an open breaker stays open before allowing a trial call
+const OPEN_COOLDOWN: Duration = Duration::from_secs(30);
+
+// Breaker state per name
+static BREAKERS: Mutex<Option<HashMap<String, Breaker>>> = Mutex::new(None);
+
+#[derive(Debug, Default)]
+struct Breaker {
+    consecutive_failures: u32,
+    opened_at: Option<Instant>,
+}
+
+impl Breaker {
+    fn state(&self) -> BreakerState {
+        match self.opened_at {
+            None => BreakerState::Closed,
+            Some(opened_at) if opened_at.elapsed() < OPEN_COOLDOWN => BreakerState::Open,
+            Some(_) => BreakerState::HalfOpen,
+        }
+    }
+}
+
+/// State of a named circuit breaker
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BreakerState {
+    /// Calls go through; failures are counted
+    Closed,
+    /// Failures reached the threshold recently
+    Open,
+    /// The cooldown has passed and the next call decides
+    HalfOpen,
+}
+
+impl BreakerState {
+    /// Uppercase name as written in log lines, e.g. "HALF_OPEN"
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BreakerState::Closed => "CLOSED",
+            BreakerState::Open => "OPEN",
+            BreakerState::HalfOpen => "HALF_OPEN",
+        }
+    }
+}
+
+impl fmt::Display for BreakerState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+fn with_breaker<R>(name: &str, f: impl FnOnce(&mut Breaker) -> R) -> R {
+    let mut breakers = BREAKERS.lock().unwrap_or_else(|e| e.into_inner());
+    f(breakers.get_or_insert_with(HashMap::new).entry(name.to_string()).or_default())
+}
+
+/// Current state of the breaker `name`; unknown names are closed
+pub(crate) fn state(name: &str) -> BreakerState {
+    with_breaker(name, |breaker| breaker.state())
+}
+
+/// Consecutive failures recorded for `name` since its last success
+pub(crate) fn consecutive_failures(name: &str) -> u32 {
+    with_breaker(name, |breaker| breaker.consecutive_failures)
+}
+
+/// Record a call to `name`, returning the breaker's state afterwards
+pub(crate) fn record(name: &str, succeeded: bool, failure_threshold: u32) -> BreakerState {
+    with_breaker(name, |breaker| {
+        if succeeded {
+            *breaker = Breaker::default();
+        } else {
+            breaker.consecutive_failures = breaker.consecutive_failures.saturating_add(1);
+            // A failed trial reopens the breaker for another cooldown
+            if breaker.consecutive_failures >= failure_threshold.max(1) && breaker.state() != BreakerState::Open {
+                breaker.opened_at = Some(Instant::now());
+            }
+        }
+        breaker.state()
+    })
+}
+
+/// Close the breaker `name` and forget its failures
+pub(crate) fn reset(name: &str) {
+    with_breaker(name, |breaker| *breaker = Breaker::default());
+}
@@ -0,0 +1,37 @@
+/*
+ * Wall clock readings that can't panic
+ *
+ * `SystemTime::now().duration_since(UNIX_EPOCH)` fails when the system clock
+ * is set before the epoch, which happens on devices without a battery-backed
+ * clock and after a bad NTP step. The IDs generated by the attribute macros
+ * read the clock through `since_epoch`, which treats such a clock as the
+ * epoch instead. Intervals are measured with `Instant`, which never goes
+ * backwards, so nothing else depends on the wall clock.
+ *
+ * With the `testing` feature the clock can be replaced to simulate skew.
+ */
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+#[cfg(feature = "testing")]
+static CLOCK: std::sync::RwLock<Option<fn() -> SystemTime>> = std::sync::RwLock::new(None);
+
+/// The current wall clock time
+pub(crate) fn now() -> SystemTime {
+    #[cfg(feature = "testing")]
+    if let Some(clock) = *CLOCK.read().unwrap_or_else(|e| e.into_inner()) {
+        return clock();
+    }
+    SystemTime::now()
+}
+
+/// Time since the Unix epoch, or zero when the clock is set before it
+pub(crate) fn since_epoch() -> Duration {
+    now().duration_since(UNIX_EPOCH).unwrap_or(Duration::ZERO)
+}
+
+/// Read the wall clock from `clock` instead of the system from now on
+#[cfg(feature = "testing")]
+pub(crate) fn set_clock(clock: fn() -> SystemTime) {
+    *CLOCK.write().unwrap_or_else(|e| e.into_inner()) = Some(clock);
+}
@@ -0,0 +1,175 @@
+/*
+ * JSON lines in the shapes cloud log agents recognize
+ *
+ * `format = "gcp_json"` writes Google Cloud Logging structured entries:
+ * `severity` (DEBUG, INFO, WARNING or ERROR), `message`, `time` and the
+ * `logging.googleapis.com/sourceLocation` object with the file and line.
+ * Key/value context becomes `logging.googleapis.com/labels`; other context
+ * is kept as a `context` string.
+ *
+ * `format = "cloudwatch_emf"` writes CloudWatch Embedded Metric Format
+ * records. Each one carries a `LogLines` count of 1 with `level` as its
+ * dimension, so CloudWatch graphs lines per level without a metric filter,
+ * and the message, location and context as plain properties.
+ *
+ * Scoped and global fields are added as top-level keys, except ones that
+ * would replace a key of the format itself. With `include_location` off the
+ * source location is left out of both formats.
+ */
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use crate::clock;
+use crate::config::{LogConfig, LogLevel};
+use crate::context::LineContext;
+
+// Keys a scoped field can't take in a GCP entry
+const GCP_KEYS: &[&str] = &["severity", "message", "time", "logging.googleapis.com/sourceLocation",
+                            "logging.googleapis.com/labels", "context", "seq", "module"];
+
+// Keys a scoped field can't take in an EMF record
+const EMF_KEYS: &[&str] = &["_aws", "level", "LogLines", "message", "file", "line", "module", "context", "seq"];
+
+/// Name of a level in Cloud Logging's LogSeverity
+fn gcp_severity(level: &LogLevel) -> &'static str {
+    match level {
+        LogLevel::Debug => "DEBUG",
+        LogLevel::Info => "INFO",
+        LogLevel::Warn => "WARNING",
+        LogLevel::Error => "ERROR",
+    }
+}
+
+fn unreserved<'a>(fields: &'a BTreeMap<String, String>, reserved: &[&str]) -> BTreeMap<&'a str, &'a str> {
+    fields.iter()
+        .filter(|(key, _)| !reserved.contains(&key.as_str()))
+        .map(|(k, v)| (k.as_str(), v.as_str()))
+        .collect()
+}
+
+#[derive(Serialize)]
+struct SourceLocation<'a> {
+    file: &'a str,
+    // LogEntrySourceLocation.line is an int64, which Cloud Logging writes as a string
+    line: String,
+}
+
+#[derive(Serialize)]
+struct GcpEntry<'a> {
+    severity: &'static str,
+    message: &'a str,
+    time: &'a str,
+    #[serde(rename = "logging.googleapis.com/sourceLocation", skip_serializing_if = "Option::is_none")]
+    source_location: Option<SourceLocation<'a>>,
+    #[serde(rename = "logging.googleapis.com/labels", skip_serializing_if = "Option::is_none")]
+    labels: Option<&'a BTreeMap<String, String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    context: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    seq: Option<u64>,
+    module: &'a str,
+    #[serde(flatten)]
+    fields: BTreeMap<&'a str, &'a str>,
+}
+
+/// Render one line as a Google Cloud Logging structured entry
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn render_gcp(timestamp: &str, seq: Option<u64>, level: &LogLevel, message: &str, context: Option<&LineContext>,
+                         fields: &BTreeMap<String, String>, file: &str, line: u32, module: &str) -> String {
+    let entry = GcpEntry {
+        severity: gcp_severity(level),
+        message,
+        time: timestamp,
+        source_location: (!file.is_empty()).then(|| SourceLocation { file, line: line.to_string() }),
+        labels: context.and_then(|ctx| ctx.pairs.as_ref()),
+        context: context.filter(|ctx| ctx.pairs.is_none()).map(|ctx| ctx.text.as_str()),
+        seq,
+        module,
+        fields: unreserved(fields, GCP_KEYS),
+    };
+    serde_json::to_string(&entry).expect("log record serialization cannot fail")
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "PascalCase")]
+struct EmfMetadata<'a> {
+    timestamp: i64,
+    cloud_watch_metrics: [EmfDirective<'a>; 1],
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "PascalCase")]
+struct EmfDirective<'a> {
+    namespace: &'a str,
+    dimensions: [[&'static str; 1]; 1],
+    metrics: [EmfMetric; 1],
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "PascalCase")]
+struct EmfMetric {
+    name: &'static str,
+    unit: &'static str,
+}
+
+#[derive(Serialize)]
+struct EmfRecord<'a> {
+    _aws: EmfMetadata<'a>,
+    level: &'static str,
+    #[serde(rename = "LogLines")]
+    log_lines: u32,
+    message: &'a str,
+    #[serde(skip_serializing_if = "str::is_empty")]
+    file: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    line: Option<u32>,
+    module: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    context: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    seq: Option<u64>,
+    #[serde(flatten)]
+    fields: BTreeMap<&'a str, &'a str>,
+}
+
+/// The namespace EMF records publish their metric under
+#[derive(Debug, Clone)]
+pub(crate) struct EmfNamespace(String);
+
+impl EmfNamespace {
+    pub(crate) fn from_config(config: &LogConfig) -> Self {
+        EmfNamespace(config.cloudwatch_namespace.clone().unwrap_or_else(|| "liblogger".to_string()))
+    }
+
+    /// Render one line as a CloudWatch Embedded Metric Format record
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn render(&self, timestamp: &str, seq: Option<u64>, level: &LogLevel, message: &str, context: Option<&LineContext>,
+                         fields: &BTreeMap<String, String>, file: &str, line: u32, module: &str) -> String {
+        // EMF wants epoch milliseconds; the line's own timestamp is RFC 3339
+        let millis = chrono::DateTime::parse_from_rfc3339(timestamp)
+            .map(|time| time.timestamp_millis())
+            .unwrap_or_else(|_| clock::since_epoch().as_millis() as i64);
+        let record = EmfRecord {
+            _aws: EmfMetadata {
+                timestamp: millis,
+                cloud_watch_metrics: [EmfDirective {
+                    namespace: &self.0,
+                    dimensions: [["level"]],
+                    metrics: [EmfMetric { name: "LogLines", unit: "Count" }],
+                }],
+            },
+            level: level.as_str(),
+            log_lines: 1,
+            message,
+            file,
+            line: (!file.is_empty()).then_some(line),
+            module,
+            context: context.map(|ctx| ctx.text.as_str()),
+            seq,
+            fields: unreserved(fields, EMF_KEYS),
+        };
+        serde_json::to_string(&record).expect("log record serialization cannot fail")
+    }
+}
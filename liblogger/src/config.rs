@@ -1,199 +1,1263 @@
-/*
- * Configuration management for the Rusty Logger v2
- * 
- * This module handles:
- * - Parsing configuration from TOML files (app_config.toml)
- * - Defining the LogType enum for output destinations (Console, File, Http)
- * - Defining the LogLevel enum for severity levels (Debug, Info, Warn, Error)
- * - Implementing methods for level comparison and string conversion
- * - Providing default configuration values for all settings
- * 
- * The configuration determines:
- * - Where logs are written (console, file with rotation, or HTTP endpoint)
- * - Which severity levels are included in the output based on threshold
- * - File paths, rotation sizes, and HTTP timeouts
- * - Behavior of both synchronous and asynchronous logging operations
- */
-
-use serde::{Deserialize, Serialize};
-use std::fs;
-use once_cell::sync::OnceCell;
-
-/// Log severity levels
-#[derive(Debug, Clone, PartialEq, Serialize)]
-pub enum LogLevel {
-    Debug,
-    Info,
-    Warn,
-    Error,
-}
-
-// Separate implementation of Deserialize to handle case-insensitive values
-impl<'de> Deserialize<'de> for LogLevel {
-    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
-    where
-        D: serde::Deserializer<'de>,
-    {
-        let s = String::deserialize(deserializer)?;
-        match s.to_lowercase().as_str() {
-            "debug" => Ok(LogLevel::Debug),
-            "info" => Ok(LogLevel::Info),
-            "warn" | "warning" => Ok(LogLevel::Warn),
-            "error" => Ok(LogLevel::Error),
-            _ => Err(serde::de::Error::unknown_variant(
-                &s,
-                &["debug", "info", "warn", "warning", "error"],
-            )),
-        }
-    }
-}
-
-impl LogLevel {
-    pub fn as_str(&self) -> &'static str {
-        match self {
-            LogLevel::Debug => "DEBUG",
-            LogLevel::Info => "INFO",
-            LogLevel::Warn => "WARN",
-            LogLevel::Error => "ERROR",
-        }
-    }
-}
-
-/// Supported output types for logging
-#[derive(Debug, Clone, PartialEq, Serialize)]
-pub enum LogType {
-    Console,
-    File,
-    Http,
-}
-
-// Separate implementation of Deserialize to handle case-insensitive values
-impl<'de> Deserialize<'de> for LogType {
-    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
-    where
-        D: serde::Deserializer<'de>,
-    {
-        let s = String::deserialize(deserializer)?;
-        match s.to_lowercase().as_str() {
-            "console" => Ok(LogType::Console),
-            "file" => Ok(LogType::File),
-            "http" => Ok(LogType::Http),
-            _ => Err(serde::de::Error::unknown_variant(
-                &s,
-                &["console", "file", "http"],
-            )),
-        }
-    }
-}
-
-static CONFIG_INSTANCE: OnceCell<LogConfig> = OnceCell::new();
-
-/// Configuration for the logger
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct LogConfig {
-    /// Type of output (console, file, http)
-    #[serde(rename = "type")]
-    pub log_type: LogType,
-    
-    /// Minimum log level to record
-    pub threshold: LogLevel,
-    
-    /// File path for file-based logging
-    #[serde(default)]
-    pub file_path: Option<String>,
-    
-    /// Folder for log files
-    #[serde(default)]
-    pub log_folder: Option<String>,
-    
-    /// Maximum file size before rotation (in MB)
-    #[serde(default)]
-    pub max_file_size_mb: Option<u64>,
-    
-    /// Endpoint URL for HTTP logging
-    #[serde(default)]
-    pub http_endpoint: Option<String>,
-    
-    /// Timeout in seconds for HTTP requests
-    #[serde(default)]
-    pub http_timeout_seconds: Option<u64>,
-    
-    /// Whether to use async logging (default: true)
-    #[serde(default = "default_async_logging")]
-    pub async_logging: bool,
-    
-    /// Whether to force flush after every write (default: false)
-    #[serde(default = "default_force_flush")]
-    pub force_flush: bool,
-}
-
-fn default_async_logging() -> bool {
-    true
-}
-
-fn default_force_flush() -> bool {
-    false  // Default to false for better performance
-}
-
-impl Default for LogConfig {
-    fn default() -> Self {
-        LogConfig {
-            log_type: LogType::Console,
-            threshold: LogLevel::Info,
-            file_path: None,
-            log_folder: None,
-            max_file_size_mb: None,
-            http_endpoint: None,
-            http_timeout_seconds: None,
-            async_logging: true,
-            force_flush: false,
-        }
-    }
-}
-
-/// Configuration wrapper to handle the [logging] section in TOML
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct ConfigWrapper {
-    logging: LogConfig
-}
-
-impl LogConfig {
-    /// Create configuration from a TOML file
-    pub fn from_file(file_path: &str) -> Result<Self, String> {
-        let config_str = match fs::read_to_string(file_path) {
-            Ok(content) => content,
-            Err(e) => {
-                println!("Warning: Could not read config file '{}': {}. Using defaults.", file_path, e);
-                return Ok(LogConfig::default());
-            }
-        };
-
-        // Try to parse with the [logging] section wrapper first
-        let config = match toml::from_str::<ConfigWrapper>(&config_str) {
-            Ok(wrapper) => wrapper.logging,
-            Err(e) => {
-                // If that fails, try the old format (direct LogConfig)
-                match toml::from_str::<LogConfig>(&config_str) {
-                    Ok(config) => config,
-                    Err(_) => {
-                        // Return the original error if both parsing attempts fail
-                        return Err(format!("Failed to parse config file: {}", e));
-                    }
-                }
-            }
-        };
-
-        // Set the global instance
-        let _ = CONFIG_INSTANCE.get_or_init(|| config.clone());
-        
-        Ok(config)
-    }
-    
-    /// Get the global instance of LogConfig
-    pub fn get_instance() -> Result<LogConfig, String> {
-        match CONFIG_INSTANCE.get() {
-            Some(config) => Ok(config.clone()),
-            None => Err("LogConfig not initialized. Call LogConfig::from_file first.".into())
-        }
-    }
-}
+/*
+ * Configuration management for the Rusty Logger v2
+ * 
+ * This module handles:
+ * - Parsing configuration from TOML files (app_config.toml), and from JSON
+ *   or YAML files when the `json` / `yaml` features are enabled
+ * - Defining the LogType enum for output destinations (Console, File, Http, UnixSocket)
+ * - Defining the LogLevel enum for severity levels (Debug, Info, Warn, Error)
+ * - Defining the ColorMode enum for console coloring (Auto, Always, Never)
+ * - Defining the ConsoleStream enum for where console output goes (Stdout, Stderr)
+ * - Implementing methods for level comparison and string conversion, including
+ *   Display and FromStr for LogLevel and LogType
+ * - Providing default configuration values for all settings
+ * - Building configurations programmatically with LogConfigBuilder
+ * - Reading a configuration from LIBLOGGER_* environment variables
+ * - Describing the sinks of a multi-output configuration with OutputSpec
+ * - Validating a configuration before any output is created
+ * 
+ * The configuration determines:
+ * - Where logs are written (console, file, or HTTP endpoint)
+ * - Which severity levels are included in the output based on threshold
+ * - File paths and HTTP timeouts
+ * - Behavior of both synchronous and asynchronous logging operations
+ */
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::str::FromStr;
+use std::path::Path;
+use once_cell::sync::OnceCell;
+use crate::outputs::RESERVED_JSON_KEYS;
+use crate::template::TextTemplate;
+
+/// Log severity levels, ordered from least to most severe
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+// Separate implementation of Deserialize to handle case-insensitive values
+impl<'de> Deserialize<'de> for LogLevel {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(|_| serde::de::Error::unknown_variant(
+            &s,
+            &["debug", "info", "warn", "warning", "error"],
+        ))
+    }
+}
+
+impl LogLevel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LogLevel::Debug => "DEBUG",
+            LogLevel::Info => "INFO",
+            LogLevel::Warn => "WARN",
+            LogLevel::Error => "ERROR",
+        }
+    }
+
+    /// Numeric severity, higher is more severe; Debug is 0
+    pub const fn severity(&self) -> u8 {
+        match self {
+            LogLevel::Debug => 0,
+            LogLevel::Info => 1,
+            LogLevel::Warn => 2,
+            LogLevel::Error => 3,
+        }
+    }
+
+    /// Level with the given severity, saturating at Error
+    pub(crate) const fn from_severity(severity: u8) -> LogLevel {
+        match severity {
+            0 => LogLevel::Debug,
+            1 => LogLevel::Info,
+            2 => LogLevel::Warn,
+            _ => LogLevel::Error,
+        }
+    }
+
+    /// Whether a message at this level passes the given threshold
+    pub const fn should_log(&self, threshold: &LogLevel) -> bool {
+        self.severity() >= threshold.severity()
+    }
+}
+
+impl fmt::Display for LogLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Parses a level name case-insensitively, accepting "warning" for Warn
+impl FromStr for LogLevel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "debug" => Ok(LogLevel::Debug),
+            "info" => Ok(LogLevel::Info),
+            "warn" | "warning" => Ok(LogLevel::Warn),
+            "error" => Ok(LogLevel::Error),
+            _ => Err(format!("Unknown log level '{}', expected debug, info, warn or error", s)),
+        }
+    }
+}
+
+/// Supported output types for logging
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogType {
+    Console,
+    File,
+    Http,
+    /// Newline-delimited lines written to a Unix domain socket, e.g. a
+    /// Vector or Fluent Bit source; Unix only
+    #[serde(rename = "unix_socket")]
+    UnixSocket,
+    /// Discards every line; for benchmarking or silencing a deployment
+    Null,
+}
+
+// Separate implementation of Deserialize to handle case-insensitive values
+impl<'de> Deserialize<'de> for LogType {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(|_| serde::de::Error::unknown_variant(
+            &s,
+            &["console", "file", "http", "unix_socket", "null"],
+        ))
+    }
+}
+
+impl LogType {
+    /// Lowercase name as written in config files
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LogType::Console => "console",
+            LogType::File => "file",
+            LogType::Http => "http",
+            LogType::UnixSocket => "unix_socket",
+            LogType::Null => "null",
+        }
+    }
+}
+
+impl fmt::Display for LogType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Parses an output type name case-insensitively
+impl FromStr for LogType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "console" => Ok(LogType::Console),
+            "file" => Ok(LogType::File),
+            "http" => Ok(LogType::Http),
+            "unix_socket" => Ok(LogType::UnixSocket),
+            "null" => Ok(LogType::Null),
+            _ => Err(format!("Unknown log type '{}', expected console, file, http, unix_socket or null", s)),
+        }
+    }
+}
+
+/// How log lines are written
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    /// Human-readable text, laid out by `text_template` if set
+    #[default]
+    Text,
+    /// One JSON object per line, versioned by `SCHEMA_VERSION`
+    Json,
+    /// ArcSight Common Event Format, with the header set by the `cef_device_*` options
+    Cef,
+    /// Google Cloud Logging structured JSON, with `severity` and `sourceLocation`
+    #[serde(rename = "gcp_json")]
+    GcpJson,
+    /// CloudWatch Embedded Metric Format, counting lines per level under `cloudwatch_namespace`
+    #[serde(rename = "cloudwatch_emf")]
+    CloudWatchEmf,
+}
+
+// Separate implementation of Deserialize to handle case-insensitive values
+impl<'de> Deserialize<'de> for LogFormat {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        match s.to_lowercase().as_str() {
+            "text" => Ok(LogFormat::Text),
+            "json" => Ok(LogFormat::Json),
+            "cef" => Ok(LogFormat::Cef),
+            "gcp_json" => Ok(LogFormat::GcpJson),
+            "cloudwatch_emf" => Ok(LogFormat::CloudWatchEmf),
+            _ => Err(serde::de::Error::unknown_variant(
+                &s,
+                &["text", "json", "cef", "gcp_json", "cloudwatch_emf"],
+            )),
+        }
+    }
+}
+
+impl LogFormat {
+    /// Lowercase name as written in config files
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LogFormat::Text => "text",
+            LogFormat::Json => "json",
+            LogFormat::Cef => "cef",
+            LogFormat::GcpJson => "gcp_json",
+            LogFormat::CloudWatchEmf => "cloudwatch_emf",
+        }
+    }
+}
+
+/// Parses a format name case-insensitively
+impl FromStr for LogFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "text" => Ok(LogFormat::Text),
+            "json" => Ok(LogFormat::Json),
+            "cef" => Ok(LogFormat::Cef),
+            "gcp_json" => Ok(LogFormat::GcpJson),
+            "cloudwatch_emf" => Ok(LogFormat::CloudWatchEmf),
+            _ => Err(format!("Unknown log format '{}', expected text, json, cef, gcp_json or cloudwatch_emf", s)),
+        }
+    }
+}
+
+/// Line terminator written after each line by file outputs
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LineEnding {
+    /// "\n"
+    #[default]
+    Lf,
+    /// "\r\n", expected by some Windows tools
+    CrLf,
+}
+
+impl LineEnding {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LineEnding::Lf => "\n",
+            LineEnding::CrLf => "\r\n",
+        }
+    }
+}
+
+// Separate implementation of Deserialize to handle case-insensitive values
+impl<'de> Deserialize<'de> for LineEnding {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        match s.to_lowercase().as_str() {
+            "lf" => Ok(LineEnding::Lf),
+            "crlf" => Ok(LineEnding::CrLf),
+            _ => Err(serde::de::Error::unknown_variant(
+                &s,
+                &["lf", "crlf"],
+            )),
+        }
+    }
+}
+
+/// What logging does when it finds the logger lock poisoned by a panic
+/// that happened while a line was being written
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PoisonPolicy {
+    /// Keep logging, with a single WARN the first time
+    #[default]
+    Recover,
+    /// Keep logging, with a WARN every time
+    Warn,
+    /// Panic in the thread that logs next
+    Panic,
+}
+
+// Separate implementation of Deserialize to handle case-insensitive values
+impl<'de> Deserialize<'de> for PoisonPolicy {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        match s.to_lowercase().as_str() {
+            "recover" => Ok(PoisonPolicy::Recover),
+            "warn" => Ok(PoisonPolicy::Warn),
+            "panic" => Ok(PoisonPolicy::Panic),
+            _ => Err(serde::de::Error::unknown_variant(
+                &s,
+                &["recover", "warn", "panic"],
+            )),
+        }
+    }
+}
+
+/// What logging does when the async channel is full
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OverflowPolicy {
+    /// Write the line from the logging thread, bypassing the channel
+    #[default]
+    WriteSync,
+    /// Discard the line and count it, so logging never waits on the output
+    DropNewest,
+}
+
+// Separate implementation of Deserialize to handle case-insensitive values
+impl<'de> Deserialize<'de> for OverflowPolicy {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        match s.to_lowercase().as_str() {
+            "write_sync" => Ok(OverflowPolicy::WriteSync),
+            "drop_newest" => Ok(OverflowPolicy::DropNewest),
+            _ => Err(serde::de::Error::unknown_variant(
+                &s,
+                &["write_sync", "drop_newest"],
+            )),
+        }
+    }
+}
+
+/// Standard stream written by console output
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConsoleStream {
+    #[default]
+    Stdout,
+    /// Keeps stdout free for program output, e.g. in CLI tools
+    Stderr,
+}
+
+// Separate implementation of Deserialize to handle case-insensitive values
+impl<'de> Deserialize<'de> for ConsoleStream {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        match s.to_lowercase().as_str() {
+            "stdout" => Ok(ConsoleStream::Stdout),
+            "stderr" => Ok(ConsoleStream::Stderr),
+            _ => Err(serde::de::Error::unknown_variant(
+                &s,
+                &["stdout", "stderr"],
+            )),
+        }
+    }
+}
+
+/// When to colorize the level token in console output
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorMode {
+    /// Colorize only when the console stream is a terminal
+    Auto,
+    Always,
+    Never,
+}
+
+// Separate implementation of Deserialize to handle case-insensitive values
+impl<'de> Deserialize<'de> for ColorMode {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        match s.to_lowercase().as_str() {
+            "auto" => Ok(ColorMode::Auto),
+            "always" => Ok(ColorMode::Always),
+            "never" => Ok(ColorMode::Never),
+            _ => Err(serde::de::Error::unknown_variant(
+                &s,
+                &["auto", "always", "never"],
+            )),
+        }
+    }
+}
+
+static CONFIG_INSTANCE: OnceCell<LogConfig> = OnceCell::new();
+
+/// Configuration for the logger
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LogConfig {
+    /// Type of output (console, file, http)
+    #[serde(rename = "type")]
+    pub log_type: LogType,
+    
+    /// Minimum log level to record
+    pub threshold: LogLevel,
+    
+    /// File path for file-based logging
+    #[serde(default)]
+    pub file_path: Option<String>,
+    
+    /// Folder for log files
+    #[serde(default)]
+    pub log_folder: Option<String>,
+    
+    /// Maximum file size before rotation (in MB). Accepted for
+    /// compatibility but not enforced: file outputs are not rotated.
+    #[serde(default)]
+    pub max_file_size_mb: Option<u64>,
+    
+    /// Endpoint URL for HTTP logging
+    #[serde(default)]
+    pub http_endpoint: Option<String>,
+    
+    /// Timeout in seconds for HTTP requests
+    #[serde(default)]
+    pub http_timeout_seconds: Option<u64>,
+    
+    /// Constant fields added to every JSON record posted to HTTP outputs,
+    /// e.g. tenant or environment; a record's own keys win (default: none)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub http_extra_fields: BTreeMap<String, String>,
+    
+    /// Path of the Unix domain socket for `unix_socket` logging
+    #[serde(default)]
+    pub socket_path: Option<String>,
+    
+    /// Whether to use async logging (default: true); when false no Tokio
+    /// runtime or background task is created and lines are written by the
+    /// calling thread, which suits short-lived processes
+    #[serde(default = "default_async_logging")]
+    pub async_logging: bool,
+    
+    /// Lines the async channel holds before `overflow_policy` applies
+    /// (default: 100)
+    #[serde(default = "default_async_channel_capacity")]
+    pub async_channel_capacity: usize,
+    
+    /// What to do with a line when the async channel is full, "write_sync"
+    /// or "drop_newest" (default: write_sync)
+    #[serde(default)]
+    pub overflow_policy: OverflowPolicy,
+    
+    /// Whether to force flush after every write (default: false)
+    #[serde(default = "default_force_flush")]
+    pub force_flush: bool,
+    
+    /// Buffer file writes in memory (default: false). Buffered lines are
+    /// written once 64 KiB are pending, on the first write a second after
+    /// the last flush, and on shutdown.
+    #[serde(default)]
+    pub buffered: bool,
+    
+    /// Write ERROR lines to disk as soon as they are logged, even when
+    /// buffered or batched by the background task (default: true)
+    #[serde(default = "default_flush_on_error")]
+    pub flush_on_error: bool,
+    
+    /// Line terminator for file outputs, "lf" or "crlf" (default: lf)
+    #[serde(default)]
+    pub line_ending: LineEnding,
+    
+    /// Longest time in milliseconds the background task keeps collecting
+    /// lines into one write while messages keep arriving (default: 100).
+    /// 0 writes every line separately.
+    #[serde(default = "default_flush_interval_ms")]
+    pub flush_interval_ms: u64,
+    
+    /// Maximum size of a single log message in bytes (default: unlimited)
+    #[serde(default)]
+    pub max_message_bytes: Option<usize>,
+    
+    /// Most recent lines kept in memory for `Logger::recent_logs()` and
+    /// the panic hook, whatever their level (default: 0, disabled)
+    #[serde(default)]
+    pub ring_buffer_size: usize,
+    
+    /// Colorize levels in console output (default: auto)
+    #[serde(default = "default_color")]
+    pub color: ColorMode,
+    
+    /// Stream console output is written to, "stdout" or "stderr" (default: stdout)
+    #[serde(default)]
+    pub console_stream: ConsoleStream,
+    
+    /// Show only the last N segments of the module path, e.g. 2 turns
+    /// "my_app::services::billing::invoices" into "billing::invoices"
+    /// (default: 0, the full path)
+    #[serde(default)]
+    pub module_truncate_segments: usize,
+    
+    /// Escape newlines and control characters in messages and context (default: true)
+    #[serde(default = "default_sanitize_messages")]
+    pub sanitize_messages: bool,
+    
+    /// Write the source file and line of each call; turn off to keep source
+    /// paths out of shipped logs (default: true)
+    #[serde(default = "default_include_location")]
+    pub include_location: bool,
+    
+    /// What to do when a panic while logging poisoned the logger lock,
+    /// "recover", "warn" or "panic" (default: recover)
+    #[serde(default)]
+    pub on_poison: PoisonPolicy,
+    
+    /// ERROR lines per second above which every level is recorded for
+    /// `escalation_secs`, regardless of the threshold (default: none)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auto_debug_on_error_rate: Option<u32>,
+    
+    /// How long an escalation from `auto_debug_on_error_rate` lasts, in
+    /// seconds (default: 60)
+    #[serde(default = "default_escalation_secs")]
+    pub escalation_secs: u64,
+    
+    /// Write lines as "text", "json", "cef", "gcp_json" or "cloudwatch_emf" (default: text)
+    #[serde(default)]
+    pub format: LogFormat,
+    
+    /// Number every line in call order, as "[#N]" after the timestamp in
+    /// text or a "seq" field in JSON (default: false)
+    #[serde(default)]
+    pub include_sequence: bool,
+    
+    /// In JSON, write a context made of key=value pairs as an object with
+    /// one key per pair instead of a single string (default: false)
+    #[serde(default)]
+    pub structured_context: bool,
+    
+    /// Output keys for built-in JSON record fields, e.g. timestamp = "@timestamp"
+    /// or message = "msg", for pipelines with a fixed schema (default: none)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub json_field_map: BTreeMap<String, String>,
+    
+    /// Device vendor in the header of CEF lines (default: "liblogger")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cef_device_vendor: Option<String>,
+    
+    /// Device product in the header of CEF lines (default: "liblogger")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cef_device_product: Option<String>,
+    
+    /// Device version in the header of CEF lines (default: liblogger's version)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cef_device_version: Option<String>,
+    
+    /// Namespace of the line count metric in CloudWatch EMF records (default: "liblogger")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cloudwatch_namespace: Option<String>,
+    
+    /// Layout of text log lines, e.g. "{timestamp} [{level}] {message}"
+    /// (default: "{timestamp} [{level}] [{file}:{line}] [{module}] {message}"
+    /// followed by " | {context}" when there is context). Placeholders are
+    /// timestamp, level, file, line, module, message, context and seq.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text_template: Option<String>,
+    
+    /// Sinks to write to instead of the single `type` output (default: none)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub outputs: Vec<OutputSpec>,
+}
+
+/// One sink of a multi-output configuration (`[[logging.outputs]]`)
+///
+/// Settings left unset are taken from the enclosing LogConfig. A sink's
+/// `threshold` can only narrow what it receives: messages below the global
+/// threshold are filtered before any output sees them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OutputSpec {
+    /// Type of output (console, file, http)
+    #[serde(rename = "type")]
+    pub log_type: LogType,
+    
+    /// Minimum level forwarded to this output (default: everything)
+    #[serde(default)]
+    pub threshold: Option<LogLevel>,
+    
+    /// File path for a file output
+    #[serde(default)]
+    pub file_path: Option<String>,
+    
+    /// Folder for a file output
+    #[serde(default)]
+    pub log_folder: Option<String>,
+    
+    /// Endpoint URL for an HTTP output
+    #[serde(default)]
+    pub http_endpoint: Option<String>,
+    
+    /// Timeout in seconds for an HTTP output
+    #[serde(default)]
+    pub http_timeout_seconds: Option<u64>,
+    
+    /// Socket path for a Unix socket output
+    #[serde(default)]
+    pub socket_path: Option<String>,
+}
+
+impl OutputSpec {
+    fn new(log_type: LogType) -> Self {
+        OutputSpec {
+            log_type,
+            threshold: None,
+            file_path: None,
+            log_folder: None,
+            http_endpoint: None,
+            http_timeout_seconds: None,
+            socket_path: None,
+        }
+    }
+
+    /// Console sink
+    pub fn console() -> Self {
+        Self::new(LogType::Console)
+    }
+
+    /// File sink writing to `file_path`
+    pub fn file(file_path: &str) -> Self {
+        let mut spec = Self::new(LogType::File);
+        spec.file_path = Some(file_path.to_string());
+        spec
+    }
+
+    /// HTTP sink posting to `endpoint`
+    pub fn http(endpoint: &str) -> Self {
+        let mut spec = Self::new(LogType::Http);
+        spec.http_endpoint = Some(endpoint.to_string());
+        spec
+    }
+
+    /// Unix socket sink writing to `socket_path`
+    pub fn unix_socket(socket_path: &str) -> Self {
+        let mut spec = Self::new(LogType::UnixSocket);
+        spec.socket_path = Some(socket_path.to_string());
+        spec
+    }
+
+    /// Only forward messages at or above `threshold` to this sink
+    pub fn threshold(mut self, threshold: LogLevel) -> Self {
+        self.threshold = Some(threshold);
+        self
+    }
+
+    /// Set the folder that holds this sink's log file
+    pub fn log_folder(mut self, log_folder: &str) -> Self {
+        self.log_folder = Some(log_folder.to_string());
+        self
+    }
+}
+
+fn default_async_logging() -> bool {
+    true
+}
+
+fn default_async_channel_capacity() -> usize {
+    100
+}
+
+fn default_force_flush() -> bool {
+    false  // Default to false for better performance
+}
+
+fn default_flush_on_error() -> bool {
+    true
+}
+
+fn default_flush_interval_ms() -> u64 {
+    100
+}
+
+fn default_color() -> ColorMode {
+    ColorMode::Auto
+}
+
+fn default_sanitize_messages() -> bool {
+    true
+}
+
+fn default_include_location() -> bool {
+    true
+}
+
+fn default_escalation_secs() -> u64 {
+    60
+}
+
+impl Default for LogConfig {
+    fn default() -> Self {
+        LogConfig {
+            log_type: LogType::Console,
+            threshold: LogLevel::Info,
+            file_path: None,
+            log_folder: None,
+            max_file_size_mb: None,
+            http_endpoint: None,
+            http_timeout_seconds: None,
+            http_extra_fields: BTreeMap::new(),
+            socket_path: None,
+            async_logging: true,
+            async_channel_capacity: 100,
+            overflow_policy: OverflowPolicy::WriteSync,
+            force_flush: false,
+            buffered: false,
+            flush_on_error: true,
+            line_ending: LineEnding::Lf,
+            flush_interval_ms: 100,
+            max_message_bytes: None,
+            ring_buffer_size: 0,
+            color: ColorMode::Auto,
+            console_stream: ConsoleStream::Stdout,
+            module_truncate_segments: 0,
+            sanitize_messages: true,
+            include_location: true,
+            on_poison: PoisonPolicy::Recover,
+            auto_debug_on_error_rate: None,
+            escalation_secs: 60,
+            format: LogFormat::Text,
+            include_sequence: false,
+            structured_context: false,
+            json_field_map: BTreeMap::new(),
+            cef_device_vendor: None,
+            cef_device_product: None,
+            cef_device_version: None,
+            cloudwatch_namespace: None,
+            text_template: None,
+            outputs: Vec::new(),
+        }
+    }
+}
+
+/// Configuration wrapper to handle the [logging] section in every format
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ConfigWrapper {
+    logging: LogConfig
+}
+
+/// File formats accepted by LogConfig::from_file
+enum ConfigFormat {
+    Toml,
+    Json,
+    Yaml,
+}
+
+impl ConfigFormat {
+    /// Pick the format from the file extension; files without one are read as TOML
+    fn from_path(file_path: &str) -> Result<Self, String> {
+        let extension = Path::new(file_path)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| ext.to_lowercase());
+
+        match extension.as_deref() {
+            None | Some("toml") => Ok(ConfigFormat::Toml),
+            Some("json") => Ok(ConfigFormat::Json),
+            Some("yaml") | Some("yml") => Ok(ConfigFormat::Yaml),
+            Some(other) => Err(format!(
+                "Unsupported config format '.{}' for '{}': expected .toml, .json, .yaml or .yml",
+                other, file_path
+            )),
+        }
+    }
+}
+
+impl LogConfig {
+    /// Start building a configuration from the defaults
+    pub fn builder() -> LogConfigBuilder {
+        LogConfigBuilder::new()
+    }
+
+    /// Create configuration from a TOML, JSON or YAML file
+    ///
+    /// The format is chosen by extension. Settings are read from the
+    /// `logging` section, or from the top level of files without one.
+    pub fn from_file(file_path: &str) -> Result<Self, String> {
+        let format = ConfigFormat::from_path(file_path)?;
+
+        let config_str = match fs::read_to_string(file_path) {
+            Ok(content) => content,
+            Err(e) => {
+                status!("Warning: Could not read config file '{}': {}. Using defaults.", file_path, e);
+                return Ok(LogConfig::default());
+            }
+        };
+
+        let config = match format {
+            ConfigFormat::Toml => Self::parse_toml(&config_str)?,
+            ConfigFormat::Json => Self::parse_json(&config_str)?,
+            ConfigFormat::Yaml => Self::parse_yaml(&config_str)?,
+        };
+
+        // Set the global instance
+        let _ = CONFIG_INSTANCE.get_or_init(|| config.clone());
+        
+        Ok(config)
+    }
+
+    // Every format accepts the settings under a `logging` section or at the
+    // top level. When neither parses, the error from the section attempt is
+    // reported since that is the documented layout.
+    fn parse_toml(config_str: &str) -> Result<Self, String> {
+        toml::from_str::<ConfigWrapper>(config_str)
+            .map(|wrapper| wrapper.logging)
+            .or_else(|e| toml::from_str::<LogConfig>(config_str)
+                .map_err(|_| format!("Failed to parse config file: {}", e)))
+    }
+
+    #[cfg(feature = "json")]
+    fn parse_json(config_str: &str) -> Result<Self, String> {
+        serde_json::from_str::<ConfigWrapper>(config_str)
+            .map(|wrapper| wrapper.logging)
+            .or_else(|e| serde_json::from_str::<LogConfig>(config_str)
+                .map_err(|_| format!("Failed to parse JSON config file: {}", e)))
+    }
+
+    #[cfg(not(feature = "json"))]
+    fn parse_json(_config_str: &str) -> Result<Self, String> {
+        Err("JSON config files require the `json` feature of liblogger".to_string())
+    }
+
+    #[cfg(feature = "yaml")]
+    fn parse_yaml(config_str: &str) -> Result<Self, String> {
+        serde_yaml::from_str::<ConfigWrapper>(config_str)
+            .map(|wrapper| wrapper.logging)
+            .or_else(|e| serde_yaml::from_str::<LogConfig>(config_str)
+                .map_err(|_| format!("Failed to parse YAML config file: {}", e)))
+    }
+
+    #[cfg(not(feature = "yaml"))]
+    fn parse_yaml(_config_str: &str) -> Result<Self, String> {
+        Err("YAML config files require the `yaml` feature of liblogger".to_string())
+    }
+    
+    /// Serialize to TOML under a `[logging]` section, as `from_file` reads it
+    pub fn to_toml(&self) -> Result<String, String> {
+        toml::to_string(&ConfigWrapper { logging: self.clone() })
+            .map_err(|e| format!("Failed to serialize config: {}", e))
+    }
+
+    /// Configuration for a single sink of `outputs`, with unset settings
+    /// taken from this configuration
+    pub(crate) fn for_output(&self, spec: &OutputSpec) -> LogConfig {
+        LogConfig {
+            log_type: spec.log_type.clone(),
+            threshold: spec.threshold.clone().unwrap_or_else(|| self.threshold.clone()),
+            file_path: spec.file_path.clone().or_else(|| self.file_path.clone()),
+            log_folder: spec.log_folder.clone().or_else(|| self.log_folder.clone()),
+            http_endpoint: spec.http_endpoint.clone().or_else(|| self.http_endpoint.clone()),
+            http_timeout_seconds: spec.http_timeout_seconds.or(self.http_timeout_seconds),
+            socket_path: spec.socket_path.clone().or_else(|| self.socket_path.clone()),
+            outputs: Vec::new(),
+            ..self.clone()
+        }
+    }
+
+    /// Whether lines use the built-in text layout (text format, no template)
+    pub(crate) fn uses_text_layout(&self) -> bool {
+        self.format == LogFormat::Text && self.text_template.is_none()
+    }
+
+    /// Build a configuration from `LIBLOGGER_*` environment variables alone
+    ///
+    /// Reads LIBLOGGER_TYPE, LIBLOGGER_THRESHOLD, LIBLOGGER_FILE_PATH,
+    /// LIBLOGGER_LOG_FOLDER, LIBLOGGER_HTTP_ENDPOINT, LIBLOGGER_SOCKET_PATH
+    /// and LIBLOGGER_FORMAT. Unset or empty variables keep their
+    /// `LogConfig::default()` values. As with the builder, a file path,
+    /// endpoint or socket path selects that output unless LIBLOGGER_TYPE
+    /// names another. No file is read.
+    pub fn from_env() -> Result<Self, String> {
+        let var = |name: &str| std::env::var(name).ok()
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty());
+        
+        let mut builder = LogConfig::builder();
+        if let Some(file_path) = var("LIBLOGGER_FILE_PATH") {
+            builder = builder.file(&file_path);
+        }
+        if let Some(log_folder) = var("LIBLOGGER_LOG_FOLDER") {
+            builder = builder.log_folder(&log_folder);
+        }
+        if let Some(endpoint) = var("LIBLOGGER_HTTP_ENDPOINT") {
+            builder = builder.http_endpoint(&endpoint);
+        }
+        if let Some(socket_path) = var("LIBLOGGER_SOCKET_PATH") {
+            builder = builder.unix_socket(&socket_path);
+        }
+        if let Some(log_type) = var("LIBLOGGER_TYPE") {
+            builder = builder.log_type(parse_env_var("LIBLOGGER_TYPE", &log_type)?);
+        }
+        if let Some(threshold) = var("LIBLOGGER_THRESHOLD") {
+            builder = builder.threshold(parse_env_var("LIBLOGGER_THRESHOLD", &threshold)?);
+        }
+        if let Some(format) = var("LIBLOGGER_FORMAT") {
+            builder = builder.format(parse_env_var("LIBLOGGER_FORMAT", &format)?);
+        }
+        Ok(builder.build())
+    }
+    
+    /// Check the settings for mistakes that would only surface once logging starts
+    ///
+    /// Called by every init function before outputs are created. Returns the
+    /// first problem found, naming the setting and how to fix it.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.outputs.is_empty() {
+            self.validate_output()?;
+        }
+        for (index, spec) in self.outputs.iter().enumerate() {
+            self.for_output(spec).validate_output()
+                .map_err(|e| format!("outputs[{}]: {}", index, e))?;
+        }
+        
+        if self.max_file_size_mb == Some(0) {
+            return Err("max_file_size_mb must be at least 1 (file rotation is not implemented)".to_string());
+        }
+        if self.http_timeout_seconds == Some(0) {
+            return Err("http_timeout_seconds must be at least 1; remove it for the default of 30".to_string());
+        }
+        if self.async_channel_capacity == 0 {
+            return Err("async_channel_capacity must be at least 1".to_string());
+        }
+        if self.auto_debug_on_error_rate == Some(0) {
+            return Err("auto_debug_on_error_rate must be at least 1; remove it to never escalate".to_string());
+        }
+        if self.auto_debug_on_error_rate.is_some() && self.escalation_secs == 0 {
+            return Err("escalation_secs must be at least 1".to_string());
+        }
+        self.validate_json_field_map()?;
+        
+        if let Some(template) = &self.text_template {
+            if self.format != LogFormat::Text {
+                return Err(format!("text_template cannot be combined with the {} format", self.format.as_str()));
+            }
+            TextTemplate::parse(template)?;
+        }
+        Ok(())
+    }
+    
+    // Renamed fields must be built-in ones and still end up with distinct keys
+    fn validate_json_field_map(&self) -> Result<(), String> {
+        for (field, name) in &self.json_field_map {
+            if !RESERVED_JSON_KEYS.contains(&field.as_str()) {
+                return Err(format!("json_field_map: unknown field '{}', expected one of {}", field, RESERVED_JSON_KEYS.join(", ")));
+            }
+            if name.trim().is_empty() {
+                return Err(format!("json_field_map: '{}' needs a non-empty name", field));
+            }
+        }
+        let mut written: BTreeMap<&str, &str> = BTreeMap::new();
+        for field in RESERVED_JSON_KEYS {
+            let name = self.json_field_map.get(*field).map_or(*field, String::as_str);
+            if let Some(other) = written.insert(name, field) {
+                return Err(format!("json_field_map: '{}' and '{}' would both be written as '{}'", other, field, name));
+            }
+        }
+        Ok(())
+    }
+    
+    // Settings required by the single `log_type` of this configuration
+    fn validate_output(&self) -> Result<(), String> {
+        match self.log_type {
+            LogType::File => match self.file_path.as_deref().map(str::trim) {
+                None | Some("") => Err("type = \"file\" requires a file_path, e.g. file_path = \"app.log\"".to_string()),
+                Some(_) => Ok(()),
+            },
+            LogType::Http => {
+                let endpoint = match self.http_endpoint.as_deref().map(str::trim) {
+                    None | Some("") => return Err(
+                        "type = \"http\" requires an http_endpoint, e.g. http_endpoint = \"https://logs.example.com/ingest\"".to_string()),
+                    Some(endpoint) => endpoint,
+                };
+                match reqwest::Url::parse(endpoint) {
+                    Ok(url) if matches!(url.scheme(), "http" | "https") => Ok(()),
+                    Ok(url) => Err(format!("http_endpoint '{}' must use http or https, not {}", endpoint, url.scheme())),
+                    Err(e) => Err(format!("http_endpoint '{}' is not a valid URL ({}); expected e.g. \"https://logs.example.com/ingest\"", endpoint, e)),
+                }
+            },
+            LogType::UnixSocket => match self.socket_path.as_deref().map(str::trim) {
+                None | Some("") => Err("type = \"unix_socket\" requires a socket_path, e.g. socket_path = \"/var/run/vector.sock\"".to_string()),
+                Some(_) => Ok(()),
+            },
+            LogType::Console | LogType::Null => Ok(()),
+        }
+    }
+    
+    /// Get the global instance of LogConfig
+    pub fn get_instance() -> Result<LogConfig, String> {
+        match CONFIG_INSTANCE.get() {
+            Some(config) => Ok(config.clone()),
+            None => Err("LogConfig not initialized. Call LogConfig::from_file first.".into())
+        }
+    }
+}
+
+// Parse the value of an environment variable, naming the variable on failure
+fn parse_env_var<T: FromStr<Err = String>>(name: &str, value: &str) -> Result<T, String> {
+    value.parse().map_err(|e| format!("{}: {}", name, e))
+}
+
+/// Chainable builder for LogConfig
+///
+/// Fields that are not set keep the values from `LogConfig::default()`:
+/// console output, INFO threshold, async logging on, force flush off and
+/// colors only when stdout is a terminal.
+///
+/// ```
+/// use liblogger::{LogConfig, LogLevel};
+///
+/// let config = LogConfig::builder()
+///     .threshold(LogLevel::Debug)
+///     .file("app.log")
+///     .log_folder("logs")
+///     .build();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct LogConfigBuilder {
+    config: LogConfig,
+}
+
+impl LogConfigBuilder {
+    pub fn new() -> Self {
+        LogConfigBuilder::default()
+    }
+
+    /// Set the output type
+    pub fn log_type(mut self, log_type: LogType) -> Self {
+        self.config.log_type = log_type;
+        self
+    }
+
+    /// Set the minimum level to record
+    pub fn threshold(mut self, threshold: LogLevel) -> Self {
+        self.config.threshold = threshold;
+        self
+    }
+
+    /// Log to a file; also selects `LogType::File`
+    pub fn file(mut self, file_path: &str) -> Self {
+        self.config.log_type = LogType::File;
+        self.config.file_path = Some(file_path.to_string());
+        self
+    }
+
+    /// Set the folder that holds the log file
+    pub fn log_folder(mut self, log_folder: &str) -> Self {
+        self.config.log_folder = Some(log_folder.to_string());
+        self
+    }
+
+    /// Set the maximum file size (in MB); not enforced, files are not rotated
+    pub fn max_file_size_mb(mut self, max_file_size_mb: u64) -> Self {
+        self.config.max_file_size_mb = Some(max_file_size_mb);
+        self
+    }
+
+    /// Send logs to an HTTP endpoint; also selects `LogType::Http`
+    pub fn http_endpoint(mut self, endpoint: &str) -> Self {
+        self.config.log_type = LogType::Http;
+        self.config.http_endpoint = Some(endpoint.to_string());
+        self
+    }
+
+    /// Send logs to a Unix domain socket; also selects `LogType::UnixSocket`
+    pub fn unix_socket(mut self, socket_path: &str) -> Self {
+        self.config.log_type = LogType::UnixSocket;
+        self.config.socket_path = Some(socket_path.to_string());
+        self
+    }
+
+    /// Set the timeout for HTTP requests (in seconds)
+    pub fn http_timeout_seconds(mut self, timeout: u64) -> Self {
+        self.config.http_timeout_seconds = Some(timeout);
+        self
+    }
+
+    /// Add a constant field to every JSON record posted to HTTP outputs
+    pub fn http_extra_field(mut self, key: &str, value: &str) -> Self {
+        self.config.http_extra_fields.insert(key.to_string(), value.to_string());
+        self
+    }
+
+    /// Enable or disable the background logging task
+    pub fn async_logging(mut self, async_logging: bool) -> Self {
+        self.config.async_logging = async_logging;
+        self
+    }
+
+    /// Set how many lines the async channel holds
+    pub fn async_channel_capacity(mut self, async_channel_capacity: usize) -> Self {
+        self.config.async_channel_capacity = async_channel_capacity;
+        self
+    }
+
+    /// Set what happens to a line when the async channel is full
+    pub fn overflow_policy(mut self, overflow_policy: OverflowPolicy) -> Self {
+        self.config.overflow_policy = overflow_policy;
+        self
+    }
+
+    /// Enable or disable flushing after every write
+    pub fn force_flush(mut self, force_flush: bool) -> Self {
+        self.config.force_flush = force_flush;
+        self
+    }
+
+    /// Enable or disable in-memory buffering of file writes
+    pub fn buffered(mut self, buffered: bool) -> Self {
+        self.config.buffered = buffered;
+        self
+    }
+
+    /// Enable or disable writing ERROR lines to disk immediately
+    pub fn flush_on_error(mut self, flush_on_error: bool) -> Self {
+        self.config.flush_on_error = flush_on_error;
+        self
+    }
+
+    /// Set the line terminator written by file outputs
+    pub fn line_ending(mut self, line_ending: LineEnding) -> Self {
+        self.config.line_ending = line_ending;
+        self
+    }
+
+    /// Set how long (in ms) the background task may coalesce lines into one write
+    pub fn flush_interval_ms(mut self, flush_interval_ms: u64) -> Self {
+        self.config.flush_interval_ms = flush_interval_ms;
+        self
+    }
+
+    /// Truncate messages larger than the given number of bytes
+    pub fn max_message_bytes(mut self, max_message_bytes: usize) -> Self {
+        self.config.max_message_bytes = Some(max_message_bytes);
+        self
+    }
+
+    /// Keep the given number of recent lines in memory (0 disables it)
+    pub fn ring_buffer_size(mut self, ring_buffer_size: usize) -> Self {
+        self.config.ring_buffer_size = ring_buffer_size;
+        self
+    }
+
+    /// Set when console output is colorized
+    pub fn color(mut self, color: ColorMode) -> Self {
+        self.config.color = color;
+        self
+    }
+
+    /// Set what happens when the logger lock is found poisoned
+    pub fn on_poison(mut self, on_poison: PoisonPolicy) -> Self {
+        self.config.on_poison = on_poison;
+        self
+    }
+
+    /// Record every level for a while once ERROR lines exceed `errors_per_second`
+    pub fn auto_debug_on_error_rate(mut self, errors_per_second: u32) -> Self {
+        self.config.auto_debug_on_error_rate = Some(errors_per_second);
+        self
+    }
+
+    /// Set how long an escalation from `auto_debug_on_error_rate` lasts
+    pub fn escalation_secs(mut self, escalation_secs: u64) -> Self {
+        self.config.escalation_secs = escalation_secs;
+        self
+    }
+
+    /// Set the stream console output is written to
+    pub fn console_stream(mut self, console_stream: ConsoleStream) -> Self {
+        self.config.console_stream = console_stream;
+        self
+    }
+
+    /// Show only the last `segments` parts of module paths (0 shows all)
+    pub fn module_truncate_segments(mut self, segments: usize) -> Self {
+        self.config.module_truncate_segments = segments;
+        self
+    }
+
+    /// Enable or disable escaping of newlines and control characters
+    pub fn sanitize_messages(mut self, sanitize_messages: bool) -> Self {
+        self.config.sanitize_messages = sanitize_messages;
+        self
+    }
+
+    /// Include or leave out the source file and line of each call
+    pub fn include_location(mut self, include_location: bool) -> Self {
+        self.config.include_location = include_location;
+        self
+    }
+
+    /// Write lines as text, JSON, CEF, GCP JSON or CloudWatch EMF
+    pub fn format(mut self, format: LogFormat) -> Self {
+        self.config.format = format;
+        self
+    }
+
+    /// Number every line with a process-wide sequence number
+    pub fn include_sequence(mut self, include_sequence: bool) -> Self {
+        self.config.include_sequence = include_sequence;
+        self
+    }
+
+    /// Write key/value context as a JSON object instead of a string
+    pub fn structured_context(mut self, structured_context: bool) -> Self {
+        self.config.structured_context = structured_context;
+        self
+    }
+
+    /// Write the built-in JSON record field `field` under the key `name`
+    pub fn json_field_name(mut self, field: &str, name: &str) -> Self {
+        self.config.json_field_map.insert(field.to_string(), name.to_string());
+        self
+    }
+
+    /// Set the device vendor, product and version written in the header of CEF lines
+    pub fn cef_device(mut self, vendor: &str, product: &str, version: &str) -> Self {
+        self.config.cef_device_vendor = Some(vendor.to_string());
+        self.config.cef_device_product = Some(product.to_string());
+        self.config.cef_device_version = Some(version.to_string());
+        self
+    }
+
+    /// Set the namespace of the line count metric in CloudWatch EMF records
+    pub fn cloudwatch_namespace(mut self, namespace: &str) -> Self {
+        self.config.cloudwatch_namespace = Some(namespace.to_string());
+        self
+    }
+
+    /// Set the layout of text log lines; unknown placeholders fail at init
+    pub fn text_template(mut self, text_template: &str) -> Self {
+        self.config.text_template = Some(text_template.to_string());
+        self
+    }
+
+    /// Add a sink; once any are added they replace the single output type
+    pub fn output(mut self, output: OutputSpec) -> Self {
+        self.config.outputs.push(output);
+        self
+    }
+
+    /// Finish building the configuration
+    pub fn build(self) -> LogConfig {
+        self.config
+    }
+}
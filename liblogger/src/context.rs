@@ -0,0 +1,393 @@
+/*
+ * Thread-local logging context for Rusty Logger v2
+ *
+ * This module maintains a per-thread map of key/value pairs (request_id,
+ * user_id, ...) that is appended to the context field of every log line
+ * emitted from that thread. It also provides:
+ * - CapturedContext for snapshotting the map and restoring it elsewhere
+ * - ContextScope, an RAII guard that restores the previous map on drop
+ * - WithContext, a future wrapper that installs a captured map around every
+ *   poll so the context follows a task across Tokio worker threads
+ * - Process-wide global fields (service name, host, pid) shared by every thread
+ * - TransactionScope, which tracks how deeply `#[log_transaction]` calls are
+ *   nested on the current thread
+ * - QuietGuard, which mutes logging from the current thread while it lives,
+ *   and QuietFuture, which mutes a future on whichever thread polls it
+ * - FieldGuard, which adds structured fields to the current thread's lines
+ *   while it lives; JSON records carry them as top-level keys
+ * - LineContext, the merged context of one line, kept as key/value pairs
+ *   alongside the flat string when it was built from them
+ * - ContextFormatter, an optional process-wide renderer for those pairs
+ */
+
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::sync::RwLock;
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+// Fields appended to every log line, whichever thread logs it
+static GLOBAL_FIELDS: RwLock<BTreeMap<String, String>> = RwLock::new(BTreeMap::new());
+
+// Renders the context of text lines; None keeps the "key=value, ..." style
+static CONTEXT_FORMATTER: RwLock<Option<Box<dyn ContextFormatter>>> = RwLock::new(None);
+
+thread_local! {
+    static CONTEXT: RefCell<BTreeMap<String, String>> = const { RefCell::new(BTreeMap::new()) };
+    static TRANSACTION_DEPTH: Cell<u32> = const { Cell::new(0) };
+    // Lowest level severity logged from this thread while quiet; 0 lets everything through
+    static QUIET_FLOOR: Cell<u8> = const { Cell::new(0) };
+    // Fields pushed by the FieldGuards alive on this thread, innermost last
+    static FIELDS: RefCell<BTreeMap<String, String>> = const { RefCell::new(BTreeMap::new()) };
+}
+
+/// Set a key in the current thread's context
+pub(crate) fn set_value(key: &str, value: &str) {
+    CONTEXT.with(|ctx| {
+        ctx.borrow_mut().insert(key.to_string(), value.to_string());
+    });
+}
+
+/// Get a key from the current thread's context
+pub(crate) fn get_value(key: &str) -> Option<String> {
+    CONTEXT.with(|ctx| ctx.borrow().get(key).cloned())
+}
+
+/// Remove a key from the current thread's context
+pub(crate) fn remove_value(key: &str) -> Option<String> {
+    CONTEXT.with(|ctx| ctx.borrow_mut().remove(key))
+}
+
+/// Clone the current thread's context
+pub(crate) fn snapshot() -> BTreeMap<String, String> {
+    CONTEXT.with(|ctx| ctx.borrow().clone())
+}
+
+/// Replace the current thread's context, returning the previous one
+pub(crate) fn replace(fields: BTreeMap<String, String>) -> BTreeMap<String, String> {
+    CONTEXT.with(|ctx| std::mem::replace(&mut *ctx.borrow_mut(), fields))
+}
+
+// Longest trace ID adopted from a caller; longer values are replaced
+const MAX_TRACE_ID_LEN: usize = 128;
+
+/// The trimmed value if it is usable as a trace ID
+///
+/// Accepts 1 to 128 ASCII letters, digits and `-_.:`, which covers UUIDs,
+/// `X-Request-Id` values and W3C `traceparent` headers, and rejects anything
+/// that could break a log line.
+pub(crate) fn valid_trace_id(value: &str) -> Option<&str> {
+    let value = value.trim();
+    let sane = !value.is_empty()
+        && value.len() <= MAX_TRACE_ID_LEN
+        && value.bytes().all(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.' | b':'));
+    sane.then_some(value)
+}
+
+/// Set a key until the returned guard is dropped, then restore the previous context
+pub(crate) fn scoped_value(key: &str, value: &str) -> ContextScope {
+    let previous = snapshot();
+    set_value(key, value);
+    ContextScope { previous: Some(previous) }
+}
+
+/// Replace the process-wide fields, adding `hostname` and `pid` unless given
+pub(crate) fn set_global_fields(fields: HashMap<String, String>) {
+    let mut global = BTreeMap::new();
+    if let Some(hostname) = crate::system::hostname() {
+        global.insert("hostname".to_string(), hostname);
+    }
+    global.insert("pid".to_string(), std::process::id().to_string());
+    global.extend(fields);
+    
+    *GLOBAL_FIELDS.write().unwrap_or_else(|e| e.into_inner()) = global;
+}
+
+/// Copy of the process-wide fields
+pub(crate) fn global_fields() -> BTreeMap<String, String> {
+    GLOBAL_FIELDS.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Renders the context of a line from its key/value pairs
+///
+/// Install one with `Logger::set_context_formatter`. It is only consulted
+/// for contexts made entirely of key=value pairs; a free-form explicit
+/// context is written as given.
+pub trait ContextFormatter: Send + Sync {
+    /// Render the pairs, sorted by key, as the line's context
+    fn format(&self, fields: &BTreeMap<String, String>) -> String;
+}
+
+/// Replace the process-wide context formatter; None restores the default style
+pub(crate) fn set_formatter(formatter: Option<Box<dyn ContextFormatter>>) {
+    *CONTEXT_FORMATTER.write().unwrap_or_else(|e| e.into_inner()) = formatter;
+}
+
+/// The context of one log line
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct LineContext {
+    /// The flat "key=value, ..." rendering used by text lines
+    pub text: String,
+    /// The same context as pairs, when every part of it is a key=value pair
+    pub pairs: Option<BTreeMap<String, String>>,
+}
+
+/// Append the current thread's context and the global fields to an explicit context string
+///
+/// Keys already present as "key=..." in the explicit context are skipped,
+/// scoped fields shadow context values, and thread-local keys shadow global
+/// ones, so the most specific value wins and no key is rendered twice. The
+/// pairs are kept too unless the explicit context has a part that is not a
+/// key=value pair.
+///
+/// With `flat_fields`, for layouts writing fields as top-level keys, the
+/// scoped and global fields are returned as those keys instead of joining
+/// the context; otherwise the returned fields are empty.
+pub(crate) fn merge_into(explicit: Option<String>, flat_fields: bool) -> (Option<LineContext>, BTreeMap<String, String>) {
+    // None for a part that is not a key=value pair
+    let explicit_parts: Vec<Option<(&str, &str)>> = explicit.as_deref()
+        .map(|explicit| explicit
+            .split(", ")
+            .map(|part| part.split_once('=').map(|(key, value)| (key.trim(), value)))
+            .collect())
+        .unwrap_or_default();
+    let explicit_keys: Vec<&str> = explicit_parts.iter().flatten().map(|(key, _)| *key).collect();
+    
+    let mut ambient = CONTEXT.with(|ctx| ctx.borrow().clone());
+    let scoped = FIELDS.with(|fields| fields.borrow().clone());
+    let mut fields = BTreeMap::new();
+    {
+        let global = GLOBAL_FIELDS.read().unwrap_or_else(|e| e.into_inner());
+        if flat_fields {
+            ambient.retain(|k, _| !scoped.contains_key(k));
+            fields.extend(global.iter()
+                .filter(|(k, _)| !ambient.contains_key(*k) && !explicit_keys.contains(&k.as_str()))
+                .map(|(k, v)| (k.clone(), v.clone())));
+            fields.extend(scoped);
+        } else {
+            ambient.extend(scoped);
+            for (k, v) in global.iter() {
+                ambient.entry(k.clone()).or_insert_with(|| v.clone());
+            }
+        }
+    }
+    let pairs = explicit_parts.iter().all(Option::is_some).then(|| {
+        let mut pairs = ambient.clone();
+        pairs.extend(explicit_parts.iter().flatten().map(|(k, v)| (k.to_string(), v.to_string())));
+        pairs
+    });
+    let ambient: Vec<String> = ambient.iter()
+        .filter(|(k, _)| !explicit_keys.contains(&k.as_str()))
+        .map(|(k, v)| format!("{}={}", k, v))
+        .collect();
+    
+    let text = match (explicit, ambient.is_empty()) {
+        (explicit, true) => explicit,
+        (Some(explicit), false) => Some(format!("{}, {}", explicit, ambient.join(", "))),
+        (None, false) => Some(ambient.join(", ")),
+    };
+    let text = match (&pairs, text) {
+        (Some(fields), Some(text)) => {
+            let formatter = CONTEXT_FORMATTER.read().unwrap_or_else(|e| e.into_inner());
+            Some(formatter.as_ref().map_or(text, |formatter| formatter.format(fields)))
+        },
+        (_, text) => text,
+    };
+    (text.map(|text| LineContext { text, pairs }), fields)
+}
+
+/// A snapshot of the thread-local logging context
+///
+/// Obtain one with `Logger::capture_context()` and re-install it on another
+/// thread or task with `scope()` or `instrument()`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CapturedContext {
+    fields: BTreeMap<String, String>,
+}
+
+impl CapturedContext {
+    pub(crate) fn capture() -> Self {
+        CapturedContext { fields: snapshot() }
+    }
+
+    /// The captured key/value pairs
+    pub fn fields(&self) -> &BTreeMap<String, String> {
+        &self.fields
+    }
+
+    /// Add or replace a key in the captured context
+    pub fn with_value(mut self, key: &str, value: &str) -> Self {
+        self.fields.insert(key.to_string(), value.to_string());
+        self
+    }
+
+    /// Install the captured context on the current thread until the guard is dropped
+    pub fn scope(&self) -> ContextScope {
+        ContextScope {
+            previous: Some(replace(self.fields.clone())),
+        }
+    }
+
+    /// Wrap a future so the captured context is active whenever it is polled
+    pub fn instrument<F: Future>(self, future: F) -> WithContext<F> {
+        WithContext {
+            inner: Box::pin(future),
+            fields: self.fields,
+        }
+    }
+}
+
+/// Guard returned by `CapturedContext::scope()` and `Logger::scoped_context_value()`
+///
+/// Restores the context that was active before the scope was entered.
+pub struct ContextScope {
+    previous: Option<BTreeMap<String, String>>,
+}
+
+impl Drop for ContextScope {
+    fn drop(&mut self) {
+        if let Some(previous) = self.previous.take() {
+            replace(previous);
+        }
+    }
+}
+
+/// Future wrapper that carries a logging context across polls
+pub struct WithContext<F: Future> {
+    inner: Pin<Box<F>>,
+    fields: BTreeMap<String, String>,
+}
+
+impl<F: Future> Future for WithContext<F> {
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+
+        // Swap our context in for the duration of the poll, keeping any
+        // changes the task made so they survive into the next poll
+        let previous = replace(std::mem::take(&mut this.fields));
+        let result = this.inner.as_mut().poll(cx);
+        this.fields = replace(previous);
+
+        result
+    }
+}
+
+/// Enter a transaction on the current thread until the returned guard is dropped
+pub(crate) fn enter_transaction() -> TransactionScope {
+    let depth = TRANSACTION_DEPTH.with(|depth| {
+        depth.set(depth.get() + 1);
+        depth.get()
+    });
+    TransactionScope { depth }
+}
+
+/// Guard for one level of transaction nesting, returned by `Logger::enter_transaction()`
+///
+/// The depth is 1 for an outermost transaction and grows by one for each
+/// transaction entered on the same thread before it is dropped.
+#[derive(Debug)]
+pub struct TransactionScope {
+    depth: u32,
+}
+
+impl TransactionScope {
+    /// Nesting depth of this transaction (1 = not nested)
+    pub fn depth(&self) -> u32 {
+        self.depth
+    }
+}
+
+impl Drop for TransactionScope {
+    fn drop(&mut self) {
+        TRANSACTION_DEPTH.with(|depth| depth.set(depth.get().saturating_sub(1)));
+    }
+}
+
+/// Suppress messages below `floor` on the current thread until the guard is dropped
+///
+/// Nested scopes can only make the thread quieter.
+pub(crate) fn enter_quiet(floor: u8) -> QuietGuard {
+    let previous = QUIET_FLOOR.with(|quiet| quiet.replace(quiet.get().max(floor)));
+    QuietGuard { previous }
+}
+
+/// Whether a message of severity `level` passes the current thread's quiet scope
+pub(crate) fn passes_quiet(level: u8) -> bool {
+    level >= QUIET_FLOOR.with(Cell::get)
+}
+
+/// Guard returned by `Logger::quiet_scope()` and `Logger::quiet_scope_above()`
+///
+/// Logging from the thread that created it is muted until it is dropped.
+#[derive(Debug)]
+#[must_use = "logging is only muted while the guard is held"]
+pub struct QuietGuard {
+    previous: u8,
+}
+
+impl Drop for QuietGuard {
+    fn drop(&mut self) {
+        QUIET_FLOOR.with(|quiet| quiet.set(self.previous));
+    }
+}
+
+/// Wrap a future so messages below `floor` are suppressed while it is polled
+pub(crate) fn quiet_future<F: Future>(future: F, floor: u8) -> QuietFuture<F> {
+    QuietFuture {
+        inner: Box::pin(future),
+        floor,
+    }
+}
+
+/// Future wrapper returned by `Logger::quiet_future()`
+///
+/// Unlike a `QuietGuard` held across an `.await`, the mute follows the task
+/// when a multi-threaded runtime moves it to another worker thread.
+pub struct QuietFuture<F: Future> {
+    inner: Pin<Box<F>>,
+    floor: u8,
+}
+
+impl<F: Future> Future for QuietFuture<F> {
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let _quiet = enter_quiet(this.floor);
+        this.inner.as_mut().poll(cx)
+    }
+}
+
+/// Add fields to the current thread's lines until the guard is dropped
+///
+/// Nested scopes merge, with the inner value winning for a repeated key.
+pub(crate) fn push_fields(fields: &[(&str, &str)]) -> FieldGuard {
+    let previous = FIELDS.with(|current| {
+        let mut merged = current.borrow().clone();
+        merged.extend(fields.iter().map(|(k, v)| (k.to_string(), v.to_string())));
+        current.replace(merged)
+    });
+    FieldGuard { previous: Some(previous) }
+}
+
+/// Guard returned by `Logger::with_fields()`
+///
+/// Lines logged from the thread that created it carry its fields until it
+/// is dropped, which restores the fields of the enclosing scope.
+#[derive(Debug)]
+#[must_use = "the fields are only added while the guard is held"]
+pub struct FieldGuard {
+    previous: Option<BTreeMap<String, String>>,
+}
+
+impl Drop for FieldGuard {
+    fn drop(&mut self) {
+        if let Some(previous) = self.previous.take() {
+            FIELDS.with(|fields| fields.replace(previous));
+        }
+    }
+}
@@ -0,0 +1,50 @@
+/*
+ * Error descriptions for `#[log_errors]` and `#[log_result]`
+ *
+ * The attribute macros see the error type only at the expansion site, so
+ * they pick a description through autoref specialization: types that
+ * implement `std::error::Error` resolve to `ChainDescription`, anything else
+ * that is `Debug` falls back to `DebugDescription`. With the `error_chain`
+ * feature the former appends every `.source()` below the error; without it
+ * both print the error's `{:?}` as before.
+ */
+
+use std::error::Error;
+use std::fmt::Debug;
+
+/// Borrowed error being described, see the module docs
+pub struct ErrorChain<'a, T: ?Sized>(pub &'a T);
+
+/// Description of an error and, with `error_chain`, its sources
+pub trait ChainDescription {
+    fn describe(&self) -> String;
+}
+
+impl<T: Error + ?Sized> ChainDescription for ErrorChain<'_, T> {
+    #[cfg(feature = "error_chain")]
+    fn describe(&self) -> String {
+        let mut description = format!("{:?}", self.0);
+        let mut source = self.0.source();
+        while let Some(cause) = source {
+            description.push_str(&format!("; caused by: {}", cause));
+            source = cause.source();
+        }
+        description
+    }
+
+    #[cfg(not(feature = "error_chain"))]
+    fn describe(&self) -> String {
+        format!("{:?}", self.0)
+    }
+}
+
+/// Fallback description for values that aren't `std::error::Error`
+pub trait DebugDescription {
+    fn describe(&self) -> String;
+}
+
+impl<T: Debug + ?Sized> DebugDescription for &ErrorChain<'_, T> {
+    fn describe(&self) -> String {
+        format!("{:?}", self.0)
+    }
+}
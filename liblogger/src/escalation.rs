@@ -0,0 +1,80 @@
+/*
+ * Automatic DEBUG escalation on a burst of errors
+ *
+ * With `auto_debug_on_error_rate` set, every ERROR line is counted in
+ * one-second buckets. The rate over the last second is estimated from the
+ * current bucket plus the part of the previous one still inside the window.
+ * When it exceeds the limit, every level is recorded for `escalation_secs`,
+ * after which the configured threshold applies again on its own. The
+ * counters are plain atomics, so a race at a bucket boundary can only lose
+ * a count, never block a logging thread.
+ */
+
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::time::Instant;
+
+use once_cell::sync::OnceCell;
+
+// ERROR lines per second that trigger escalation; 0 disables it
+static LIMIT: AtomicU32 = AtomicU32::new(0);
+// How long an escalation lasts
+static DURATION_MS: AtomicU64 = AtomicU64::new(0);
+// End of the current escalation in ms since EPOCH; 0 when not escalated
+static ESCALATED_UNTIL: AtomicU64 = AtomicU64::new(0);
+// Second the current bucket counts, and the counts of it and the one before
+static BUCKET_SECOND: AtomicU64 = AtomicU64::new(0);
+static CURRENT: AtomicU32 = AtomicU32::new(0);
+static PREVIOUS: AtomicU32 = AtomicU32::new(0);
+
+static EPOCH: OnceCell<Instant> = OnceCell::new();
+
+fn now_ms() -> u64 {
+    EPOCH.get_or_init(Instant::now).elapsed().as_millis() as u64
+}
+
+/// Apply the configured limit and duration, ending any escalation in progress
+pub(crate) fn configure(limit: Option<u32>, escalation_secs: u64) {
+    LIMIT.store(limit.unwrap_or(0), Ordering::Relaxed);
+    DURATION_MS.store(escalation_secs.saturating_mul(1000), Ordering::Relaxed);
+    ESCALATED_UNTIL.store(0, Ordering::Relaxed);
+    CURRENT.store(0, Ordering::Relaxed);
+    PREVIOUS.store(0, Ordering::Relaxed);
+}
+
+/// Whether every level is currently being recorded
+pub(crate) fn is_escalated() -> bool {
+    let until = ESCALATED_UNTIL.load(Ordering::Relaxed);
+    until != 0 && now_ms() < until
+}
+
+/// Count an ERROR line, returning a notice if it started an escalation
+pub(crate) fn record_error() -> Option<String> {
+    let limit = LIMIT.load(Ordering::Relaxed);
+    if limit == 0 {
+        return None;
+    }
+    let now = now_ms();
+    let second = now / 1000;
+
+    // The first thread into a new second rotates the buckets
+    let bucket = BUCKET_SECOND.load(Ordering::Relaxed);
+    if second != bucket && BUCKET_SECOND.compare_exchange(bucket, second, Ordering::Relaxed, Ordering::Relaxed).is_ok() {
+        let previous = if second == bucket + 1 { CURRENT.load(Ordering::Relaxed) } else { 0 };
+        PREVIOUS.store(previous, Ordering::Relaxed);
+        CURRENT.store(0, Ordering::Relaxed);
+    }
+    let current = CURRENT.fetch_add(1, Ordering::Relaxed) + 1;
+
+    // Weight the previous second by how much of it is still in the window
+    let remaining = 1000 - (now % 1000);
+    let rate = current as u64 + PREVIOUS.load(Ordering::Relaxed) as u64 * remaining / 1000;
+    if rate <= limit as u64 || is_escalated() {
+        return None;
+    }
+    let duration_ms = DURATION_MS.load(Ordering::Relaxed);
+    let previous_until = ESCALATED_UNTIL.swap(now + duration_ms, Ordering::Relaxed);
+    // Another thread may have escalated in the meantime
+    (previous_until == 0 || previous_until <= now).then(|| format!(
+        "More than {} errors per second; recording every level for {}s",
+        limit, duration_ms / 1000))
+}
@@ -0,0 +1,78 @@
+/*
+ * Health check registry for `#[health_check]`
+ *
+ * Every run of an instrumented check records its outcome under the
+ * function's name in a process-global map, replacing the previous run.
+ * `Logger::health_report()` summarizes the latest outcome of each check,
+ * for example to answer a `/healthz` endpoint.
+ */
+
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+// Latest result per check name
+static CHECKS: Mutex<BTreeMap<String, HealthCheckResult>> = Mutex::new(BTreeMap::new());
+
+/// Latest outcome of one health check
+#[derive(Debug, Clone, PartialEq)]
+pub struct HealthCheckResult {
+    /// Name of the check function
+    pub name: String,
+    /// Whether the check returned `Ok`
+    pub passed: bool,
+    /// The error the check returned, rendered with `Debug`
+    pub error: Option<String>,
+    /// How long the check took, in milliseconds
+    pub duration_ms: f64,
+    /// When the check finished
+    pub checked_at: SystemTime,
+}
+
+/// Summary of every health check run so far, returned by `Logger::health_report()`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HealthReport {
+    /// Checks whose latest run passed
+    pub passed: usize,
+    /// Checks whose latest run failed
+    pub failed: usize,
+    /// Latest result of each check, ordered by name
+    pub checks: Vec<HealthCheckResult>,
+}
+
+impl HealthReport {
+    /// Whether no check is currently failing (true before any check has run)
+    pub fn is_healthy(&self) -> bool {
+        self.failed == 0
+    }
+
+    /// The checks whose latest run failed
+    pub fn failing(&self) -> Vec<&HealthCheckResult> {
+        self.checks.iter().filter(|check| !check.passed).collect()
+    }
+}
+
+/// Record the outcome of a run of `name`, replacing its previous result
+pub(crate) fn record(name: &str, error: Option<String>, duration_ms: f64) {
+    let result = HealthCheckResult {
+        name: name.to_string(),
+        passed: error.is_none(),
+        error,
+        duration_ms,
+        checked_at: SystemTime::now(),
+    };
+    let mut checks = CHECKS.lock().unwrap_or_else(|e| e.into_inner());
+    checks.insert(name.to_string(), result);
+}
+
+/// Summary of the latest result of every check
+pub(crate) fn report() -> HealthReport {
+    let checks = CHECKS.lock().unwrap_or_else(|e| e.into_inner());
+    let checks: Vec<HealthCheckResult> = checks.values().cloned().collect();
+    let passed = checks.iter().filter(|check| check.passed).count();
+    HealthReport {
+        passed,
+        failed: checks.len() - passed,
+        checks,
+    }
+}
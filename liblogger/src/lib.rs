@@ -5,15 +5,58 @@
  * - Re-exporting the Logger struct for initialization and configuration
  * - Re-exporting LogConfig, LogLevel, and LogType for custom configuration
  * - Defining logging macros (log_debug, log_info, log_warn, log_error)
- * - Providing a shutdown function for graceful termination of async logging
+ * - Defining per-site rate-limited variants (log_info_every and friends)
+ * - Providing a shutdown function and drop guard for graceful termination of async logging
+ * - Re-exporting the thread-local context types used for propagation
+ * - Re-exporting the provider traits consulted by the attribute macros
+ * - Re-exporting the duration baselines used for anomaly detection
+ * - Re-exporting the step ordering used by workflow monitoring
+ * - Re-exporting the hold tracking used by distributed lock monitoring
+ * - Re-exporting the health report aggregated from health checks
+ * - Describing errors, with their source chains, for the error macros
+ * - Checking that modules using attribute macros called initialize_logger_attributes!
+ * - Selecting the compile-time level ceiling from the max_level_* features
+ * - Compiling all logging out with the `disabled` feature
+ * - In-memory capture for unit tests behind the `testing` feature
  * 
  * The library supports both synchronous and asynchronous logging operations
  * with multiple output targets (console, file, HTTP).
  */
 
+// println! for the logger's status messages, to stderr when console output goes there
+macro_rules! status {
+    ($($arg:tt)*) => {
+        if $crate::logger::STATUS_TO_STDERR.load(::std::sync::atomic::Ordering::Relaxed) {
+            eprintln!($($arg)*)
+        } else {
+            println!($($arg)*)
+        }
+    };
+}
+
+pub mod attributes;
+mod backtrace;
+mod baseline;
+mod breakers;
+mod cef;
+mod clock;
+mod cloud;
 mod config;
+mod context;
+pub mod error_chain;
+mod escalation;
+mod health;
+mod locks;
 mod outputs;
+mod progress;
 mod logger;
+mod providers;
+mod recent;
+mod system;
+mod template;
+mod workflow;
+#[cfg(feature = "testing")]
+mod testing;
 
 /// Main logger class that handles initialization and log operations
 /// 
@@ -21,35 +64,133 @@ mod logger;
 /// Example: `Logger::init_with_config_file("app_config.toml")`
 pub use logger::Logger;
 
+/// Guard returned by `Logger::init_with_guard()` that shuts the logger down on drop
+pub use logger::LoggerGuard;
+
+/// Logger returned by `Logger::child()` that tags its messages with a prefix
+pub use logger::ChildLogger;
+
 /// Configuration structures for customizing logger behavior
 /// 
 /// - LogConfig: Main configuration struct with all settings
+/// - LogConfigBuilder: Chainable builder returned by `LogConfig::builder()`
 /// - LogLevel: Enum for severity levels (Debug, Info, Warn, Error)
-pub use config::{LogConfig, LogLevel};
+/// - ColorMode: When console output is colorized (Auto, Always, Never)
+/// - ConsoleStream: Which stream console output is written to (Stdout, Stderr)
+/// - LineEnding: Line terminator written by file outputs (Lf, CrLf)
+/// - PoisonPolicy: Handling of a logger lock poisoned by a panic (Recover, Warn, Panic)
+/// - OverflowPolicy: Handling of a line when the async channel is full (WriteSync, DropNewest)
+/// - LogFormat: Whether lines are written as text, JSON, CEF or a cloud provider's JSON shape
+/// - OutputSpec: One sink of a multi-output configuration
+pub use config::{ColorMode, ConsoleStream, LineEnding, LogConfig, LogConfigBuilder, LogFormat, LogLevel, OutputSpec, OverflowPolicy, PoisonPolicy};
+
+/// Trait implemented by log destinations
+///
+/// Implement it for a custom sink and install it with `Logger::init_with_output`.
+pub use outputs::LogOutput;
+
+/// Version of the JSON record written in JSON format and posted to HTTP endpoints
+pub use outputs::SCHEMA_VERSION;
 
 /// Enum defining available output destinations
 /// 
 /// - Console: Logs to standard output
-/// - File: Logs to a file with rotation
+/// - File: Logs to a file
 /// - Http: Sends logs to a remote endpoint
+/// - Null: Discards logs, for measuring overhead
 pub use config::LogType;
 
+/// Thread-local logging context propagation
+///
+/// - CapturedContext: Snapshot returned by `Logger::capture_context()`
+/// - ContextScope: Guard that restores the previous context on drop
+/// - WithContext: Future wrapper that carries a context across polls
+/// - TransactionScope: Nesting depth of `#[log_transaction]` calls on a thread
+/// - QuietGuard: Mutes logging on a thread, returned by `Logger::quiet_scope()`
+/// - QuietFuture: Mutes logging from a future, returned by `Logger::quiet_future()`
+/// - FieldGuard: Adds fields to a thread's lines, returned by `Logger::with_fields()`
+/// - ContextFormatter: Custom rendering of context, installed with `Logger::set_context_formatter()`
+pub use context::{CapturedContext, ContextFormatter, ContextScope, FieldGuard, QuietFuture, QuietGuard, TransactionScope, WithContext};
+
+/// Provider traits that supply runtime data to the attribute macros
+///
+/// - FeatureFlagProvider: Flag state for `#[feature_flag]`
+/// - DiskMetricsProvider: Disk space for `#[log_disk_usage]`
+/// - CacheMetricsProvider: Cache counters for `#[log_cache_hit_ratio]`
+/// - QueueMetricsProvider: Queue backlog for `#[log_queue_depth]`
+pub use providers::{
+    CacheMetricsProvider, CacheStats, DataQualityProvider, DataQualityReport, DiskInfo,
+    DiskMetricsProvider, FeatureFlagProvider, QueueMetricsProvider, QueueStats,
+};
+
+/// Running call-duration statistics used by `#[log_anomaly_detection]`
+pub use baseline::DurationBaseline;
+
+/// One run of a step tracked by `#[log_workflow_step]`
+pub use workflow::WorkflowStep;
+
+/// One hold of a lock tracked by `#[log_distributed_lock]`
+pub use locks::LockHold;
+
+/// Per-call-site state of `log_progress!`
+#[doc(hidden)]
+pub use progress::ProgressSite;
+
+/// Duration formatting shared by `log_progress!` and the attribute macros
+#[doc(hidden)]
+pub use progress::format_duration;
+
+/// State of a named circuit breaker used by `#[log_service_communication]`
+pub use breakers::BreakerState;
+
+/// Latest results of `#[health_check]` functions, from `Logger::health_report()`
+pub use health::{HealthCheckResult, HealthReport};
+
+/// Buffer of captured lines returned by `Logger::init_for_tests()`
+#[cfg(feature = "testing")]
+pub use testing::CapturedLines;
+
+/// Most verbose level compiled into the logging macros
+///
+/// Selected with the `max_level_info`, `max_level_warn` or `max_level_error`
+/// features (the most restrictive one wins). Macro calls below this level
+/// expand to dead code, so their arguments are never evaluated.
+#[cfg(feature = "max_level_error")]
+pub const STATIC_MAX_LEVEL: LogLevel = LogLevel::Error;
+#[cfg(all(feature = "max_level_warn", not(feature = "max_level_error")))]
+pub const STATIC_MAX_LEVEL: LogLevel = LogLevel::Warn;
+#[cfg(all(feature = "max_level_info", not(any(feature = "max_level_warn", feature = "max_level_error"))))]
+pub const STATIC_MAX_LEVEL: LogLevel = LogLevel::Info;
+#[cfg(not(any(feature = "max_level_info", feature = "max_level_warn", feature = "max_level_error")))]
+pub const STATIC_MAX_LEVEL: LogLevel = LogLevel::Debug;
+
 /// Log a debug-level message
+///
+/// The optional second argument is the line's context: anything that
+/// converts into `Option<String>`, such as a `String`, `Some(String)` or `None`.
 /// 
 /// # Example
 /// ```
+/// # use liblogger::log_debug;
+/// # let user_id = 42;
 /// log_debug!("Connection pool initialized with 10 connections");
-/// log_debug!("User authenticated", Some(format!("user_id={}", user_id)));
+/// log_debug!("User authenticated", format!("user_id={}", user_id));
 /// ```
 /// 
-/// Debug logs are typically only recorded when the threshold is set to "debug"
+/// Debug logs are typically only recorded when the threshold is set to "debug",
+/// and are compiled out entirely by any of the `max_level_*` features
+#[cfg(not(feature = "disabled"))]
 #[macro_export]
 macro_rules! log_debug {
     ($message:expr) => {
-        $crate::Logger::debug($message, None, file!(), line!(), module_path!())
+        if $crate::LogLevel::Debug.should_log(&$crate::STATIC_MAX_LEVEL) {
+            $crate::Logger::debug($message, None, file!(), line!(), module_path!())
+        }
     };
     ($message:expr, $context:expr) => {
-        $crate::Logger::debug($message, $context, file!(), line!(), module_path!())
+        if $crate::LogLevel::Debug.should_log(&$crate::STATIC_MAX_LEVEL) {
+            $crate::Logger::debug($message, ::std::convert::Into::<Option<String>>::into($context), file!(), line!(), module_path!())
+        }
     };
 }
 
@@ -57,18 +198,25 @@ macro_rules! log_debug {
 /// 
 /// # Example
 /// ```
+/// # use liblogger::log_info;
 /// log_info!("Application started successfully");
-/// log_info!("User profile updated", Some("profile_id=12345".to_string()));
+/// log_info!("User profile updated", "profile_id=12345".to_string());
 /// ```
 /// 
-/// Info logs are recorded when the threshold is "debug" or "info"
+/// Info logs are recorded when the threshold is "debug" or "info", and are
+/// compiled out by the `max_level_warn` and `max_level_error` features
+#[cfg(not(feature = "disabled"))]
 #[macro_export]
 macro_rules! log_info {
     ($message:expr) => {
-        $crate::Logger::info($message, None, file!(), line!(), module_path!())
+        if $crate::LogLevel::Info.should_log(&$crate::STATIC_MAX_LEVEL) {
+            $crate::Logger::info($message, None, file!(), line!(), module_path!())
+        }
     };
     ($message:expr, $context:expr) => {
-        $crate::Logger::info($message, $context, file!(), line!(), module_path!())
+        if $crate::LogLevel::Info.should_log(&$crate::STATIC_MAX_LEVEL) {
+            $crate::Logger::info($message, ::std::convert::Into::<Option<String>>::into($context), file!(), line!(), module_path!())
+        }
     };
 }
 
@@ -76,18 +224,26 @@ macro_rules! log_info {
 /// 
 /// # Example
 /// ```
+/// # use liblogger::log_warn;
+/// # let rate = 95;
 /// log_warn!("Database connection pool running low");
-/// log_warn!("API rate limit approaching", Some(format!("current_rate={}/sec", rate)));
+/// log_warn!("API rate limit approaching", format!("current_rate={}/sec", rate));
 /// ```
 /// 
-/// Warning logs are recorded when the threshold is "debug", "info", or "warn"
+/// Warning logs are recorded when the threshold is "debug", "info", or "warn",
+/// and are compiled out by the `max_level_error` feature
+#[cfg(not(feature = "disabled"))]
 #[macro_export]
 macro_rules! log_warn {
     ($message:expr) => {
-        $crate::Logger::warn($message, None, file!(), line!(), module_path!())
+        if $crate::LogLevel::Warn.should_log(&$crate::STATIC_MAX_LEVEL) {
+            $crate::Logger::warn($message, None, file!(), line!(), module_path!())
+        }
     };
     ($message:expr, $context:expr) => {
-        $crate::Logger::warn($message, $context, file!(), line!(), module_path!())
+        if $crate::LogLevel::Warn.should_log(&$crate::STATIC_MAX_LEVEL) {
+            $crate::Logger::warn($message, ::std::convert::Into::<Option<String>>::into($context), file!(), line!(), module_path!())
+        }
     };
 }
 
@@ -95,39 +251,204 @@ macro_rules! log_warn {
 /// 
 /// # Example
 /// ```
+/// # use liblogger::log_error;
+/// # let code = 500;
 /// log_error!("Failed to connect to database");
-/// log_error!("Payment processing failed", Some(format!("error_code={}", code)));
+/// log_error!("Payment processing failed", format!("error_code={}", code));
 /// ```
 /// 
 /// Error logs are always recorded regardless of threshold level
+#[cfg(not(feature = "disabled"))]
 #[macro_export]
 macro_rules! log_error {
     ($message:expr) => {
-        $crate::Logger::error($message, None, file!(), line!(), module_path!())
+        if $crate::LogLevel::Error.should_log(&$crate::STATIC_MAX_LEVEL) {
+            $crate::Logger::error($message, None, file!(), line!(), module_path!())
+        }
     };
     ($message:expr, $context:expr) => {
-        $crate::Logger::error($message, $context, file!(), line!(), module_path!())
+        if $crate::LogLevel::Error.should_log(&$crate::STATIC_MAX_LEVEL) {
+            $crate::Logger::error($message, ::std::convert::Into::<Option<String>>::into($context), file!(), line!(), module_path!())
+        }
+    };
+}
+
+/// Log an info-level message at most once every `interval_ms` milliseconds from this call site
+///
+/// Repeats within the interval are dropped, which keeps hot loops from
+/// flooding the log. Each call site has its own timer.
+///
+/// # Example
+/// ```
+/// # use liblogger::log_info_every;
+/// for attempt in 0..1000 {
+///     log_info_every!(5000, "Still waiting for the lock", format!("attempt={}", attempt));
+/// }
+/// ```
+///
+/// `log_debug_every!`, `log_warn_every!` and `log_error_every!` work the same way.
+#[cfg(not(feature = "disabled"))]
+#[macro_export]
+macro_rules! log_info_every {
+    ($interval_ms:expr, $($arg:expr),+) => { $crate::__log_every!(Info, log_info, $interval_ms, $($arg),+) };
+}
+
+/// Log a debug-level message at most once every `interval_ms` milliseconds from this call site
+#[cfg(not(feature = "disabled"))]
+#[macro_export]
+macro_rules! log_debug_every {
+    ($interval_ms:expr, $($arg:expr),+) => { $crate::__log_every!(Debug, log_debug, $interval_ms, $($arg),+) };
+}
+
+/// Log a warning-level message at most once every `interval_ms` milliseconds from this call site
+#[cfg(not(feature = "disabled"))]
+#[macro_export]
+macro_rules! log_warn_every {
+    ($interval_ms:expr, $($arg:expr),+) => { $crate::__log_every!(Warn, log_warn, $interval_ms, $($arg),+) };
+}
+
+/// Log an error-level message at most once every `interval_ms` milliseconds from this call site
+#[cfg(not(feature = "disabled"))]
+#[macro_export]
+macro_rules! log_error_every {
+    ($interval_ms:expr, $($arg:expr),+) => { $crate::__log_every!(Error, log_error, $interval_ms, $($arg),+) };
+}
+
+// Shared body of the log_*_every macros: one timestamp per expansion site
+#[cfg(not(feature = "disabled"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __log_every {
+    ($level:ident, $log_macro:ident, $interval_ms:expr, $($arg:expr),+) => {
+        if $crate::LogLevel::$level.should_log(&$crate::STATIC_MAX_LEVEL) {
+            static LAST_EMIT: ::std::sync::atomic::AtomicU64 = ::std::sync::atomic::AtomicU64::new(0);
+            if $crate::Logger::with_min_interval(&LAST_EMIT, $interval_ms) {
+                $crate::$log_macro!($($arg),+)
+            }
+        }
+    };
+}
+
+/// Log "processed N of M" at info level every `every` items and for the last one
+///
+/// `current` counts the items processed so far, starting at 1. Lines include
+/// the percentage and an ETA from the time since this call site was first
+/// reached; a count lower than the previous one starts a new run. An
+/// optional label replaces the "Progress" prefix.
+///
+/// # Example
+/// ```
+/// # use liblogger::log_progress;
+/// let rows = vec![0u8; 1000];
+/// for (i, _row) in rows.iter().enumerate() {
+///     // "Importing rows: 100/1000 (10.0%), elapsed 1.20ms, ETA 10.80ms", ...
+///     log_progress!(i + 1, rows.len(), 100, "Importing rows");
+/// }
+/// ```
+#[cfg(not(feature = "disabled"))]
+#[macro_export]
+macro_rules! log_progress {
+    ($current:expr, $total:expr, $every:expr) => { $crate::log_progress!($current, $total, $every, "Progress") };
+    ($current:expr, $total:expr, $every:expr, $label:expr) => {
+        if $crate::LogLevel::Info.should_log(&$crate::STATIC_MAX_LEVEL) {
+            static SITE: $crate::ProgressSite = $crate::ProgressSite::new();
+            if let Some(line) = SITE.update($label, ($current) as u64, ($total) as u64, ($every) as u64) {
+                $crate::log_info!(&line)
+            }
+        }
     };
 }
 
+// With the `disabled` feature the logging macros still type-check their
+// arguments but never evaluate them, and reference nothing in this crate
+#[cfg(feature = "disabled")]
+#[macro_export]
+macro_rules! log_debug {
+    ($message:expr) => { if false { let _ = &$message; } };
+    ($message:expr, $context:expr) => { if false { let _ = &$message; let _ = ::std::convert::Into::<Option<String>>::into($context); } };
+}
+
+#[cfg(feature = "disabled")]
+#[macro_export]
+macro_rules! log_info {
+    ($message:expr) => { if false { let _ = &$message; } };
+    ($message:expr, $context:expr) => { if false { let _ = &$message; let _ = ::std::convert::Into::<Option<String>>::into($context); } };
+}
+
+#[cfg(feature = "disabled")]
+#[macro_export]
+macro_rules! log_warn {
+    ($message:expr) => { if false { let _ = &$message; } };
+    ($message:expr, $context:expr) => { if false { let _ = &$message; let _ = ::std::convert::Into::<Option<String>>::into($context); } };
+}
+
+#[cfg(feature = "disabled")]
+#[macro_export]
+macro_rules! log_error {
+    ($message:expr) => { if false { let _ = &$message; } };
+    ($message:expr, $context:expr) => { if false { let _ = &$message; let _ = ::std::convert::Into::<Option<String>>::into($context); } };
+}
+
+#[cfg(feature = "disabled")]
+#[macro_export]
+macro_rules! log_debug_every {
+    ($interval_ms:expr, $message:expr) => { if false { let _ = (&$interval_ms, &$message); } };
+    ($interval_ms:expr, $message:expr, $context:expr) => {
+        if false { let _ = (&$interval_ms, &$message); let _ = ::std::convert::Into::<Option<String>>::into($context); }
+    };
+}
+
+#[cfg(feature = "disabled")]
+#[macro_export]
+macro_rules! log_info_every {
+    ($interval_ms:expr, $message:expr) => { if false { let _ = (&$interval_ms, &$message); } };
+    ($interval_ms:expr, $message:expr, $context:expr) => {
+        if false { let _ = (&$interval_ms, &$message); let _ = ::std::convert::Into::<Option<String>>::into($context); }
+    };
+}
+
+#[cfg(feature = "disabled")]
+#[macro_export]
+macro_rules! log_warn_every {
+    ($interval_ms:expr, $message:expr) => { if false { let _ = (&$interval_ms, &$message); } };
+    ($interval_ms:expr, $message:expr, $context:expr) => {
+        if false { let _ = (&$interval_ms, &$message); let _ = ::std::convert::Into::<Option<String>>::into($context); }
+    };
+}
+
+#[cfg(feature = "disabled")]
+#[macro_export]
+macro_rules! log_error_every {
+    ($interval_ms:expr, $message:expr) => { if false { let _ = (&$interval_ms, &$message); } };
+    ($interval_ms:expr, $message:expr, $context:expr) => {
+        if false { let _ = (&$interval_ms, &$message); let _ = ::std::convert::Into::<Option<String>>::into($context); }
+    };
+}
+
+#[cfg(feature = "disabled")]
+#[macro_export]
+macro_rules! log_progress {
+    ($current:expr, $total:expr, $every:expr) => { if false { let _ = (&$current, &$total, &$every); } };
+    ($current:expr, $total:expr, $every:expr, $label:expr) => { if false { let _ = (&$current, &$total, &$every, &$label); } };
+}
+
 /// Ensures all pending log messages are processed before application exit
 /// 
 /// Call this function before your application terminates to ensure that
-/// asynchronous log messages in the channel are properly flushed.
+/// asynchronous log messages in the channel are properly flushed. Holding the
+/// `LoggerGuard` from `Logger::init_with_guard()` in `main` does this for you.
 /// 
 /// # Returns
 /// - `Result<(), String>`: Success or error message
 /// 
 /// # Example
 /// ```
-/// fn main() {
-///     // Initialize logger and application code...
-///     
-///     // Before exit, ensure logs are flushed
-///     liblogger::shutdown_logger().unwrap_or_else(|e| {
-///         eprintln!("Error during logger shutdown: {}", e);
-///     });
-/// }
+/// // Initialize logger and application code...
+///
+/// // Before exit, ensure logs are flushed
+/// liblogger::shutdown_logger().unwrap_or_else(|e| {
+///     eprintln!("Error during logger shutdown: {}", e);
+/// });
 /// ```
 pub fn shutdown_logger() -> Result<(), String> {
     Logger::shutdown()
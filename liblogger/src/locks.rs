@@ -0,0 +1,110 @@
+/*
+ * Hold tracking for `#[log_distributed_lock]`
+ *
+ * Each call of an instrumented function is one hold of its lock, from entry
+ * to return. Holds are counted per lock id in a process-global map, so a
+ * hold knows how many others of the same lock were in flight when it
+ * started (its wait queue) and how that compares with the previous hold,
+ * and the release measures how long the lock was held.
+ */
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+// Hold state per lock id
+static LOCKS: Mutex<Option<HashMap<String, LockState>>> = Mutex::new(None);
+
+#[derive(Debug, Default)]
+struct LockState {
+    in_flight: u32,
+    // Queue size seen by the most recent hold
+    last_queue: Option<u32>,
+    longest_hold: Duration,
+}
+
+fn with_state<R>(lock_id: &str, f: impl FnOnce(&mut LockState) -> R) -> R {
+    let mut locks = LOCKS.lock().unwrap_or_else(|e| e.into_inner());
+    f(locks.get_or_insert_with(HashMap::new).entry(lock_id.to_string()).or_default())
+}
+
+/// One hold of a lock, returned by `Logger::begin_lock_hold()`
+///
+/// Dropping it without calling `release()` releases it too.
+#[derive(Debug)]
+pub struct LockHold {
+    lock_id: String,
+    acquired_at: Instant,
+    queue_size: u32,
+    previous_queue_size: Option<u32>,
+    held_for: Option<Duration>,
+}
+
+impl LockHold {
+    /// Id of the lock being held
+    pub fn lock_id(&self) -> &str {
+        &self.lock_id
+    }
+
+    /// Other holds of the same lock in flight when this one started
+    pub fn queue_size(&self) -> u32 {
+        self.queue_size
+    }
+
+    /// Queue size seen by the hold before this one, if any
+    pub fn previous_queue_size(&self) -> Option<u32> {
+        self.previous_queue_size
+    }
+
+    /// "growing", "shrinking" or "steady", comparing the queue with the previous hold's
+    pub fn queue_trend(&self) -> &'static str {
+        match self.previous_queue_size {
+            Some(previous) if self.queue_size > previous => "growing",
+            Some(previous) if self.queue_size < previous => "shrinking",
+            _ => "steady",
+        }
+    }
+
+    /// Release the lock, returning how long it was held
+    ///
+    /// Later calls return the same duration.
+    pub fn release(&mut self) -> Duration {
+        if let Some(held_for) = self.held_for {
+            return held_for;
+        }
+        let held_for = self.acquired_at.elapsed();
+        self.held_for = Some(held_for);
+        with_state(&self.lock_id, |state| {
+            state.in_flight = state.in_flight.saturating_sub(1);
+            state.longest_hold = state.longest_hold.max(held_for);
+        });
+        held_for
+    }
+
+    /// Longest completed hold of this lock so far, including this one once released
+    pub fn longest_hold(&self) -> Duration {
+        with_state(&self.lock_id, |state| state.longest_hold)
+    }
+}
+
+impl Drop for LockHold {
+    fn drop(&mut self) {
+        self.release();
+    }
+}
+
+/// Start a hold of `lock_id`
+pub(crate) fn begin_hold(lock_id: &str) -> LockHold {
+    let (queue_size, previous_queue_size) = with_state(lock_id, |state| {
+        let queue_size = state.in_flight;
+        state.in_flight += 1;
+        (queue_size, state.last_queue.replace(queue_size))
+    });
+    LockHold {
+        lock_id: lock_id.to_string(),
+        acquired_at: Instant::now(),
+        queue_size,
+        previous_queue_size,
+        held_for: None,
+    }
+}
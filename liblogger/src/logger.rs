@@ -1,446 +1,1760 @@
-/*
- * Logger implementation module for Rusty Logger v2
- * 
- * This file implements the core Logger functionality which includes:
- * - Creation and initialization of the global logger instance
- * - Configuration of the logger from TOML files or programmatically
- * - Asynchronous logging through Tokio with message passing
- * - Automatic fallback to synchronous logging when needed
- * - Thread-safe logging with proper synchronization
- * 
- * The Logger uses a singleton pattern with lazy initialization via OnceCell
- * to ensure there's only one logger instance throughout the application.
- */
-
-use once_cell::sync::OnceCell;
-use std::sync::{Arc, Mutex, atomic::{AtomicU64, Ordering}};
-use std::path::Path;
-use chrono::Utc;
-use std::io::{self, Write};
-use tokio::sync::{mpsc::{self, Sender, Receiver}, oneshot};
-use tokio::runtime::Runtime;
-use tokio::time::{timeout, Duration as TokioDuration};
-
-use crate::config::{LogConfig, LogLevel};
-use crate::outputs::{LogOutput, create_log_output, create_async_log_output, AsyncLogOutputTrait};
-use crate::outputs::AsyncLogOutput;
-
-// Global logger instance
-static LOGGER_INSTANCE: OnceCell<Arc<Mutex<LoggerInner>>> = OnceCell::new();
-static RUNTIME: OnceCell<Runtime> = OnceCell::new();
-
-// Message structure for async logging channel
-struct LogMessage {
-    timestamp: String,
-    level: LogLevel,
-    message: String,
-    context: Option<String>,
-    file: String,
-    line: u32,
-    module: String,
-}
-
-// Command enum for controlling the background worker
-enum LogCommand {
-    Entry(LogMessage),
-    Shutdown(oneshot::Sender<()>),
-}
-
-struct LoggerInner {
-    initialized: bool,
-    config: Option<LogConfig>,
-    output: Option<Box<dyn LogOutput>>,
-    // Channel sender for async logging
-    async_sender: Option<Sender<LogCommand>>,
-    /// Flag to indicate if asynchronous logging is enabled
-    /// When false, all logging operations will be synchronous
-    async_enabled: bool,
-    /// Counter for messages dropped due to channel backpressure
-    dropped_logs: AtomicU64,
-    /// Counter to track when to report dropped logs
-    log_counter: AtomicU64,
-}
-
-impl LoggerInner {
-    /// Creates a new uninitialized logger inner structure
-    fn new() -> Self {
-        LoggerInner {
-            initialized: false,
-            config: None,
-            output: None,
-            async_sender: None,
-            async_enabled: false,
-            dropped_logs: AtomicU64::new(0),
-            log_counter: AtomicU64::new(0),
-        }
-    }
-
-    /// Initializes the logger with the provided configuration
-    fn init_with_config(&mut self, config: LogConfig) -> Result<(), String> {
-        println!("Setting up logger with log type: {:?}", config.log_type);
-        
-        // Create the appropriate log output based on configuration
-        let output = create_log_output(&config.log_type)?;
-        self.output = Some(output);
-        
-        // Set up async logging if enabled
-        if config.async_logging {
-            // Create Tokio runtime if not already initialized
-            let runtime = RUNTIME.get_or_init(|| {
-                Runtime::new().expect("Failed to create Tokio runtime")
-            });
-            
-            // Create channel for async logging with LogCommand instead of LogMessage
-            let (tx, rx) = mpsc::channel::<LogCommand>(100);
-            self.async_sender = Some(tx);
-            
-            // Create the async output
-            let async_output = create_async_log_output(&config.log_type)?;
-            
-            // Spawn a task to process log messages
-            runtime.spawn(async move {
-                process_log_commands(rx, async_output).await
-                    .unwrap_or_else(|e| eprintln!("Async logging failed: {}", e));
-            });
-        }
-        
-        // Store the configuration
-        self.config = Some(config.clone());
-        self.async_enabled = config.async_logging;
-        self.initialized = true;
-        
-        Ok(())
-    }
-
-    /// Log a message with the configured output
-    fn log(&mut self, level: LogLevel, message: &str, context: Option<&str>, file: &str, line: u32, module: &str) {
-        // Check if we're initialized with a configuration
-        if let Some(ref config) = self.config {
-            // Skip logging if level is below threshold
-            if (level.clone() as usize) < (config.threshold.clone() as usize) {
-                return;
-            }
-            
-            // Format timestamp
-            let timestamp = Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string();
-            
-            // Increment log counter
-            let count = self.log_counter.fetch_add(1, Ordering::Relaxed) + 1;
-            
-            // Check if we need to report dropped logs (every 100 logs)
-            if count % 100 == 0 {
-                self.report_dropped_logs();
-            }
-            
-            // Try async logging first if enabled
-            if self.async_enabled {
-                if let Some(ref sender) = self.async_sender {
-                    // Create a log message for the async channel
-                    let log_message = LogMessage {
-                        timestamp: timestamp.clone(),
-                        level: level.clone(),
-                        message: message.to_string(),
-                        context: context.map(|s| s.to_string()),
-                        file: file.to_string(),
-                        line,
-                        module: module.to_string(),
-                    };
-                    
-                    // Send to the async channel as a LogCommand::Entry, fallback to sync if channel is full
-                    if let Err(_) = sender.try_send(LogCommand::Entry(log_message)) {
-                        // Increment dropped logs counter before falling back to sync
-                        self.dropped_logs.fetch_add(1, Ordering::Relaxed);
-                        
-                        // Channel full or closed, fallback to sync logging
-                        self.log_sync(&timestamp, &level, message, context, file, line, module);
-                    }
-                } else {
-                    // Async sender not initialized, fallback to sync logging
-                    self.log_sync(&timestamp, &level, message, context, file, line, module);
-                }
-            } else {
-                // Async logging disabled, use sync logging
-                self.log_sync(&timestamp, &level, message, context, file, line, module);
-            }
-        } else {
-            // Fallback to stderr for uninitialized logger
-            let timestamp = Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string();
-            self.log_sync(&timestamp, &level, message, context, file, line, module);
-        }
-    }
-    
-    /// Report dropped logs if any
-    fn report_dropped_logs(&mut self) {
-        let dropped = self.dropped_logs.load(Ordering::Relaxed);
-        if dropped > 0 {
-            // Reset the counter first to avoid multiple reports of the same drops
-            let actual_dropped = self.dropped_logs.swap(0, Ordering::Relaxed);
-            
-            // Log a warning about dropped messages
-            let timestamp = Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string();
-            let warning_message = format!("WARNING: {} log messages were dropped due to backpressure", actual_dropped);
-            self.log_sync(
-                &timestamp, 
-                &LogLevel::Warn, 
-                &warning_message, 
-                None,
-                "logger.rs",
-                0,
-                "liblogger"
-            );
-        }
-    }
-
-    /// Synchronous logging fallback
-    fn log_sync(&mut self, timestamp: &str, level: &LogLevel, message: &str, 
-                context: Option<&str>, file: &str, line: u32, module: &str) {
-        if let Some(ref mut output) = self.output {
-            // Format the log message
-            let formatted_message = format_log_message(timestamp, level, message, context, file, line, module);
-            
-            // Write the log
-            if let Err(e) = output.write_log(&formatted_message) {
-                eprintln!("Failed to write log: {}", e);
-            }
-        } else {
-            // No output configured, write to stderr
-            let level_str = level.as_str();
-            let log_line = match context {
-                Some(ctx) => format!("{} [{}] [{}:{}] [{}] {} | {}\n", 
-                    timestamp, level_str, file, line, module, message, ctx),
-                None => format!("{} [{}] [{}:{}] [{}] {}\n",
-                    timestamp, level_str, file, line, module, message),
-            };
-            
-            let _ = io::stderr().write_all(log_line.as_bytes());
-        }
-    }
-}
-
-// Format a log message for output
-fn format_log_message(timestamp: &str, level: &LogLevel, message: &str, 
-                    context: Option<&str>, file: &str, line: u32, module: &str) -> String {
-    let level_str = level.as_str();
-    match context {
-        Some(ctx) => format!("{} [{}] [{}:{}] [{}] {} | {}", 
-            timestamp, level_str, file, line, module, message, ctx),
-        None => format!("{} [{}] [{}:{}] [{}] {}",
-            timestamp, level_str, file, line, module, message),
-    }
-}
-
-// Async function to process log commands from the channel
-async fn process_log_commands(mut receiver: Receiver<LogCommand>, mut output: AsyncLogOutput) -> Result<(), String> {
-    while let Some(cmd) = receiver.recv().await {
-        match cmd {
-            LogCommand::Entry(msg) => {
-                // Format the log message
-                let formatted_message = format_log_message(
-                    &msg.timestamp, &msg.level, &msg.message, 
-                    msg.context.as_deref(), &msg.file, msg.line, &msg.module);
-                
-                // Write using the async output
-                if let Err(e) = output.write_log_async(&formatted_message).await {
-                    eprintln!("Async logging error: {}", e);
-                }
-            },
-            LogCommand::Shutdown(completion_sender) => {
-                // Final log message before shutdown
-                let timestamp = Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string();
-                let message = "Logger shutdown initiated, ensuring all logs are flushed";
-                let formatted_message = format_log_message(
-                    &timestamp, &LogLevel::Info, message, None, "logger.rs", 0, "liblogger");
-                
-                // Final flush before shutdown
-                if let Err(e) = output.write_log_async(&formatted_message).await {
-                    eprintln!("Error writing final log message: {}", e);
-                }
-                
-                // Notify that shutdown is complete
-                let _ = completion_sender.send(());
-                
-                // Break the loop to end the task
-                break;
-            }
-        }
-    }
-    
-    Ok(())
-}
-
-pub struct Logger;
-
-impl Logger {
-    /// Initialize the logger with default configuration file "app_config.toml"
-    pub fn init() {
-        let _ = Self::init_with_config_file("app_config.toml");
-    }
-
-    /// Initialize the logger with a specific configuration file
-    pub fn init_with_config_file(config_path: &str) -> Result<(), String> {
-        let config = LogConfig::from_file(config_path)?;
-        Self::init_with_config(config)
-    }
-
-    /// Initialize the logger with a LogConfig struct
-    pub fn init_with_config(config: LogConfig) -> Result<(), String> {
-        println!("Setting up logger with log type: {:?}", config.log_type);
-        
-        let logger = LOGGER_INSTANCE.get_or_init(|| Arc::new(Mutex::new(LoggerInner::new())));
-        let mut logger_guard = match logger.lock() {
-            Ok(guard) => guard,
-            Err(poisoned) => {
-                println!("Logger mutex was poisoned, recovering...");
-                poisoned.into_inner()
-            }
-        };
-        
-        match logger_guard.init_with_config(config) {
-            Ok(_) => {
-                println!("Logger initialized successfully");
-                Ok(())
-            },
-            Err(e) => {
-                println!("Failed to initialize logger: {}", e);
-                Err(e)
-            }
-        }
-    }
-
-    /// Log a debug message
-    pub fn debug(message: &str, context: Option<String>, file: &'static str, line: u32, module: &'static str) {
-        Self::log_with_metadata(LogLevel::Debug, message, context, file, line, module)
-    }
-
-    /// Log an info message
-    pub fn info(message: &str, context: Option<String>, file: &'static str, line: u32, module: &'static str) {
-        Self::log_with_metadata(LogLevel::Info, message, context, file, line, module)
-    }
-
-    /// Log a warning message
-    pub fn warn(message: &str, context: Option<String>, file: &'static str, line: u32, module: &'static str) {
-        Self::log_with_metadata(LogLevel::Warn, message, context, file, line, module)
-    }
-
-    /// Log an error message
-    pub fn error(message: &str, context: Option<String>, file: &'static str, line: u32, module: &'static str) {
-        Self::log_with_metadata(LogLevel::Error, message, context, file, line, module)
-    }
-
-    fn log_with_metadata(level: LogLevel, message: &str, context: Option<String>, file: &str, line: u32, module: &str) {
-        // Extract just the filename from the path
-        let file_name = Path::new(file)
-            .file_name()
-            .and_then(|n| n.to_str())
-            .unwrap_or(file);
-
-        let logger = LOGGER_INSTANCE.get_or_init(|| Arc::new(Mutex::new(LoggerInner::new())));
-        
-        // Use a block to limit the scope of the mutex lock
-        {
-            if let Ok(mut logger) = logger.lock() {
-                logger.log(level, message, context.as_deref(), file_name, line, module);
-            } else {
-                // If the mutex is poisoned, log to stderr
-                let timestamp = Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string();
-                let level_str = level.as_str();
-                let log_line = format!("{} [{}] [{}:{}] [{}] {} | MUTEX POISONED\n",
-                    timestamp, level_str, file_name, line, module, message);
-                let _ = io::stderr().write_all(log_line.as_bytes());
-            }
-        }
-    }
-
-    /// Shutdown the logger gracefully, ensuring all pending logs are written
-    pub fn shutdown() -> Result<(), String> {
-        // Try to get the runtime
-        if let Some(rt) = RUNTIME.get() {
-            // Check if we have an async logger initialized
-            if let Some(logger) = LOGGER_INSTANCE.get() {
-                if let Ok(mut logger_guard) = logger.lock() {
-                    // Report any dropped logs before shutdown
-                    logger_guard.report_dropped_logs();
-                    
-                    if logger_guard.async_enabled {
-                        if let Some(sender) = &logger_guard.async_sender {
-                            // Create a oneshot channel for completion notification
-                            let (completion_tx, completion_rx) = oneshot::channel();
-                            
-                            // Clone sender outside of task to avoid reference issues
-                            let sender_clone = sender.clone();
-                            
-                            // Send shutdown command
-                            // Use block to release the mutex guard before the blocking operation
-                            drop(logger_guard);
-                            
-                            // Spawn a Tokio task to send the shutdown command
-                            let handle = rt.spawn(async move {
-                                if let Err(e) = sender_clone.send(LogCommand::Shutdown(completion_tx)).await {
-                                    eprintln!("Failed to send shutdown command: {}", e);
-                                    return false;
-                                }
-                                
-                                // Wait for completion with timeout
-                                match timeout(TokioDuration::from_secs(5), completion_rx).await {
-                                    Ok(Ok(())) => {
-                                        println!("Logger shutdown completed successfully");
-                                        true
-                                    },
-                                    Ok(Err(_)) => {
-                                        eprintln!("Shutdown completion channel was closed");
-                                        false
-                                    },
-                                    Err(_) => {
-                                        eprintln!("Logger shutdown timed out after 5 seconds");
-                                        false
-                                    }
-                                }
-                            });
-                            
-                            // Wait for the shutdown to complete
-                            match rt.block_on(handle) {
-                                Ok(true) => return Ok(()),
-                                Ok(false) => return Err("Logger shutdown failed".to_string()),
-                                Err(e) => return Err(format!("Logger shutdown task panicked: {}", e)),
-                            }
-                        }
-                    }
-                }
-            }
-            
-            // If we can't do an async shutdown, still try to flush any file outputs
-            if let Some(logger) = LOGGER_INSTANCE.get() {
-                if let Ok(mut guard) = logger.lock() {
-                    if let Some(ref mut output) = guard.output {
-                        // For non-async loggers, write an empty message which will trigger a flush
-                        let _ = output.write_log("");
-                    }
-                }
-            }
-            
-            println!("Logger shutdown completed");
-            Ok(())
-        } else {
-            // No runtime means no async logging was initialized
-            println!("No async logger to shutdown");
-            Ok(())
-        }
-    }
-    
-    /// Get the number of dropped log messages due to backpressure
-    pub fn get_dropped_log_count() -> u64 {
-        if let Some(logger) = LOGGER_INSTANCE.get() {
-            if let Ok(logger_guard) = logger.lock() {
-                return logger_guard.dropped_logs.load(Ordering::Relaxed);
-            }
-        }
-        0
-    }
-}
-
-// Ensure the logger is properly shutdown when the program exits
-impl Drop for Logger {
-    fn drop(&mut self) {
-        let _ = Self::shutdown();
-    }
-}
+/*
+ * Logger implementation module for Rusty Logger v2
+ * 
+ * This file implements the core Logger functionality which includes:
+ * - Creation and initialization of the global logger instance
+ * - Configuration of the logger from TOML files or programmatically
+ * - Asynchronous logging through Tokio with message passing
+ * - Automatic fallback to synchronous logging when needed
+ * - Thread-safe logging with proper synchronization
+ * - Per-thread output overrides for routing a thread's lines elsewhere
+ * - Deferring lines logged while the logger lock is held, so outputs and
+ *   Drop impls that log can't deadlock
+ * 
+ * The Logger uses a singleton pattern with lazy initialization via OnceCell
+ * to ensure there's only one logger instance throughout the application.
+ */
+
+use once_cell::sync::OnceCell;
+use std::any::Any;
+use std::cell::{Cell, RefCell};
+use std::backtrace::Backtrace;
+use std::sync::{Arc, Mutex, MutexGuard, Once, atomic::{AtomicBool, AtomicU8, AtomicU64, Ordering}};
+use std::path::Path;
+use std::borrow::Cow;
+use std::ops::{Deref, DerefMut};
+use std::collections::{BTreeMap, HashMap};
+use std::time::{Duration, Instant};
+use chrono::Utc;
+use std::io::{self, Write};
+use tokio::sync::{mpsc::{self, error::TrySendError, Sender, Receiver}, oneshot};
+use tokio::runtime::Runtime;
+use tokio::time::{timeout, Duration as TokioDuration};
+
+use crate::backtrace;
+use crate::escalation;
+use crate::baseline::{self, DurationBaseline};
+use crate::breakers::{self, BreakerState};
+use crate::cef::CefHeader;
+use crate::cloud::{self, EmfNamespace};
+use crate::health::{self, HealthReport};
+use crate::locks::{self, LockHold};
+use crate::recent;
+use crate::workflow::{self, WorkflowStep};
+use crate::config::{ConsoleStream, LogConfig, LogFormat, LogLevel, OverflowPolicy, PoisonPolicy};
+use crate::context::{self, CapturedContext, ContextFormatter, ContextScope, FieldGuard, LineContext, QuietFuture, QuietGuard, TransactionScope, WithContext};
+use crate::providers::{
+    self, CacheMetricsProvider, CacheStats, DataQualityProvider, DataQualityReport, DiskInfo,
+    DiskMetricsProvider, FeatureFlagProvider, QueueMetricsProvider, QueueStats,
+};
+use crate::outputs::{LogOutput, create_log_outputs, AsyncLogOutputTrait};
+use crate::outputs::{AsyncLogOutput, LogPayload, PayloadContext, RenamedPayload, RESERVED_JSON_KEYS, SCHEMA_VERSION};
+use crate::template::TextTemplate;
+
+// Global logger instance
+static LOGGER_INSTANCE: OnceCell<Arc<Mutex<LoggerInner>>> = OnceCell::new();
+static RUNTIME: OnceCell<Runtime> = OnceCell::new();
+static PANIC_HOOK: Once = Once::new();
+static DEFAULT_CONFIG_NOTICE: Once = Once::new();
+static SYNC_FALLBACK_NOTICE: Once = Once::new();
+static HTTP_EXTRA_FIELDS_NOTICE: Once = Once::new();
+#[cfg(feature = "testing")]
+static TEST_INIT: Once = Once::new();
+// Active threshold, kept outside the mutex so filtered logs never lock
+static THRESHOLD: AtomicU8 = AtomicU8::new(LogLevel::Debug.severity());
+// `include_location` of the active configuration, read before a line is queued
+static INCLUDE_LOCATION: AtomicBool = AtomicBool::new(true);
+// Whether the active layout writes scoped and global fields as top-level keys
+static FLAT_FIELDS: AtomicBool = AtomicBool::new(false);
+// Last sequence number handed out when `include_sequence` is on
+static SEQUENCE: AtomicU64 = AtomicU64::new(0);
+// Lines discarded because the async channel was full under `drop_newest`
+static DROPPED: AtomicU64 = AtomicU64::new(0);
+// The logger's own status messages follow `console_stream`, so stderr mode leaves stdout alone
+pub(crate) static STATUS_TO_STDERR: AtomicBool = AtomicBool::new(false);
+// `on_poison` of the active configuration, as the PoisonPolicy discriminant
+static POISON_POLICY: AtomicU8 = AtomicU8::new(PoisonPolicy::Recover as u8);
+// Times a poisoned logger lock was recovered
+static POISON_RECOVERIES: AtomicU64 = AtomicU64::new(0);
+static POISON_NOTICE: Once = Once::new();
+
+thread_local! {
+    // Output set with `Logger::set_thread_output`, dropped (and flushed) with the thread
+    static THREAD_OUTPUT: RefCell<Option<ThreadOutput>> = const { RefCell::new(None) };
+}
+
+// A thread's override output, flushed when replaced, cleared or dropped at thread exit
+struct ThreadOutput(Box<dyn LogOutput>);
+
+impl Drop for ThreadOutput {
+    fn drop(&mut self) {
+        if let Err(e) = self.0.flush() {
+            eprintln!("Failed to flush thread output: {}", e);
+        }
+    }
+}
+
+/// Write a line to the calling thread's override output
+///
+/// Returns false, without formatting the line, when the thread has none.
+fn write_thread_output(level: &LogLevel, line: impl FnOnce() -> String) -> bool {
+    THREAD_OUTPUT.try_with(|cell| {
+        match cell.borrow_mut().as_mut() {
+            Some(ThreadOutput(output)) => {
+                if let Err(e) = output.write_entry(level, &line()) {
+                    eprintln!("Failed to write log: {}", e);
+                }
+                true
+            }
+            None => false,
+        }
+    }).unwrap_or(false)
+}
+
+thread_local! {
+    // Whether this thread holds the logger lock, and the lines it logged meanwhile
+    static HOLDING_LOCK: Cell<bool> = const { Cell::new(false) };
+    static DEFERRED: RefCell<Vec<DeferredLine>> = const { RefCell::new(Vec::new()) };
+    // Set while the deferred lines are being written
+    static REPLAYING: Cell<bool> = const { Cell::new(false) };
+}
+
+// A line logged on a thread that already holds the logger lock
+enum DeferredLine {
+    Entry {
+        level: LogLevel,
+        messages: Vec<String>,
+        context: Option<LineContext>,
+        fields: BTreeMap<String, String>,
+        file: String,
+        line: u32,
+        module: String,
+    },
+    Raw(LogLevel, String),
+}
+
+impl DeferredLine {
+    fn write(self) {
+        match self {
+            DeferredLine::Entry { level, messages, context, fields, file, line, module } => {
+                let messages: Vec<&str> = messages.iter().map(String::as_str).collect();
+                Logger::write_lines(&level, &messages, context.as_ref(), &fields, &file, line, &module);
+            }
+            DeferredLine::Raw(level, line) => Logger::write_raw(level, &line),
+        }
+    }
+    
+    // Last resort for lines logged while deferred lines are being written
+    fn write_stderr(&self) {
+        match self {
+            DeferredLine::Entry { level, messages, context, file, line, module, .. } => {
+                let timestamp = Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string();
+                for message in messages {
+                    let _ = match context {
+                        Some(ctx) => writeln!(io::stderr(), "{} [{}] {}[{}] {} | {}",
+                            timestamp, level.as_str(), Location(file, *line), module, message, ctx.text),
+                        None => writeln!(io::stderr(), "{} [{}] {}[{}] {}",
+                            timestamp, level.as_str(), Location(file, *line), module, message),
+                    };
+                }
+            }
+            DeferredLine::Raw(_, line) => {
+                let _ = writeln!(io::stderr(), "{}", line);
+            }
+        }
+    }
+}
+
+/// Queue a line logged on a thread that already holds the logger lock
+///
+/// Taking the lock again would deadlock, so the line is written once the
+/// lock is released. Returns false, without building the line, when the
+/// thread doesn't hold the lock.
+fn defer_if_locked(line: impl FnOnce() -> DeferredLine) -> bool {
+    if !HOLDING_LOCK.try_with(Cell::get).unwrap_or(false) {
+        return false;
+    }
+    let line = line();
+    // An output that logs from every write would otherwise never finish
+    if REPLAYING.try_with(Cell::get).unwrap_or(false) {
+        line.write_stderr();
+    } else {
+        let _ = DEFERRED.try_with(|deferred| deferred.borrow_mut().push(line));
+    }
+    true
+}
+
+// The logger lock, marking the thread as holding it
+//
+// User code may run while it is held: an output's `write_log` or `flush`,
+// or the `Drop` of an output being replaced. Lines those log are deferred
+// and written when the lock is released.
+struct LoggerLock<'a>(Option<MutexGuard<'a, LoggerInner>>);
+
+// Take the logger lock, handling poisoning as `on_poison` says
+fn lock(logger: &Mutex<LoggerInner>) -> LoggerLock<'_> {
+    let (guard, poisoned) = match logger.lock() {
+        Ok(guard) => (guard, false),
+        Err(poisoned) => (poisoned.into_inner(), true),
+    };
+    let policy = match POISON_POLICY.load(Ordering::Relaxed) {
+        1 => PoisonPolicy::Warn,
+        2 => PoisonPolicy::Panic,
+        _ => PoisonPolicy::Recover,
+    };
+    // Lines written while unwinding (such as the panic itself) leave the
+    // poison for the next caller to handle
+    let handle_poison = poisoned && !std::thread::panicking();
+    if handle_poison && policy == PoisonPolicy::Panic {
+        drop(guard);
+        panic!("The logger lock was poisoned by a panic while logging (on_poison = \"panic\")");
+    }
+    
+    let _ = HOLDING_LOCK.try_with(|holding| holding.set(true));
+    let mut lock = LoggerLock(Some(guard));
+    if handle_poison {
+        logger.clear_poison();
+        POISON_RECOVERIES.fetch_add(1, Ordering::Relaxed);
+        // Written ahead of the line that found the poison
+        let mut warn = policy == PoisonPolicy::Warn;
+        POISON_NOTICE.call_once(|| warn = true);
+        if warn && Logger::is_enabled(&LogLevel::Warn) {
+            lock.log(LogLevel::Warn,
+                "Recovered the logger lock, poisoned by a panic while logging; lines from that moment may be missing",
+                None, &BTreeMap::new(), "logger.rs", 0, "liblogger");
+        }
+    }
+    lock
+}
+
+impl Deref for LoggerLock<'_> {
+    type Target = LoggerInner;
+    
+    fn deref(&self) -> &LoggerInner {
+        self.0.as_ref().expect("logger lock released")
+    }
+}
+
+impl DerefMut for LoggerLock<'_> {
+    fn deref_mut(&mut self) -> &mut LoggerInner {
+        self.0.as_mut().expect("logger lock released")
+    }
+}
+
+impl Drop for LoggerLock<'_> {
+    fn drop(&mut self) {
+        drop(self.0.take());
+        let _ = HOLDING_LOCK.try_with(|holding| holding.set(false));
+        // Lines deferred while writing deferred lines were already sent to stderr
+        if REPLAYING.try_with(Cell::get).unwrap_or(true) {
+            return;
+        }
+        let deferred = DEFERRED.try_with(|deferred| std::mem::take(&mut *deferred.borrow_mut())).unwrap_or_default();
+        if deferred.is_empty() {
+            return;
+        }
+        REPLAYING.with(|replaying| replaying.set(true));
+        for line in deferred {
+            line.write();
+        }
+        REPLAYING.with(|replaying| replaying.set(false));
+    }
+}
+
+// Message structure for async logging channel
+struct LogMessage {
+    timestamp: String,
+    seq: Option<u64>,
+    level: LogLevel,
+    message: String,
+    context: Option<LineContext>,
+    fields: BTreeMap<String, String>,
+    file: String,
+    line: u32,
+    module: String,
+}
+
+// Command enum for controlling the background worker
+enum LogCommand {
+    Entry(LogMessage),
+    // Pre-formatted line written as-is
+    Raw(LogLevel, String),
+    Shutdown(oneshot::Sender<()>),
+}
+
+struct LoggerInner {
+    initialized: bool,
+    config: Option<LogConfig>,
+    output: Option<Box<dyn LogOutput>>,
+    /// How lines are formatted, from `format` and `text_template`
+    layout: LineLayout,
+    // Channel sender for async logging
+    async_sender: Option<Sender<LogCommand>>,
+    /// Flag to indicate if asynchronous logging is enabled
+    /// When false, all logging operations will be synchronous
+    async_enabled: bool,
+    /// Counter for messages dropped due to channel backpressure
+    dropped_logs: AtomicU64,
+    /// Counter to track when to report dropped logs
+    log_counter: AtomicU64,
+}
+
+impl LoggerInner {
+    /// Creates a new uninitialized logger inner structure
+    fn new() -> Self {
+        LoggerInner {
+            initialized: false,
+            config: None,
+            output: None,
+            layout: LineLayout::default(),
+            async_sender: None,
+            async_enabled: false,
+            dropped_logs: AtomicU64::new(0),
+            log_counter: AtomicU64::new(0),
+        }
+    }
+
+    /// Initializes the logger with the provided configuration
+    fn init_with_config(&mut self, config: LogConfig) -> Result<(), String> {
+        // Reject misconfigurations before anything is replaced
+        config.validate()?;
+        
+        // A second init must not spawn another background task or reopen outputs
+        if self.initialized {
+            return if self.config.as_ref() == Some(&config) {
+                Ok(())
+            } else {
+                Err("Logger is already initialized with a different configuration; use Logger::reconfigure to change it".to_string())
+            };
+        }
+        
+        status!("Setting up logger with log type: {:?}", config.log_type);
+        
+        // Parse the line layout once, before anything is replaced
+        let layout = LineLayout::from_config(&config)?;
+        
+        // Without a runtime (e.g. in a restricted sandbox) async logging degrades to sync
+        let runtime = if config.async_logging {
+            Some(RUNTIME.get_or_try_init(create_runtime))
+        } else {
+            None
+        };
+        
+        // Create the appropriate log outputs based on configuration
+        let (output, async_output) = create_log_outputs(&config, matches!(runtime, Some(Ok(_))))?;
+        self.output = Some(output);
+        self.layout = layout.clone();
+        
+        let shadowed: Vec<&str> = config.http_extra_fields.keys()
+            .map(String::as_str)
+            .filter(|key| RESERVED_JSON_KEYS.contains(key))
+            .collect();
+        if !shadowed.is_empty() {
+            HTTP_EXTRA_FIELDS_NOTICE.call_once(|| {
+                let timestamp = Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string();
+                let warning = format!("http_extra_fields {} ignored: built-in record fields take precedence", shadowed.join(", "));
+                self.log_sync(&timestamp, None, &LogLevel::Warn, &warning, None, &BTreeMap::new(),
+                    "logger.rs", 0, "liblogger");
+            });
+        }
+        
+        let runtime = match runtime {
+            Some(Ok(runtime)) => Some(runtime),
+            Some(Err(e)) => {
+                SYNC_FALLBACK_NOTICE.call_once(|| {
+                    let timestamp = Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string();
+                    let warning = format!("Could not create the Tokio runtime ({}); logging synchronously", e);
+                    self.log_sync(&timestamp, None, &LogLevel::Warn, &warning, None, &BTreeMap::new(),
+                        "logger.rs", 0, "liblogger");
+                });
+                None
+            }
+            None => None,
+        };
+        
+        // Set up async logging if enabled
+        if let (Some(runtime), Some(async_output)) = (runtime, async_output) {
+            // Create channel for async logging with LogCommand instead of LogMessage
+            let (tx, rx) = mpsc::channel::<LogCommand>(config.async_channel_capacity);
+            self.async_sender = Some(tx);
+            
+            // Spawn a task to process log messages
+            let flush_interval = TokioDuration::from_millis(config.flush_interval_ms);
+            let flush_on_error = config.flush_on_error;
+            runtime.spawn(async move {
+                process_log_commands(rx, async_output, layout, flush_interval, flush_on_error).await
+                    .unwrap_or_else(|e| eprintln!("Async logging failed: {}", e));
+            });
+        }
+        
+        // Store the configuration
+        THRESHOLD.store(config.threshold.severity(), Ordering::Relaxed);
+        INCLUDE_LOCATION.store(config.include_location, Ordering::Relaxed);
+        FLAT_FIELDS.store(self.layout.has_flat_fields(), Ordering::Relaxed);
+        POISON_POLICY.store(config.on_poison as u8, Ordering::Relaxed);
+        escalation::configure(config.auto_debug_on_error_rate, config.escalation_secs);
+        recent::set_capacity(config.ring_buffer_size);
+        self.config = Some(config.clone());
+        self.async_enabled = runtime.is_some();
+        self.initialized = true;
+        
+        Ok(())
+    }
+
+    /// Installs a caller-supplied synchronous output
+    fn init_with_output(&mut self, output: Box<dyn LogOutput>, threshold: LogLevel) {
+        let config = LogConfig::builder()
+            .threshold(threshold)
+            .async_logging(false)
+            .build();
+        
+        // Writes go straight to the custom output; drop any background channel
+        self.output = Some(output);
+        self.layout = LineLayout::default();
+        self.async_sender = None;
+        self.async_enabled = false;
+        THRESHOLD.store(config.threshold.severity(), Ordering::Relaxed);
+        INCLUDE_LOCATION.store(config.include_location, Ordering::Relaxed);
+        FLAT_FIELDS.store(false, Ordering::Relaxed);
+        POISON_POLICY.store(config.on_poison as u8, Ordering::Relaxed);
+        escalation::configure(None, config.escalation_secs);
+        self.config = Some(config);
+        self.initialized = true;
+    }
+
+    /// Log a message with the configured output
+    #[allow(clippy::too_many_arguments)]
+    fn log(&mut self, level: LogLevel, message: &str, context: Option<&LineContext>, fields: &BTreeMap<String, String>,
+           file: &str, line: u32, module: &str) {
+        // Check if we're initialized with a configuration
+        if let Some(ref config) = self.config {
+            // Cap oversized messages before they reach the channel or output
+            let message = match config.max_message_bytes {
+                Some(max_bytes) => truncate_message(message, max_bytes),
+                None => Cow::Borrowed(message),
+            };
+            // Escape line breaks and control characters so user data can't forge log lines
+            let (message, context) = if config.sanitize_messages {
+                (sanitize(message.as_ref()), context.map(sanitize_context))
+            } else {
+                (message, context.map(Cow::Borrowed))
+            };
+            let message = message.as_ref();
+            let context = context.as_deref();
+            // Only the displayed name is shortened; level checks have already seen the full path
+            let module = shorten_module(module, config.module_truncate_segments);
+            
+            // Format timestamp
+            let timestamp = Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string();
+            // Numbered under the logger lock, so the order matches the channel order
+            let seq = config.include_sequence.then(|| SEQUENCE.fetch_add(1, Ordering::Relaxed) + 1);
+            let drop_newest = config.overflow_policy == OverflowPolicy::DropNewest;
+            
+            // A thread with its own output bypasses the channel and the global output
+            if write_thread_output(&level, || format_log_message(
+                &self.layout, &timestamp, seq, &level, message, context, fields, file, line, module)) {
+                return;
+            }
+            
+            // Increment log counter
+            let count = self.log_counter.fetch_add(1, Ordering::Relaxed) + 1;
+            
+            // Check if we need to report dropped logs (every 100 logs)
+            if count.is_multiple_of(100) {
+                self.report_dropped_logs();
+            }
+            
+            // Try async logging first if enabled
+            if self.async_enabled {
+                if let Some(ref sender) = self.async_sender {
+                    // Create a log message for the async channel
+                    let log_message = LogMessage {
+                        timestamp: timestamp.clone(),
+                        seq,
+                        level: level.clone(),
+                        message: message.to_string(),
+                        context: context.cloned(),
+                        fields: fields.clone(),
+                        file: file.to_string(),
+                        line,
+                        module: module.to_string(),
+                    };
+                    
+                    // Send to the async channel as a LogCommand::Entry; when it is full,
+                    // drop the line or fall back to sync as `overflow_policy` says
+                    match sender.try_send(LogCommand::Entry(log_message)) {
+                        Ok(()) => {},
+                        Err(TrySendError::Full(_)) if drop_newest => {
+                            self.dropped_logs.fetch_add(1, Ordering::Relaxed);
+                            DROPPED.fetch_add(1, Ordering::Relaxed);
+                        },
+                        Err(_) => {
+                            // Increment dropped logs counter before falling back to sync
+                            self.dropped_logs.fetch_add(1, Ordering::Relaxed);
+                            
+                            // Channel full or closed, fallback to sync logging
+                            self.log_sync(&timestamp, seq, &level, message, context, fields, file, line, module);
+                        },
+                    }
+                } else {
+                    // Async sender not initialized, fallback to sync logging
+                    self.log_sync(&timestamp, seq, &level, message, context, fields, file, line, module);
+                }
+            } else {
+                // Async logging disabled, use sync logging
+                self.log_sync(&timestamp, seq, &level, message, context, fields, file, line, module);
+            }
+        } else {
+            // Fallback to stderr for uninitialized logger
+            let timestamp = Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string();
+            if write_thread_output(&level, || format_log_message(
+                &self.layout, &timestamp, None, &level, message, context, fields, file, line, module)) {
+                return;
+            }
+            self.log_sync(&timestamp, None, &level, message, context, fields, file, line, module);
+        }
+    }
+    
+    /// Write an already formatted line through the configured output
+    fn log_raw(&mut self, level: LogLevel, line: &str) {
+        if write_thread_output(&level, || line.to_string()) {
+            return;
+        }
+        if self.async_enabled {
+            if let Some(ref sender) = self.async_sender {
+                let drop_newest = self.config.as_ref()
+                    .is_some_and(|config| config.overflow_policy == OverflowPolicy::DropNewest);
+                match sender.try_send(LogCommand::Raw(level.clone(), line.to_string())) {
+                    Ok(()) => {},
+                    Err(TrySendError::Full(_)) if drop_newest => {
+                        self.dropped_logs.fetch_add(1, Ordering::Relaxed);
+                        DROPPED.fetch_add(1, Ordering::Relaxed);
+                    },
+                    Err(_) => {
+                        // Channel full or closed, fallback to sync logging
+                        self.dropped_logs.fetch_add(1, Ordering::Relaxed);
+                        self.log_raw_sync(&level, line);
+                    },
+                }
+                return;
+            }
+        }
+        self.log_raw_sync(&level, line);
+    }
+
+    /// Synchronous raw write, to stderr when no output is configured
+    fn log_raw_sync(&mut self, level: &LogLevel, line: &str) {
+        if let Some(ref mut output) = self.output {
+            if let Err(e) = output.write_entry(level, line) {
+                eprintln!("Failed to write log: {}", e);
+            }
+        } else {
+            let _ = writeln!(io::stderr(), "{}", line);
+        }
+    }
+    
+    /// Report dropped logs if any
+    fn report_dropped_logs(&mut self) {
+        let dropped = self.dropped_logs.load(Ordering::Relaxed);
+        if dropped > 0 {
+            // Reset the counter first to avoid multiple reports of the same drops
+            let actual_dropped = self.dropped_logs.swap(0, Ordering::Relaxed);
+            
+            // Log a warning about dropped messages
+            let timestamp = Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string();
+            let warning_message = format!("WARNING: {} log messages were dropped due to backpressure", actual_dropped);
+            self.log_sync(
+                &timestamp, 
+                None,
+                &LogLevel::Warn, 
+                &warning_message, 
+                None,
+                &BTreeMap::new(),
+                "logger.rs",
+                0,
+                "liblogger"
+            );
+        }
+    }
+
+    /// Synchronous logging fallback
+    #[allow(clippy::too_many_arguments)]
+    fn log_sync(&mut self, timestamp: &str, seq: Option<u64>, level: &LogLevel, message: &str, 
+                context: Option<&LineContext>, fields: &BTreeMap<String, String>, file: &str, line: u32, module: &str) {
+        if let Some(ref mut output) = self.output {
+            // Format the log message
+            let formatted_message = format_log_message(
+                &self.layout, timestamp, seq, level, message, context, fields, file, line, module);
+            
+            // Write the log
+            if let Err(e) = output.write_entry(level, &formatted_message) {
+                eprintln!("Failed to write log: {}", e);
+            }
+        } else {
+            // No output configured, write to stderr
+            let level_str = level.as_str();
+            let log_line = match context {
+                Some(ctx) => format!("{} [{}] {}[{}] {} | {}\n", 
+                    timestamp, level_str, Location(file, line), module, message, ctx.text),
+                None => format!("{} [{}] {}[{}] {}\n",
+                    timestamp, level_str, Location(file, line), module, message),
+            };
+            
+            let _ = io::stderr().write_all(log_line.as_bytes());
+        }
+    }
+}
+
+// Build the shared Tokio runtime; tests can make this fail to exercise the sync fallback
+fn create_runtime() -> io::Result<Runtime> {
+    #[cfg(feature = "testing")]
+    if crate::testing::runtime_creation_fails() {
+        return Err(io::Error::other("runtime creation disabled for tests"));
+    }
+    Runtime::new()
+}
+
+// Truncate a message to at most `max_bytes` (on a char boundary), noting how much was cut
+fn truncate_message(message: &str, max_bytes: usize) -> Cow<'_, str> {
+    if message.len() <= max_bytes {
+        return Cow::Borrowed(message);
+    }
+    
+    let mut end = max_bytes;
+    while !message.is_char_boundary(end) {
+        end -= 1;
+    }
+    Cow::Owned(format!("{}...[truncated {} bytes]", &message[..end], message.len() - end))
+}
+
+// Keep the last `segments` parts of a module path (0 keeps all of it)
+fn shorten_module(module: &str, segments: usize) -> &str {
+    if segments == 0 {
+        return module;
+    }
+    match module.rmatch_indices("::").nth(segments - 1) {
+        Some((index, separator)) => &module[index + separator.len()..],
+        None => module,
+    }
+}
+
+// Escape newlines, carriage returns and other control characters
+fn sanitize<'a>(text: impl Into<Cow<'a, str>>) -> Cow<'a, str> {
+    let text = text.into();
+    if !text.chars().any(char::is_control) {
+        return text;
+    }
+    
+    let mut escaped = String::with_capacity(text.len() + 8);
+    for c in text.chars() {
+        match c {
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => escaped.extend(c.escape_unicode()),
+            c => escaped.push(c),
+        }
+    }
+    Cow::Owned(escaped)
+}
+
+// Sanitize a context's text and, if it has them, its pairs
+fn sanitize_context(context: &LineContext) -> Cow<'_, LineContext> {
+    let clean = |text: &str| !text.chars().any(char::is_control);
+    let pairs_clean = context.pairs.as_ref()
+        .is_none_or(|pairs| pairs.iter().all(|(k, v)| clean(k) && clean(v)));
+    if clean(&context.text) && pairs_clean {
+        return Cow::Borrowed(context);
+    }
+    Cow::Owned(LineContext {
+        text: sanitize(context.text.as_str()).into_owned(),
+        pairs: context.pairs.as_ref().map(|pairs| pairs.iter()
+            .map(|(k, v)| (sanitize(k.as_str()).into_owned(), sanitize(v.as_str()).into_owned()))
+            .collect()),
+    })
+}
+
+// How log lines are formatted
+#[derive(Clone, Default)]
+enum LineLayout {
+    // The built-in text layout
+    #[default]
+    Text,
+    // A parsed `text_template`
+    Template(TextTemplate),
+    // One JSON record per line, with key/value context as an object when
+    // `structured_context` is set and built-in keys renamed by `field_names`
+    Json { structured_context: bool, field_names: BTreeMap<String, String> },
+    // Common Event Format, with the configured device header
+    Cef(CefHeader),
+    // Google Cloud Logging structured entries
+    GcpJson,
+    // CloudWatch Embedded Metric Format records
+    CloudWatchEmf(EmfNamespace),
+}
+
+impl LineLayout {
+    fn from_config(config: &LogConfig) -> Result<Self, String> {
+        match (config.format, config.text_template.as_deref()) {
+            (LogFormat::Text, None) => Ok(LineLayout::Text),
+            (LogFormat::Text, Some(template)) => Ok(LineLayout::Template(TextTemplate::parse(template)?)),
+            (LogFormat::Json, None) => Ok(LineLayout::Json {
+                structured_context: config.structured_context,
+                field_names: config.json_field_map.clone(),
+            }),
+            (LogFormat::Cef, None) => Ok(LineLayout::Cef(CefHeader::from_config(config))),
+            (LogFormat::GcpJson, None) => Ok(LineLayout::GcpJson),
+            (LogFormat::CloudWatchEmf, None) => Ok(LineLayout::CloudWatchEmf(EmfNamespace::from_config(config))),
+            (format, Some(_)) => Err(format!("text_template cannot be combined with the {} format", format.as_str())),
+        }
+    }
+
+    // Whether fields are written as top-level keys rather than in the context
+    fn has_flat_fields(&self) -> bool {
+        matches!(self, LineLayout::Json { .. } | LineLayout::GcpJson | LineLayout::CloudWatchEmf(_))
+    }
+}
+
+// The "[file:line] " segment of text lines, left out when the file is empty
+// because `include_location` is off
+struct Location<'a>(&'a str, u32);
+
+impl std::fmt::Display for Location<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.0.is_empty() {
+            Ok(())
+        } else {
+            write!(f, "[{}:{}] ", self.0, self.1)
+        }
+    }
+}
+
+// Format a log message for output with the configured layout
+//
+// An empty `file` means the line has no location, which every layout omits.
+#[allow(clippy::too_many_arguments)]
+fn format_log_message(layout: &LineLayout, timestamp: &str, seq: Option<u64>, level: &LogLevel, message: &str, 
+                    context: Option<&LineContext>, fields: &BTreeMap<String, String>, file: &str, line: u32, module: &str) -> String {
+    match layout {
+        LineLayout::Text => {
+            let level_str = level.as_str();
+            let timestamp: Cow<str> = match seq {
+                Some(seq) => Cow::Owned(format!("{} [#{}]", timestamp, seq)),
+                None => Cow::Borrowed(timestamp),
+            };
+            match context {
+                Some(ctx) => format!("{} [{}] {}[{}] {} | {}", 
+                    timestamp, level_str, Location(file, line), module, message, ctx.text),
+                None => format!("{} [{}] {}[{}] {}",
+                    timestamp, level_str, Location(file, line), module, message),
+            }
+        },
+        LineLayout::Template(template) => template.render(
+            timestamp, seq, level, message, context.map(|ctx| ctx.text.as_str()), file, line, module),
+        LineLayout::Json { structured_context, field_names } => {
+            // Text lines already show the fields in their context
+            let reserved = |key: &str| RESERVED_JSON_KEYS.contains(&key) || field_names.values().any(|name| name == key);
+            let fields = if fields.keys().any(|key| reserved(key)) {
+                Cow::Owned(fields.iter()
+                    .filter(|(key, _)| !reserved(key))
+                    .map(|(k, v)| (k.clone(), v.clone()))
+                    .collect())
+            } else {
+                Cow::Borrowed(fields)
+            };
+            let payload = LogPayload {
+                v: SCHEMA_VERSION,
+                seq,
+                timestamp: Cow::Borrowed(timestamp),
+                level: Cow::Borrowed(level.as_str()),
+                message: Cow::Borrowed(message),
+                file: Cow::Borrowed(file),
+                line: (!file.is_empty()).then_some(line),
+                module: Cow::Borrowed(module),
+                context: context.map(|ctx| match (&ctx.pairs, structured_context) {
+                    (Some(pairs), true) => PayloadContext::Pairs(Cow::Borrowed(pairs)),
+                    _ => PayloadContext::Text(Cow::Borrowed(&ctx.text)),
+                }),
+                fields,
+            };
+            if field_names.is_empty() {
+                serde_json::to_string(&payload)
+            } else {
+                serde_json::to_string(&RenamedPayload { payload: &payload, names: field_names })
+            }.expect("log record serialization cannot fail")
+        },
+        LineLayout::Cef(header) => header.render(
+            timestamp, seq, level, message, context.map(|ctx| ctx.text.as_str()), file, line, module),
+        LineLayout::GcpJson => cloud::render_gcp(timestamp, seq, level, message, context, fields, file, line, module),
+        LineLayout::CloudWatchEmf(namespace) => namespace.render(
+            timestamp, seq, level, message, context, fields, file, line, module),
+    }
+}
+
+// Most lines the background task writes in one batch
+const MAX_BATCH_LINES: usize = 1024;
+
+// Async function to process log commands from the channel
+//
+// Lines already waiting in the channel are coalesced into a single write, so
+// a burst costs one syscall per batch instead of one per line. A batch is
+// written as soon as the channel is momentarily empty, or once it has been
+// collecting for `flush_interval` while the channel stays busy. A zero
+// interval writes every line on its own. With `flush_on_error`, an ERROR line
+// ends its batch so it is written (and flushed) without waiting for more.
+async fn process_log_commands(mut receiver: Receiver<LogCommand>, mut output: AsyncLogOutput,
+                              layout: LineLayout, flush_interval: TokioDuration,
+                              flush_on_error: bool) -> Result<(), String> {
+    let mut batch: Vec<(LogLevel, String)> = Vec::new();
+    
+    while let Some(cmd) = receiver.recv().await {
+        let batch_started = Instant::now();
+        let mut shutdown = None;
+        let mut next = Some(cmd);
+        
+        while let Some(cmd) = next.take() {
+            match cmd {
+                LogCommand::Entry(msg) => {
+                    // Format the log message
+                    let formatted_message = format_log_message(
+                        &layout, &msg.timestamp, msg.seq, &msg.level, &msg.message, 
+                        msg.context.as_ref(), &msg.fields, &msg.file, msg.line, &msg.module);
+                    batch.push((msg.level, formatted_message));
+                },
+                LogCommand::Raw(level, line) => batch.push((level, line)),
+                LogCommand::Shutdown(completion_sender) => {
+                    shutdown = Some(completion_sender);
+                    break;
+                }
+            }
+            
+            let error_pending = flush_on_error && batch.last().is_some_and(|(level, _)| *level == LogLevel::Error);
+            if error_pending || batch.len() >= MAX_BATCH_LINES || batch_started.elapsed() >= flush_interval {
+                break;
+            }
+            next = receiver.try_recv().ok();
+        }
+        
+        // Write using the async output, preserving the order lines were sent in
+        if !batch.is_empty() {
+            if let Err(e) = output.write_batch_async(&batch).await {
+                eprintln!("Async logging error: {}", e);
+            }
+            batch.clear();
+        }
+        
+        if let Some(completion_sender) = shutdown {
+            // Final log message before shutdown
+            let timestamp = Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string();
+            let message = "Logger shutdown initiated, ensuring all logs are flushed";
+            let formatted_message = format_log_message(
+                &layout, &timestamp, None, &LogLevel::Info, message, None, &BTreeMap::new(), "logger.rs", 0, "liblogger");
+            
+            // Final flush before shutdown
+            if let Err(e) = output.write_log_async(&formatted_message).await {
+                eprintln!("Error writing final log message: {}", e);
+            }
+            if let Err(e) = output.flush_async().await {
+                eprintln!("Error flushing log output: {}", e);
+            }
+            
+            // Notify that shutdown is complete
+            let _ = completion_sender.send(());
+            
+            // Break the loop to end the task
+            break;
+        }
+    }
+    
+    Ok(())
+}
+
+pub struct Logger;
+
+impl Logger {
+    /// Initialize the logger with default configuration file "app_config.toml"
+    ///
+    /// When the file does not exist the logger falls back to
+    /// `LogConfig::default()` (console output at INFO). Errors reading or
+    /// parsing a file that does exist are returned.
+    pub fn init() -> Result<(), String> {
+        let config_path = "app_config.toml";
+        if !Path::new(config_path).exists() {
+            DEFAULT_CONFIG_NOTICE.call_once(|| {
+                status!("Notice: '{}' not found, logging to console with default settings", config_path);
+            });
+            return Self::init_with_config(LogConfig::default());
+        }
+        
+        Self::init_with_config_file(config_path)
+    }
+
+    /// Initialize the logger like `init()` and return a guard that shuts it
+    /// down when dropped
+    pub fn init_with_guard() -> Result<LoggerGuard, String> {
+        Self::init()?;
+        Ok(Self::guard())
+    }
+
+    /// Guard that shuts the logger down when dropped, for use with any of
+    /// the other init functions
+    pub fn guard() -> LoggerGuard {
+        LoggerGuard { _private: () }
+    }
+
+    /// Initialize the logger with a specific configuration file
+    pub fn init_with_config_file(config_path: &str) -> Result<(), String> {
+        let config = LogConfig::from_file(config_path)?;
+        Self::init_with_config(config)
+    }
+
+    /// Initialize the logger from `LIBLOGGER_*` environment variables only
+    ///
+    /// For containers without a config file; see `LogConfig::from_env` for
+    /// the variables read. Unset variables take the defaults.
+    pub fn init_from_env() -> Result<(), String> {
+        Self::init_with_config(LogConfig::from_env()?)
+    }
+
+    /// Initialize the logger with a LogConfig struct
+    ///
+    /// Calling this again with an equal configuration does nothing; a
+    /// different configuration is an error until `shutdown()`, or use
+    /// `reconfigure()` to switch.
+    pub fn init_with_config(config: LogConfig) -> Result<(), String> {
+        STATUS_TO_STDERR.store(config.console_stream == ConsoleStream::Stderr, Ordering::Relaxed);
+        status!("Setting up logger with log type: {:?}", config.log_type);
+        
+        let logger = LOGGER_INSTANCE.get_or_init(|| Arc::new(Mutex::new(LoggerInner::new())));
+        let mut logger_guard = lock(logger);
+        
+        match logger_guard.init_with_config(config) {
+            Ok(_) => {
+                status!("Logger initialized successfully");
+                Ok(())
+            },
+            Err(e) => {
+                status!("Failed to initialize logger: {}", e);
+                Err(e)
+            }
+        }
+    }
+
+    /// Initialize the logger with a custom synchronous output
+    ///
+    /// Use this for destinations the built-in console, file and HTTP outputs
+    /// don't cover. Messages at or above `threshold` are formatted as usual
+    /// and passed to `output` on the calling thread.
+    pub fn init_with_output(output: Box<dyn LogOutput>, threshold: LogLevel) {
+        let logger = LOGGER_INSTANCE.get_or_init(|| Arc::new(Mutex::new(LoggerInner::new())));
+        let mut logger_guard = lock(logger);
+        logger_guard.init_with_output(output, threshold);
+    }
+
+    /// Route all logging to in-memory capture for unit tests
+    ///
+    /// Safe to call from any number of tests: the logger is switched to a
+    /// synchronous in-memory output at DEBUG once, and every call returns the
+    /// calling thread's capture buffer. Only lines logged from that thread are
+    /// captured. Requires the `testing` feature; not for production use.
+    #[cfg(feature = "testing")]
+    pub fn init_for_tests() -> crate::testing::CapturedLines {
+        TEST_INIT.call_once(|| {
+            Self::init_with_output(Box::new(crate::testing::MemoryOutput), LogLevel::Debug);
+        });
+        
+        crate::testing::thread_buffer()
+    }
+
+    /// Clear the lines captured for the calling thread
+    #[cfg(feature = "testing")]
+    pub fn reset_for_tests() {
+        crate::testing::clear_thread_buffer();
+    }
+
+    /// Make later inits behave as if the Tokio runtime couldn't be created
+    ///
+    /// For testing the fallback to synchronous logging. Has no effect once
+    /// the runtime exists. Requires the `testing` feature.
+    #[cfg(feature = "testing")]
+    pub fn fail_runtime_creation_for_tests() {
+        crate::testing::fail_runtime_creation();
+    }
+
+    /// Read the wall clock from `clock` instead of the system from now on
+    ///
+    /// For testing behaviour under clock skew, such as a clock set before
+    /// the Unix epoch. Requires the `testing` feature.
+    #[cfg(feature = "testing")]
+    pub fn set_clock_for_tests(clock: fn() -> std::time::SystemTime) {
+        crate::clock::set_clock(clock);
+    }
+
+    /// Time since the Unix epoch by the wall clock
+    ///
+    /// Zero when the system clock is set before the epoch, so callers that
+    /// derive IDs from it never panic.
+    pub fn unix_time() -> Duration {
+        crate::clock::since_epoch()
+    }
+
+    /// Log a debug message
+    pub fn debug(message: &str, context: Option<String>, file: &'static str, line: u32, module: &'static str) {
+        Self::log_with_metadata(LogLevel::Debug, message, context, file, line, module)
+    }
+
+    /// Log an info message
+    pub fn info(message: &str, context: Option<String>, file: &'static str, line: u32, module: &'static str) {
+        Self::log_with_metadata(LogLevel::Info, message, context, file, line, module)
+    }
+
+    /// Log a warning message
+    pub fn warn(message: &str, context: Option<String>, file: &'static str, line: u32, module: &'static str) {
+        Self::log_with_metadata(LogLevel::Warn, message, context, file, line, module)
+    }
+
+    /// Log an error message
+    pub fn error(message: &str, context: Option<String>, file: &'static str, line: u32, module: &'static str) {
+        Self::log_with_metadata(LogLevel::Error, message, context, file, line, module)
+    }
+
+    /// Write an already formatted line without adding a timestamp, level or location
+    ///
+    /// Meant for forwarding lines produced elsewhere, such as a child process
+    /// or another logger. The line still honours the threshold and goes to
+    /// the configured output, but is not truncated, sanitized or given the
+    /// thread-local context.
+    pub fn log_raw(level: LogLevel, line: &str) {
+        if !Self::is_enabled(&level) {
+            return;
+        }
+
+        if defer_if_locked(|| DeferredLine::Raw(level.clone(), line.to_string())) {
+            return;
+        }
+        Self::write_raw(level, line)
+    }
+
+    fn write_raw(level: LogLevel, line: &str) {
+        let logger = LOGGER_INSTANCE.get_or_init(|| Arc::new(Mutex::new(LoggerInner::new())));
+        lock(logger).log_raw(level, line);
+    }
+
+    /// Log several messages at one level under a single lock acquisition
+    ///
+    /// For emitting many related lines at once, such as a captured sub-log.
+    /// The lines are written in order with nothing from other threads in
+    /// between, and share the caller's file and line (the module column
+    /// shows the caller's file name). Context and fields are taken once.
+    #[track_caller]
+    pub fn log_batch(level: LogLevel, messages: &[&str]) {
+        let (file, line, module) = caller_location();
+        Self::log_lines(level, messages, None, file, line, module)
+    }
+
+    /// Logger that tags every message with `[prefix]`
+    ///
+    /// For libraries and components that want their lines marked without
+    /// repeating the tag in every call. The child logs through the global
+    /// logger with the caller's file and line.
+    ///
+    /// ```
+    /// # use liblogger::Logger;
+    /// let log = Logger::child("db");
+    /// log.info("Pool ready"); // ... [db] Pool ready
+    /// ```
+    pub fn child(prefix: &str) -> ChildLogger {
+        ChildLogger { prefix: prefix.to_string() }
+    }
+
+    fn log_with_metadata(level: LogLevel, message: &str, context: Option<String>, file: &str, line: u32, module: &str) {
+        Self::log_lines(level, &[message], context, file, line, module)
+    }
+
+    fn log_lines(level: LogLevel, messages: &[&str], context: Option<String>, file: &str, line: u32, module: &str) {
+        // Skip logging if level is below threshold, unless the ring buffer wants every line
+        let enabled = Self::is_enabled(&level);
+        if !enabled && !recent::is_enabled() {
+            return;
+        }
+        // A burst of errors switches every level on for a while
+        if enabled && matches!(level, LogLevel::Error) {
+            if let Some(notice) = escalation::record_error() {
+                Self::log_lines(LogLevel::Warn, &[&notice], None, "logger.rs", 0, "liblogger");
+            }
+        }
+
+        // Extract just the filename from the path; an empty name leaves the location out
+        let (file_name, line) = if INCLUDE_LOCATION.load(Ordering::Relaxed) {
+            (Path::new(file).file_name().and_then(|n| n.to_str()).unwrap_or(file), line)
+        } else {
+            ("", 0)
+        };
+
+        // Append the thread-local context to any explicit context; fields are
+        // taken on the calling thread, before the line is queued
+        let flat_fields = FLAT_FIELDS.load(Ordering::Relaxed);
+        let text_context = recent::is_enabled().then(|| context::merge_into(context.clone(), false).0);
+        let (context, fields) = context::merge_into(context, flat_fields);
+        
+        if let Some(text_context) = text_context {
+            let timestamp = Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string();
+            for message in messages {
+                recent::push(format_log_message(
+                    &LineLayout::Text, &timestamp, None, &level, message, text_context.as_ref(), &BTreeMap::new(), file_name, line, module));
+            }
+        }
+        if !enabled {
+            return;
+        }
+        
+        // Logged from an output or a Drop while this thread holds the lock
+        if defer_if_locked(|| DeferredLine::Entry {
+            level: level.clone(),
+            messages: messages.iter().map(|message| message.to_string()).collect(),
+            context: context.clone(),
+            fields: fields.clone(),
+            file: file_name.to_string(),
+            line,
+            module: module.to_string(),
+        }) {
+            return;
+        }
+        Self::write_lines(&level, messages, context.as_ref(), &fields, file_name, line, module)
+    }
+
+    // Write lines whose context and fields have already been gathered
+    fn write_lines(level: &LogLevel, messages: &[&str], context: Option<&LineContext>, fields: &BTreeMap<String, String>,
+                   file_name: &str, line: u32, module: &str) {
+        let logger = LOGGER_INSTANCE.get_or_init(|| Arc::new(Mutex::new(LoggerInner::new())));
+        
+        let mut logger = lock(logger);
+        for message in messages {
+            logger.log(level.clone(), message, context, fields, file_name, line, module);
+        }
+    }
+
+    /// Change the minimum level recorded, effective immediately for all threads
+    pub fn set_threshold(level: LogLevel) {
+        THRESHOLD.store(level.severity(), Ordering::Relaxed);
+        
+        // Keep the stored configuration in sync
+        if let Some(logger) = LOGGER_INSTANCE.get() {
+            if let Some(ref mut config) = lock(logger).config {
+                config.threshold = level;
+            }
+        }
+    }
+
+    /// Get the minimum level currently recorded
+    pub fn threshold() -> LogLevel {
+        LogLevel::from_severity(THRESHOLD.load(Ordering::Relaxed))
+    }
+
+    /// Lines discarded since the process started because the async channel
+    /// was full with `overflow_policy = "drop_newest"`
+    pub fn dropped_lines() -> u64 {
+        DROPPED.load(Ordering::Relaxed)
+    }
+
+    /// Whether a message at `level` would currently be recorded from this thread
+    ///
+    /// Every level is recorded while a burst of errors has escalated logging
+    /// (see `auto_debug_on_error_rate`).
+    pub fn is_enabled(level: &LogLevel) -> bool {
+        let level = level.severity();
+        (level >= THRESHOLD.load(Ordering::Relaxed) || escalation::is_escalated()) && context::passes_quiet(level)
+    }
+
+    /// Whether a log site may emit again, at most once every `interval_ms`
+    ///
+    /// `last_emit` is the site's own timestamp, kept in a static by the
+    /// `log_*_every!` macros. When several threads pass the site at once,
+    /// only one of them is let through.
+    pub fn with_min_interval(last_emit: &AtomicU64, interval_ms: u64) -> bool {
+        // Milliseconds since the first call, plus one so 0 means "never emitted"
+        static EPOCH: OnceCell<Instant> = OnceCell::new();
+        let now = EPOCH.get_or_init(Instant::now).elapsed().as_millis() as u64 + 1;
+        let last = last_emit.load(Ordering::Relaxed);
+        if last != 0 && now - last < interval_ms {
+            return false;
+        }
+        last_emit.compare_exchange(last, now, Ordering::Relaxed, Ordering::Relaxed).is_ok()
+    }
+
+    /// Shutdown the logger gracefully, ensuring all pending logs are written
+    ///
+    /// Lines logged afterwards are written synchronously, and the logger can
+    /// be initialized again.
+    pub fn shutdown() -> Result<(), String> {
+        // Try to get the runtime
+        if let Some(rt) = RUNTIME.get() {
+            // Check if we have an async logger initialized
+            if let Some(logger) = LOGGER_INSTANCE.get() {
+                let mut logger_guard = lock(logger);
+                // Report any dropped logs before shutdown
+                logger_guard.report_dropped_logs();
+                
+                if logger_guard.async_enabled {
+                    if let Some(sender) = &logger_guard.async_sender {
+                        // Create a oneshot channel for completion notification
+                        let (completion_tx, completion_rx) = oneshot::channel();
+                        
+                        // Clone sender outside of task to avoid reference issues
+                        let sender_clone = sender.clone();
+                        
+                        // Send shutdown command
+                        // Use block to release the mutex guard before the blocking operation
+                        drop(logger_guard);
+                        
+                        // Spawn a Tokio task to send the shutdown command
+                        let handle = rt.spawn(async move {
+                            if let Err(e) = sender_clone.send(LogCommand::Shutdown(completion_tx)).await {
+                                eprintln!("Failed to send shutdown command: {}", e);
+                                return false;
+                            }
+                            
+                            // Wait for completion with timeout
+                            match timeout(TokioDuration::from_secs(5), completion_rx).await {
+                                Ok(Ok(())) => {
+                                    status!("Logger shutdown completed successfully");
+                                    true
+                                },
+                                Ok(Err(_)) => {
+                                    eprintln!("Shutdown completion channel was closed");
+                                    false
+                                },
+                                Err(_) => {
+                                    eprintln!("Logger shutdown timed out after 5 seconds");
+                                    false
+                                }
+                            }
+                        });
+                        
+                        // Wait for the shutdown to complete
+                        let outcome = match rt.block_on(handle) {
+                            Ok(true) => Ok(()),
+                            Ok(false) => Err("Logger shutdown failed".to_string()),
+                            Err(e) => Err(format!("Logger shutdown task panicked: {}", e)),
+                        };
+                        Self::detach_worker();
+                        return outcome;
+                    }
+                }
+            }
+            
+            status!("Logger shutdown completed");
+            Self::detach_worker();
+            Ok(())
+        } else {
+            // No runtime means no async logging was initialized
+            status!("No async logger to shutdown");
+            Self::detach_worker();
+            Ok(())
+        }
+    }
+
+    /// Replace the configuration of an initialized logger
+    ///
+    /// Queued lines are written with the old outputs first, then the new
+    /// configuration is applied as by `init_with_config`.
+    pub fn reconfigure(config: LogConfig) -> Result<(), String> {
+        Self::shutdown()?;
+        Self::init_with_config(config)
+    }
+
+    // Forget the stopped background task: later lines are written
+    // synchronously, and the logger may be initialized again
+    fn detach_worker() {
+        if let Some(logger) = LOGGER_INSTANCE.get() {
+            let mut guard = lock(logger);
+            // Buffered file outputs hold lines until flushed
+            if let Some(ref mut output) = guard.output {
+                let _ = output.flush();
+            }
+            guard.async_sender = None;
+            guard.async_enabled = false;
+            guard.initialized = false;
+        }
+    }
+    
+    /// Get the number of dropped log messages due to backpressure
+    pub fn get_dropped_log_count() -> u64 {
+        if let Some(logger) = LOGGER_INSTANCE.get() {
+            return lock(logger).dropped_logs.load(Ordering::Relaxed);
+        }
+        0
+    }
+
+    /// Get the number of times the logger lock was recovered after a panic poisoned it
+    ///
+    /// Stays 0 with `on_poison = "panic"`, which never recovers.
+    pub fn get_poison_recovery_count() -> u64 {
+        POISON_RECOVERIES.load(Ordering::Relaxed)
+    }
+
+    /// Set fields attached to every log line from any thread, such as the
+    /// service name or version
+    ///
+    /// `hostname` and `pid` are filled in unless `fields` provides them;
+    /// until this is called lines carry neither. Calling this again replaces
+    /// the previous set. A thread-local context value or explicit context
+    /// with the same key takes precedence. JSON, GCP and EMF records get the
+    /// fields as top-level keys, where `with_fields` values replace them.
+    pub fn set_global_fields(fields: HashMap<String, String>) {
+        context::set_global_fields(fields);
+    }
+
+    /// The fields set with `set_global_fields()`, including `hostname` and `pid`
+    pub fn global_fields() -> BTreeMap<String, String> {
+        context::global_fields()
+    }
+
+    /// Set a key in the current thread's logging context
+    ///
+    /// Every log line emitted from this thread will carry `key=value` in its
+    /// context until the key is removed.
+    pub fn set_context_value(key: &str, value: &str) {
+        context::set_value(key, value);
+    }
+
+    /// Get a key from the current thread's logging context
+    pub fn get_context_value(key: &str) -> Option<String> {
+        context::get_value(key)
+    }
+
+    /// Remove a key from the current thread's logging context
+    pub fn remove_context_value(key: &str) -> Option<String> {
+        context::remove_value(key)
+    }
+
+    /// Set a key in the current thread's logging context for the lifetime of the guard
+    ///
+    /// The context that was active before the call is restored when the
+    /// returned guard is dropped.
+    pub fn scoped_context_value(key: &str, value: &str) -> ContextScope {
+        context::scoped_value(key, value)
+    }
+
+    /// Set the current thread's trace ID from an incoming header value
+    ///
+    /// Adopts the value of an upstream `X-Request-Id` or `traceparent` header
+    /// when it is present and sane (1 to 128 ASCII letters, digits or `-_.:`),
+    /// and generates a new UUID otherwise. The ID is stored as the `trace_id`
+    /// context value read by `#[trace_span]` and `#[log_trace_correlation]`.
+    /// Returns the ID now in effect.
+    pub fn set_trace_id_from(header_value: Option<&str>) -> String {
+        let trace_id = header_value
+            .and_then(context::valid_trace_id)
+            .map(str::to_string)
+            .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+        context::set_value("trace_id", &trace_id);
+        trace_id
+    }
+
+    /// Snapshot the current thread's logging context for manual propagation
+    pub fn capture_context() -> CapturedContext {
+        CapturedContext::capture()
+    }
+
+    /// Spawn a Tokio task that inherits the current thread's logging context
+    ///
+    /// Must be called from within a Tokio runtime, like `tokio::spawn`.
+    pub fn spawn_with_context<F>(future: F) -> tokio::task::JoinHandle<F::Output>
+    where
+        F: std::future::Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        let captured: WithContext<F> = Self::capture_context().instrument(future);
+        tokio::spawn(captured)
+    }
+
+    /// Enter a transaction on the current thread, as `#[log_transaction]` does
+    ///
+    /// The returned scope reports how deeply transactions are nested and
+    /// leaves the transaction when dropped.
+    pub fn enter_transaction() -> TransactionScope {
+        context::enter_transaction()
+    }
+
+    /// Mute logging from the current thread until the guard is dropped
+    ///
+    /// Useful around noisy operations such as bulk imports. Other threads
+    /// keep logging as usual.
+    pub fn quiet_scope() -> QuietGuard {
+        context::enter_quiet(u8::MAX)
+    }
+
+    /// Mute logging from a future for as long as it runs
+    ///
+    /// The async counterpart of `quiet_scope`: the mute is applied around
+    /// every poll, so it holds even if the task moves between threads.
+    pub fn quiet_future<F: std::future::Future>(future: F) -> QuietFuture<F> {
+        context::quiet_future(future, u8::MAX)
+    }
+
+    /// Like `quiet_scope`, but messages at `level` or above still get through
+    ///
+    /// `Logger::quiet_scope_above(LogLevel::Warn)` keeps warnings and errors.
+    pub fn quiet_scope_above(level: LogLevel) -> QuietGuard {
+        context::enter_quiet(level.severity())
+    }
+
+    /// Add fields to every line logged from the current thread until the guard is dropped
+    ///
+    /// The fields are appended to the context of text lines and written as
+    /// top-level keys of JSON records (except keys the record already uses,
+    /// such as `message`). Nested scopes merge, the inner value winning.
+    ///
+    /// ```
+    /// # use liblogger::{Logger, log_info};
+    /// let _fields = Logger::with_fields(&[("order_id", "123")]);
+    /// log_info!("Order received"); // ... | order_id=123
+    /// ```
+    pub fn with_fields(fields: &[(&str, &str)]) -> FieldGuard {
+        context::push_fields(fields)
+    }
+
+    /// Send lines logged from the current thread to `output` instead of the global output
+    ///
+    /// For worker pools or per-tenant threads that log to their own
+    /// destination. Lines are formatted with the global layout and written
+    /// synchronously on the calling thread; the global threshold still
+    /// applies. The output replaces any previous one for the thread and is
+    /// flushed and dropped when the thread exits or `clear_thread_output` is
+    /// called. Other threads are unaffected.
+    pub fn set_thread_output(output: Box<dyn LogOutput>) {
+        let previous = THREAD_OUTPUT.with(|cell| cell.borrow_mut().replace(ThreadOutput(output)));
+        // Flushed outside the borrow, in case the output logs
+        drop(previous);
+    }
+
+    /// Send the current thread's lines back to the global output
+    pub fn clear_thread_output() {
+        let previous = THREAD_OUTPUT.with(|cell| cell.borrow_mut().take());
+        drop(previous);
+    }
+
+    /// Render the context of every line with `formatter` instead of `key=value, key=value`
+    ///
+    /// Applies to text lines and to the context string of JSON records.
+    /// A context that isn't made of key=value pairs is written as given.
+    pub fn set_context_formatter(formatter: Box<dyn ContextFormatter>) {
+        context::set_formatter(Some(formatter));
+    }
+
+    /// Go back to rendering context as `key=value, key=value`
+    pub fn clear_context_formatter() {
+        context::set_formatter(None);
+    }
+
+    /// Register the provider consulted by the `#[feature_flag]` attribute
+    pub fn set_feature_flag_provider(provider: Box<dyn FeatureFlagProvider>) {
+        providers::set_feature_flag_provider(provider);
+    }
+
+    /// Check a feature flag against the registered provider
+    ///
+    /// Returns false when no provider has been registered.
+    pub fn is_feature_enabled(flag: &str) -> bool {
+        providers::is_feature_enabled(flag)
+    }
+
+    /// Register the provider consulted by the `#[log_disk_usage]` attribute
+    pub fn set_disk_provider(provider: Box<dyn DiskMetricsProvider>) {
+        providers::set_disk_provider(provider);
+    }
+
+    /// Disk metrics from the registered provider
+    ///
+    /// Without a provider, the filesystem holding the configured log folder
+    /// (or the working directory) is measured; sample values are returned on
+    /// platforms where that is not supported.
+    pub fn disk_info() -> DiskInfo {
+        let log_folder = LOGGER_INSTANCE.get()
+            .and_then(|logger| lock(logger).config.as_ref()?.log_folder.clone())
+            .unwrap_or_else(|| ".".to_string());
+        providers::disk_info(Path::new(&log_folder))
+    }
+
+    /// Number of file descriptors (handles on Windows) open in this process
+    ///
+    /// Returns None on platforms where the count cannot be read.
+    pub fn open_file_descriptors() -> Option<u64> {
+        crate::system::open_file_descriptors()
+    }
+
+    /// Soft limit on open file descriptors, when the platform has one
+    pub fn file_descriptor_limit() -> Option<u64> {
+        crate::system::file_descriptor_limit()
+    }
+
+    /// Register the provider consulted by the `#[log_cache_hit_ratio]` attribute
+    pub fn set_cache_provider(provider: Box<dyn CacheMetricsProvider>) {
+        providers::set_cache_provider(provider);
+    }
+
+    /// Metrics for the named cache from the registered provider, or sample values
+    pub fn cache_stats(cache_name: &str) -> CacheStats {
+        providers::cache_stats(cache_name)
+    }
+
+    /// Register the provider consulted by the `#[log_queue_depth]` attribute
+    pub fn set_queue_provider(provider: Box<dyn QueueMetricsProvider>) {
+        providers::set_queue_provider(provider);
+    }
+
+    /// Metrics for the named queue from the registered provider, or sample values
+    pub fn queue_stats(queue_name: &str) -> QueueStats {
+        providers::queue_stats(queue_name)
+    }
+
+    /// Register the provider consulted by the `#[log_data_quality]` attribute
+    pub fn set_data_quality_provider(provider: Box<dyn DataQualityProvider>) {
+        providers::set_data_quality_provider(provider);
+    }
+
+    /// Validation results for the data domain from the registered provider, or sample values
+    pub fn data_quality(domain: &str) -> DataQualityReport {
+        providers::data_quality(domain)
+    }
+
+    /// Duration statistics recorded under `key` by `record_duration`
+    pub fn duration_baseline(key: &str) -> DurationBaseline {
+        baseline::baseline(key)
+    }
+
+    /// Add a call duration to the baseline kept under `key`, as
+    /// `#[log_anomaly_detection]` does after every call
+    pub fn record_duration(key: &str, duration_ms: f64) {
+        baseline::record(key, duration_ms);
+    }
+
+    /// Start a step of the workflow `workflow_id`, as `#[log_workflow_step]` does
+    ///
+    /// Steps are numbered in the order they first run. Call `complete()` on
+    /// the returned step when it succeeds; a later step whose index is not
+    /// past the last completed one reports `is_out_of_order()`.
+    pub fn begin_workflow_step(workflow_id: &str, step_name: &str) -> WorkflowStep {
+        workflow::begin_step(workflow_id, step_name)
+    }
+
+    /// Start a hold of the lock `lock_id`, as `#[log_distributed_lock]` does
+    ///
+    /// Call `release()` on the returned hold when the lock is released to get
+    /// the hold time. Holds of the same lock still in flight when this one
+    /// starts make up its `queue_size()`.
+    pub fn begin_lock_hold(lock_id: &str) -> LockHold {
+        locks::begin_hold(lock_id)
+    }
+
+    /// Current state of the circuit breaker for `service`
+    pub fn circuit_breaker_state(service: &str) -> BreakerState {
+        breakers::state(service)
+    }
+
+    /// Failures of calls to `service` in a row since its last success
+    pub fn circuit_breaker_failures(service: &str) -> u32 {
+        breakers::consecutive_failures(service)
+    }
+
+    /// Record a call to `service`, as `#[log_service_communication]` does
+    ///
+    /// The breaker opens once `failure_threshold` calls in a row have
+    /// failed. Returns its state after this call.
+    pub fn record_service_call(service: &str, succeeded: bool, failure_threshold: u32) -> BreakerState {
+        breakers::record(service, succeeded, failure_threshold)
+    }
+
+    /// Close the circuit breaker for `service` and forget its failures
+    pub fn reset_circuit_breaker(service: &str) {
+        breakers::reset(service)
+    }
+
+    /// Record the outcome of a health check, as `#[health_check]` does after every run
+    ///
+    /// `error` is `None` when the check passed. The result replaces the
+    /// previous one recorded under `name`.
+    pub fn record_health_check(name: &str, error: Option<String>, duration_ms: f64) {
+        health::record(name, error, duration_ms);
+    }
+
+    /// Pass and fail counts and the latest result of every health check run so far
+    ///
+    /// ```
+    /// # use liblogger::Logger;
+    /// let report = Logger::health_report();
+    /// let status = if report.is_healthy() { 200 } else { 503 };
+    /// # assert_eq!(status, 200);
+    /// ```
+    pub fn health_report() -> HealthReport {
+        health::report()
+    }
+
+    /// The active configuration as TOML, including changes made since init
+    ///
+    /// The output loads back with `LogConfig::from_file`. Before the logger
+    /// is initialized this is the default configuration.
+    pub fn dump_config() -> String {
+        let config = LOGGER_INSTANCE.get()
+            .and_then(|logger| lock(logger).config.clone())
+            .unwrap_or_default();
+        config.to_toml().expect("LogConfig always serializes to TOML")
+    }
+
+    /// The most recent lines kept by the `ring_buffer_size` buffer, oldest first
+    ///
+    /// Lines below the threshold are included. Empty when the buffer is disabled.
+    pub fn recent_logs() -> Vec<String> {
+        recent::lines()
+    }
+
+    /// Run `f`, catching a panic along with the backtrace taken where it happened
+    ///
+    /// Used by `#[catch_panic(backtrace = true)]`. The backtrace is only
+    /// resolved when RUST_BACKTRACE (or RUST_LIB_BACKTRACE) enables it, and
+    /// is `None` if a panic hook installed later replaced the one this sets
+    /// up without calling it.
+    pub fn catch_unwind_with_backtrace<F, R>(f: F) -> Result<R, (Box<dyn Any + Send>, Option<Backtrace>)>
+    where
+        F: FnOnce() -> R,
+    {
+        backtrace::catch_unwind(f)
+    }
+
+    /// Install a process-wide panic hook that logs unhandled panics
+    ///
+    /// The panic payload and source location are logged at ERROR level before
+    /// the previously installed hook (usually the default stderr printer) runs,
+    /// followed on stderr by the lines kept by `ring_buffer_size`, if any.
+    /// Calling this more than once has no additional effect.
+    pub fn install_panic_hook() {
+        PANIC_HOOK.call_once(|| {
+            let previous_hook = std::panic::take_hook();
+            std::panic::set_hook(Box::new(move |info| {
+                let payload = if let Some(s) = info.payload().downcast_ref::<&str>() {
+                    s.to_string()
+                } else if let Some(s) = info.payload().downcast_ref::<String>() {
+                    s.clone()
+                } else {
+                    "Unknown panic".to_string()
+                };
+                
+                let thread = std::thread::current();
+                let context = Some(format!("thread={}", thread.name().unwrap_or("unnamed")));
+                
+                match info.location() {
+                    Some(location) => Self::log_with_metadata(
+                        LogLevel::Error,
+                        &format!("PANIC: {}", payload),
+                        context,
+                        location.file(),
+                        location.line(),
+                        "panic",
+                    ),
+                    None => Self::log_with_metadata(
+                        LogLevel::Error,
+                        &format!("PANIC: {}", payload),
+                        context,
+                        "unknown",
+                        0,
+                        "panic",
+                    ),
+                }
+                
+                // Lines leading up to the crash, including filtered-out ones
+                let recent = recent::lines();
+                if !recent.is_empty() {
+                    let mut stderr = io::stderr().lock();
+                    let _ = writeln!(stderr, "Last {} log lines before the panic:", recent.len());
+                    for line in &recent {
+                        let _ = writeln!(stderr, "{}", line);
+                    }
+                }
+                
+                previous_hook(info);
+            }));
+        });
+    }
+}
+
+// File, line and module column for the APIs called without the logging macros
+#[track_caller]
+fn caller_location() -> (&'static str, u32, &'static str) {
+    let caller = std::panic::Location::caller();
+    let module = Path::new(caller.file())
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or("");
+    (caller.file(), caller.line(), module)
+}
+
+/// Logger that prefixes its messages with a component tag
+///
+/// Returned by `Logger::child()`. Its methods log through the global logger
+/// like the `log_*!` macros, with the caller's file and line; the module
+/// column shows the caller's file name.
+#[derive(Debug, Clone)]
+pub struct ChildLogger {
+    prefix: String,
+}
+
+impl ChildLogger {
+    /// The tag added to this logger's messages
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    /// Log a debug message
+    #[track_caller]
+    pub fn debug(&self, message: &str) {
+        self.log(LogLevel::Debug, message)
+    }
+
+    /// Log an info message
+    #[track_caller]
+    pub fn info(&self, message: &str) {
+        self.log(LogLevel::Info, message)
+    }
+
+    /// Log a warning message
+    #[track_caller]
+    pub fn warn(&self, message: &str) {
+        self.log(LogLevel::Warn, message)
+    }
+
+    /// Log an error message
+    #[track_caller]
+    pub fn error(&self, message: &str) {
+        self.log(LogLevel::Error, message)
+    }
+
+    #[track_caller]
+    fn log(&self, level: LogLevel, message: &str) {
+        // Skip formatting the prefix for lines nobody will see
+        if !Logger::is_enabled(&level) && !recent::is_enabled() {
+            return;
+        }
+        let (file, line, module) = caller_location();
+        Logger::log_with_metadata(level, &format!("[{}] {}", self.prefix, message), None, file, line, module)
+    }
+}
+
+/// Flushes and shuts down the logger when dropped
+///
+/// Returned by `Logger::init_with_guard()` and `Logger::guard()`. Hold it in
+/// a binding for the lifetime of `main` (`let _guard = ...;`, not `let _ = ...;`)
+/// so pending asynchronous log messages are written before the process exits.
+/// The global logging functions keep working with or without a guard.
+#[must_use = "the logger is shut down as soon as the guard is dropped"]
+pub struct LoggerGuard {
+    _private: (),
+}
+
+impl Drop for LoggerGuard {
+    fn drop(&mut self) {
+        if let Err(e) = Logger::shutdown() {
+            eprintln!("Error during logger shutdown: {}", e);
+        }
+    }
+}
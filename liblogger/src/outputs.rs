@@ -1,397 +1,932 @@
-/*
- * Log output implementations
- * 
- * This module defines different logging backends:
- * - ConsoleOutput: Writes logs to stdout
- * - FileOutput: Writes logs to files with rotation support
- * - HttpOutput: Sends logs to a remote endpoint
- * 
- * Each output implements the LogOutput trait, which defines how
- * log messages are formatted and written. The module also provides
- * factory functions to create the appropriate output based on configuration.
- */
-
-use std::fs::{File, OpenOptions};
-use std::io::{self, Write};
-use std::path::Path;
-use std::sync::{Arc, Mutex};
-use std::time::Duration;
-use tokio::io::{AsyncWriteExt, stdout};
-use reqwest::{Client, blocking::Client as BlockingClient};
-use serde::{Serialize, Deserialize};
-use crate::config::{LogConfig, LogType};
-use async_trait::async_trait;
-
-// Original synchronous trait, kept for backward compatibility
-pub trait LogOutput: Send + Sync {
-    fn write_log(&mut self, formatted_message: &str) -> Result<(), String>;
-}
-
-// Instead of using an async trait directly, define a trait with a function
-// that returns a future boxed to make it object-safe
-#[async_trait]
-pub trait AsyncLogOutputTrait: Send + Sync {
-    async fn write_log_async(&mut self, formatted_message: &str) -> Result<(), String>;
-}
-
-// Enum to hold all possible output types
-pub enum AsyncLogOutput {
-    Console(ConsoleOutput),
-    File(AsyncFileOutput),
-    Http(HttpOutput),
-}
-
-// Console output implementation
-pub struct ConsoleOutput;
-
-impl ConsoleOutput {
-    pub fn new() -> Self {
-        ConsoleOutput {}
-    }
-}
-
-impl LogOutput for ConsoleOutput {
-    fn write_log(&mut self, formatted_message: &str) -> Result<(), String> {
-        if let Err(e) = writeln!(io::stdout(), "{}", formatted_message) {
-            return Err(format!("Failed to write to console: {}", e));
-        }
-        
-        Ok(())
-    }
-}
-
-#[async_trait]
-impl AsyncLogOutputTrait for ConsoleOutput {
-    async fn write_log_async(&mut self, formatted_message: &str) -> Result<(), String> {
-        let mut stdout = stdout();
-        let mut log_bytes = formatted_message.as_bytes().to_vec();
-        log_bytes.push(b'\n');
-        
-        if let Err(e) = stdout.write_all(&log_bytes).await {
-            return Err(format!("Failed to write to console: {}", e));
-        }
-        
-        if let Err(e) = stdout.flush().await {
-            return Err(format!("Failed to flush console output: {}", e));
-        }
-        
-        Ok(())
-    }
-}
-
-// Update the FileOutput struct to include force_flush flag
-pub struct FileOutput {
-    file_handle: Arc<Mutex<File>>,
-    force_flush: bool,
-}
-
-impl FileOutput {
-    #[allow(dead_code)]
-    pub fn new(file_path: &str, force_flush: bool) -> Result<Self, String> {
-        // Create directory if it doesn't exist
-        if let Some(parent) = Path::new(file_path).parent() {
-            if !parent.exists() {
-                std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create log directory: {}", e))?;
-            }
-        }
-        
-        // Open the file once with append mode
-        let file = OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(file_path)
-            .map_err(|e| format!("Failed to open log file: {}", e))?;
-        
-        // Wrap the file in Arc<Mutex<_>> for shared access
-        let file_handle = Arc::new(Mutex::new(file));
-        
-        Ok(FileOutput {
-            file_handle,
-            force_flush,
-        })
-    }
-}
-
-impl LogOutput for FileOutput {
-    fn write_log(&mut self, formatted_message: &str) -> Result<(), String> {
-        // Lock the file handle and write to it
-        let mut file = self.file_handle.lock()
-            .map_err(|_| "Failed to lock file mutex".to_string())?;
-        
-        file.write_all(formatted_message.as_bytes())
-            .map_err(|e| format!("Failed to write to log file: {}", e))?;
-        file.write_all(b"\n")
-            .map_err(|e| format!("Failed to write newline to log file: {}", e))?;
-        
-        // Only flush immediately if force_flush is true
-        if self.force_flush {
-            file.flush()
-                .map_err(|e| format!("Failed to flush log file: {}", e))?;
-        }
-        
-        Ok(())
-    }
-}
-
-// Update AsyncFileOutput to include force_flush flag
-pub struct AsyncFileOutput {
-    file_handle: Arc<Mutex<File>>,
-    force_flush: bool,
-}
-
-// Implementation of AsyncFileOutput
-impl AsyncFileOutput {
-    #[allow(dead_code)]
-    pub fn new(file_path: &str, force_flush: bool) -> Result<Self, String> {
-        // Create directory if it doesn't exist
-        if let Some(parent) = Path::new(file_path).parent() {
-            if !parent.exists() {
-                std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create log directory: {}", e))?;
-            }
-        }
-        
-        // Open the file once with append mode
-        let file = OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(file_path)
-            .map_err(|e| format!("Failed to open log file: {}", e))?;
-            
-        // Wrap the file in Arc<Mutex<_>> for shared access
-        let file_handle = Arc::new(Mutex::new(file));
-        
-        Ok(AsyncFileOutput {
-            file_handle,
-            force_flush,
-        })
-    }
-}
-
-#[async_trait]
-impl AsyncLogOutputTrait for AsyncFileOutput {
-    async fn write_log_async(&mut self, formatted_message: &str) -> Result<(), String> {
-        // Lock the file handle and write to it
-        let mut file = self.file_handle.lock()
-            .map_err(|_| "Failed to lock file mutex".to_string())?;
-            
-        file.write_all(formatted_message.as_bytes())
-            .map_err(|e| format!("Failed to write to log file: {}", e))?;
-        file.write_all(b"\n")
-            .map_err(|e| format!("Failed to write newline to log file: {}", e))?;
-        
-        // Only flush immediately if force_flush is true
-        if self.force_flush {
-            file.flush()
-                .map_err(|e| format!("Failed to flush log file: {}", e))?;
-        }
-        
-        Ok(())
-    }
-}
-
-// Update the create_file_output function to include force_flush
-pub fn create_file_output(file_path: &str, force_flush: bool) -> Result<(FileOutput, AsyncFileOutput), String> {
-    // Open the file once 
-    let file = OpenOptions::new()
-        .create(true)
-        .append(true)
-        .open(file_path)
-        .map_err(|e| format!("Failed to open log file: {}", e))?;
-        
-    // Create shared file handle
-    let file_handle = Arc::new(Mutex::new(file));
-    
-    // Create both output instances with the same file handle and force_flush setting
-    let file_output = FileOutput {
-        file_handle: Arc::clone(&file_handle),
-        force_flush,
-    };
-    
-    let async_file_output = AsyncFileOutput {
-        file_handle,
-        force_flush,
-    };
-    
-    Ok((file_output, async_file_output))
-}
-
-#[derive(Serialize, Deserialize)]
-struct LogPayload<'a> {
-    timestamp: &'a str,
-    level: &'a str,
-    message: &'a str,
-    file: &'a str,
-    line: u32,
-    module: &'a str,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    context: Option<&'a str>,
-}
-
-// HTTP output implementation - updated to support async operations
-pub struct HttpOutput {
-    blocking_client: BlockingClient,
-    async_client: Client,
-    endpoint: String,
-}
-
-impl HttpOutput {
-    pub fn new(endpoint: &str, timeout_seconds: u64) -> Result<Self, String> {
-        let blocking_client = BlockingClient::builder()
-            .timeout(Duration::from_secs(timeout_seconds))
-            .build()
-            .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
-            
-        let async_client = Client::builder()
-            .timeout(Duration::from_secs(timeout_seconds))
-            .build()
-            .map_err(|e| format!("Failed to create async HTTP client: {}", e))?;
-            
-        Ok(HttpOutput {
-            blocking_client,
-            async_client,
-            endpoint: endpoint.to_string(),
-        })
-    }
-}
-
-impl LogOutput for HttpOutput {
-    fn write_log(&mut self, formatted_message: &str) -> Result<(), String> {
-        let payload: LogPayload = serde_json::from_str(formatted_message)
-            .map_err(|e| format!("Failed to parse log payload: {}", e))?;
-        
-        match self.blocking_client.post(&self.endpoint)
-            .json(&payload)
-            .send() {
-            Ok(response) => {
-                if !response.status().is_success() {
-                    return Err(format!("HTTP log failed with status: {}", response.status()));
-                }
-            },
-            Err(e) => {
-                return Err(format!("Failed to send HTTP log: {}", e));
-            }
-        }
-        
-        Ok(())
-    }
-}
-
-#[async_trait]
-impl AsyncLogOutputTrait for HttpOutput {
-    async fn write_log_async(&mut self, formatted_message: &str) -> Result<(), String> {
-        let payload: LogPayload = serde_json::from_str(formatted_message)
-            .map_err(|e| format!("Failed to parse log payload: {}", e))?;
-        
-        let response = match self.async_client.post(&self.endpoint)
-            .json(&payload)
-            .send()
-            .await {
-                Ok(resp) => resp,
-                Err(e) => return Err(format!("Failed to send HTTP log: {}", e))
-            };
-        
-        if !response.status().is_success() {
-            return Err(format!("HTTP log failed with status: {}", response.status()));
-        }
-        
-        Ok(())
-    }
-}
-
-// Implement AsyncLogOutputTrait for the AsyncLogOutput enum
-#[async_trait]
-impl AsyncLogOutputTrait for AsyncLogOutput {
-    async fn write_log_async(&mut self, formatted_message: &str) -> Result<(), String> {
-        match self {
-            AsyncLogOutput::Console(output) => output.write_log_async(formatted_message).await,
-            AsyncLogOutput::File(output) => output.write_log_async(formatted_message).await,
-            AsyncLogOutput::Http(output) => output.write_log_async(formatted_message).await,
-        }
-    }
-}
-
-/// Creates a synchronous log output based on configuration
-pub fn create_log_output(log_type: &LogType) -> Result<Box<dyn LogOutput>, String> {
-    match log_type {
-        LogType::Console => Ok(Box::new(ConsoleOutput::new())),
-        LogType::File => {
-            // Get the config instance to retrieve settings
-            let config = LogConfig::get_instance()?;
-            
-            // Get file path and combine with log folder if specified
-            let file_path = config.file_path.as_ref()
-                .ok_or_else(|| "File path not specified in configuration".to_string())?;
-                
-            // Construct the full path using the log_folder if provided
-            let full_path = if let Some(folder) = &config.log_folder {
-                // Create the log directory if it doesn't exist
-                std::fs::create_dir_all(folder)
-                    .map_err(|e| format!("Failed to create log directory '{}': {}", folder, e))?;
-                
-                // Use platform-specific path separator
-                let path = Path::new(folder).join(file_path);
-                path.to_string_lossy().into_owned()
-            } else {
-                file_path.clone()
-            };
-            
-            println!("Creating log file at: {}", full_path);
-            
-            // Use the force_flush directly since it's already a bool
-            let force_flush = config.force_flush;
-            
-            let (file_output, _) = create_file_output(&full_path, force_flush)?;
-            Ok(Box::new(file_output))
-        },
-        LogType::Http => {
-            // Assuming the config is properly updated to include http_endpoint and http_timeout_seconds
-            let config = LogConfig::get_instance()?;
-            let endpoint = &config.http_endpoint.as_ref().ok_or_else(|| 
-                "HTTP endpoint not specified in configuration".to_string())?;
-            let timeout = config.http_timeout_seconds.unwrap_or(30);
-            Ok(Box::new(HttpOutput::new(endpoint, timeout)?))
-        },
-    }
-}
-
-/// Creates an asynchronous log output based on configuration
-pub fn create_async_log_output(log_type: &LogType) -> Result<AsyncLogOutput, String> {
-    match log_type {
-        LogType::Console => Ok(AsyncLogOutput::Console(ConsoleOutput::new())),
-        LogType::File => {
-            // Get the config instance to retrieve settings
-            let config = LogConfig::get_instance()?;
-            
-            // Get file path and combine with log folder if specified
-            let file_path = config.file_path.as_ref()
-                .ok_or_else(|| "File path not specified in configuration".to_string())?;
-                
-            // Construct the full path using the log_folder if provided
-            let full_path = if let Some(folder) = &config.log_folder {
-                // Create the log directory if it doesn't exist
-                std::fs::create_dir_all(folder)
-                    .map_err(|e| format!("Failed to create log directory '{}': {}", folder, e))?;
-                
-                // Use platform-specific path separator
-                let path = Path::new(folder).join(file_path);
-                path.to_string_lossy().into_owned()
-            } else {
-                file_path.clone()
-            };
-            
-            // Use the force_flush directly since it's already a bool
-            let force_flush = config.force_flush;
-            
-            let (_, async_file_output) = create_file_output(&full_path, force_flush)?;
-            Ok(AsyncLogOutput::File(async_file_output))
-        },
-        LogType::Http => {
-            // Get the config instance to retrieve HTTP settings
-            let config = LogConfig::get_instance()?;
-            let endpoint = &config.http_endpoint.as_ref().ok_or_else(|| 
-                "HTTP endpoint not specified in configuration".to_string())?;
-            let timeout = config.http_timeout_seconds.unwrap_or(30);
-            Ok(AsyncLogOutput::Http(HttpOutput::new(endpoint, timeout)?))
-        },
-    }
-}
+/*
+ * Log output implementations
+ * 
+ * This module defines different logging backends:
+ * - ConsoleOutput: Writes logs to stdout or stderr, optionally colorizing the level
+ * - FileOutput: Writes logs to files, optionally buffered in memory
+ * - HttpOutput: Sends logs to a remote endpoint
+ * - UnixSocketOutput: Writes newline-delimited logs to a Unix domain socket (Unix only)
+ * - CompositeOutput: Forwards logs to several outputs, each with its own threshold
+ * - NullOutput: Discards logs, for benchmarking the rest of the logging path
+ * 
+ * Each output implements the LogOutput trait, which defines how
+ * log messages are formatted and written. The module also provides
+ * factory functions to create the appropriate output based on configuration.
+ */
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufWriter, IsTerminal, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+use reqwest::{Client, blocking::Client as BlockingClient};
+use serde::{Serialize, Deserialize};
+use crate::config::{ColorMode, ConsoleStream, LineEnding, LogConfig, LogLevel, LogType};
+use async_trait::async_trait;
+
+/// Synchronous log destination
+///
+/// Implemented by the built-in outputs; implement it for a custom sink and
+/// install it with `Logger::init_with_output`.
+///
+/// Methods and `Drop` run while the logger lock is held. Lines they log
+/// (directly or from a `Drop` they trigger) are held back and written once
+/// the outer line is done; lines logged while writing those go to stderr.
+pub trait LogOutput: Send + Sync {
+    /// Write one formatted log line (without a trailing newline)
+    fn write_log(&mut self, formatted_message: &str) -> Result<(), String>;
+
+    /// Write a message logged at `level`; outputs that filter by level override this
+    fn write_entry(&mut self, _level: &LogLevel, formatted_message: &str) -> Result<(), String> {
+        self.write_log(formatted_message)
+    }
+
+    /// Flush anything buffered by the output; called on shutdown
+    fn flush(&mut self) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+// Instead of using an async trait directly, define a trait with a function
+// that returns a future boxed to make it object-safe
+#[async_trait]
+pub trait AsyncLogOutputTrait: Send + Sync {
+    async fn write_log_async(&mut self, formatted_message: &str) -> Result<(), String>;
+
+    /// Write several lines in order; outputs that can coalesce writes override this
+    async fn write_batch_async(&mut self, entries: &[(LogLevel, String)]) -> Result<(), String> {
+        for (_, formatted_message) in entries {
+            self.write_log_async(formatted_message).await?;
+        }
+        Ok(())
+    }
+
+    /// Write out anything buffered; called by the background task on shutdown
+    async fn flush_async(&mut self) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+// Enum to hold all possible output types
+pub enum AsyncLogOutput {
+    Console(ConsoleOutput),
+    File(AsyncFileOutput),
+    Http(HttpOutput),
+    #[cfg(unix)]
+    UnixSocket(AsyncUnixSocketOutput),
+    Null(NullOutput),
+    /// Several outputs, each with an optional minimum level
+    Composite(Vec<(Option<LogLevel>, AsyncLogOutput)>),
+}
+
+// Console output implementation
+pub struct ConsoleOutput {
+    colored: bool,
+    stream: ConsoleStream,
+}
+
+impl ConsoleOutput {
+    /// `text_layout` tells whether lines use the built-in text layout; the
+    /// level is only colored there, never in JSON, CEF or templated lines
+    pub fn new(color: ColorMode, stream: ConsoleStream, text_layout: bool) -> Self {
+        let colored = text_layout && match color {
+            ColorMode::Always => true,
+            ColorMode::Never => false,
+            ColorMode::Auto => match stream {
+                ConsoleStream::Stdout => io::stdout().is_terminal(),
+                ConsoleStream::Stderr => io::stderr().is_terminal(),
+            },
+        };
+        ConsoleOutput { colored, stream }
+    }
+
+    // Tokio handle for the configured stream
+    fn async_stream(&self) -> std::pin::Pin<Box<dyn AsyncWrite + Send>> {
+        match self.stream {
+            ConsoleStream::Stdout => Box::pin(tokio::io::stdout()),
+            ConsoleStream::Stderr => Box::pin(tokio::io::stderr()),
+        }
+    }
+
+    fn render<'a>(&self, formatted_message: &'a str) -> Cow<'a, str> {
+        if self.colored {
+            colorize_level(formatted_message)
+        } else {
+            Cow::Borrowed(formatted_message)
+        }
+    }
+}
+
+// Wrap the "[LEVEL]" token of a text line in its ANSI color
+//
+// It is the first bracketed token naming a level: only the timestamp and the
+// "[#N]" sequence number come before it.
+fn colorize_level(formatted_message: &str) -> Cow<'_, str> {
+    const LEVEL_COLORS: [(&str, &str); 4] = [
+        ("[DEBUG]", "\x1b[90m"),
+        ("[INFO]", "\x1b[32m"),
+        ("[WARN]", "\x1b[33m"),
+        ("[ERROR]", "\x1b[31m"),
+    ];
+
+    for (start, _) in formatted_message.match_indices('[') {
+        let rest = &formatted_message[start..];
+        for (token, color) in LEVEL_COLORS {
+            if rest.starts_with(token) {
+                let end = start + token.len();
+                return Cow::Owned(format!("{}{}{}\x1b[0m{}",
+                    &formatted_message[..start], color, token, &formatted_message[end..]));
+            }
+        }
+    }
+    Cow::Borrowed(formatted_message)
+}
+
+impl LogOutput for ConsoleOutput {
+    fn write_log(&mut self, formatted_message: &str) -> Result<(), String> {
+        let rendered = self.render(formatted_message);
+        let written = match self.stream {
+            ConsoleStream::Stdout => writeln!(io::stdout(), "{}", rendered),
+            ConsoleStream::Stderr => writeln!(io::stderr(), "{}", rendered),
+        };
+        if let Err(e) = written {
+            return Err(format!("Failed to write to console: {}", e));
+        }
+        
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl AsyncLogOutputTrait for ConsoleOutput {
+    async fn write_log_async(&mut self, formatted_message: &str) -> Result<(), String> {
+        let mut stream = self.async_stream();
+        let mut log_bytes = self.render(formatted_message).as_bytes().to_vec();
+        log_bytes.push(b'\n');
+        
+        if let Err(e) = stream.write_all(&log_bytes).await {
+            return Err(format!("Failed to write to console: {}", e));
+        }
+        
+        if let Err(e) = stream.flush().await {
+            return Err(format!("Failed to flush console output: {}", e));
+        }
+        
+        Ok(())
+    }
+
+    async fn write_batch_async(&mut self, entries: &[(LogLevel, String)]) -> Result<(), String> {
+        let mut log_bytes = Vec::new();
+        for (_, formatted_message) in entries {
+            log_bytes.extend_from_slice(self.render(formatted_message).as_bytes());
+            log_bytes.push(b'\n');
+        }
+        
+        let mut stream = self.async_stream();
+        stream.write_all(&log_bytes).await
+            .map_err(|e| format!("Failed to write to console: {}", e))?;
+        stream.flush().await
+            .map_err(|e| format!("Failed to flush console output: {}", e))
+    }
+}
+
+// Bytes a buffered file output holds before writing them out
+const FILE_BUFFER_BYTES: usize = 64 * 1024;
+
+// Longest a buffered file output keeps lines before flushing them
+const FILE_BUFFER_FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How a file output writes its lines
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FileWriteOptions {
+    /// Flush after every write
+    pub force_flush: bool,
+    /// Keep lines in memory until the buffer fills, a second has passed or the output is flushed
+    pub buffered: bool,
+    /// Flush as soon as an ERROR line is written
+    pub flush_on_error: bool,
+    /// Terminator written after every line
+    pub line_ending: LineEnding,
+}
+
+impl FileWriteOptions {
+    pub fn from_config(config: &LogConfig) -> Self {
+        FileWriteOptions {
+            force_flush: config.force_flush,
+            buffered: config.buffered,
+            flush_on_error: config.flush_on_error,
+            line_ending: config.line_ending,
+        }
+    }
+}
+
+// Open log file shared by FileOutput and AsyncFileOutput
+//
+// Unbuffered outputs write every call straight through to the file; the
+// BufWriter only coalesces the lines of one call.
+struct FileSink {
+    writer: BufWriter<File>,
+    options: FileWriteOptions,
+    last_flush: Instant,
+}
+
+impl FileSink {
+    fn open(file_path: &str, options: FileWriteOptions) -> Result<Self, String> {
+        // Create directory if it doesn't exist
+        if let Some(parent) = Path::new(file_path).parent() {
+            if !parent.as_os_str().is_empty() && !parent.exists() {
+                std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create log directory: {}", e))?;
+            }
+        }
+        
+        // Open the file once with append mode
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(file_path)
+            .map_err(|e| format!("Failed to open log file: {}", e))?;
+        
+        Ok(FileSink {
+            writer: BufWriter::with_capacity(FILE_BUFFER_BYTES, file),
+            options,
+            last_flush: Instant::now(),
+        })
+    }
+    
+    // Open the file behind a shared handle, with a thread flushing it every
+    // FILE_BUFFER_FLUSH_INTERVAL when buffered, so lines don't wait for the
+    // next write; the thread ends once every handle is dropped
+    fn open_shared(file_path: &str, options: FileWriteOptions) -> Result<Arc<Mutex<Self>>, String> {
+        let sink = Arc::new(Mutex::new(FileSink::open(file_path, options)?));
+        if options.buffered && !options.force_flush {
+            let sink = Arc::downgrade(&sink);
+            // Without the thread, buffered lines still go out on the next write after the interval
+            let _ = std::thread::Builder::new()
+                .name("liblogger-flush".to_string())
+                .spawn(move || flush_periodically(sink));
+        }
+        Ok(sink)
+    }
+    
+    // `has_error` marks writes containing an ERROR line, flushed when `flush_on_error` is set
+    fn write_lines<'a>(&mut self, lines: impl Iterator<Item = &'a str>, has_error: bool) -> Result<(), String> {
+        let line_ending = self.options.line_ending.as_str().as_bytes();
+        for line in lines {
+            self.writer.write_all(line.as_bytes())
+                .map_err(|e| format!("Failed to write to log file: {}", e))?;
+            self.writer.write_all(line_ending)
+                .map_err(|e| format!("Failed to write newline to log file: {}", e))?;
+        }
+        
+        if !self.options.buffered || self.options.force_flush
+            || (has_error && self.options.flush_on_error)
+            || self.last_flush.elapsed() >= FILE_BUFFER_FLUSH_INTERVAL {
+            self.flush()?;
+        }
+        Ok(())
+    }
+    
+    fn flush(&mut self) -> Result<(), String> {
+        self.last_flush = Instant::now();
+        self.writer.flush()
+            .map_err(|e| format!("Failed to flush log file: {}", e))
+    }
+}
+
+// Flush a buffered sink's lines once they have waited FILE_BUFFER_FLUSH_INTERVAL
+fn flush_periodically(sink: Weak<Mutex<FileSink>>) {
+    loop {
+        std::thread::sleep(FILE_BUFFER_FLUSH_INTERVAL / 4);
+        let Some(sink) = sink.upgrade() else {
+            return;
+        };
+        let mut file = sink.lock().unwrap_or_else(|e| e.into_inner());
+        if !file.writer.buffer().is_empty() && file.last_flush.elapsed() >= FILE_BUFFER_FLUSH_INTERVAL {
+            if let Err(e) = file.flush() {
+                eprintln!("{}", e);
+            }
+        }
+    }
+}
+
+// Synchronous file output
+pub struct FileOutput {
+    file_handle: Arc<Mutex<FileSink>>,
+}
+
+impl FileOutput {
+    #[allow(dead_code)]
+    pub fn new(file_path: &str, options: FileWriteOptions) -> Result<Self, String> {
+        Ok(FileOutput {
+            file_handle: FileSink::open_shared(file_path, options)?,
+        })
+    }
+}
+
+impl LogOutput for FileOutput {
+    fn write_log(&mut self, formatted_message: &str) -> Result<(), String> {
+        let mut file = self.file_handle.lock()
+            .map_err(|_| "Failed to lock file mutex".to_string())?;
+        file.write_lines(std::iter::once(formatted_message), false)
+    }
+
+    fn write_entry(&mut self, level: &LogLevel, formatted_message: &str) -> Result<(), String> {
+        let mut file = self.file_handle.lock()
+            .map_err(|_| "Failed to lock file mutex".to_string())?;
+        file.write_lines(std::iter::once(formatted_message), *level == LogLevel::Error)
+    }
+
+    fn flush(&mut self) -> Result<(), String> {
+        let mut file = self.file_handle.lock()
+            .map_err(|_| "Failed to lock file mutex".to_string())?;
+        file.flush()
+    }
+}
+
+// Asynchronous file output, written to by the background task
+pub struct AsyncFileOutput {
+    file_handle: Arc<Mutex<FileSink>>,
+}
+
+// Implementation of AsyncFileOutput
+impl AsyncFileOutput {
+    #[allow(dead_code)]
+    pub fn new(file_path: &str, options: FileWriteOptions) -> Result<Self, String> {
+        Ok(AsyncFileOutput {
+            file_handle: FileSink::open_shared(file_path, options)?,
+        })
+    }
+    
+    fn flush(&mut self) -> Result<(), String> {
+        let mut file = self.file_handle.lock()
+            .map_err(|_| "Failed to lock file mutex".to_string())?;
+        file.flush()
+    }
+}
+
+#[async_trait]
+impl AsyncLogOutputTrait for AsyncFileOutput {
+    async fn write_log_async(&mut self, formatted_message: &str) -> Result<(), String> {
+        let mut file = self.file_handle.lock()
+            .map_err(|_| "Failed to lock file mutex".to_string())?;
+        file.write_lines(std::iter::once(formatted_message), false)
+    }
+
+    async fn write_batch_async(&mut self, entries: &[(LogLevel, String)]) -> Result<(), String> {
+        // One write (and at most one flush) for the whole batch
+        let mut file = self.file_handle.lock()
+            .map_err(|_| "Failed to lock file mutex".to_string())?;
+        let has_error = entries.iter().any(|(level, _)| *level == LogLevel::Error);
+        file.write_lines(entries.iter().map(|(_, line)| line.as_str()), has_error)
+    }
+
+    async fn flush_async(&mut self) -> Result<(), String> {
+        self.flush()
+    }
+}
+
+/// Open a file once and return sync and async outputs sharing the handle
+pub fn create_file_output(file_path: &str, options: FileWriteOptions) -> Result<(FileOutput, AsyncFileOutput), String> {
+    let file_handle = FileSink::open_shared(file_path, options)?;
+    
+    let file_output = FileOutput {
+        file_handle: Arc::clone(&file_handle),
+    };
+    
+    let async_file_output = AsyncFileOutput {
+        file_handle,
+    };
+    
+    Ok((file_output, async_file_output))
+}
+
+/// Version of the JSON log record, sent as its `"v"` field
+///
+/// Bumped whenever fields are renamed, removed or change meaning, so
+/// downstream processors can tell record shapes apart.
+pub const SCHEMA_VERSION: u32 = 1;
+
+// One JSON log record, as written in JSON format and posted to HTTP endpoints
+#[derive(Serialize, Deserialize)]
+pub(crate) struct LogPayload<'a> {
+    pub v: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seq: Option<u64>,
+    #[serde(borrow)]
+    pub timestamp: Cow<'a, str>,
+    #[serde(borrow)]
+    pub level: Cow<'a, str>,
+    #[serde(borrow)]
+    pub message: Cow<'a, str>,
+    // Both left out when `include_location` is off
+    #[serde(borrow, default, skip_serializing_if = "str::is_empty")]
+    pub file: Cow<'a, str>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub line: Option<u32>,
+    #[serde(borrow)]
+    pub module: Cow<'a, str>,
+    #[serde(borrow, skip_serializing_if = "Option::is_none")]
+    pub context: Option<PayloadContext<'a>>,
+    // Fields from `Logger::with_fields()`, as top-level keys
+    #[serde(flatten)]
+    pub fields: Cow<'a, BTreeMap<String, String>>,
+}
+
+// The "context" of a JSON record: a string, or an object with `structured_context`
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+pub(crate) enum PayloadContext<'a> {
+    Text(#[serde(borrow)] Cow<'a, str>),
+    Pairs(Cow<'a, BTreeMap<String, String>>),
+}
+
+// A record written with the keys renamed by `json_field_map`
+//
+// Lists the fields in the same order as the derived Serialize of LogPayload.
+pub(crate) struct RenamedPayload<'a> {
+    pub payload: &'a LogPayload<'a>,
+    pub names: &'a BTreeMap<String, String>,
+}
+
+impl Serialize for RenamedPayload<'_> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeMap;
+        
+        let name = |field: &'static str| self.names.get(field).map_or(field, String::as_str);
+        let payload = self.payload;
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry(name("v"), &payload.v)?;
+        if let Some(seq) = payload.seq {
+            map.serialize_entry(name("seq"), &seq)?;
+        }
+        map.serialize_entry(name("timestamp"), &payload.timestamp)?;
+        map.serialize_entry(name("level"), &payload.level)?;
+        map.serialize_entry(name("message"), &payload.message)?;
+        if !payload.file.is_empty() {
+            map.serialize_entry(name("file"), &payload.file)?;
+        }
+        if let Some(line) = payload.line {
+            map.serialize_entry(name("line"), &line)?;
+        }
+        map.serialize_entry(name("module"), &payload.module)?;
+        if let Some(context) = &payload.context {
+            map.serialize_entry(name("context"), context)?;
+        }
+        for (key, value) in payload.fields.iter() {
+            map.serialize_entry(key, value)?;
+        }
+        map.end()
+    }
+}
+
+/// Keys of the JSON record that scoped fields may not replace
+pub(crate) const RESERVED_JSON_KEYS: &[&str] = &["v", "seq", "timestamp", "level", "message", "file", "line", "module", "context"];
+
+// HTTP output implementation - updated to support async operations
+pub struct HttpOutput {
+    blocking_client: BlockingClient,
+    async_client: Client,
+    endpoint: String,
+    extra_fields: BTreeMap<String, String>,
+}
+
+impl HttpOutput {
+    pub fn new(endpoint: &str, timeout_seconds: u64) -> Result<Self, String> {
+        let blocking_client = BlockingClient::builder()
+            .timeout(Duration::from_secs(timeout_seconds))
+            .build()
+            .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+            
+        let async_client = Client::builder()
+            .timeout(Duration::from_secs(timeout_seconds))
+            .build()
+            .map_err(|e| format!("Failed to create async HTTP client: {}", e))?;
+            
+        Ok(HttpOutput {
+            blocking_client,
+            async_client,
+            endpoint: endpoint.to_string(),
+            extra_fields: BTreeMap::new(),
+        })
+    }
+
+    /// Add constant fields to every JSON record, unless the record has the key
+    pub fn with_extra_fields(mut self, extra_fields: BTreeMap<String, String>) -> Self {
+        self.extra_fields = extra_fields;
+        self
+    }
+
+    // Merge the extra fields into a record; built-in keys and the record's own fields win
+    fn add_extra_fields(&self, payload: &mut LogPayload) {
+        for (key, value) in &self.extra_fields {
+            if !RESERVED_JSON_KEYS.contains(&key.as_str()) && !payload.fields.contains_key(key) {
+                payload.fields.to_mut().insert(key.clone(), value.clone());
+            }
+        }
+    }
+
+    // The JSON body for a line, or None to post it as plain text
+    //
+    // Records with keys renamed by `json_field_map` don't parse as a
+    // LogPayload, so any other JSON object is forwarded as an object too.
+    fn json_body(&self, formatted_message: &str) -> Option<String> {
+        if let Ok(mut payload) = serde_json::from_str::<LogPayload>(formatted_message) {
+            self.add_extra_fields(&mut payload);
+            return serde_json::to_string(&payload).ok();
+        }
+        let mut record = serde_json::from_str::<serde_json::Map<String, serde_json::Value>>(formatted_message).ok()?;
+        for (key, value) in &self.extra_fields {
+            if !RESERVED_JSON_KEYS.contains(&key.as_str()) && !record.contains_key(key) {
+                record.insert(key.clone(), serde_json::Value::String(value.clone()));
+            }
+        }
+        serde_json::to_string(&record).ok()
+    }
+}
+
+impl LogOutput for HttpOutput {
+    fn write_log(&mut self, formatted_message: &str) -> Result<(), String> {
+        // JSON payloads are forwarded as JSON, anything else as plain text
+        let request = self.blocking_client.post(&self.endpoint);
+        let request = match self.json_body(formatted_message) {
+            Some(body) => request
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .body(body),
+            None => request
+                .header(reqwest::header::CONTENT_TYPE, "text/plain; charset=utf-8")
+                .body(formatted_message.to_string()),
+        };
+        
+        match request.send() {
+            Ok(response) => {
+                if !response.status().is_success() {
+                    return Err(format!("HTTP log failed with status: {}", response.status()));
+                }
+            },
+            Err(e) => {
+                return Err(format!("Failed to send HTTP log: {}", e));
+            }
+        }
+        
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl AsyncLogOutputTrait for HttpOutput {
+    async fn write_log_async(&mut self, formatted_message: &str) -> Result<(), String> {
+        // JSON payloads are forwarded as JSON, anything else as plain text
+        let request = self.async_client.post(&self.endpoint);
+        let request = match self.json_body(formatted_message) {
+            Some(body) => request
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .body(body),
+            None => request
+                .header(reqwest::header::CONTENT_TYPE, "text/plain; charset=utf-8")
+                .body(formatted_message.to_string()),
+        };
+        
+        let response = match request.send().await {
+                Ok(resp) => resp,
+                Err(e) => return Err(format!("Failed to send HTTP log: {}", e))
+            };
+        
+        if !response.status().is_success() {
+            return Err(format!("HTTP log failed with status: {}", response.status()));
+        }
+        
+        Ok(())
+    }
+}
+
+/// Output writing newline-delimited lines to a Unix domain socket
+///
+/// Selected with `LogType::UnixSocket`, for log collectors such as Vector or
+/// Fluent Bit that listen on a socket. Lines are sent in the configured
+/// format. The socket is connected on the first write, so the collector may
+/// start after the application; a failed write reconnects and resends once.
+#[cfg(unix)]
+pub struct UnixSocketOutput {
+    socket_path: String,
+    stream: Option<std::os::unix::net::UnixStream>,
+}
+
+#[cfg(unix)]
+impl UnixSocketOutput {
+    pub fn new(socket_path: &str) -> Self {
+        UnixSocketOutput {
+            socket_path: socket_path.to_string(),
+            stream: None,
+        }
+    }
+    
+    fn send(&mut self, bytes: &[u8]) -> Result<(), String> {
+        if let Some(stream) = self.stream.as_mut() {
+            if stream.write_all(bytes).is_ok() {
+                return Ok(());
+            }
+            // The collector went away; reconnect and resend below
+            self.stream = None;
+        }
+        
+        let mut stream = std::os::unix::net::UnixStream::connect(&self.socket_path)
+            .map_err(|e| format!("Failed to connect to log socket '{}': {}", self.socket_path, e))?;
+        stream.write_all(bytes)
+            .map_err(|e| format!("Failed to write to log socket '{}': {}", self.socket_path, e))?;
+        self.stream = Some(stream);
+        Ok(())
+    }
+}
+
+#[cfg(unix)]
+impl LogOutput for UnixSocketOutput {
+    fn write_log(&mut self, formatted_message: &str) -> Result<(), String> {
+        self.send(format!("{}\n", formatted_message).as_bytes())
+    }
+}
+
+/// Asynchronous Unix socket output, written to by the background task
+#[cfg(unix)]
+pub struct AsyncUnixSocketOutput {
+    socket_path: String,
+    stream: Option<tokio::net::UnixStream>,
+}
+
+#[cfg(unix)]
+impl AsyncUnixSocketOutput {
+    pub fn new(socket_path: &str) -> Self {
+        AsyncUnixSocketOutput {
+            socket_path: socket_path.to_string(),
+            stream: None,
+        }
+    }
+    
+    async fn send(&mut self, bytes: &[u8]) -> Result<(), String> {
+        if let Some(stream) = self.stream.as_mut() {
+            if stream.write_all(bytes).await.is_ok() {
+                return Ok(());
+            }
+            // The collector went away; reconnect and resend below
+            self.stream = None;
+        }
+        
+        let mut stream = tokio::net::UnixStream::connect(&self.socket_path).await
+            .map_err(|e| format!("Failed to connect to log socket '{}': {}", self.socket_path, e))?;
+        stream.write_all(bytes).await
+            .map_err(|e| format!("Failed to write to log socket '{}': {}", self.socket_path, e))?;
+        self.stream = Some(stream);
+        Ok(())
+    }
+}
+
+#[cfg(unix)]
+#[async_trait]
+impl AsyncLogOutputTrait for AsyncUnixSocketOutput {
+    async fn write_log_async(&mut self, formatted_message: &str) -> Result<(), String> {
+        self.send(format!("{}\n", formatted_message).as_bytes()).await
+    }
+
+    async fn write_batch_async(&mut self, entries: &[(LogLevel, String)]) -> Result<(), String> {
+        // One write for the whole batch
+        let mut bytes = Vec::new();
+        for (_, formatted_message) in entries {
+            bytes.extend_from_slice(formatted_message.as_bytes());
+            bytes.push(b'\n');
+        }
+        self.send(&bytes).await
+    }
+}
+
+/// Output that discards every line
+///
+/// Selected with `LogType::Null`. Messages still pass the threshold checks
+/// and the background channel, so only the final write is skipped.
+pub struct NullOutput;
+
+impl LogOutput for NullOutput {
+    fn write_log(&mut self, _formatted_message: &str) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl AsyncLogOutputTrait for NullOutput {
+    async fn write_log_async(&mut self, _formatted_message: &str) -> Result<(), String> {
+        Ok(())
+    }
+
+    async fn write_batch_async(&mut self, _entries: &[(LogLevel, String)]) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+// Implement AsyncLogOutputTrait for the AsyncLogOutput enum
+#[async_trait]
+impl AsyncLogOutputTrait for AsyncLogOutput {
+    async fn write_log_async(&mut self, formatted_message: &str) -> Result<(), String> {
+        match self {
+            AsyncLogOutput::Console(output) => output.write_log_async(formatted_message).await,
+            AsyncLogOutput::File(output) => output.write_log_async(formatted_message).await,
+            AsyncLogOutput::Http(output) => output.write_log_async(formatted_message).await,
+            #[cfg(unix)]
+            AsyncLogOutput::UnixSocket(output) => output.write_log_async(formatted_message).await,
+            AsyncLogOutput::Null(output) => output.write_log_async(formatted_message).await,
+            AsyncLogOutput::Composite(outputs) => {
+                let mut errors = Vec::new();
+                for (_, output) in outputs.iter_mut() {
+                    if let Err(e) = output.write_log_async(formatted_message).await {
+                        errors.push(e);
+                    }
+                }
+                join_errors(errors)
+            },
+        }
+    }
+
+    async fn write_batch_async(&mut self, entries: &[(LogLevel, String)]) -> Result<(), String> {
+        match self {
+            AsyncLogOutput::Console(output) => output.write_batch_async(entries).await,
+            AsyncLogOutput::File(output) => output.write_batch_async(entries).await,
+            AsyncLogOutput::Http(output) => output.write_batch_async(entries).await,
+            #[cfg(unix)]
+            AsyncLogOutput::UnixSocket(output) => output.write_batch_async(entries).await,
+            AsyncLogOutput::Null(output) => output.write_batch_async(entries).await,
+            // Each child receives the lines at or above its own threshold
+            AsyncLogOutput::Composite(outputs) => {
+                let mut errors = Vec::new();
+                for (threshold, output) in outputs.iter_mut() {
+                    let accepted: Vec<(LogLevel, String)> = entries.iter()
+                        .filter(|(level, _)| level_passes(level, threshold.as_ref()))
+                        .cloned()
+                        .collect();
+                    if accepted.is_empty() {
+                        continue;
+                    }
+                    if let Err(e) = output.write_batch_async(&accepted).await {
+                        errors.push(e);
+                    }
+                }
+                join_errors(errors)
+            },
+        }
+    }
+
+    async fn flush_async(&mut self) -> Result<(), String> {
+        match self {
+            AsyncLogOutput::File(output) => output.flush_async().await,
+            AsyncLogOutput::Console(_) | AsyncLogOutput::Http(_) | AsyncLogOutput::Null(_) => Ok(()),
+            #[cfg(unix)]
+            AsyncLogOutput::UnixSocket(_) => Ok(()),
+            AsyncLogOutput::Composite(outputs) => {
+                let mut errors = Vec::new();
+                for (_, output) in outputs.iter_mut() {
+                    if let Err(e) = output.flush_async().await {
+                        errors.push(e);
+                    }
+                }
+                join_errors(errors)
+            },
+        }
+    }
+}
+
+/// Forwards each message to several outputs
+///
+/// Built from `LogConfig::outputs`. A child with a threshold only receives
+/// messages at or above it; one failing child does not stop the others.
+pub struct CompositeOutput {
+    outputs: Vec<(Option<LogLevel>, Box<dyn LogOutput>)>,
+}
+
+impl LogOutput for CompositeOutput {
+    fn write_log(&mut self, formatted_message: &str) -> Result<(), String> {
+        let mut errors = Vec::new();
+        for (_, output) in self.outputs.iter_mut() {
+            if let Err(e) = output.write_log(formatted_message) {
+                errors.push(e);
+            }
+        }
+        join_errors(errors)
+    }
+
+    fn write_entry(&mut self, level: &LogLevel, formatted_message: &str) -> Result<(), String> {
+        let mut errors = Vec::new();
+        for (threshold, output) in self.outputs.iter_mut() {
+            if !level_passes(level, threshold.as_ref()) {
+                continue;
+            }
+            if let Err(e) = output.write_entry(level, formatted_message) {
+                errors.push(e);
+            }
+        }
+        join_errors(errors)
+    }
+
+    fn flush(&mut self) -> Result<(), String> {
+        let errors = self.outputs.iter_mut()
+            .filter_map(|(_, output)| output.flush().err())
+            .collect();
+        join_errors(errors)
+    }
+}
+
+// Whether a message at `level` reaches an output with the given threshold
+fn level_passes(level: &LogLevel, threshold: Option<&LogLevel>) -> bool {
+    threshold.is_none_or(|threshold| level.should_log(threshold))
+}
+
+// Combine the errors of several outputs into one
+fn join_errors(errors: Vec<String>) -> Result<(), String> {
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors.join("; "))
+    }
+}
+
+/// Creates the synchronous log output for a configuration, and the
+/// asynchronous one too when `with_async` is set
+///
+/// A file is opened once and both outputs write through the same handle,
+/// so lines written synchronously stay in order with the background task's.
+pub fn create_log_outputs(config: &LogConfig, with_async: bool) -> Result<(Box<dyn LogOutput>, Option<AsyncLogOutput>), String> {
+    if !config.outputs.is_empty() {
+        let mut outputs = Vec::new();
+        let mut async_outputs = Vec::new();
+        for spec in &config.outputs {
+            let (output, async_output) = create_single_log_outputs(&config.for_output(spec), with_async)?;
+            outputs.push((spec.threshold.clone(), output));
+            async_outputs.extend(async_output.map(|output| (spec.threshold.clone(), output)));
+        }
+        let async_output = with_async.then_some(AsyncLogOutput::Composite(async_outputs));
+        return Ok((Box::new(CompositeOutput { outputs }), async_output));
+    }
+    
+    create_single_log_outputs(config, with_async)
+}
+
+// Outputs for the single `log_type` of a configuration
+fn create_single_log_outputs(config: &LogConfig, with_async: bool) -> Result<(Box<dyn LogOutput>, Option<AsyncLogOutput>), String> {
+    // Pair a sync output with an async one, built only when wanted
+    let pair = |output: Box<dyn LogOutput>, async_output: &dyn Fn() -> Result<AsyncLogOutput, String>| {
+        Ok((output, if with_async { Some(async_output()?) } else { None }))
+    };
+    match config.log_type {
+        LogType::Console => {
+            let console = || ConsoleOutput::new(config.color, config.console_stream, config.uses_text_layout());
+            pair(Box::new(console()), &|| Ok(AsyncLogOutput::Console(console())))
+        },
+        LogType::Null => pair(Box::new(NullOutput), &|| Ok(AsyncLogOutput::Null(NullOutput))),
+        LogType::File => {
+            // Get file path and combine with log folder if specified
+            let file_path = config.file_path.as_ref()
+                .ok_or_else(|| "File path not specified in configuration".to_string())?;
+                
+            // Construct the full path using the log_folder if provided
+            let full_path = if let Some(folder) = &config.log_folder {
+                // Create the log directory if it doesn't exist
+                std::fs::create_dir_all(folder)
+                    .map_err(|e| format!("Failed to create log directory '{}': {}", folder, e))?;
+                
+                // Use platform-specific path separator
+                let path = Path::new(folder).join(file_path);
+                path.to_string_lossy().into_owned()
+            } else {
+                file_path.clone()
+            };
+            
+            status!("Creating log file at: {}", full_path);
+            
+            let (file_output, async_file_output) = create_file_output(&full_path, FileWriteOptions::from_config(config))?;
+            Ok((Box::new(file_output), with_async.then_some(AsyncLogOutput::File(async_file_output))))
+        },
+        LogType::Http => {
+            let endpoint = &config.http_endpoint.as_ref().ok_or_else(|| 
+                "HTTP endpoint not specified in configuration".to_string())?;
+            let timeout = config.http_timeout_seconds.unwrap_or(30);
+            let http = || Ok::<_, String>(HttpOutput::new(endpoint, timeout)?.with_extra_fields(config.http_extra_fields.clone()));
+            pair(Box::new(http()?), &|| Ok(AsyncLogOutput::Http(http()?)))
+        },
+        #[cfg(unix)]
+        LogType::UnixSocket => {
+            let socket_path = socket_path(config)?;
+            pair(Box::new(UnixSocketOutput::new(socket_path)), &|| Ok(AsyncLogOutput::UnixSocket(AsyncUnixSocketOutput::new(socket_path))))
+        },
+        #[cfg(not(unix))]
+        LogType::UnixSocket => Err(unix_socket_unsupported()),
+    }
+}
+
+#[cfg(unix)]
+fn socket_path(config: &LogConfig) -> Result<&str, String> {
+    config.socket_path.as_deref()
+        .ok_or_else(|| "Socket path not specified in configuration".to_string())
+}
+
+#[cfg(not(unix))]
+fn unix_socket_unsupported() -> String {
+    "Unix socket output is only available on Unix platforms".to_string()
+}
@@ -0,0 +1,69 @@
+/*
+ * Progress lines for `log_progress!`
+ *
+ * Each expansion of the macro keeps a `ProgressSite` in a static, holding
+ * when the site was first reached and the last count it saw. A count lower
+ * than the last one means the loop started over, so the clock restarts and
+ * the ETA of a second run doesn't include the first. The ETA assumes the
+ * remaining items take as long on average as the ones processed so far.
+ */
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Duration as shown in log lines: µs under a millisecond, ms under a
+/// second, s under a minute, and minutes and seconds beyond
+pub fn format_duration(duration: Duration) -> String {
+    let micros = duration.as_micros();
+    if micros < 1_000 {
+        format!("{}µs", micros)
+    } else if micros < 1_000_000 {
+        format!("{:.2}ms", duration.as_secs_f64() * 1000.0)
+    } else if micros < 60_000_000 {
+        format!("{:.2}s", duration.as_secs_f64())
+    } else {
+        let secs = duration.as_secs();
+        format!("{}m {}s", secs / 60, secs % 60)
+    }
+}
+
+/// State of one `log_progress!` call site
+#[derive(Debug, Default)]
+pub struct ProgressSite {
+    // When the current run started, and the last count seen in it
+    run: Mutex<Option<(Instant, u64)>>,
+}
+
+impl ProgressSite {
+    pub const fn new() -> Self {
+        ProgressSite { run: Mutex::new(None) }
+    }
+
+    /// The line to log for `current` of `total` items, if one is due
+    ///
+    /// A line is due every `every` items and for the last one. `label`
+    /// replaces the "Progress" prefix.
+    pub fn update(&self, label: &str, current: u64, total: u64, every: u64) -> Option<String> {
+        let started = {
+            let mut run = self.run.lock().unwrap_or_else(|e| e.into_inner());
+            let started = match *run {
+                Some((started, last)) if current >= last => started,
+                _ => Instant::now(),
+            };
+            *run = Some((started, current));
+            started
+        };
+        if current == 0 || (!current.is_multiple_of(every.max(1)) && current < total) {
+            return None;
+        }
+
+        let elapsed = started.elapsed();
+        let percent = if total == 0 { 100.0 } else { current.min(total) as f64 * 100.0 / total as f64 };
+        if current >= total {
+            return Some(format!("{}: {}/{} ({:.1}%), done in {}", label, current, total, percent, format_duration(elapsed)));
+        }
+        let eta = elapsed.mul_f64((total - current) as f64 / current as f64);
+        Some(format!("{}: {}/{} ({:.1}%), elapsed {}, ETA {}",
+            label, current, total, percent, format_duration(elapsed), format_duration(eta)))
+    }
+}
@@ -0,0 +1,229 @@
+/*
+ * Pluggable providers for the attribute macros
+ *
+ * The procedural macros in liblogger_macros need information that only the
+ * application can supply (feature flag state, infrastructure metrics, ...).
+ * This module defines the traits applications implement to provide it and a
+ * process-global registry the macro-generated code consults at runtime.
+ *
+ * When no provider is registered, a conservative default is used: flags are
+ * disabled, disk metrics are measured for the log folder's filesystem, and
+ * the other metrics report fixed sample values.
+ */
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::RwLock;
+
+use crate::system;
+
+/// Source of feature flag state for the `#[feature_flag]` attribute
+pub trait FeatureFlagProvider: Send + Sync {
+    /// Whether the named flag is currently enabled
+    fn is_enabled(&self, flag: &str) -> bool;
+}
+
+/// A static map of flag names to their state
+impl FeatureFlagProvider for HashMap<String, bool> {
+    fn is_enabled(&self, flag: &str) -> bool {
+        self.get(flag).copied().unwrap_or(false)
+    }
+}
+
+/// Disk space of the filesystem being monitored, for `#[log_disk_usage]`
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiskInfo {
+    pub total_space_gb: f64,
+    pub used_space_gb: f64,
+    pub available_space_gb: f64,
+    pub used_percentage: f64,
+    pub filesystem: String,
+    pub mount_point: String,
+}
+
+/// Source of disk metrics for the `#[log_disk_usage]` attribute
+pub trait DiskMetricsProvider: Send + Sync {
+    fn disk_info(&self) -> DiskInfo;
+}
+
+/// Hit/miss counters of a named cache, for `#[log_cache_hit_ratio]`
+#[derive(Debug, Clone, PartialEq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub hit_ratio_percentage: f64,
+    pub total_entries: u64,
+    pub memory_usage_mb: f64,
+    pub evictions: u64,
+}
+
+/// Source of cache metrics for the `#[log_cache_hit_ratio]` attribute
+pub trait CacheMetricsProvider: Send + Sync {
+    fn cache_stats(&self, cache_name: &str) -> CacheStats;
+}
+
+/// Backlog and throughput of a named queue, for `#[log_queue_depth]`
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueueStats {
+    pub depth: u64,
+    pub processing_rate: f64,
+    pub avg_processing_time_ms: f64,
+    pub total_processed: u64,
+    pub failed_messages: u64,
+}
+
+/// Source of queue metrics for the `#[log_queue_depth]` attribute
+pub trait QueueMetricsProvider: Send + Sync {
+    fn queue_stats(&self, queue_name: &str) -> QueueStats;
+}
+
+/// Outcome of a data domain's validation rules, for `#[log_data_quality]`
+#[derive(Debug, Clone, PartialEq)]
+pub struct DataQualityReport {
+    pub quality_score_percentage: f64,
+    pub records_processed: u64,
+    pub total_validation_rules: u32,
+    /// Names of the rules that failed; the rest passed
+    pub failed_rules: Vec<String>,
+    pub data_completeness: f64,
+    pub data_accuracy: f64,
+}
+
+impl DataQualityReport {
+    /// Number of rules that passed
+    pub fn validation_rules_passed(&self) -> u32 {
+        self.total_validation_rules.saturating_sub(self.failed_rules.len() as u32)
+    }
+}
+
+/// Source of validation results for the `#[log_data_quality]` attribute
+pub trait DataQualityProvider: Send + Sync {
+    fn data_quality(&self, domain: &str) -> DataQualityReport;
+}
+
+/// Fixed sample values used until the application registers real providers,
+/// and for disk metrics on platforms that cannot be measured
+struct SimulatedMetrics;
+
+impl DiskMetricsProvider for SimulatedMetrics {
+    fn disk_info(&self) -> DiskInfo {
+        DiskInfo {
+            total_space_gb: 500.0,
+            used_space_gb: 300.0,
+            available_space_gb: 200.0,
+            used_percentage: 60.0,
+            filesystem: "ext4".to_string(),
+            mount_point: "/".to_string(),
+        }
+    }
+}
+
+impl CacheMetricsProvider for SimulatedMetrics {
+    fn cache_stats(&self, _cache_name: &str) -> CacheStats {
+        CacheStats {
+            hits: 850,
+            misses: 150,
+            hit_ratio_percentage: 85.0,
+            total_entries: 10000,
+            memory_usage_mb: 256.0,
+            evictions: 10,
+        }
+    }
+}
+
+impl QueueMetricsProvider for SimulatedMetrics {
+    fn queue_stats(&self, _queue_name: &str) -> QueueStats {
+        QueueStats {
+            depth: 150,
+            processing_rate: 25.5,
+            avg_processing_time_ms: 100.0,
+            total_processed: 10000,
+            failed_messages: 5,
+        }
+    }
+}
+
+impl DataQualityProvider for SimulatedMetrics {
+    fn data_quality(&self, _domain: &str) -> DataQualityReport {
+        DataQualityReport {
+            quality_score_percentage: 96.5,
+            records_processed: 10000,
+            total_validation_rules: 20,
+            failed_rules: vec!["sample_rule_19".to_string(), "sample_rule_20".to_string()],
+            data_completeness: 98.0,
+            data_accuracy: 95.0,
+        }
+    }
+}
+
+static FEATURE_FLAG_PROVIDER: RwLock<Option<Box<dyn FeatureFlagProvider>>> = RwLock::new(None);
+static DISK_PROVIDER: RwLock<Option<Box<dyn DiskMetricsProvider>>> = RwLock::new(None);
+static CACHE_PROVIDER: RwLock<Option<Box<dyn CacheMetricsProvider>>> = RwLock::new(None);
+static QUEUE_PROVIDER: RwLock<Option<Box<dyn QueueMetricsProvider>>> = RwLock::new(None);
+static DATA_QUALITY_PROVIDER: RwLock<Option<Box<dyn DataQualityProvider>>> = RwLock::new(None);
+
+// Install a provider in a registry slot, replacing any previous one
+fn register<T: ?Sized>(slot: &RwLock<Option<Box<T>>>, provider: Box<T>) {
+    *slot.write().unwrap_or_else(|e| e.into_inner()) = Some(provider);
+}
+
+// Query the provider in a registry slot, if one is registered
+fn query<T: ?Sized, R>(slot: &RwLock<Option<Box<T>>>, f: impl FnOnce(&T) -> R) -> Option<R> {
+    slot.read().unwrap_or_else(|e| e.into_inner()).as_deref().map(f)
+}
+
+/// Register the global feature flag provider, replacing any previous one
+pub(crate) fn set_feature_flag_provider(provider: Box<dyn FeatureFlagProvider>) {
+    register(&FEATURE_FLAG_PROVIDER, provider);
+}
+
+/// Check a flag against the registered provider; disabled when none is set
+pub(crate) fn is_feature_enabled(flag: &str) -> bool {
+    query(&FEATURE_FLAG_PROVIDER, |provider| provider.is_enabled(flag)).unwrap_or(false)
+}
+
+/// Register the global disk metrics provider, replacing any previous one
+pub(crate) fn set_disk_provider(provider: Box<dyn DiskMetricsProvider>) {
+    register(&DISK_PROVIDER, provider);
+}
+
+/// Disk metrics from the registered provider, otherwise measured for the
+/// filesystem holding `default_path`, or simulated where that is unsupported
+pub(crate) fn disk_info(default_path: &Path) -> DiskInfo {
+    query(&DISK_PROVIDER, |provider| provider.disk_info())
+        .or_else(|| system::disk_info(default_path))
+        .unwrap_or_else(|| SimulatedMetrics.disk_info())
+}
+
+/// Register the global cache metrics provider, replacing any previous one
+pub(crate) fn set_cache_provider(provider: Box<dyn CacheMetricsProvider>) {
+    register(&CACHE_PROVIDER, provider);
+}
+
+/// Cache metrics from the registered provider, or simulated values
+pub(crate) fn cache_stats(cache_name: &str) -> CacheStats {
+    query(&CACHE_PROVIDER, |provider| provider.cache_stats(cache_name))
+        .unwrap_or_else(|| SimulatedMetrics.cache_stats(cache_name))
+}
+
+/// Register the global queue metrics provider, replacing any previous one
+pub(crate) fn set_queue_provider(provider: Box<dyn QueueMetricsProvider>) {
+    register(&QUEUE_PROVIDER, provider);
+}
+
+/// Queue metrics from the registered provider, or simulated values
+pub(crate) fn queue_stats(queue_name: &str) -> QueueStats {
+    query(&QUEUE_PROVIDER, |provider| provider.queue_stats(queue_name))
+        .unwrap_or_else(|| SimulatedMetrics.queue_stats(queue_name))
+}
+
+/// Register the global data quality provider, replacing any previous one
+pub(crate) fn set_data_quality_provider(provider: Box<dyn DataQualityProvider>) {
+    register(&DATA_QUALITY_PROVIDER, provider);
+}
+
+/// Validation results from the registered provider, or simulated values
+pub(crate) fn data_quality(domain: &str) -> DataQualityReport {
+    query(&DATA_QUALITY_PROVIDER, |provider| provider.data_quality(domain))
+        .unwrap_or_else(|| SimulatedMetrics.data_quality(domain))
+}
@@ -0,0 +1,50 @@
+/*
+ * Ring buffer of the most recent log lines, for post-mortem debugging
+ *
+ * Messages are recorded here before the threshold is checked, so the lines
+ * returned by `Logger::recent_logs()` and dumped by the panic hook include
+ * DEBUG lines the configured outputs filtered out. The buffer holds
+ * `ring_buffer_size` lines; 0 disables it.
+ */
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+// Lines kept; 0 while the buffer is disabled
+static CAPACITY: AtomicUsize = AtomicUsize::new(0);
+
+static LINES: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
+
+/// Resize the buffer, dropping the oldest lines that no longer fit
+pub(crate) fn set_capacity(capacity: usize) {
+    CAPACITY.store(capacity, Ordering::Relaxed);
+    let mut lines = LINES.lock().unwrap_or_else(|e| e.into_inner());
+    while lines.len() > capacity {
+        lines.pop_front();
+    }
+}
+
+/// Whether lines are being recorded
+pub(crate) fn is_enabled() -> bool {
+    CAPACITY.load(Ordering::Relaxed) > 0
+}
+
+/// Record a formatted line, evicting the oldest one when full
+pub(crate) fn push(line: String) {
+    let capacity = CAPACITY.load(Ordering::Relaxed);
+    if capacity == 0 {
+        return;
+    }
+    let mut lines = LINES.lock().unwrap_or_else(|e| e.into_inner());
+    while lines.len() >= capacity {
+        lines.pop_front();
+    }
+    lines.push_back(line);
+}
+
+/// The recorded lines, oldest first
+pub(crate) fn lines() -> Vec<String> {
+    let lines = LINES.lock().unwrap_or_else(|e| e.into_inner());
+    lines.iter().cloned().collect()
+}
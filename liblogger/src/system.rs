@@ -0,0 +1,242 @@
+/*
+ * Measurements of the host system used by the default metrics providers
+ *
+ * This module implements:
+ * - Disk space of the filesystem holding a path, via statvfs on Unix and
+ *   GetDiskFreeSpaceExW on Windows
+ * - Filesystem type and mount point lookup from /proc/self/mounts on Linux
+ * - Open file descriptors of this process (/proc/self/fd on Linux,
+ *   proc_pidinfo on macOS, GetProcessHandleCount on Windows) and the
+ *   RLIMIT_NOFILE soft limit on Unix
+ * - The host name, via gethostname on Unix and %COMPUTERNAME% on Windows
+ *
+ * Every function returns None when the platform is unsupported or the
+ * system call fails, so callers can fall back to sample values.
+ */
+
+use std::path::{Path, PathBuf};
+
+use crate::providers::DiskInfo;
+
+const BYTES_PER_GB: f64 = 1024.0 * 1024.0 * 1024.0;
+
+/// Disk space of the filesystem that holds `path`
+///
+/// A path that does not exist yet (such as a log folder created on first
+/// write) is measured through its closest existing ancestor.
+pub(crate) fn disk_info(path: &Path) -> Option<DiskInfo> {
+    let path = existing_ancestor(path)?;
+    let (total, available, free) = disk_space(&path)?;
+    if total == 0 {
+        return None;
+    }
+    
+    // Like df, report usage against the space available to unprivileged users
+    let used = total.saturating_sub(free);
+    let used_percentage = if used + available == 0 {
+        0.0
+    } else {
+        used as f64 / (used + available) as f64 * 100.0
+    };
+    let (filesystem, mount_point) = mount_info(&path)
+        .unwrap_or_else(|| ("unknown".to_string(), mount_root(&path)));
+    
+    Some(DiskInfo {
+        total_space_gb: total as f64 / BYTES_PER_GB,
+        used_space_gb: used as f64 / BYTES_PER_GB,
+        available_space_gb: available as f64 / BYTES_PER_GB,
+        used_percentage,
+        filesystem,
+        mount_point,
+    })
+}
+
+// Canonical form of `path` or of the nearest ancestor that exists
+fn existing_ancestor(path: &Path) -> Option<PathBuf> {
+    let absolute = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        std::env::current_dir().ok()?.join(path)
+    };
+    absolute.ancestors().find_map(|ancestor| ancestor.canonicalize().ok())
+}
+
+// Root component of `path`, used as the mount point when it is not known
+fn mount_root(path: &Path) -> String {
+    path.ancestors()
+        .last()
+        .unwrap_or(path)
+        .to_string_lossy()
+        .into_owned()
+}
+
+// (total, available to the caller, free) in bytes
+#[cfg(unix)]
+#[allow(clippy::unnecessary_cast)]
+fn disk_space(path: &Path) -> Option<(u64, u64, u64)> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+    
+    let c_path = CString::new(path.as_os_str().as_bytes()).ok()?;
+    // SAFETY: statvfs only writes to the zero-initialized struct we pass in
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) } != 0 {
+        return None;
+    }
+    
+    let fragment_size = stat.f_frsize as u64;
+    Some((
+        stat.f_blocks as u64 * fragment_size,
+        stat.f_bavail as u64 * fragment_size,
+        stat.f_bfree as u64 * fragment_size,
+    ))
+}
+
+// (total, available to the caller, free) in bytes
+#[cfg(windows)]
+fn disk_space(path: &Path) -> Option<(u64, u64, u64)> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::Storage::FileSystem::GetDiskFreeSpaceExW;
+    
+    let wide: Vec<u16> = path.as_os_str().encode_wide().chain(Some(0)).collect();
+    let (mut available, mut total, mut free) = (0u64, 0u64, 0u64);
+    // SAFETY: `wide` is NUL-terminated and the out pointers are valid u64s
+    let ok = unsafe { GetDiskFreeSpaceExW(wide.as_ptr(), &mut available, &mut total, &mut free) };
+    if ok == 0 {
+        return None;
+    }
+    Some((total, available, free))
+}
+
+#[cfg(not(any(unix, windows)))]
+fn disk_space(_path: &Path) -> Option<(u64, u64, u64)> {
+    None
+}
+
+// (filesystem type, mount point) of the longest mount that contains `path`
+#[cfg(target_os = "linux")]
+fn mount_info(path: &Path) -> Option<(String, String)> {
+    let mounts = std::fs::read_to_string("/proc/self/mounts").ok()?;
+    mounts.lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let _device = fields.next()?;
+            // Spaces in mount points are escaped as \040
+            let mount_point = fields.next()?.replace("\\040", " ");
+            let filesystem = fields.next()?.to_string();
+            Some((filesystem, mount_point))
+        })
+        .filter(|(_, mount_point)| path.starts_with(mount_point))
+        .max_by_key(|(_, mount_point)| mount_point.len())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn mount_info(_path: &Path) -> Option<(String, String)> {
+    None
+}
+
+/// Number of file descriptors (handles on Windows) open in this process
+#[cfg(target_os = "linux")]
+pub(crate) fn open_file_descriptors() -> Option<u64> {
+    // Reading the directory holds one descriptor of its own
+    let entries = std::fs::read_dir("/proc/self/fd").ok()?.count() as u64;
+    Some(entries.saturating_sub(1))
+}
+
+/// Number of file descriptors (handles on Windows) open in this process
+#[cfg(target_os = "macos")]
+pub(crate) fn open_file_descriptors() -> Option<u64> {
+    let entry_size = std::mem::size_of::<libc::proc_fdinfo>();
+    // SAFETY: a null buffer only asks for the size needed to list every descriptor
+    let pid = unsafe { libc::getpid() };
+    let needed = unsafe { libc::proc_pidinfo(pid, libc::PROC_PIDLISTFDS, 0, std::ptr::null_mut(), 0) };
+    if needed <= 0 {
+        return None;
+    }
+    
+    let mut fds: Vec<libc::proc_fdinfo> = Vec::with_capacity(needed as usize / entry_size);
+    // SAFETY: the buffer holds `capacity` entries and its byte size is passed in
+    let written = unsafe {
+        libc::proc_pidinfo(
+            pid,
+            libc::PROC_PIDLISTFDS,
+            0,
+            fds.as_mut_ptr() as *mut libc::c_void,
+            (fds.capacity() * entry_size) as libc::c_int,
+        )
+    };
+    if written <= 0 {
+        return None;
+    }
+    Some(written as u64 / entry_size as u64)
+}
+
+/// Number of file descriptors (handles on Windows) open in this process
+#[cfg(windows)]
+pub(crate) fn open_file_descriptors() -> Option<u64> {
+    use windows_sys::Win32::System::Threading::{GetCurrentProcess, GetProcessHandleCount};
+    
+    let mut count = 0u32;
+    // SAFETY: GetCurrentProcess returns a pseudo handle that needs no closing
+    let ok = unsafe { GetProcessHandleCount(GetCurrentProcess(), &mut count) };
+    if ok == 0 {
+        return None;
+    }
+    Some(u64::from(count))
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+pub(crate) fn open_file_descriptors() -> Option<u64> {
+    None
+}
+
+/// Soft limit on open file descriptors for this process
+#[cfg(unix)]
+#[allow(clippy::unnecessary_cast)]
+pub(crate) fn file_descriptor_limit() -> Option<u64> {
+    // SAFETY: getrlimit only writes to the zero-initialized struct we pass in
+    let mut limit: libc::rlimit = unsafe { std::mem::zeroed() };
+    if unsafe { libc::getrlimit(libc::RLIMIT_NOFILE, &mut limit) } != 0 {
+        return None;
+    }
+    if limit.rlim_cur == libc::RLIM_INFINITY {
+        return None;
+    }
+    Some(limit.rlim_cur as u64)
+}
+
+/// Soft limit on open file descriptors for this process
+#[cfg(windows)]
+pub(crate) fn file_descriptor_limit() -> Option<u64> {
+    // Windows caps handles per process at 2^24 rather than via a tunable limit
+    Some(1 << 24)
+}
+
+#[cfg(not(any(unix, windows)))]
+pub(crate) fn file_descriptor_limit() -> Option<u64> {
+    None
+}
+
+/// Name of the host this process runs on
+#[cfg(unix)]
+pub(crate) fn hostname() -> Option<String> {
+    let mut buf = [0u8; 256];
+    // SAFETY: gethostname writes at most buf.len() bytes into buf
+    if unsafe { libc::gethostname(buf.as_mut_ptr().cast(), buf.len()) } != 0 {
+        return None;
+    }
+    let len = buf.iter().position(|&b| b == 0).unwrap_or(buf.len());
+    let name = String::from_utf8_lossy(&buf[..len]).into_owned();
+    (!name.is_empty()).then_some(name)
+}
+
+/// Name of the host this process runs on
+#[cfg(windows)]
+pub(crate) fn hostname() -> Option<String> {
+    std::env::var("COMPUTERNAME").ok().filter(|name| !name.is_empty())
+}
+
+#[cfg(not(any(unix, windows)))]
+pub(crate) fn hostname() -> Option<String> {
+    None
+}
@@ -0,0 +1,126 @@
+/*
+ * Custom text layout for log lines, set with the `text_template` option
+ *
+ * This module implements:
+ * - Parsing a template such as "{timestamp} [{level}] {message}" once at init
+ * - Rejecting placeholders the logger doesn't know about
+ * - Rendering a log line by substituting the fields into the parsed template
+ *
+ * "{{" and "}}" produce literal braces.
+ */
+
+use crate::config::LogLevel;
+
+/// Field substituted for a placeholder
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Field {
+    Timestamp,
+    Level,
+    File,
+    Line,
+    Module,
+    Message,
+    Context,
+    Sequence,
+}
+
+impl Field {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "timestamp" => Some(Field::Timestamp),
+            "level" => Some(Field::Level),
+            "file" => Some(Field::File),
+            "line" => Some(Field::Line),
+            "module" => Some(Field::Module),
+            "message" => Some(Field::Message),
+            "context" => Some(Field::Context),
+            "seq" => Some(Field::Sequence),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Literal(String),
+    Field(Field),
+}
+
+/// A parsed `text_template`
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct TextTemplate {
+    segments: Vec<Segment>,
+}
+
+impl TextTemplate {
+    /// Parse a template, failing on unknown or unterminated placeholders
+    pub(crate) fn parse(template: &str) -> Result<Self, String> {
+        let mut segments = Vec::new();
+        let mut literal = String::new();
+        let mut chars = template.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    literal.push('{');
+                },
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    literal.push('}');
+                },
+                '{' => {
+                    let mut name = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => name.push(c),
+                            None => return Err(format!("Unterminated placeholder '{{{}' in text_template", name)),
+                        }
+                    }
+                    let field = Field::from_name(&name)
+                        .ok_or_else(|| format!("Unknown placeholder '{{{}}}' in text_template", name))?;
+                    if !literal.is_empty() {
+                        segments.push(Segment::Literal(std::mem::take(&mut literal)));
+                    }
+                    segments.push(Segment::Field(field));
+                },
+                '}' => return Err("Unmatched '}' in text_template (use '}}' for a literal brace)".to_string()),
+                c => literal.push(c),
+            }
+        }
+        if !literal.is_empty() {
+            segments.push(Segment::Literal(literal));
+        }
+
+        Ok(TextTemplate { segments })
+    }
+
+    /// Render one log line; `{context}` and `{seq}` are empty when absent
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn render(&self, timestamp: &str, seq: Option<u64>, level: &LogLevel, message: &str,
+                         context: Option<&str>, file: &str, line: u32, module: &str) -> String {
+        let mut rendered = String::with_capacity(message.len() + 64);
+        for segment in &self.segments {
+            match segment {
+                Segment::Literal(text) => rendered.push_str(text),
+                Segment::Field(Field::Timestamp) => rendered.push_str(timestamp),
+                Segment::Field(Field::Level) => rendered.push_str(level.as_str()),
+                Segment::Field(Field::File) => rendered.push_str(file),
+                // Without a location both {file} and {line} are empty
+                Segment::Field(Field::Line) => if !file.is_empty() {
+                    rendered.push_str(&line.to_string());
+                },
+                Segment::Field(Field::Module) => rendered.push_str(module),
+                Segment::Field(Field::Message) => rendered.push_str(message),
+                Segment::Field(Field::Context) => rendered.push_str(context.unwrap_or_default()),
+                Segment::Field(Field::Sequence) => {
+                    if let Some(seq) = seq {
+                        rendered.push_str(&seq.to_string());
+                    }
+                },
+            }
+        }
+        rendered
+    }
+}
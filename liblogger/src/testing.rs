@@ -0,0 +1,77 @@
+/*
+ * Test support for Rusty Logger v2 (requires the `testing` feature)
+ *
+ * Captures formatted log lines in memory so unit tests can assert on them.
+ * Each thread gets its own buffer: the default test harness runs every
+ * #[test] on a separate thread, so parallel tests never see each other's
+ * lines. Lines logged from threads that never called
+ * Logger::init_for_tests() are discarded.
+ *
+ * Also holds the switch that simulates a Tokio runtime that can't be created.
+ *
+ * Not intended for production use.
+ */
+
+use std::cell::RefCell;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::outputs::LogOutput;
+
+/// Lines captured for one thread
+pub type CapturedLines = Arc<Mutex<Vec<String>>>;
+
+static FAIL_RUNTIME: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    static CAPTURED: RefCell<Option<CapturedLines>> = const { RefCell::new(None) };
+}
+
+/// The calling thread's capture buffer, created on first use
+pub(crate) fn thread_buffer() -> CapturedLines {
+    CAPTURED.with(|cell| {
+        cell.borrow_mut()
+            .get_or_insert_with(|| Arc::new(Mutex::new(Vec::new())))
+            .clone()
+    })
+}
+
+/// Clear the calling thread's capture buffer
+pub(crate) fn clear_thread_buffer() {
+    CAPTURED.with(|cell| {
+        if let Some(buffer) = cell.borrow().as_ref() {
+            buffer.lock().unwrap_or_else(|e| e.into_inner()).clear();
+        }
+    });
+}
+
+/// Output that appends each line to the logging thread's capture buffer
+pub(crate) struct MemoryOutput;
+
+impl LogOutput for MemoryOutput {
+    fn write_log(&mut self, formatted_message: &str) -> Result<(), String> {
+        // Shutdown writes an empty message to trigger a flush
+        if formatted_message.is_empty() {
+            return Ok(());
+        }
+
+        CAPTURED.with(|cell| {
+            if let Some(buffer) = cell.borrow().as_ref() {
+                buffer.lock()
+                    .map_err(|_| "Failed to lock capture buffer".to_string())?
+                    .push(formatted_message.to_string());
+            }
+            Ok(())
+        })
+    }
+}
+
+/// Make runtime creation fail from now on
+pub(crate) fn fail_runtime_creation() {
+    FAIL_RUNTIME.store(true, Ordering::Relaxed);
+}
+
+/// Whether runtime creation has been made to fail
+pub(crate) fn runtime_creation_fails() -> bool {
+    FAIL_RUNTIME.load(Ordering::Relaxed)
+}
@@ -0,0 +1,95 @@
+/*
+ * Step ordering for `#[log_workflow_step]`
+ *
+ * Each workflow, identified by its workflow id, numbers its steps in the
+ * order they first run and remembers the index of the last step that
+ * completed. A step whose index is not past that one is running again
+ * (a retry) or out of order. The state lives in a process-global map so
+ * steps may run on different threads.
+ */
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+// Step state per workflow id
+static WORKFLOWS: Mutex<Option<HashMap<String, Workflow>>> = Mutex::new(None);
+
+#[derive(Debug, Default)]
+struct Workflow {
+    // Step names in the order they first ran; a step's index is its position + 1
+    steps: Vec<String>,
+    last_completed: Option<u32>,
+    completed: u32,
+}
+
+/// One run of a workflow step, returned by `Logger::begin_workflow_step()`
+#[derive(Debug)]
+pub struct WorkflowStep {
+    workflow_id: String,
+    index: u32,
+    previous: Option<u32>,
+    known_steps: u32,
+    completed_steps: u32,
+}
+
+impl WorkflowStep {
+    /// Position of this step in its workflow, starting at 1
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    /// Index of the last step that completed before this one started
+    pub fn previous_index(&self) -> Option<u32> {
+        self.previous
+    }
+
+    /// Whether this step is not past the last completed one (a retry or misorder)
+    pub fn is_out_of_order(&self) -> bool {
+        self.previous.is_some_and(|previous| self.index <= previous)
+    }
+
+    /// Number of distinct steps the workflow has run so far
+    pub fn known_steps(&self) -> u32 {
+        self.known_steps
+    }
+
+    /// Number of step runs in the workflow that have completed
+    pub fn completed_steps(&self) -> u32 {
+        self.completed_steps
+    }
+
+    /// Record that this step completed
+    pub fn complete(&mut self) {
+        let mut workflows = WORKFLOWS.lock().unwrap_or_else(|e| e.into_inner());
+        let workflow = workflows.get_or_insert_with(HashMap::new)
+            .entry(self.workflow_id.clone())
+            .or_default();
+        workflow.last_completed = Some(self.index);
+        workflow.completed += 1;
+        self.completed_steps = workflow.completed;
+    }
+}
+
+/// Start `step_name` in `workflow_id`, numbering it if it hasn't run before
+pub(crate) fn begin_step(workflow_id: &str, step_name: &str) -> WorkflowStep {
+    let mut workflows = WORKFLOWS.lock().unwrap_or_else(|e| e.into_inner());
+    let workflow = workflows.get_or_insert_with(HashMap::new)
+        .entry(workflow_id.to_string())
+        .or_default();
+
+    let position = match workflow.steps.iter().position(|step| step == step_name) {
+        Some(position) => position,
+        None => {
+            workflow.steps.push(step_name.to_string());
+            workflow.steps.len() - 1
+        },
+    };
+
+    WorkflowStep {
+        workflow_id: workflow_id.to_string(),
+        index: position as u32 + 1,
+        previous: workflow.last_completed,
+        known_steps: workflow.steps.len() as u32,
+        completed_steps: workflow.completed,
+    }
+}
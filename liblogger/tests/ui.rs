@@ -0,0 +1,23 @@
+/*
+ * Compile-time checks of the attribute macros
+ *
+ * The .stderr files pin the errors for using an attribute macro without
+ * calling `initialize_logger_attributes!()` and for naming a level that
+ * doesn't exist. Regenerate them with TRYBUILD=overwrite after an intended
+ * change to a message. With the `disabled` feature the macros return their
+ * input unchanged, so there is nothing to check.
+ */
+
+#[cfg(not(feature = "disabled"))]
+#[test]
+fn attribute_macros_need_initializer() {
+    let cases = trybuild::TestCases::new();
+    cases.pass("tests/ui/initialized.rs");
+    cases.compile_fail("tests/ui/missing_initializer.rs");
+}
+
+#[cfg(not(feature = "disabled"))]
+#[test]
+fn log_result_rejects_unknown_level() {
+    trybuild::TestCases::new().compile_fail("tests/ui/unknown_result_level.rs");
+}
@@ -0,0 +1,10 @@
+use liblogger_macros::*;
+
+initialize_logger_attributes!();
+
+#[log_entry_exit]
+fn handle_request() {}
+
+fn main() {
+    handle_request();
+}
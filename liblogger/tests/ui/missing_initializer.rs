@@ -0,0 +1,8 @@
+use liblogger_macros::*;
+
+#[log_entry_exit]
+fn handle_request() {}
+
+fn main() {
+    handle_request();
+}
@@ -0,0 +1,12 @@
+use liblogger_macros::*;
+
+initialize_logger_attributes!();
+
+#[log_result(success_level = "verbose")]
+fn parse_port(raw: &str) -> Result<u16, std::num::ParseIntError> {
+    raw.parse()
+}
+
+fn main() {
+    let _ = parse_port("8080");
+}
@@ -1,2423 +1,3224 @@
-/*
- * Procedural macros for enhanced logging capabilities
- *
- * This module provides procedural macros that can be applied to functions
- * for various logging, monitoring, and instrumentation purposes.
- * 
- * These macros work with the liblogger crate to provide automatic context
- * capturing, timing measurements, and other advanced logging features.
- */
-
-extern crate proc_macro;
-
-// Import our utils module (keep it private)
-mod macro_utils;
-
-use proc_macro::TokenStream;
-use quote::{quote, format_ident};
-use syn::{parse_macro_input, parse_quote, ItemFn};
-
-// Import helpers from our utils module
-use crate::macro_utils::{get_fn_name, IdList, MacroArgs, define_helper_functions, generate_utility_functions};
-
-/// Initialization macro that must be called at the module level to enable attribute macros
-///
-/// This macro defines helper functions needed by the attribute macros, such as
-/// error extraction, success checking, trace ID management, and feature flag checking.
-///
-#[proc_macro]
-pub fn initialize_logger_attributes(_input: TokenStream) -> TokenStream {
-    TokenStream::from(define_helper_functions())
-}
-
-/// Logs function entry and exit points to track execution flow
-///
-/// Automatically adds INFO level logs at the start and end of the function.
-/// Useful for tracing code execution paths during debugging and in production.
-///
-#[proc_macro_attribute]
-pub fn log_entry_exit(_args: TokenStream, input: TokenStream) -> TokenStream {
-    let mut input_fn = parse_macro_input!(input as ItemFn);
-    let fn_name = get_fn_name(&input_fn);
-    let orig_block = input_fn.block.clone();
-    
-    input_fn.block = Box::new(parse_quote!({
-        liblogger::log_info!(&format!("ENTRY: {}", #fn_name));
-        
-        let result = (|| #orig_block)();
-        
-        liblogger::log_info!(&format!("EXIT: {}", #fn_name));
-        result
-    }));
-    
-    TokenStream::from(quote!(#input_fn))
-}
-
-/// Log errors and panics
-#[proc_macro_attribute]
-pub fn log_errors(_args: TokenStream, input: TokenStream) -> TokenStream {
-    let mut input_fn = parse_macro_input!(input as ItemFn);
-    let fn_name = get_fn_name(&input_fn);
-    let orig_block = input_fn.block.clone();
-    let is_async = input_fn.sig.asyncness.is_some();
-    
-    if is_async {
-        input_fn.block = Box::new(parse_quote!({
-            async move {
-                let result = async move #orig_block.await;
-                
-                // Use pattern matching to handle Result types
-                match &result {
-                    Ok(_) => {},  // Success case, no logging needed
-                    Err(err) => {
-                        // Error case, log the error
-                        liblogger::log_error!(&format!("{} returned error: {:?}", #fn_name, err), None);
-                    }
-                }
-                result
-            }.await
-        }));
-    } else {
-        input_fn.block = Box::new(parse_quote!({
-            use std::panic::{catch_unwind, AssertUnwindSafe};
-            
-            let result = catch_unwind(AssertUnwindSafe(|| #orig_block));
-            
-            match result {
-                Ok(inner_result) => {
-                    // Use pattern matching to handle Result types
-                    match &inner_result {
-                        Ok(_) => {},  // Success case, no logging needed
-                        Err(err) => {
-                            // Error case, log the error
-                            liblogger::log_error!(&format!("{} returned error: {:?}", #fn_name, err), None);
-                        }
-                    }
-                    inner_result
-                },
-                Err(panic_err) => {
-                    let panic_msg = if let Some(s) = panic_err.downcast_ref::<&str>() {
-                        s.to_string()
-                    } else if let Some(s) = panic_err.downcast_ref::<String>() {
-                        s.clone()
-                    } else {
-                        "Unknown panic".to_string()
-                    };
-                    
-                    liblogger::log_error!(&format!("{} panicked: {}", #fn_name, panic_msg), None);
-                    std::panic::resume_unwind(panic_err);
-                }
-            }
-        }));
-    }
-    
-    TokenStream::from(quote!(#input_fn))
-}
-
-/// Measure execution time of a function
-#[proc_macro_attribute]
-pub fn measure_time(_args: TokenStream, input: TokenStream) -> TokenStream {
-    let mut input_fn = parse_macro_input!(input as ItemFn);
-    let fn_name = get_fn_name(&input_fn);
-    let orig_block = input_fn.block.clone();
-    let is_async = input_fn.sig.asyncness.is_some();
-    
-    if is_async {
-        input_fn.block = Box::new(parse_quote!({
-            async move {
-                use std::time::Instant;
-                
-                let start_time = Instant::now();
-                let result = async move #orig_block.await;
-                let duration = start_time.elapsed();
-                let duration_ms = duration.as_millis();
-                
-                liblogger::log_info!(&format!("{} completed in {} ms ", #fn_name, duration_ms), None);
-                result
-            }.await
-        }));
-    } else {
-        input_fn.block = Box::new(parse_quote!({
-            use std::time::Instant;
-            use std::panic::{catch_unwind, AssertUnwindSafe};
-            
-            let start_time = Instant::now();
-            
-            let result = catch_unwind(AssertUnwindSafe(|| #orig_block));
-            
-            let duration = start_time.elapsed();
-            let duration_ms = duration.as_millis();
-            
-            match result {
-                Ok(output) => {
-                    liblogger::log_info!(&format!("{} completed in {} ms ", #fn_name, duration_ms), None);
-                    output
-                },
-                Err(panic_err) => {
-                    liblogger::log_error!(
-                        &format!("{} panicked after {} ms ", #fn_name, duration_ms), 
-                        None
-                    );
-                    std::panic::resume_unwind(panic_err);
-                }
-            }
-        }));
-    }
-    
-    TokenStream::from(quote!(#input_fn))
-}
-
-/// Log specified function arguments
-#[proc_macro_attribute]
-pub fn log_args(args: TokenStream, input: TokenStream) -> TokenStream {
-    let args = parse_macro_input!(args as IdList);
-    let mut input_fn = parse_macro_input!(input as ItemFn);
-    let fn_name = get_fn_name(&input_fn);
-    let orig_block = input_fn.block.clone();
-    let arg_names = args.ids;
-    let mut log_stmts = Vec::new();
-    
-    for arg_name in &arg_names {
-        let arg_str = arg_name.to_string();
-        log_stmts.push(quote! {
-            let arg_value = format!("{:?}", #arg_name);
-            args_str.push_str(&format!("{} = {}, ", #arg_str, arg_value));
-        });
-    }
-    
-    input_fn.block = Box::new(parse_quote!({
-        use std::time::Instant;
-        let start_time = Instant::now();
-        let mut args_str = String::new();
-        #(#log_stmts)*;
-        // Remove trailing comma and space
-        if !args_str.is_empty() {
-            args_str.truncate(args_str.len() - 2);
-        }
-        liblogger::log_info!(&format!("Entering {} with args: {}", #fn_name, args_str), None);
-        #orig_block
-    }));
-    
-    TokenStream::from(quote!(#input_fn))
-}
-
-/// Log and implement retry logic
-#[proc_macro_attribute]
-pub fn log_retries(args: TokenStream, input: TokenStream) -> TokenStream {
-    let args = parse_macro_input!(args as MacroArgs);
-    let max_attempts = args.max_attempts.unwrap_or(3);
-      let mut input_fn = parse_macro_input!(input as ItemFn);
-    let fn_name = get_fn_name(&input_fn);
-    let orig_block = input_fn.block.clone();
-    let is_async = input_fn.sig.asyncness.is_some();
-
-    if is_async {
-        input_fn.block = Box::new(parse_quote!({
-            async move {
-                let mut attempts = 0u32;
-                loop {
-                    attempts += 1;
-                    if attempts > 1 {
-                        liblogger::log_info!(
-                            &format!("Retry attempt {} of {} for {}", attempts, #max_attempts, #fn_name), 
-                            None
-                        );
-                        // For async functions, we skip the delay to avoid tokio dependency
-                        // The user should implement their own delay if needed
-                        liblogger::log_info!(
-                            &format!("Async retry delay skipped for {} (implement your own async delay if needed)", #fn_name), 
-                            None
-                        );
-                    }
-                    
-                    let result = async move #orig_block.await;
-                    
-                    // Use pattern matching to determine success or failure
-                    match &result {
-                        Ok(_) => {
-                            // Success case
-                            if attempts > 1 {
-                                liblogger::log_info!(
-                                    &format!("{} succeeded after {} attempts", #fn_name, attempts), 
-                                    None
-                                );
-                            }
-                            return result;
-                        },
-                        Err(err) => {
-                            // Error case
-                            if attempts >= #max_attempts {
-                                liblogger::log_error!(
-                                    &format!("{} failed after {} attempts: {:?}", #fn_name, attempts, err), 
-                                    None
-                                );
-                                return result;
-                            }
-                            
-                            liblogger::log_warn!(
-                                &format!("{} attempt {} failed: {:?}", #fn_name, attempts, err), 
-                                None
-                            );
-                            // Continue to next retry iteration
-                        }
-                    }
-                }
-            }.await
-        }));
-    } else {
-        input_fn.block = Box::new(parse_quote!({
-            let mut attempts = 0u32;
-            loop {
-                attempts += 1;
-                if attempts > 1 {
-                    liblogger::log_info!(
-                        &format!("Retry attempt {} of {} for {}", attempts, #max_attempts, #fn_name), 
-                        None
-                    );
-                    // Simple exponential backoff
-                    std::thread::sleep(std::time::Duration::from_millis((2u64.pow(attempts - 1) * 50) as u64));
-                }
-                
-                let result = (|| #orig_block)();
-                
-                // Use pattern matching to determine success or failure
-                match &result {
-                    Ok(_) => {
-                        // Success case
-                        if attempts > 1 {
-                            liblogger::log_info!(
-                                &format!("{} succeeded after {} attempts", #fn_name, attempts), 
-                                None
-                            );
-                        }
-                        return result;
-                    },
-                    Err(err) => {
-                        // Error case
-                        if attempts >= #max_attempts {
-                            liblogger::log_error!(
-                                &format!("{} failed after {} attempts: {:?}", #fn_name, attempts, err), 
-                                None
-                            );
-                            return result;
-                        }
-                        
-                        liblogger::log_warn!(
-                            &format!("{} attempt {} failed: {:?}", #fn_name, attempts, err), 
-                            None
-                        );
-                        // Continue to next retry iteration
-                    }
-                }
-            }
-        }));
-    }
-    
-    TokenStream::from(quote!(#input_fn))
-}
-
-/// Create detailed audit logs
-#[proc_macro_attribute]
-pub fn audit_log(_args: TokenStream, input: TokenStream) -> TokenStream {
-    let mut input_fn = parse_macro_input!(input as ItemFn);
-    let fn_name = get_fn_name(&input_fn);
-    let orig_block = input_fn.block.clone();
-    let is_async = input_fn.sig.asyncness.is_some();
-    
-    if is_async {
-        input_fn.block = Box::new(parse_quote!({
-            async move {
-                let user_id = get_thread_local_value("user_id").unwrap_or_else(|| "unknown".to_string());
-                liblogger::log_info!(&format!("AUDIT: {} called", #fn_name), Some(format!("user_id={}", user_id)));
-                
-                let start_time = std::time::Instant::now();
-                let result = async move #orig_block.await;
-                let duration = start_time.elapsed();
-                
-                liblogger::log_info!(
-                    &format!("AUDIT: {} completed in {} ms", #fn_name, duration.as_millis()),
-                    Some(format!("user_id={}", user_id))
-                );
-                
-                result
-            }.await
-        }));
-    } else {
-        input_fn.block = Box::new(parse_quote!({
-            let user_id = get_thread_local_value("user_id").unwrap_or_else(|| "unknown".to_string());
-            liblogger::log_info!(&format!("AUDIT: {} called", #fn_name), Some(format!("user_id={}", user_id)));
-            
-            let start_time = std::time::Instant::now();
-            let result = #orig_block;
-            let duration = start_time.elapsed();
-            
-            // Use pattern matching on result
-            match &result {
-                () => {
-                    // Unit return type
-                    liblogger::log_info!(
-                        &format!("AUDIT: {} completed in {} ms", #fn_name, duration.as_millis()),
-                        Some(format!("user_id={}", user_id))
-                    );
-                },
-                _ => {
-                    // Any other return type
-                    liblogger::log_info!(
-                        &format!("AUDIT: {} completed in {} ms with result: {:?}", 
-                            #fn_name, duration.as_millis(), result),
-                        Some(format!("user_id={}", user_id))
-                    );
-                }
-            }
-            
-            result
-        }));
-    }
-    
-    TokenStream::from(quote!(#input_fn))
-}
-
-/// Circuit breaker pattern with logging
-#[proc_macro_attribute]
-pub fn circuit_breaker(args: TokenStream, input: TokenStream) -> TokenStream {
-    let args = parse_macro_input!(args as MacroArgs);
-    let threshold = args.failure_threshold.unwrap_or(3);
-    
-    let mut input_fn = parse_macro_input!(input as ItemFn);
-    let fn_name = get_fn_name(&input_fn);
-    let orig_block = input_fn.block.clone();
-    let is_async = input_fn.sig.asyncness.is_some();
-    
-    if is_async {
-        input_fn.block = Box::new(parse_quote!({
-            async move {
-                use std::sync::atomic::{AtomicU32, Ordering};
-                use std::sync::Mutex;
-                use std::time::{Instant, Duration};
-                
-                // Thread-safe failure counters
-                static FAILURE_COUNT: AtomicU32 = AtomicU32::new(0);
-                static LAST_SUCCESS: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
-                
-                // Reset failure count after 30 seconds of success
-                let now = Instant::now();
-                let last_success_time = LAST_SUCCESS.load(Ordering::Relaxed);
-                
-                if last_success_time > 0 {
-                    let elapsed = now.duration_since(Instant::now() - Duration::from_secs(last_success_time));
-                    if elapsed > Duration::from_secs(30) {
-                        FAILURE_COUNT.store(0, Ordering::Relaxed);
-                    }
-                }
-                
-                // Check if circuit is open (too many failures)
-                let failures = FAILURE_COUNT.load(Ordering::Relaxed);
-                if failures >= #threshold {
-                    liblogger::log_error!(
-                        &format!("Circuit breaker open for {}: {} failures exceeded threshold {}", 
-                            #fn_name, failures, #threshold),
-                        None
-                    );
-                    return Err(format!("Circuit breaker open for {}", #fn_name).into());
-                }
-                
-                // Call the function and track success/failure
-                let result = async move #orig_block.await;
-                
-                // Use pattern matching for Result
-                match &result {
-                    Ok(_) => {
-                        // Reset failure count on success
-                        FAILURE_COUNT.store(0, Ordering::Relaxed);
-                        LAST_SUCCESS.store(now.elapsed().as_secs(), Ordering::Relaxed);
-                    },
-                    Err(_) => {
-                        // Increment failure count
-                        FAILURE_COUNT.fetch_add(1, Ordering::Relaxed);
-                        let new_count = FAILURE_COUNT.load(Ordering::Relaxed);
-                        
-                        liblogger::log_warn!(&format!(
-                            "Circuit breaker: {} failed ({}/{} failures)", 
-                            #fn_name, new_count, #threshold
-                        ), None);
-                    }
-                }
-                
-                result
-            }.await
-        }));
-    } else {
-        input_fn.block = Box::new(parse_quote!({
-            use std::sync::atomic::{AtomicU32, Ordering};
-            use std::sync::Mutex;
-            use std::time::{Instant, Duration};
-            
-            // Thread-safe failure counters
-            static FAILURE_COUNT: AtomicU32 = AtomicU32::new(0);
-            static LAST_SUCCESS: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
-            
-            // Reset failure count after 30 seconds of success
-            let now = Instant::now();
-            let last_success_time = LAST_SUCCESS.load(Ordering::Relaxed);
-            
-            if last_success_time > 0 {
-                let elapsed = now.duration_since(Instant::now() - Duration::from_secs(last_success_time));
-                if elapsed > Duration::from_secs(30) {
-                    FAILURE_COUNT.store(0, Ordering::Relaxed);
-                }
-            }
-            
-            // Check if circuit is open (too many failures)
-            let failures = FAILURE_COUNT.load(Ordering::Relaxed);
-            if failures >= #threshold {
-                liblogger::log_error!(
-                    &format!("Circuit breaker open for {}: {} failures exceeded threshold {}", 
-                        #fn_name, failures, #threshold),
-                    None
-                );
-                return Err(format!("Circuit breaker open for {}", #fn_name).into());
-            }
-            
-            // Call the function and track success/failure
-            let result = #orig_block;
-            
-            // Use pattern matching for Result
-            match &result {
-                Ok(_) => {
-                    // Reset failure count on success
-                    FAILURE_COUNT.store(0, Ordering::Relaxed);
-                    LAST_SUCCESS.store(now.elapsed().as_secs(), Ordering::Relaxed);
-                },
-                Err(_) => {
-                    // Increment failure count
-                    FAILURE_COUNT.fetch_add(1, Ordering::Relaxed);
-                    let new_count = FAILURE_COUNT.load(Ordering::Relaxed);
-                    
-                    liblogger::log_warn!(&format!(
-                        "Circuit breaker: {} failed ({}/{} failures)", 
-                        #fn_name, new_count, #threshold
-                    ), None);
-                }
-            }
-            
-            result
-        }));
-    }
-    
-    TokenStream::from(quote!(#input_fn))
-}
-
-/// Throttle logs to avoid flooding during incidents
-#[proc_macro_attribute]
-pub fn throttle_log(args: TokenStream, input: TokenStream) -> TokenStream {
-    let args = parse_macro_input!(args as MacroArgs);
-    let rate = args.rate.unwrap_or(5);
-    
-    let mut input_fn = parse_macro_input!(input as ItemFn);
-    let fn_name = get_fn_name(&input_fn);
-    let orig_block = input_fn.block.clone();
-    
-    input_fn.block = Box::new(parse_quote!({
-        use std::sync::atomic::{AtomicUsize, Ordering};
-        use std::time::{SystemTime, UNIX_EPOCH};
-        
-        static COUNTER: AtomicUsize = AtomicUsize::new(0);
-        static LAST_MINUTE: AtomicUsize = AtomicUsize::new(0);
-        static SKIPPED_COUNT: AtomicUsize = AtomicUsize::new(0);
-        
-        // Get current minute for rate limiting window
-        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
-        let current_minute = (now.as_secs() / 60) as usize;
-        
-        // Check if we're in a new minute or still in the rate limit
-        let should_log = {
-            let last_minute = LAST_MINUTE.load(Ordering::SeqCst);
-            if last_minute != current_minute {
-                // New minute, reset counter and log a summary of skipped messages
-                LAST_MINUTE.store(current_minute, Ordering::SeqCst);
-                let skipped = SKIPPED_COUNT.swap(0, Ordering::SeqCst);
-                if skipped > 0 {
-                    liblogger::log_info!(
-                        &format!("Throttled logs for {}: skipped {} logs in previous minute", 
-                            #fn_name, skipped),
-                        None
-                    );
-                }
-                COUNTER.store(1, Ordering::SeqCst);
-                true
-            } else {
-                // Same minute, check counter
-                let count = COUNTER.fetch_add(1, Ordering::SeqCst) + 1;
-                if count <= #rate as usize {
-                    true
-                } else {
-                    SKIPPED_COUNT.fetch_add(1, Ordering::SeqCst);
-                    false
-                }
-            }
-        };
-        
-        let result = #orig_block;
-        
-        // Only log if within rate limits
-        if should_log {
-            // Simple logging without trying to match on the result type
-            liblogger::log_info!(&format!("{} executed", #fn_name), None);
-        }
-        
-        result
-    }));
-    
-    TokenStream::from(quote!(#input_fn))
-}
-
-/// Measure latency to external dependencies
-#[proc_macro_attribute]
-pub fn dependency_latency(args: TokenStream, input: TokenStream) -> TokenStream {
-    let args = parse_macro_input!(args as MacroArgs);
-    let target = args.target.unwrap_or_else(|| "unknown".to_string());
-    
-    let mut input_fn = parse_macro_input!(input as ItemFn);
-    let fn_name = get_fn_name(&input_fn);
-    let orig_block = input_fn.block.clone();
-    
-    input_fn.block = Box::new(parse_quote!({
-        use std::time::Instant;
-        liblogger::log_info!(
-            &format!("Dependency call to {} started for {}", #target, #fn_name),
-            None
-        );
-        let start_time = Instant::now();
-        let result = #orig_block;
-        let duration_ms = start_time.elapsed().as_millis();
-        
-        // Use pattern matching to handle different result types
-        match &result {
-            Ok(_) => {
-                liblogger::log_info!(&format!("Dependency call to {} completed in {} ms", #target, duration_ms), None);
-            },
-            Err(err) => {
-                liblogger::log_error!(
-                    &format!("Dependency call to {} failed after {} ms with error: {:?}",
-                        #target, duration_ms, err),
-                    None
-                );
-            },
-            _ => {
-                // For non-Result types
-                liblogger::log_info!(&format!("Dependency call to {} completed in {} ms", #target, duration_ms), None);
-            }
-        }
-        
-        result
-    }));
-    
-    TokenStream::from(quote!(#input_fn))
-}
-
-/// Log the returned value from a function
-#[proc_macro_attribute]
-pub fn log_response(_args: TokenStream, input: TokenStream) -> TokenStream {
-    let mut input_fn = parse_macro_input!(input as ItemFn);
-    let fn_name = get_fn_name(&input_fn);
-    let orig_block = input_fn.block.clone();
-    
-    input_fn.block = Box::new(parse_quote!({
-        let result = #orig_block;
-        liblogger::log_debug!(&format!("{} returned: {:?}", #fn_name, result), None);
-        result
-    }));
-    
-    TokenStream::from(quote!(#input_fn))
-}
-
-/// Track concurrent invocations of a function
-#[proc_macro_attribute]
-pub fn log_concurrency(_args: TokenStream, input: TokenStream) -> TokenStream {
-    let mut input_fn = parse_macro_input!(input as ItemFn);
-    let fn_name = get_fn_name(&input_fn);
-    let orig_block = input_fn.block.clone();
-    let counter_var = format_ident!("CONCURRENCY_{}", fn_name.to_uppercase());
-    
-    input_fn.block = Box::new(parse_quote!({
-        use std::sync::atomic::{AtomicU32, Ordering};
-        static #counter_var: AtomicU32 = AtomicU32::new(0);
-        
-        let current = #counter_var.fetch_add(1, Ordering::SeqCst) + 1;
-        liblogger::log_debug!(
-            &format!("{} concurrent invocations: {}", #fn_name, current),
-            None
-        );
-        
-        let result = #orig_block;
-        
-        let after = #counter_var.fetch_sub(1, Ordering::SeqCst) - 1;
-        liblogger::log_debug!(
-            &format!("{} concurrent invocations after exit: {}", #fn_name, after),
-            None
-        );
-        
-        result
-    }));
-    
-    TokenStream::from(quote!(#input_fn))
-}
-
-/// Create and propagate a trace ID for request flow tracking
-#[proc_macro_attribute]
-pub fn trace_span(_args: TokenStream, input: TokenStream) -> TokenStream {
-    let mut input_fn = parse_macro_input!(input as ItemFn);
-    let fn_name = get_fn_name(&input_fn);
-    let orig_block = input_fn.block.clone();
-    
-    input_fn.block = Box::new(parse_quote!({
-        use uuid::Uuid;
-        // Generate or reuse trace ID
-        let trace_id = if let Some(existing_id) = get_trace_id() {
-            existing_id
-        } else {
-            let new_id = Uuid::new_v4().to_string();
-            set_trace_id(&new_id);
-            new_id
-        };
-        
-        liblogger::log_info!(
-            &format!("[TraceID: {}] {} started", trace_id, #fn_name),
-            None
-        );
-        
-        let result = #orig_block;
-        
-        liblogger::log_info!(
-            &format!("[TraceID: {}] {} completed", trace_id, #fn_name),
-            None
-        );
-        
-        result
-    }));
-    
-    TokenStream::from(quote!(#input_fn))
-}
-
-/// Log feature flag state
-#[proc_macro_attribute]
-pub fn feature_flag(args: TokenStream, input: TokenStream) -> TokenStream {
-    let args = parse_macro_input!(args as MacroArgs);
-    let flag_name = args.flag_name.unwrap_or_else(|| "unknown".to_string());
-    let mut input_fn = parse_macro_input!(input as ItemFn);
-    let fn_name = get_fn_name(&input_fn);
-    let orig_block = input_fn.block.clone();
-    
-    input_fn.block = Box::new(parse_quote!({
-        // Check feature flag (placeholder function)
-        let is_enabled = is_feature_enabled(#flag_name);
-        
-        liblogger::log_info!(
-            &format!("{} called with feature flag {} = {}", 
-                #fn_name, #flag_name, is_enabled),
-            None
-        );
-        
-        let result = #orig_block;
-        result
-    }));
-    
-    TokenStream::from(quote!(#input_fn))
-}
-
-/// Increment a metrics counter for function calls
-#[proc_macro_attribute]
-pub fn metrics_counter(args: TokenStream, input: TokenStream) -> TokenStream {
-    let args = parse_macro_input!(args as MacroArgs);
-    let counter_name = args.counter_name.unwrap_or_else(|| "function_calls".to_string());
-        
-    let mut input_fn = parse_macro_input!(input as ItemFn);
-    let orig_block = input_fn.block.clone();
-      input_fn.block = Box::new(parse_quote!({
-        // Increment counter using Prometheus
-        {
-            use prometheus::{Counter, register_counter};
-            use std::sync::Once;
-            static INIT: Once = Once::new();
-            static mut COUNTER: Option<Counter> = None;
-            
-            INIT.call_once(|| {
-                let counter = register_counter!(#counter_name, "Function call counter").unwrap();
-                unsafe {
-                    COUNTER = Some(counter);
-                }
-            });
-            
-            if let Some(counter) = unsafe { COUNTER.as_ref() } {
-                counter.inc();
-            }
-        }
-        
-        let result = #orig_block;
-        result
-    }));
-    
-    TokenStream::from(quote!(#input_fn))
-}
-
-/// Log memory usage during function execution
-#[proc_macro_attribute]
-pub fn log_memory_usage(_args: TokenStream, input: TokenStream) -> TokenStream {
-    let mut input_fn = parse_macro_input!(input as ItemFn);
-    let fn_name = get_fn_name(&input_fn);
-    let orig_block = input_fn.block.clone();
-      input_fn.block = Box::new(parse_quote!({
-        let (start_rss, start_vms) = {
-            use psutil::process::Process;
-            let process = Process::current().unwrap();
-            let memory = process.memory_info().unwrap();
-            (memory.rss(), memory.vms())
-        };
-        
-        let result = #orig_block;
-        
-        {
-            use psutil::process::Process;
-            let process = Process::current().unwrap();
-            let memory = process.memory_info().unwrap();
-            let end_rss = memory.rss();
-            let end_vms = memory.vms();
-            
-            liblogger::log_info!(
-                &format!("{} starting memory usage - RSS: {} bytes, VMS: {} bytes", 
-                    #fn_name, start_rss, start_vms),
-                None
-            );
-            liblogger::log_info!(
-                &format!("{} ending memory usage - RSS: {} bytes (delta: {} bytes), VMS: {} bytes (delta: {} bytes)", 
-                    #fn_name, end_rss, end_rss as i64 - start_rss as i64, 
-                    end_vms, end_vms as i64 - start_vms as i64),
-                None
-            );
-        }
-        
-        result
-    }));
-    
-    TokenStream::from(quote!(#input_fn))
-}
-
-/// Log CPU time used during function execution
-#[proc_macro_attribute]
-pub fn log_cpu_time(_args: TokenStream, input: TokenStream) -> TokenStream {
-    let mut input_fn = parse_macro_input!(input as ItemFn);
-    let fn_name = get_fn_name(&input_fn);
-    let orig_block = input_fn.block.clone();
-    
-    input_fn.block = Box::new(parse_quote!({
-        use std::time::Instant;
-        let wall_time_start = Instant::now();
-        
-        // There's no direct CPU time measurement in standard Rust
-        // This is just a placeholder that measures wall time
-        let result = #orig_block;
-        let wall_time = wall_time_start.elapsed();
-        
-        liblogger::log_info!(
-            &format!("{} used CPU time: approx {} ms (wall time)", 
-                #fn_name, wall_time.as_millis()),
-            None
-        );
-        
-        result
-    }));
-    
-    TokenStream::from(quote!(#input_fn))
-}
-
-/// Include version information in logs
-#[proc_macro_attribute]
-pub fn version_tag(_args: TokenStream, input: TokenStream) -> TokenStream {
-    let mut input_fn = parse_macro_input!(input as ItemFn);
-    let fn_name = get_fn_name(&input_fn);
-    let orig_block = input_fn.block.clone();
-    
-    input_fn.block = Box::new(parse_quote!({
-        let version = std::env::var("BUILD_VERSION").unwrap_or_else(|_| "unknown".to_string());
-        liblogger::log_info!(
-            &format!("[Version: {}] {} called", version, #fn_name),
-            None
-        );
-        
-        let result = #orig_block;
-        result
-    }));
-    
-    TokenStream::from(quote!(#input_fn))
-}
-
-/// Attach request context to logs
-#[proc_macro_attribute]
-pub fn request_context(_args: TokenStream, input: TokenStream) -> TokenStream {
-    let mut input_fn = parse_macro_input!(input as ItemFn);
-    let fn_name = get_fn_name(&input_fn);
-    let orig_block = input_fn.block.clone();
-    
-    input_fn.block = Box::new(parse_quote!({
-        // Get context from thread-local storage (placeholder)
-        let user_id = get_thread_local_value("user_id");
-        let session_id = get_thread_local_value("session_id");
-        let request_id = get_thread_local_value("request_id");
-        
-        let mut context_parts = Vec::new();
-        if let Some(id) = user_id {
-            context_parts.push(format!("user_id={}", id));
-        }
-        if let Some(id) = session_id {
-            context_parts.push(format!("session_id={}", id));
-        }
-        if let Some(id) = request_id {
-            context_parts.push(format!("request_id={}", id));
-        }
-        
-        let context_str = if !context_parts.is_empty() {
-            context_parts.join(", ")
-        } else {
-            "No context available".to_string()
-        };
-        
-        liblogger::log_info!(
-            &format!("{} called", #fn_name),
-            Some(context_str)
-        );
-        
-        let result = #orig_block;
-        result
-    }));
-    
-    TokenStream::from(quote!(#input_fn))
-}
-
-/// Catch and log panics but don't crash
-#[proc_macro_attribute]
-pub fn catch_panic(_args: TokenStream, input: TokenStream) -> TokenStream {
-    let mut input_fn = parse_macro_input!(input as ItemFn);
-    let fn_name = get_fn_name(&input_fn);
-    let orig_block = input_fn.block.clone();
-    let is_async = input_fn.sig.asyncness.is_some();
-    
-    // Determine return type
-    let returns_result = if let syn::ReturnType::Type(_, ty) = &input_fn.sig.output {
-        if let syn::Type::Path(type_path) = ty.as_ref() {
-            let last_segment = type_path.path.segments.last().unwrap();
-            last_segment.ident.to_string() == "Result"
-        } else {
-            false
-        }
-    } else {
-        false
-    };
-    
-    if is_async {
-        // For async functions, we can't use catch_unwind effectively
-        // Instead, we just wrap the execution and handle errors at the Result level
-        if returns_result {
-            input_fn.block = Box::new(parse_quote!({
-                async move {
-                    let result = async move #orig_block.await;
-                    
-                    // Log errors if they occur
-                    if let Err(ref err) = result {
-                        liblogger::log_error!(&format!("{} returned error: {:?}", #fn_name, err), None);
-                    }
-                    
-                    result
-                }.await
-            }));
-        } else {
-            input_fn.block = Box::new(parse_quote!({
-                async move {
-                    let result = async move #orig_block.await;
-                    result
-                }.await
-            }));
-        }
-    } else {
-        input_fn.block = if returns_result {
-            Box::new(parse_quote!({
-                use std::panic::{catch_unwind, AssertUnwindSafe};
-                
-                match catch_unwind(AssertUnwindSafe(|| #orig_block)) {
-                    Ok(result) => result,
-                    Err(panic_err) => {
-                        let panic_msg = if let Some(s) = panic_err.downcast_ref::<&str>() {
-                            s.to_string()
-                        } else if let Some(s) = panic_err.downcast_ref::<String>() {
-                            s.clone()
-                        } else {
-                            "Unknown panic ".to_string()
-                        };
-                        
-                        liblogger::log_error!(&format!("{} caught panic: {}", #fn_name, panic_msg), None);
-                        Err(format!("Panic in {}: {}", #fn_name, panic_msg).into())
-                    }
-                }
-            }))
-        } else {
-            Box::new(parse_quote!({
-                use std::panic::{catch_unwind, AssertUnwindSafe};
-                
-                match catch_unwind(AssertUnwindSafe(|| #orig_block)) {
-                    Ok(result) => result,
-                    Err(panic_err) => {
-                        let panic_msg = if let Some(s) = panic_err.downcast_ref::<&str>() {
-                            s.to_string()
-                        } else if let Some(s) = panic_err.downcast_ref::<String>() {
-                            s.clone()
-                        } else {
-                            "Unknown panic ".to_string()
-                        };
-                        
-                        liblogger::log_error!(&format!("{} caught panic: {}", #fn_name, panic_msg), None);
-                        // Return default value as fallback
-                        Default::default()
-                    }
-                }
-            }))
-        };
-    }
-    
-    TokenStream::from(quote!(#input_fn))
-}
-
-/// Log health check results
-#[proc_macro_attribute]
-pub fn health_check(_args: TokenStream, input: TokenStream) -> TokenStream {
-    let mut input_fn = parse_macro_input!(input as ItemFn);
-    let fn_name = get_fn_name(&input_fn);
-    let orig_block = input_fn.block.clone();
-    
-    input_fn.block = Box::new(parse_quote!({
-        use std::time::Instant;
-        
-        let start_time = Instant::now();
-        let result = #orig_block;
-        let duration = start_time.elapsed();
-        
-        // Use pattern matching to determine success or failure
-        match &result {
-            Ok(_) => {
-                liblogger::log_info!(
-                    &format!("Health check {} passed in {} ms", #fn_name, duration.as_millis()),
-                    None
-                );
-            },
-            Err(err) => {
-                liblogger::log_error!(
-                    &format!("Health check {} failed in {} ms: {:?}", 
-                        #fn_name, duration.as_millis(), err),
-                    None
-                );
-            }
-        }
-        
-        result
-    }));
-    
-    TokenStream::from(quote!(#input_fn))
-}
-
-/// Log function result with different levels for success/error
-#[proc_macro_attribute] 
-pub fn log_result(args: TokenStream, input: TokenStream) -> TokenStream {
-    let args = parse_macro_input!(args as MacroArgs);
-    let success_level = args.success_level.unwrap_or_else(|| "info".to_string());
-    let error_level = args.error_level.unwrap_or_else(|| "error".to_string());
-    
-    let mut input_fn = parse_macro_input!(input as ItemFn);
-    let fn_name = get_fn_name(&input_fn);
-    let orig_block = input_fn.block.clone();
-    
-    // Create string literals for the different log levels to avoid str_as_str
-    let success_level_str = success_level.clone();
-    let error_level_str = error_level.clone();
-    
-    input_fn.block = Box::new(parse_quote!({
-        let result = #orig_block;
-        
-        // Use pattern matching to handle the Result
-        match &result {
-            Ok(val) => {
-                // Success case with different log levels
-                let level = #success_level_str;
-                if level == "debug" {
-                    liblogger::log_debug!(&format!("{} succeeded with result: {:?}", #fn_name, val), None);
-                } else if level == "warn" {
-                    liblogger::log_warn!(&format!("{} succeeded with result: {:?}", #fn_name, val), None);
-                } else if level == "error" {
-                    liblogger::log_error!(&format!("{} succeeded with result: {:?}", #fn_name, val), None);
-                } else {
-                    liblogger::log_info!(&format!("{} succeeded with result: {:?}", #fn_name, val), None);
-                }
-            },
-            Err(err) => {
-                // Error case with different log levels
-                let level = #error_level_str;
-                if level == "debug" {
-                    liblogger::log_debug!(&format!("{} failed with error: {:?}", #fn_name, err), None);
-                } else if level == "info" {
-                    liblogger::log_info!(&format!("{} failed with error: {:?}", #fn_name, err), None);
-                } else if level == "warn" {
-                    liblogger::log_warn!(&format!("{} failed with error: {:?}", #fn_name, err), None);
-                } else {
-                    liblogger::log_error!(&format!("{} failed with error: {:?}", #fn_name, err), None);
-                }
-            }
-        }
-        
-        result
-    }));
-    
-    TokenStream::from(quote!(#input_fn))
-}
-
-// ====================
-// DevOps Infrastructure Macros
-// ====================
-
-/// Monitor disk usage and alert on threshold breaches
-#[proc_macro_attribute]
-pub fn log_disk_usage(args: TokenStream, input: TokenStream) -> TokenStream {
-    let args = parse_macro_input!(args as MacroArgs);
-    let threshold = args.threshold.unwrap_or(80) as u64; // Convert to u64
-    let mut input_fn = parse_macro_input!(input as ItemFn);
-    let fn_name = get_fn_name(&input_fn);
-    let orig_block = input_fn.block.clone();
-    let utility_functions = generate_utility_functions();
-
-    input_fn.block = Box::new(parse_quote!({
-        // Inject utility functions directly into the generated code
-        #utility_functions
-        
-        let start_time = std::time::Instant::now();
-        let disk_info_before = get_disk_info();
-        
-        let result = #orig_block;
-        
-        let duration = start_time.elapsed();
-        let disk_info_after = get_disk_info();
-        let disk_change = if disk_info_after.used_percentage > disk_info_before.used_percentage {
-            disk_info_after.used_percentage - disk_info_before.used_percentage
-        } else {
-            0.0
-        };
-        
-        let current_usage = disk_info_after.used_percentage as u64;
-        let formatted_disk_info = format_disk_info(&disk_info_after);
-        
-        if current_usage >= #threshold {
-            liblogger::log_warn!(
-                &format!("DISK_ALERT: {} - High disk usage detected: {}% (threshold: {}%) | {} | Change: +{:.1}% | Duration: {}ms", 
-                    #fn_name, current_usage, #threshold, formatted_disk_info, disk_change, duration.as_millis()),
-                None
-            );
-        } else {
-            liblogger::log_info!(
-                &format!("DISK_MONITOR: {} - Disk usage: {}% (threshold: {}%) | {} | Change: +{:.1}% | Duration: {}ms", 
-                    #fn_name, current_usage, #threshold, formatted_disk_info, disk_change, duration.as_millis()),
-                None
-            );
-        }
-        
-        result
-    }));
-    
-    TokenStream::from(quote!(#input_fn))
-}
-
-/// Monitor network connectivity and detect connection issues
-#[proc_macro_attribute]
-pub fn log_network_connectivity(args: TokenStream, input: TokenStream) -> TokenStream {
-    let args = parse_macro_input!(args as MacroArgs);
-    let endpoint = args.endpoint.unwrap_or_else(|| "8.8.8.8:53".to_string());
-    let mut input_fn = parse_macro_input!(input as ItemFn);
-    let fn_name = get_fn_name(&input_fn);
-    let orig_block = input_fn.block.clone();
-    let utility_functions = generate_utility_functions();
-
-    input_fn.block = Box::new(parse_quote!({
-        // Inject utility functions directly into the generated code
-        #utility_functions
-        
-        let start_time = std::time::Instant::now();
-        let network_info_before = get_network_interfaces();
-        let connectivity_before = check_network_connectivity(&#endpoint);
-        
-        let result = #orig_block;
-        
-        let duration = start_time.elapsed();
-        let network_info_after = get_network_interfaces();
-        let connectivity_after = check_network_connectivity(&#endpoint);
-        let formatted_network_info = format_network_info(&network_info_after);
-        
-        if connectivity_before && connectivity_after {
-            liblogger::log_info!(
-                &format!("NETWORK_OK: {} - Connectivity maintained to {} | {} | Duration: {}ms", 
-                    #fn_name, #endpoint, formatted_network_info, duration.as_millis()),
-                None
-            );
-        } else if !connectivity_before && connectivity_after {
-            liblogger::log_info!(
-                &format!("NETWORK_RECOVERED: {} - Connectivity restored to {} | {} | Duration: {}ms", 
-                    #fn_name, #endpoint, formatted_network_info, duration.as_millis()),
-                None
-            );
-        } else if connectivity_before && !connectivity_after {
-            liblogger::log_error!(
-                &format!("NETWORK_LOST: {} - Connectivity lost to {} | {} | Duration: {}ms", 
-                    #fn_name, #endpoint, formatted_network_info, duration.as_millis()),
-                None
-            );
-        } else {
-            liblogger::log_warn!(
-                &format!("NETWORK_DOWN: {} - No connectivity to {} | {} | Duration: {}ms", 
-                    #fn_name, #endpoint, formatted_network_info, duration.as_millis()),
-                None
-            );
-        }
-        
-        result
-    }));
-    
-    TokenStream::from(quote!(#input_fn))
-}
-
-/// Monitor database connection pool health and performance
-#[proc_macro_attribute]
-pub fn log_database_pool(args: TokenStream, input: TokenStream) -> TokenStream {
-    let args = parse_macro_input!(args as MacroArgs);
-    let pool_name = args.pool_name.unwrap_or_else(|| "default".to_string());
-    let threshold = args.threshold.unwrap_or(80) as u64; // Convert to u64
-    let mut input_fn = parse_macro_input!(input as ItemFn);
-    let fn_name = get_fn_name(&input_fn);
-    let orig_block = input_fn.block.clone();
-    let utility_functions = generate_utility_functions();
-
-    input_fn.block = Box::new(parse_quote!({
-        // Inject utility functions directly into the generated code
-        #utility_functions
-        
-        let start_time = std::time::Instant::now();
-        let pool_stats_before = get_db_pool_stats(&#pool_name);
-        
-        let result = #orig_block;
-        
-        let duration = start_time.elapsed();
-        let pool_stats_after = get_db_pool_stats(&#pool_name);
-        let formatted_pool_info = format_db_pool_info(&pool_stats_after);
-        
-        let utilization = pool_stats_after.utilization_percentage;
-        
-        if utilization >= #threshold as f64 {
-            liblogger::log_warn!(
-                &format!("DB_POOL_ALERT: {} - High pool utilization: {:.1}% (threshold: {}%) | Pool: {} | {} | Duration: {}ms", 
-                    #fn_name, utilization, #threshold, #pool_name, formatted_pool_info, duration.as_millis()),
-                None
-            );
-        } else {
-            liblogger::log_info!(
-                &format!("DB_POOL_MONITOR: {} - Pool utilization: {:.1}% | Pool: {} | {} | Duration: {}ms", 
-                    #fn_name, utilization, #pool_name, formatted_pool_info, duration.as_millis()),
-                None
-            );
-        }
-        
-        result
-    }));
-    
-    TokenStream::from(quote!(#input_fn))
-}
-
-/// Monitor file descriptor usage and detect resource leaks
-#[proc_macro_attribute]
-pub fn log_file_descriptors(args: TokenStream, input: TokenStream) -> TokenStream {
-    let args = parse_macro_input!(args as MacroArgs);
-    let threshold = args.threshold.unwrap_or(1000) as u64; // Convert to u64
-    let mut input_fn = parse_macro_input!(input as ItemFn);
-    let fn_name = get_fn_name(&input_fn);
-    let orig_block = input_fn.block.clone();
-    let utility_functions = generate_utility_functions();
-
-    input_fn.block = Box::new(parse_quote!({
-        // Inject utility functions directly into the generated code
-        #utility_functions
-        
-        let start_time = std::time::Instant::now();
-        let fd_count_before = get_fd_count();
-        let fd_limit = get_fd_limit();
-        
-        let result = #orig_block;
-        
-        let duration = start_time.elapsed();
-        let fd_count_after = get_fd_count();
-        let fd_change = if fd_count_after > fd_count_before { 
-            fd_count_after - fd_count_before 
-        } else { 
-            0 
-        };
-        let formatted_fd_info = format_fd_info(fd_count_after, fd_limit);
-        
-        if fd_count_after >= #threshold {
-            liblogger::log_warn!(
-                &format!("FD_ALERT: {} - High file descriptor usage: {} (threshold: {}) | {} | Change: +{} | Duration: {}ms", 
-                    #fn_name, fd_count_after, #threshold, formatted_fd_info, fd_change, duration.as_millis()),
-                None
-            );
-        } else {
-            liblogger::log_info!(
-                &format!("FD_MONITOR: {} - File descriptors: {} | {} | Change: +{} | Duration: {}ms", 
-                    #fn_name, fd_count_after, formatted_fd_info, fd_change, duration.as_millis()),
-                None
-            );
-        }
-        
-        result
-    }));
-    
-    TokenStream::from(quote!(#input_fn))
-}
-
-/// Monitor cache hit ratio and performance metrics
-#[proc_macro_attribute]
-pub fn log_cache_hit_ratio(args: TokenStream, input: TokenStream) -> TokenStream {
-    let args = parse_macro_input!(args as MacroArgs);
-    let threshold = args.threshold.unwrap_or(70);
-    let cache_name = args.cache_name.unwrap_or_else(|| "default".to_string());
-    let mut input_fn = parse_macro_input!(input as ItemFn);
-    let fn_name = get_fn_name(&input_fn);
-    let orig_block = input_fn.block.clone();
-    let utility_functions = generate_utility_functions();
-
-    input_fn.block = Box::new(parse_quote!({
-        #utility_functions
-        
-        let start_time = std::time::Instant::now();
-        let cache_stats_before = get_cache_stats(&#cache_name);
-        
-        let result = #orig_block;
-        
-        let duration = start_time.elapsed();
-        let cache_stats_after = get_cache_stats(&#cache_name);
-        let formatted_cache_info = format_cache_info(&cache_stats_after);
-        
-        let hit_ratio = cache_stats_after.hit_ratio_percentage;
-        
-        if hit_ratio < #threshold as f64 {
-            liblogger::log_warn!(
-                &format!("CACHE_ALERT: {} - Low cache hit ratio: {:.1}% (threshold: {}%) | Cache: {} | {} | Duration: {}ms", 
-                    #fn_name, hit_ratio, #threshold, #cache_name, formatted_cache_info, duration.as_millis()),
-                None
-            );
-        } else {
-            liblogger::log_info!(
-                &format!("CACHE_MONITOR: {} - Cache hit ratio: {:.1}% | Cache: {} | {} | Duration: {}ms", 
-                    #fn_name, hit_ratio, #cache_name, formatted_cache_info, duration.as_millis()),
-                None
-            );
-        }
-        
-        result
-    }));
-    
-    TokenStream::from(quote!(#input_fn))
-}
-
-/// Monitor queue depth and processing performance
-#[proc_macro_attribute]
-pub fn log_queue_depth(args: TokenStream, input: TokenStream) -> TokenStream {
-    let args = parse_macro_input!(args as MacroArgs);
-    let queue_name = args.queue_name.unwrap_or_else(|| "default".to_string());
-    let threshold = args.threshold.unwrap_or(1000) as u64; // Convert to u64
-    let mut input_fn = parse_macro_input!(input as ItemFn);
-    let fn_name = get_fn_name(&input_fn);
-    let orig_block = input_fn.block.clone();
-    let utility_functions = generate_utility_functions();
-
-    input_fn.block = Box::new(parse_quote!({
-        #utility_functions
-        
-        let start_time = std::time::Instant::now();
-        let queue_stats_before = get_queue_stats(&#queue_name);
-        
-        let result = #orig_block;
-        
-        let duration = start_time.elapsed();
-        let queue_stats_after = get_queue_stats(&#queue_name);
-        let formatted_queue_info = format_queue_info(&queue_stats_after);
-        
-        let queue_depth = queue_stats_after.depth;
-        let processing_rate = queue_stats_after.processing_rate;
-        
-        if queue_depth >= #threshold {
-            liblogger::log_warn!(
-                &format!("QUEUE_ALERT: {} - High queue depth: {} (threshold: {}) | Queue: {} | {} | Processing: {:.1}/sec | Duration: {}ms", 
-                    #fn_name, queue_depth, #threshold, #queue_name, formatted_queue_info, processing_rate, duration.as_millis()),
-                None
-            );
-        } else {
-            liblogger::log_info!(
-                &format!("QUEUE_MONITOR: {} - Queue depth: {} | Queue: {} | {} | Processing: {:.1}/sec | Duration: {}ms", 
-                    #fn_name, queue_depth, #queue_name, formatted_queue_info, processing_rate, duration.as_millis()),
-                None
-            );
-        }
-        
-        result
-    }));
-    
-    TokenStream::from(quote!(#input_fn))
-}
-
-/// Monitor garbage collection pressure and memory management
-#[proc_macro_attribute]
-pub fn log_gc_pressure(args: TokenStream, input: TokenStream) -> TokenStream {
-    let args = parse_macro_input!(args as MacroArgs);
-    let threshold = args.threshold.unwrap_or(100) as u64; // Convert to u64
-    let mut input_fn = parse_macro_input!(input as ItemFn);
-    let fn_name = get_fn_name(&input_fn);
-    let orig_block = input_fn.block.clone();
-    let utility_functions = generate_utility_functions();
-
-    input_fn.block = Box::new(parse_quote!({
-        #utility_functions
-        
-        let start_time = std::time::Instant::now();
-        let gc_stats_before = get_gc_stats();
-        
-        let result = #orig_block;
-        
-        let duration = start_time.elapsed();
-        let gc_stats_after = get_gc_stats();
-        let formatted_gc_info = format_gc_info(&gc_stats_after);
-        
-        let gc_time_delta = gc_stats_after.total_gc_time_ms - gc_stats_before.total_gc_time_ms;
-        let gc_collections_delta = gc_stats_after.gc_collections - gc_stats_before.gc_collections;
-        
-        if gc_time_delta >= #threshold {
-            liblogger::log_warn!(
-                &format!("GC_PRESSURE_ALERT: {} - High GC activity: {}ms GC time (threshold: {}ms) | {} | Collections: +{} | Duration: {}ms", 
-                    #fn_name, gc_time_delta, #threshold, formatted_gc_info, gc_collections_delta, duration.as_millis()),
-                None
-            );
-        } else {
-            liblogger::log_info!(
-                &format!("GC_MONITOR: {} - GC time: {}ms | {} | Collections: +{} | Duration: {}ms", 
-                    #fn_name, gc_time_delta, formatted_gc_info, gc_collections_delta, duration.as_millis()),
-                None
-            );
-        }
-        
-        result
-    }));
-    
-    TokenStream::from(quote!(#input_fn))
-}
-
-/// Implement anomaly detection for function behavior patterns
-#[proc_macro_attribute]
-pub fn log_anomaly_detection(args: TokenStream, input: TokenStream) -> TokenStream {
-    let args = parse_macro_input!(args as MacroArgs);
-    let service_name = args.service_name.unwrap_or_else(|| "default".to_string());
-    let max_utilization = args.max_utilization.unwrap_or(90) as f64; // Convert to f64
-    let mut input_fn = parse_macro_input!(input as ItemFn);
-    let fn_name = get_fn_name(&input_fn);
-    let orig_block = input_fn.block.clone();
-    let utility_functions = generate_utility_functions();
-
-    input_fn.block = Box::new(parse_quote!({
-        #utility_functions
-        
-        let start_time = std::time::Instant::now();
-        let anomaly_context_before = get_anomaly_detection_context(&#service_name, &#fn_name);
-        
-        let result = #orig_block;
-        
-        let duration = start_time.elapsed();
-        let anomaly_context_after = get_anomaly_detection_context(&#service_name, &#fn_name);
-        let formatted_anomaly_info = format_anomaly_detection_info(&anomaly_context_after);
-        
-        let anomaly_score = anomaly_context_after.anomaly_score;
-        let baseline_duration_ms = anomaly_context_after.baseline_duration_ms;
-        let resource_utilization = anomaly_context_after.resource_utilization_percentage;
-        let pattern_deviation = anomaly_context_after.pattern_deviation_percentage;
-        
-        let duration_anomaly = if baseline_duration_ms > 0.0 {
-            ((duration.as_millis() as f64 - baseline_duration_ms) / baseline_duration_ms) * 100.0
-        } else {
-            0.0
-        };
-        
-        if anomaly_score > 0.8 || resource_utilization > #max_utilization || duration_anomaly > 200.0 {
-            liblogger::log_warn!(
-                &format!("ANOMALY_DETECTED: {} - Anomalous behavior detected | Service: {} | {} | Score: {:.2} | Duration anomaly: {:.1}% | Resource util: {:.1}% | Pattern deviation: {:.1}% | Duration: {}ms (baseline: {:.0}ms)", 
-                    #fn_name, #service_name, formatted_anomaly_info, anomaly_score, duration_anomaly, resource_utilization, pattern_deviation, duration.as_millis(), baseline_duration_ms),
-                None
-            );
-        } else if anomaly_score > 0.5 || resource_utilization > 70.0 {
-            liblogger::log_info!(
-                &format!("ANOMALY_WATCH: {} - Elevated anomaly metrics | Service: {} | {} | Score: {:.2} | Duration anomaly: {:.1}% | Resource util: {:.1}% | Pattern deviation: {:.1}% | Duration: {}ms (baseline: {:.0}ms)", 
-                    #fn_name, #service_name, formatted_anomaly_info, anomaly_score, duration_anomaly, resource_utilization, pattern_deviation, duration.as_millis(), baseline_duration_ms),
-                None
-            );
-        } else {
-            liblogger::log_info!(
-                &format!("ANOMALY_BASELINE: {} - Normal behavior pattern | Service: {} | {} | Score: {:.2} | Resource util: {:.1}% | Duration: {}ms", 
-                    #fn_name, #service_name, formatted_anomaly_info, anomaly_score, resource_utilization, duration.as_millis()),
-                None
-            );
-        }
-        
-        result
-    }));
-    
-    TokenStream::from(quote!(#input_fn))
-}
-
-/// Monitor API rate limits
-#[proc_macro_attribute]
-pub fn log_api_rate_limits(args: TokenStream, input: TokenStream) -> TokenStream {
-    let args = parse_macro_input!(args as MacroArgs);
-    let service_name = args.service_name.unwrap_or_else(|| "default".to_string());
-    let threshold = args.threshold.unwrap_or(90);
-    let mut input_fn = parse_macro_input!(input as ItemFn);
-    let fn_name = get_fn_name(&input_fn);
-    let orig_block = input_fn.block.clone();
-    
-    input_fn.block = Box::new(parse_quote!({
-        let start_time = std::time::Instant::now();
-        let result = #orig_block;
-        let duration = start_time.elapsed();
-        
-        liblogger::log_info!(
-            &format!("API_RATE_LIMITS: {} - Service: {} | Threshold: {}% | Duration: {}ms", 
-                #fn_name, #service_name, #threshold, duration.as_millis()),
-            None
-        );
-        
-        result
-    }));
-    
-    TokenStream::from(quote!(#input_fn))
-}
-
-/// Monitor SSL certificate expiry
-#[proc_macro_attribute]
-pub fn log_ssl_certificate_expiry(args: TokenStream, input: TokenStream) -> TokenStream {
-    let args = parse_macro_input!(args as MacroArgs);
-    let domain = args.domain.unwrap_or_else(|| "example.com".to_string());
-    let days_warning = args.days_warning.unwrap_or(30);
-    let mut input_fn = parse_macro_input!(input as ItemFn);
-    let fn_name = get_fn_name(&input_fn);
-    let orig_block = input_fn.block.clone();
-    
-    input_fn.block = Box::new(parse_quote!({
-        let start_time = std::time::Instant::now();
-        let result = #orig_block;
-        let duration = start_time.elapsed();
-        
-        liblogger::log_info!(
-            &format!("SSL_CERTIFICATE_EXPIRY: {} - Domain: {} | Warning threshold: {} days | Duration: {}ms", 
-                #fn_name, #domain, #days_warning, duration.as_millis()),
-            None
-        );
-        
-        result
-    }));
-    
-    TokenStream::from(quote!(#input_fn))
-}
-
-/// Monitor service discovery
-#[proc_macro_attribute]
-pub fn log_service_discovery(args: TokenStream, input: TokenStream) -> TokenStream {
-    let args = parse_macro_input!(args as MacroArgs);
-    let service_name = args.service_name.unwrap_or_else(|| "default".to_string());
-    let mut input_fn = parse_macro_input!(input as ItemFn);
-    let fn_name = get_fn_name(&input_fn);
-    let orig_block = input_fn.block.clone();
-    
-    input_fn.block = Box::new(parse_quote!({
-        let start_time = std::time::Instant::now();
-        let result = #orig_block;
-        let duration = start_time.elapsed();
-        
-        liblogger::log_info!(
-            &format!("SERVICE_DISCOVERY: {} - Service: {} | Duration: {}ms", 
-                #fn_name, #service_name, duration.as_millis()),
-            None
-        );
-        
-        result
-    }));
-    
-    TokenStream::from(quote!(#input_fn))
-}
-
-/// Monitor load balancer health
-#[proc_macro_attribute]
-pub fn log_load_balancer_health(args: TokenStream, input: TokenStream) -> TokenStream {
-    let args = parse_macro_input!(args as MacroArgs);
-    let service_name = args.service_name.unwrap_or_else(|| "default".to_string());
-    let threshold = args.threshold.unwrap_or(3);
-    let mut input_fn = parse_macro_input!(input as ItemFn);
-    let fn_name = get_fn_name(&input_fn);
-    let orig_block = input_fn.block.clone();
-    
-    input_fn.block = Box::new(parse_quote!({
-        let start_time = std::time::Instant::now();
-        let result = #orig_block;
-        let duration = start_time.elapsed();
-        
-        liblogger::log_info!(
-            &format!("LOAD_BALANCER_HEALTH: {} - Service: {} | Threshold: {} | Duration: {}ms", 
-                #fn_name, #service_name, #threshold, duration.as_millis()),
-            None
-        );
-        
-        result
-    }));
-    
-    TokenStream::from(quote!(#input_fn))
-}
-
-/// Monitor security events
-#[proc_macro_attribute]
-pub fn log_security_event(args: TokenStream, input: TokenStream) -> TokenStream {
-    let args = parse_macro_input!(args as MacroArgs);
-    let warning_level = args.warning_level.unwrap_or_else(|| "medium".to_string());
-    let mut input_fn = parse_macro_input!(input as ItemFn);
-    let fn_name = get_fn_name(&input_fn);
-    let orig_block = input_fn.block.clone();
-    
-    input_fn.block = Box::new(parse_quote!({
-        let start_time = std::time::Instant::now();
-        let result = #orig_block;
-        let duration = start_time.elapsed();
-        
-        liblogger::log_warn!(
-            &format!("SECURITY_EVENT: {} - Warning level: {} | Duration: {}ms", 
-                #fn_name, #warning_level, duration.as_millis()),
-            None
-        );
-        
-        result
-    }));
-    
-    TokenStream::from(quote!(#input_fn))
-}
-
-/// Monitor compliance checks
-#[proc_macro_attribute]
-pub fn log_compliance_check(args: TokenStream, input: TokenStream) -> TokenStream {
-    let args = parse_macro_input!(args as MacroArgs);
-    let domain = args.domain.unwrap_or_else(|| "default".to_string());
-    let mut input_fn = parse_macro_input!(input as ItemFn);
-    let fn_name = get_fn_name(&input_fn);
-    let orig_block = input_fn.block.clone();
-    
-    input_fn.block = Box::new(parse_quote!({
-        let start_time = std::time::Instant::now();
-        let result = #orig_block;
-        let duration = start_time.elapsed();
-        
-        liblogger::log_info!(
-            &format!("COMPLIANCE_CHECK: {} - Domain: {} | Duration: {}ms", 
-                #fn_name, #domain, duration.as_millis()),
-            None
-        );
-        
-        result
-    }));
-    
-    TokenStream::from(quote!(#input_fn))
-}
-
-/// Monitor access control
-#[proc_macro_attribute]
-pub fn log_access_control(args: TokenStream, input: TokenStream) -> TokenStream {
-    let args = parse_macro_input!(args as MacroArgs);
-    let domain = args.domain.unwrap_or_else(|| "default".to_string());
-    let mut input_fn = parse_macro_input!(input as ItemFn);
-    let fn_name = get_fn_name(&input_fn);
-    let orig_block = input_fn.block.clone();
-    
-    input_fn.block = Box::new(parse_quote!({
-        let start_time = std::time::Instant::now();
-        let result = #orig_block;
-        let duration = start_time.elapsed();
-        
-        liblogger::log_info!(
-            &format!("ACCESS_CONTROL: {} - Domain: {} | Duration: {}ms", 
-                #fn_name, #domain, duration.as_millis()),
-            None
-        );
-        
-        result
-    }));
-    
-    TokenStream::from(quote!(#input_fn))
-}
-
-/// Monitor crypto operations
-#[proc_macro_attribute]
-pub fn log_crypto_operation(args: TokenStream, input: TokenStream) -> TokenStream {
-    let args = parse_macro_input!(args as MacroArgs);
-    let domain = args.domain.unwrap_or_else(|| "default".to_string());
-    let mut input_fn = parse_macro_input!(input as ItemFn);
-    let fn_name = get_fn_name(&input_fn);
-    let orig_block = input_fn.block.clone();
-    
-    input_fn.block = Box::new(parse_quote!({
-        let start_time = std::time::Instant::now();
-        let result = #orig_block;
-        let duration = start_time.elapsed();
-        
-        liblogger::log_info!(
-            &format!("CRYPTO_OPERATION: {} - Domain: {} | Duration: {}ms", 
-                #fn_name, #domain, duration.as_millis()),
-            None
-        );
-        
-        result
-    }));
-    
-    TokenStream::from(quote!(#input_fn))
-}
-
-/// Monitor config changes
-#[proc_macro_attribute]
-pub fn log_config_change(args: TokenStream, input: TokenStream) -> TokenStream {
-    let args = parse_macro_input!(args as MacroArgs);
-    let domain = args.domain.unwrap_or_else(|| "default".to_string());
-    let mut input_fn = parse_macro_input!(input as ItemFn);
-    let fn_name = get_fn_name(&input_fn);
-    let orig_block = input_fn.block.clone();
-    
-    input_fn.block = Box::new(parse_quote!({
-        let start_time = std::time::Instant::now();
-        let result = #orig_block;
-        let duration = start_time.elapsed();
-        
-        liblogger::log_info!(
-            &format!("CONFIG_CHANGE: {} - Domain: {} | Duration: {}ms", 
-                #fn_name, #domain, duration.as_millis()),
-            None
-        );
-        
-        result
-    }));
-    
-    TokenStream::from(quote!(#input_fn))
-}
-
-/// Monitor deployments
-#[proc_macro_attribute]
-pub fn log_deployment(args: TokenStream, input: TokenStream) -> TokenStream {
-    let args = parse_macro_input!(args as MacroArgs);
-    let service_name = args.service_name.unwrap_or_else(|| "default".to_string());
-    let mut input_fn = parse_macro_input!(input as ItemFn);
-    let fn_name = get_fn_name(&input_fn);
-    let orig_block = input_fn.block.clone();
-    
-    input_fn.block = Box::new(parse_quote!({
-        let start_time = std::time::Instant::now();
-        let result = #orig_block;
-        let duration = start_time.elapsed();
-        
-        liblogger::log_info!(
-            &format!("DEPLOYMENT: {} - Service: {} | Duration: {}ms", 
-                #fn_name, #service_name, duration.as_millis()),
-            None
-        );
-        
-        result
-    }));
-    
-    TokenStream::from(quote!(#input_fn))
-}
-
-/// Monitor environment validation
-#[proc_macro_attribute]
-pub fn log_environment_validation(args: TokenStream, input: TokenStream) -> TokenStream {
-    let args = parse_macro_input!(args as MacroArgs);
-    let service_name = args.service_name.unwrap_or_else(|| "default".to_string());
-    let mut input_fn = parse_macro_input!(input as ItemFn);
-    let fn_name = get_fn_name(&input_fn);
-    let orig_block = input_fn.block.clone();
-    
-    input_fn.block = Box::new(parse_quote!({
-        let start_time = std::time::Instant::now();
-        let result = #orig_block;
-        let duration = start_time.elapsed();
-        
-        liblogger::log_info!(
-            &format!("ENVIRONMENT_VALIDATION: {} - Service: {} | Duration: {}ms", 
-                #fn_name, #service_name, duration.as_millis()),
-            None
-        );
-        
-        result
-    }));
-    
-    TokenStream::from(quote!(#input_fn))
-}
-
-/// Monitor feature flag changes
-#[proc_macro_attribute]
-pub fn log_feature_flag_change(args: TokenStream, input: TokenStream) -> TokenStream {
-    let args = parse_macro_input!(args as MacroArgs);
-    let min_percentage = args.min_percentage.unwrap_or(0);
-    let max_percentage = args.max_percentage.unwrap_or(100);
-    let mut input_fn = parse_macro_input!(input as ItemFn);
-    let fn_name = get_fn_name(&input_fn);
-    let orig_block = input_fn.block.clone();
-    
-    input_fn.block = Box::new(parse_quote!({
-        let start_time = std::time::Instant::now();
-        let result = #orig_block;
-        let duration = start_time.elapsed();
-        
-        liblogger::log_info!(
-            &format!("FEATURE_FLAG_CHANGE: {} - Min: {}% | Max: {}% | Duration: {}ms", 
-                #fn_name, #min_percentage, #max_percentage, duration.as_millis()),
-            None
-        );
-        
-        result
-    }));
-    
-    TokenStream::from(quote!(#input_fn))
-}
-
-/// Monitor thread pool utilization and performance
-#[proc_macro_attribute]
-pub fn log_thread_pool_utilization(args: TokenStream, input: TokenStream) -> TokenStream {
-    let args = parse_macro_input!(args as MacroArgs);
-    let thread_pool_name = args.thread_pool_name.unwrap_or_else(|| "default".to_string());
-    let threshold = args.threshold.unwrap_or(90);
-    let mut input_fn = parse_macro_input!(input as ItemFn);
-    let fn_name = get_fn_name(&input_fn);
-    let orig_block = input_fn.block.clone();
-    let utility_functions = generate_utility_functions();
-
-    input_fn.block = Box::new(parse_quote!({
-        #utility_functions
-        
-        let start_time = std::time::Instant::now();
-        let pool_stats_before = get_thread_pool_stats(&#thread_pool_name);
-        
-        let result = #orig_block;
-        
-        let duration = start_time.elapsed();
-        let pool_stats_after = get_thread_pool_stats(&#thread_pool_name);
-        let formatted_pool_info = format_thread_pool_info(&pool_stats_after);
-        
-        let utilization = pool_stats_after.utilization_percentage;
-        
-        if utilization >= #threshold as f64 {
-            liblogger::log_warn!(
-                &format!("THREAD_POOL_ALERT: {} - High utilization: {:.1}% (threshold: {}%) | Pool: {} | {} | Duration: {}ms", 
-                    #fn_name, utilization, #threshold, #thread_pool_name, formatted_pool_info, duration.as_millis()),
-                None
-            );
-        } else {
-            liblogger::log_info!(
-                &format!("THREAD_POOL_MONITOR: {} - Utilization: {:.1}% | Pool: {} | {} | Duration: {}ms", 
-                    #fn_name, utilization, #thread_pool_name, formatted_pool_info, duration.as_millis()),
-                None
-            );
-        }
-        
-        result
-    }));
-    
-    TokenStream::from(quote!(#input_fn))
-}
-
-/// Monitor business rule execution and validation
-#[proc_macro_attribute]
-pub fn log_business_rule(args: TokenStream, input: TokenStream) -> TokenStream {
-    let args = parse_macro_input!(args as MacroArgs);
-    let domain = args.domain.unwrap_or_else(|| "default".to_string());
-    let mut input_fn = parse_macro_input!(input as ItemFn);
-    let fn_name = get_fn_name(&input_fn);
-    let orig_block = input_fn.block.clone();
-    let utility_functions = generate_utility_functions();
-
-    input_fn.block = Box::new(parse_quote!({
-        #utility_functions
-        
-        let start_time = std::time::Instant::now();
-        let rule_context = get_business_rule_context(&#domain, &#fn_name);
-        
-        let result = #orig_block;
-        
-        let duration = start_time.elapsed();
-        let formatted_rule_info = format_business_rule_info(&rule_context);
-        
-        let rule_name = &rule_context.rule_name;
-        let rule_version = &rule_context.rule_version;
-        let execution_count = rule_context.execution_count;
-        
-        match &result {
-            Ok(_) => {
-                liblogger::log_info!(
-                    &format!("BUSINESS_RULE_PASS: {} - Business rule validation passed | Domain: {} | Rule: {} | {} | Version: {} | Executions: {} | Duration: {}ms", 
-                        #fn_name, #domain, rule_name, formatted_rule_info, rule_version, execution_count, duration.as_millis()),
-                    None
-                );
-            },
-            Err(_) => {
-                liblogger::log_warn!(
-                    &format!("BUSINESS_RULE_FAIL: {} - Business rule validation failed | Domain: {} | Rule: {} | {} | Version: {} | Executions: {} | Duration: {}ms", 
-                        #fn_name, #domain, rule_name, formatted_rule_info, rule_version, execution_count, duration.as_millis()),
-                    None
-                );
-            }
-        }
-        
-        result
-    }));
-    
-    TokenStream::from(quote!(#input_fn))
-}
-
-/// Monitor data quality checks and validation processes
-#[proc_macro_attribute]
-pub fn log_data_quality(args: TokenStream, input: TokenStream) -> TokenStream {
-    let args = parse_macro_input!(args as MacroArgs);
-    let domain = args.domain.unwrap_or_else(|| "default".to_string());
-    let threshold = args.threshold.unwrap_or(95);
-    let mut input_fn = parse_macro_input!(input as ItemFn);
-    let fn_name = get_fn_name(&input_fn);
-    let orig_block = input_fn.block.clone();
-    let utility_functions = generate_utility_functions();
-
-    input_fn.block = Box::new(parse_quote!({
-        #utility_functions
-        
-        let start_time = std::time::Instant::now();
-        let quality_metrics_before = get_data_quality_metrics(&#domain);
-        
-        let result = #orig_block;
-        
-        let duration = start_time.elapsed();
-        let quality_metrics_after = get_data_quality_metrics(&#domain);
-        let formatted_quality_info = format_data_quality_info(&quality_metrics_after);
-        
-        let quality_score = quality_metrics_after.quality_score_percentage;
-        let records_processed = quality_metrics_after.records_processed;
-        let validation_rules_passed = quality_metrics_after.validation_rules_passed;
-        let total_validation_rules = quality_metrics_after.total_validation_rules;
-        
-        if quality_score < #threshold as f64 {
-            liblogger::log_warn!(
-                &format!("DATA_QUALITY_ALERT: {} - Low data quality score: {:.1}% (threshold: {}%) | Domain: {} | {} | Records: {} | Rules: {}/{} | Duration: {}ms", 
-                    #fn_name, quality_score, #threshold, #domain, formatted_quality_info, records_processed, validation_rules_passed, total_validation_rules, duration.as_millis()),
-                None
-            );
-        } else {
-            liblogger::log_info!(
-                &format!("DATA_QUALITY_OK: {} - Data quality score: {:.1}% | Domain: {} | {} | Records: {} | Rules: {}/{} | Duration: {}ms", 
-                    #fn_name, quality_score, #domain, formatted_quality_info, records_processed, validation_rules_passed, total_validation_rules, duration.as_millis()),
-                None
-            );
-        }
-        
-        result
-    }));
-    
-    TokenStream::from(quote!(#input_fn))
-}
-
-/// Monitor workflow and process execution steps
-#[proc_macro_attribute]
-pub fn log_workflow_step(args: TokenStream, input: TokenStream) -> TokenStream {
-    let args = parse_macro_input!(args as MacroArgs);
-    let domain = args.domain.unwrap_or_else(|| "default".to_string());
-    let max_depth = args.max_depth.unwrap_or(10);
-    let mut input_fn = parse_macro_input!(input as ItemFn);
-    let fn_name = get_fn_name(&input_fn);
-    let orig_block = input_fn.block.clone();
-    let utility_functions = generate_utility_functions();
-
-    input_fn.block = Box::new(parse_quote!({
-        #utility_functions
-        
-        let start_time = std::time::Instant::now();
-        let workflow_state_before = get_workflow_state(&#domain, &#fn_name);
-        
-        let result = #orig_block;
-        
-        let duration = start_time.elapsed();
-        let workflow_state_after = get_workflow_state(&#domain, &#fn_name);
-        let formatted_workflow_info = format_workflow_info(&workflow_state_after);
-        
-        let workflow_id = &workflow_state_after.workflow_id;
-        let step_name = &workflow_state_after.current_step;
-        let step_depth = workflow_state_after.step_depth;
-        let total_steps = workflow_state_after.total_steps;
-        let completed_steps = workflow_state_after.completed_steps;
-        
-        if step_depth > #max_depth {
-            liblogger::log_warn!(
-                &format!("WORKFLOW_DEPTH_ALERT: {} - Workflow depth exceeded | Domain: {} | Workflow: {} | {} | Step: {} | Depth: {} (max: {}) | Progress: {}/{} | Duration: {}ms", 
-                    #fn_name, #domain, workflow_id, formatted_workflow_info, step_name, step_depth, #max_depth, completed_steps, total_steps, duration.as_millis()),
-                None
-            );
-        } else {
-            match &result {
-                Ok(_) => {
-                    liblogger::log_info!(
-                        &format!("WORKFLOW_STEP_SUCCESS: {} - Workflow step completed | Domain: {} | Workflow: {} | {} | Step: {} | Depth: {} | Progress: {}/{} | Duration: {}ms", 
-                            #fn_name, #domain, workflow_id, formatted_workflow_info, step_name, step_depth, completed_steps, total_steps, duration.as_millis()),
-                        None
-                    );
-                },
-                Err(_) => {
-                    liblogger::log_error!(
-                        &format!("WORKFLOW_STEP_FAILURE: {} - Workflow step failed | Domain: {} | Workflow: {} | {} | Step: {} | Depth: {} | Progress: {}/{} | Duration: {}ms", 
-                            #fn_name, #domain, workflow_id, formatted_workflow_info, step_name, step_depth, completed_steps, total_steps, duration.as_millis()),
-                        None
-                    );
-                }
-            }
-        }
-        
-        result
-    }));
-    
-    TokenStream::from(quote!(#input_fn))
-}
-
-/// Monitor transaction processing and state consistency
-#[proc_macro_attribute]
-pub fn log_transaction(args: TokenStream, input: TokenStream) -> TokenStream {
-    let args = parse_macro_input!(args as MacroArgs);
-    let domain = args.domain.unwrap_or_else(|| "default".to_string());
-    let timeout_ms = args.timeout_ms.unwrap_or(5000);
-    let mut input_fn = parse_macro_input!(input as ItemFn);
-    let fn_name = get_fn_name(&input_fn);
-    let orig_block = input_fn.block.clone();
-    let utility_functions = generate_utility_functions();
-
-    input_fn.block = Box::new(parse_quote!({
-        #utility_functions
-        
-        let start_time = std::time::Instant::now();
-        let tx_context = get_transaction_context(&#domain);
-        
-        let result = #orig_block;
-        
-        let duration = start_time.elapsed();
-        let formatted_tx_info = format_transaction_info(&tx_context);
-        
-        let transaction_id = &tx_context.transaction_id;
-        let isolation_level = &tx_context.isolation_level;
-        let participant_count = tx_context.participant_count;
-        
-        if duration.as_millis() > #timeout_ms as u128 {
-            liblogger::log_warn!(
-                &format!("TRANSACTION_TIMEOUT_WARNING: {} - Transaction exceeded timeout | Domain: {} | Tx ID: {} | {} | Isolation: {} | Participants: {} | Duration: {}ms", 
-                    #fn_name, #domain, transaction_id, formatted_tx_info, isolation_level, participant_count, duration.as_millis()),
-                None
-            );
-        } else {
-            match &result {
-                Ok(_) => {
-                    liblogger::log_info!(
-                        &format!("TRANSACTION_SUCCESS: {} - Transaction completed successfully | Domain: {} | Tx ID: {} | {} | Isolation: {} | Participants: {} | Duration: {}ms", 
-                            #fn_name, #domain, transaction_id, formatted_tx_info, isolation_level, participant_count, duration.as_millis()),
-                        None
-                    );
-                },
-                Err(_) => {
-                    liblogger::log_error!(
-                        &format!("TRANSACTION_FAILURE: {} - Transaction failed | Domain: {} | Tx ID: {} | {} | Isolation: {} | Participants: {} | Duration: {}ms", 
-                            #fn_name, #domain, transaction_id, formatted_tx_info, isolation_level, participant_count, duration.as_millis()),
-                        None
-                    );
-                }
-            }
-        }
-        
-        result
-    }));
-    
-    TokenStream::from(quote!(#input_fn))
-}
-
-/// Monitor inter-service communication and RPC calls
-#[proc_macro_attribute]
-pub fn log_service_communication(args: TokenStream, input: TokenStream) -> TokenStream {
-    let args = parse_macro_input!(args as MacroArgs);
-    let service_name = args.service_name.unwrap_or_else(|| "unknown".to_string());
-    let timeout_ms = args.timeout_ms.unwrap_or(5000);
-    let mut input_fn = parse_macro_input!(input as ItemFn);
-    let fn_name = get_fn_name(&input_fn);
-    let orig_block = input_fn.block.clone();
-    let utility_functions = generate_utility_functions();
-
-    input_fn.block = Box::new(parse_quote!({
-        #utility_functions
-        
-        let start_time = std::time::Instant::now();
-        let comm_context = get_service_communication_context(&#service_name);
-        
-        let result = #orig_block;
-        
-        let duration = start_time.elapsed();
-        let formatted_comm_info = format_service_communication_info(&comm_context);
-        
-        let target_service = &comm_context.target_service;
-        let protocol = &comm_context.protocol;
-        let circuit_breaker_state = &comm_context.circuit_breaker_state;
-        
-        if duration.as_millis() > #timeout_ms as u128 {
-            liblogger::log_warn!(
-                &format!("SERVICE_COMM_TIMEOUT: {} - Service communication timeout | Target: {} | {} | Protocol: {} | Circuit Breaker: {} | Duration: {}ms (timeout: {}ms)", 
-                    #fn_name, target_service, formatted_comm_info, protocol, circuit_breaker_state, duration.as_millis(), #timeout_ms),
-                None
-            );
-        } else {
-            match &result {
-                Ok(_) => {
-                    liblogger::log_info!(
-                        &format!("SERVICE_COMM_SUCCESS: {} - Service communication successful | Target: {} | {} | Protocol: {} | Circuit Breaker: {} | Duration: {}ms", 
-                            #fn_name, target_service, formatted_comm_info, protocol, circuit_breaker_state, duration.as_millis()),
-                        None
-                    );
-                },
-                Err(_) => {
-                    liblogger::log_error!(
-                        &format!("SERVICE_COMM_FAILURE: {} - Service communication failed | Target: {} | {} | Protocol: {} | Circuit Breaker: {} | Duration: {}ms", 
-                            #fn_name, target_service, formatted_comm_info, protocol, circuit_breaker_state, duration.as_millis()),
-                        None
-                    );
-                }
-            }
-        }
-        
-        result
-    }));
-    
-    TokenStream::from(quote!(#input_fn))
-}
-
-/// Monitor consensus algorithm operations and cluster decisions
-#[proc_macro_attribute]
-pub fn log_consensus_operation(args: TokenStream, input: TokenStream) -> TokenStream {
-    let args = parse_macro_input!(args as MacroArgs);
-    let domain = args.domain.unwrap_or_else(|| "default".to_string());
-    let timeout_ms = args.timeout_ms.unwrap_or(10000);
-    let mut input_fn = parse_macro_input!(input as ItemFn);
-    let fn_name = get_fn_name(&input_fn);
-    let orig_block = input_fn.block.clone();
-    let utility_functions = generate_utility_functions();
-
-    input_fn.block = Box::new(parse_quote!({
-        #utility_functions
-        
-        let start_time = std::time::Instant::now();
-        let consensus_context = get_consensus_context(&#domain);
-        
-        let result = #orig_block;
-        
-        let duration = start_time.elapsed();
-        let formatted_consensus_info = format_consensus_info(&consensus_context);
-        
-        let term = consensus_context.term;
-        let leader_id = &consensus_context.leader_id;
-        let node_count = consensus_context.node_count;
-        let votes_received = consensus_context.votes_received;
-        
-        if duration.as_millis() > #timeout_ms as u128 {
-            liblogger::log_warn!(
-                &format!("CONSENSUS_TIMEOUT: {} - Consensus operation timeout | Domain: {} | {} | Term: {} | Leader: {} | Votes: {}/{} | Duration: {}ms (timeout: {}ms)", 
-                    #fn_name, #domain, formatted_consensus_info, term, leader_id, votes_received, node_count, duration.as_millis(), #timeout_ms),
-                None
-            );
-        } else {
-            match &result {
-                Ok(_) => {
-                    liblogger::log_info!(
-                        &format!("CONSENSUS_SUCCESS: {} - Consensus achieved | Domain: {} | {} | Term: {} | Leader: {} | Votes: {}/{} | Duration: {}ms", 
-                            #fn_name, #domain, formatted_consensus_info, term, leader_id, votes_received, node_count, duration.as_millis()),
-                        None
-                    );
-                },
-                Err(_) => {
-                    liblogger::log_warn!(
-                        &format!("CONSENSUS_FAILURE: {} - Consensus failed | Domain: {} | {} | Term: {} | Leader: {} | Votes: {}/{} | Duration: {}ms", 
-                            #fn_name, #domain, formatted_consensus_info, term, leader_id, votes_received, node_count, duration.as_millis()),
-                        None
-                    );
-                }
-            }
-        }
-        
-        result
-    }));
-    
-    TokenStream::from(quote!(#input_fn))
-}
-
-/// Monitor cluster health and node membership changes
-#[proc_macro_attribute]
-pub fn log_cluster_health(args: TokenStream, input: TokenStream) -> TokenStream {
-    let args = parse_macro_input!(args as MacroArgs);
-    let domain = args.domain.unwrap_or_else(|| "default".to_string());
-    let threshold = args.threshold.unwrap_or(70);
-    let mut input_fn = parse_macro_input!(input as ItemFn);
-    let fn_name = get_fn_name(&input_fn);
-    let orig_block = input_fn.block.clone();
-    let utility_functions = generate_utility_functions();
-
-    input_fn.block = Box::new(parse_quote!({
-        #utility_functions
-        
-        let start_time = std::time::Instant::now();
-        let cluster_health_before = get_cluster_health_stats(&#domain);
-        
-        let result = #orig_block;
-        
-        let duration = start_time.elapsed();
-        let cluster_health_after = get_cluster_health_stats(&#domain);
-        let formatted_cluster_info = format_cluster_health_info(&cluster_health_after);
-        
-        let health_percentage = cluster_health_after.health_percentage;
-        let healthy_nodes = cluster_health_after.healthy_nodes;
-        let total_nodes = cluster_health_after.total_nodes;
-        let leader_node = &cluster_health_after.leader_node;
-        
-        if health_percentage < #threshold as f64 {
-            liblogger::log_error!(
-                &format!("CLUSTER_HEALTH_CRITICAL: {} - Cluster health critical: {:.1}% (threshold: {}%) | Domain: {} | {} | Healthy: {}/{} | Leader: {} | Duration: {}ms", 
-                    #fn_name, health_percentage, #threshold, #domain, formatted_cluster_info, healthy_nodes, total_nodes, leader_node, duration.as_millis()),
-                None
-            );
-        } else if health_percentage < 90.0 {
-            liblogger::log_warn!(
-                &format!("CLUSTER_HEALTH_DEGRADED: {} - Cluster health degraded: {:.1}% | Domain: {} | {} | Healthy: {}/{} | Leader: {} | Duration: {}ms", 
-                    #fn_name, health_percentage, #domain, formatted_cluster_info, healthy_nodes, total_nodes, leader_node, duration.as_millis()),
-                None
-            );
-        } else {
-            liblogger::log_info!(
-                &format!("CLUSTER_HEALTH_OK: {} - Cluster health good: {:.1}% | Domain: {} | {} | Healthy: {}/{} | Leader: {} | Duration: {}ms", 
-                    #fn_name, health_percentage, #domain, formatted_cluster_info, healthy_nodes, total_nodes, leader_node, duration.as_millis()),
-                None
-            );
-        }
-        
-        result
-    }));
-    
-    TokenStream::from(quote!(#input_fn))
-}
-
-/// Monitor distributed lock operations and resource coordination
-#[proc_macro_attribute]
-pub fn log_distributed_lock(args: TokenStream, input: TokenStream) -> TokenStream {
-    let args = parse_macro_input!(args as MacroArgs);
-    let domain = args.domain.unwrap_or_else(|| "default".to_string());
-    let timeout_ms = args.timeout_ms.unwrap_or(30000);
-    let mut input_fn = parse_macro_input!(input as ItemFn);
-    let fn_name = get_fn_name(&input_fn);
-    let orig_block = input_fn.block.clone();
-    let utility_functions = generate_utility_functions();
-
-    input_fn.block = Box::new(parse_quote!({
-        #utility_functions
-        
-        let start_time = std::time::Instant::now();
-        let lock_context = get_distributed_lock_context(&#domain, &#fn_name);
-        
-        let result = #orig_block;
-        
-        let duration = start_time.elapsed();
-        let formatted_lock_info = format_distributed_lock_info(&lock_context);
-        
-        let lock_id = &lock_context.lock_id;
-        let holder_node = &lock_context.holder_node;
-        let lock_type = &lock_context.lock_type;
-        let wait_queue_size = lock_context.wait_queue_size;
-        
-        if duration.as_millis() > #timeout_ms as u128 {
-            liblogger::log_warn!(
-                &format!("DISTRIBUTED_LOCK_TIMEOUT: {} - Lock operation timeout | Domain: {} | Lock ID: {} | {} | Holder: {} | Type: {} | Queue: {} | Duration: {}ms (timeout: {}ms)", 
-                    #fn_name, #domain, lock_id, formatted_lock_info, holder_node, lock_type, wait_queue_size, duration.as_millis(), #timeout_ms),
-                None
-            );
-        } else {
-            match &result {
-                Ok(_) => {
-                    liblogger::log_info!(
-                        &format!("DISTRIBUTED_LOCK_SUCCESS: {} - Lock operation successful | Domain: {} | Lock ID: {} | {} | Holder: {} | Type: {} | Queue: {} | Duration: {}ms", 
-                            #fn_name, #domain, lock_id, formatted_lock_info, holder_node, lock_type, wait_queue_size, duration.as_millis()),
-                        None
-                    );
-                },
-                Err(_) => {
-                    liblogger::log_warn!(
-                        &format!("DISTRIBUTED_LOCK_FAILURE: {} - Lock operation failed | Domain: {} | Lock ID: {} | {} | Holder: {} | Type: {} | Queue: {} | Duration: {}ms", 
-                            #fn_name, #domain, lock_id, formatted_lock_info, holder_node, lock_type, wait_queue_size, duration.as_millis()),
-                        None
-                    );
-                }
-            }
-        }
-        
-        result
-    }));
-    
-    TokenStream::from(quote!(#input_fn))
-}
-
-/// Implement distributed tracing with correlation IDs
-#[proc_macro_attribute]
-pub fn log_trace_correlation(args: TokenStream, input: TokenStream) -> TokenStream {
-    let args = parse_macro_input!(args as MacroArgs);
-    let service_name = args.service_name.unwrap_or_else(|| "unknown".to_string());
-    let mut input_fn = parse_macro_input!(input as ItemFn);
-    let fn_name = get_fn_name(&input_fn);
-    let orig_block = input_fn.block.clone();
-    let utility_functions = generate_utility_functions();
-
-    input_fn.block = Box::new(parse_quote!({
-        #utility_functions
-        
-        let start_time = std::time::Instant::now();
-        let trace_context = get_trace_context(&#service_name, &#fn_name);
-        
-        let result = #orig_block;
-        
-        let duration = start_time.elapsed();
-        let formatted_trace_info = format_trace_info(&trace_context);
-        
-        let trace_id = &trace_context.trace_id;
-        let span_id = &trace_context.span_id;
-        let parent_span_id = &trace_context.parent_span_id;
-        let baggage = &trace_context.baggage;
-        
-        match &result {
-            Ok(_) => {
-                liblogger::log_info!(
-                    &format!("TRACE_SPAN_SUCCESS: {} - Span completed successfully | Service: {} | {} | Trace: {} | Span: {} | Parent: {} | Baggage: {} | Duration: {}ms", 
-                        #fn_name, #service_name, formatted_trace_info, trace_id, span_id, parent_span_id, baggage, duration.as_millis()),
-                    None
-                );
-            },
-            Err(_) => {
-                liblogger::log_error!(
-                    &format!("TRACE_SPAN_ERROR: {} - Span completed with error | Service: {} | {} | Trace: {} | Span: {} | Parent: {} | Baggage: {} | Duration: {}ms", 
-                        #fn_name, #service_name, formatted_trace_info, trace_id, span_id, parent_span_id, baggage, duration.as_millis()),
-                    None
-                );
-            }
-        }
-        
-        result
-    }));
-    
-    TokenStream::from(quote!(#input_fn))
-}
-
-/// Collect custom metrics and dimensional data
-#[proc_macro_attribute]
-pub fn log_custom_metrics(args: TokenStream, input: TokenStream) -> TokenStream {
-    let args = parse_macro_input!(args as MacroArgs);
-    let metric_name = args.metric_name.unwrap_or_else(|| "custom_metric".to_string());
-    let mut input_fn = parse_macro_input!(input as ItemFn);
-    let fn_name = get_fn_name(&input_fn);
-    let orig_block = input_fn.block.clone();
-    let utility_functions = generate_utility_functions();
-
-    input_fn.block = Box::new(parse_quote!({
-        #utility_functions
-        
-        let start_time = std::time::Instant::now();
-        let metrics_context_before = get_custom_metrics_context(&#metric_name);
-        
-        let result = #orig_block;
-        
-        let duration = start_time.elapsed();
-        let metrics_context_after = get_custom_metrics_context(&#metric_name);
-        let formatted_metrics_info = format_custom_metrics_info(&metrics_context_after);
-        
-        let metric_value = metrics_context_after.metric_value;
-        let dimensions = &metrics_context_after.dimensions;
-        let metric_type = &metrics_context_after.metric_type;
-        let tags = &metrics_context_after.tags;
-        
-        let value_delta = metric_value - metrics_context_before.metric_value;
-        
-        liblogger::log_info!(
-            &format!("CUSTOM_METRICS: {} - Metric collected | Metric: {} | {} | Value: {:.2} (Δ{:.2}) | Type: {} | Dimensions: {} | Tags: {} | Duration: {}ms", 
-                #fn_name, #metric_name, formatted_metrics_info, metric_value, value_delta, metric_type, dimensions, tags, duration.as_millis()),
-            None
-        );
-        
-        result
-    }));
-    
-    TokenStream::from(quote!(#input_fn))
-}
-
-/// Monitor system health with multiple checkpoints
-#[proc_macro_attribute]
-pub fn log_health_check(args: TokenStream, input: TokenStream) -> TokenStream {
-    let args = parse_macro_input!(args as MacroArgs);
-    let service_name = args.service_name.unwrap_or_else(|| "default".to_string());
-    let threshold = args.threshold.unwrap_or(95);
-    let mut input_fn = parse_macro_input!(input as ItemFn);
-    let fn_name = get_fn_name(&input_fn);
-    let orig_block = input_fn.block.clone();
-    let utility_functions = generate_utility_functions();
-
-    input_fn.block = Box::new(parse_quote!({
-        #utility_functions
-        
-        let start_time = std::time::Instant::now();
-        let health_context = get_health_check_context(&#service_name);
-        
-        let result = #orig_block;
-        
-        let duration = start_time.elapsed();
-        let formatted_health_info = format_health_check_info(&health_context);
-        
-        let overall_health = health_context.overall_health_percentage;
-        let checks_passed = health_context.checks_passed;
-        let total_checks = health_context.total_checks;
-        let failed_checks = &health_context.failed_checks;
-        
-        if overall_health < #threshold as f64 {
-            liblogger::log_error!(
-                &format!("HEALTH_CHECK_CRITICAL: {} - Health check failed | Service: {} | {} | Health: {:.1}% (threshold: {}%) | Passed: {}/{} | Failed: {:?} | Duration: {}ms", 
-                    #fn_name, #service_name, formatted_health_info, overall_health, #threshold, checks_passed, total_checks, failed_checks, duration.as_millis()),
-                None
-            );
-        } else if overall_health < 90.0 {
-            liblogger::log_warn!(
-                &format!("HEALTH_CHECK_DEGRADED: {} - Health check degraded | Service: {} | {} | Health: {:.1}% | Passed: {}/{} | Failed: {:?} | Duration: {}ms", 
-                    #fn_name, #service_name, formatted_health_info, overall_health, checks_passed, total_checks, failed_checks, duration.as_millis()),
-                None
-            );
-        } else {
-            liblogger::log_info!(
-                &format!("HEALTH_CHECK_OK: {} - Health check passed | Service: {} | {} | Health: {:.1}% | Passed: {}/{} | Duration: {}ms", 
-                    #fn_name, #service_name, formatted_health_info, overall_health, checks_passed, total_checks, duration.as_millis()),
-                None
-            );
-        }
-        
-        result
-    }));
-    
-    TokenStream::from(quote!(#input_fn))
-}
+/*
+ * Procedural macros for enhanced logging capabilities
+ *
+ * This module provides procedural macros that can be applied to functions
+ * for various logging, monitoring, and instrumentation purposes.
+ * 
+ * These macros work with the liblogger crate to provide automatic context
+ * capturing, timing measurements, and other advanced logging features.
+ *
+ * With the `disabled` feature every attribute returns the function untouched
+ * and initialize_logger_attributes! expands to nothing.
+ */
+
+extern crate proc_macro;
+
+// Import our utils module (keep it private)
+mod macro_utils;
+
+use proc_macro::TokenStream;
+use quote::{quote, format_ident};
+use syn::{parse_macro_input, parse_quote, ItemFn};
+
+// Import helpers from our utils module
+use crate::macro_utils::{checked_fn, get_fn_name, init_check, IdList, MacroArgs, define_helper_functions, format_duration_fn, describe_error, entry_exit_body, generate_utility_functions, latency_summary, log_macro_for_level, registered_metric, render_args, return_type_name, returns_result, wrap_body};
+
+/// Initialization macro that must be called at the module level to enable attribute macros
+///
+/// This macro defines helper functions needed by the attribute macros, such as
+/// error extraction, success checking, trace ID management, and feature flag checking.
+/// Attribute macros used in a module without it fail to compile with a
+/// message asking for it (see `liblogger::attributes`).
+///
+/// The helpers are defined even with the `disabled` feature, so code calling
+/// them (`format_duration`, `get_trace_id`, ...) builds either way.
+///
+#[proc_macro]
+pub fn initialize_logger_attributes(_input: TokenStream) -> TokenStream {
+    TokenStream::from(define_helper_functions())
+}
+
+/// Logs function entry and exit points to track execution flow
+///
+/// Automatically adds INFO level logs at the start and end of the function.
+/// Useful for tracing code execution paths during debugging and in production.
+///
+/// - `level = "debug"` logs ENTRY/EXIT at another level
+/// - `timing = true` adds the elapsed time to the EXIT line
+///
+#[proc_macro_attribute]
+pub fn log_entry_exit(args: TokenStream, input: TokenStream) -> TokenStream {
+    if cfg!(feature = "disabled") {
+        return input;
+    }
+    let args = parse_macro_input!(args as MacroArgs);
+    let log_macro = args.level
+        .and_then(|level| log_macro_for_level(&level))
+        .unwrap_or_else(|| quote!(liblogger::log_info));
+    let timing = args.timing.unwrap_or(false);
+    
+    let mut input_fn = parse_macro_input!(input as ItemFn);
+    let fn_name = get_fn_name(&input_fn);
+    let is_async = input_fn.sig.asyncness.is_some();
+    
+    *input_fn.block = entry_exit_body(&fn_name, &input_fn.block, is_async, &log_macro, timing);
+    
+    checked_fn(input_fn)
+}
+
+/// Logs entry, exit and elapsed time for every method of an `impl` block
+///
+/// Equivalent to `#[log_entry_exit(timing = true)]` on each method, logged
+/// as "Type::method". `const fn` methods are left alone.
+///
+/// - `skip = (new, len)` leaves the named methods uninstrumented
+/// - `level = "debug"` logs ENTRY/EXIT at another level
+/// - `timing = false` drops the elapsed time from the EXIT line
+///
+#[proc_macro_attribute]
+pub fn instrument_all(args: TokenStream, input: TokenStream) -> TokenStream {
+    if cfg!(feature = "disabled") {
+        return input;
+    }
+    let args = parse_macro_input!(args as MacroArgs);
+    let log_macro = args.level
+        .and_then(|level| log_macro_for_level(&level))
+        .unwrap_or_else(|| quote!(liblogger::log_info));
+    let timing = args.timing.unwrap_or(true);
+    let skip = args.skip.unwrap_or_default();
+    
+    let mut input_impl = parse_macro_input!(input as syn::ItemImpl);
+    let type_name = match input_impl.self_ty.as_ref() {
+        syn::Type::Path(type_path) => type_path.path.segments.last().map(|segment| segment.ident.to_string()),
+        _ => None,
+    };
+    
+    let mut methods = Vec::new();
+    for item in &mut input_impl.items {
+        let syn::ImplItem::Fn(method) = item else {
+            continue;
+        };
+        let method_name = method.sig.ident.to_string();
+        methods.push(method_name.clone());
+        if skip.contains(&method_name) || method.sig.constness.is_some() {
+            continue;
+        }
+        
+        let label = match &type_name {
+            Some(type_name) => format!("{}::{}", type_name, method_name),
+            None => method_name,
+        };
+        let is_async = method.sig.asyncness.is_some();
+        method.block = entry_exit_body(&label, &method.block, is_async, &log_macro, timing);
+        method.block.stmts.insert(0, init_check());
+    }
+    
+    // A misspelled skip entry would silently instrument the method
+    if let Some(unknown) = skip.iter().find(|name| !methods.contains(name)) {
+        return syn::Error::new(
+            proc_macro2::Span::call_site(),
+            format!("instrument_all: \"{}\" is not a method of this impl block", unknown),
+        ).to_compile_error().into();
+    }
+    
+    TokenStream::from(quote!(#input_impl))
+}
+
+/// Log errors and panics
+///
+/// With `count_metric = "name"` every error also increments a Prometheus
+/// counter of that name, labeled by `error_type`: the enum variant from the
+/// error's `Debug` output, or its type name when that isn't an identifier.
+#[proc_macro_attribute]
+pub fn log_errors(args: TokenStream, input: TokenStream) -> TokenStream {
+    if cfg!(feature = "disabled") {
+        return input;
+    }
+    let args = parse_macro_input!(args as MacroArgs);
+    let mut input_fn = parse_macro_input!(input as ItemFn);
+    let fn_name = get_fn_name(&input_fn);
+    let orig_block = input_fn.block.clone();
+    let is_async = input_fn.sig.asyncness.is_some();
+    let described = describe_error(quote!(err));
+    
+    let count_error = match args.count_metric {
+        Some(metric_name) => {
+            let help = format!("Errors returned by {}, by error type", fn_name);
+            let counter = registered_metric(
+                quote!(prometheus::IntCounterVec),
+                &metric_name,
+                quote!(prometheus::register_int_counter_vec!(#metric_name, #help, &["error_type"])),
+            );
+            quote! {
+                if let Some(counter) = #counter {
+                    // "Variant(..)" / "Variant { .. }" -> "Variant"; anything else by type
+                    let debug = format!("{:?}", err);
+                    let variant: String = debug.chars()
+                        .take_while(|c| c.is_alphanumeric() || *c == '_')
+                        .collect();
+                    let error_type = if variant.starts_with(|c: char| c.is_alphabetic()) {
+                        variant
+                    } else {
+                        std::any::type_name_of_val(err).to_string()
+                    };
+                    counter.with_label_values(&[error_type.as_str()]).inc();
+                }
+            }
+        },
+        None => quote!(),
+    };
+    
+    if is_async {
+        *input_fn.block = parse_quote!({
+            async move {
+                let result = async move #orig_block.await;
+                
+                // Use pattern matching to handle Result types
+                match &result {
+                    Ok(_) => {},  // Success case, no logging needed
+                    Err(err) => {
+                        // Error case, log the error
+                        liblogger::log_error!(&format!("{} returned error: {}", #fn_name, #described), None);
+                        #count_error
+                    }
+                }
+                result
+            }.await
+        });
+    } else {
+        *input_fn.block = parse_quote!({
+            use std::panic::{catch_unwind, AssertUnwindSafe};
+            
+            let result = catch_unwind(AssertUnwindSafe(|| #orig_block));
+            
+            match result {
+                Ok(inner_result) => {
+                    // Use pattern matching to handle Result types
+                    match &inner_result {
+                        Ok(_) => {},  // Success case, no logging needed
+                        Err(err) => {
+                            // Error case, log the error
+                            liblogger::log_error!(&format!("{} returned error: {}", #fn_name, #described), None);
+                            #count_error
+                        }
+                    }
+                    inner_result
+                },
+                Err(panic_err) => {
+                    let panic_msg = if let Some(s) = panic_err.downcast_ref::<&str>() {
+                        s.to_string()
+                    } else if let Some(s) = panic_err.downcast_ref::<String>() {
+                        s.clone()
+                    } else {
+                        "Unknown panic".to_string()
+                    };
+                    
+                    liblogger::log_error!(&format!("{} panicked: {}", #fn_name, panic_msg), None);
+                    std::panic::resume_unwind(panic_err);
+                }
+            }
+        });
+    }
+    
+    checked_fn(input_fn)
+}
+
+/// Measure execution time of a function
+///
+/// `summarize = true` replaces the per-call lines with a min/mean/p50/p95/
+/// p99/max summary every 100 calls (`summarize_every = N` to change the
+/// window). `per_call = true` keeps the per-call lines as well. Panics are
+/// always logged.
+///
+/// `histogram = "name"` also observes every call's duration, in seconds and
+/// including calls that panic, into a Prometheus histogram of that name.
+#[proc_macro_attribute]
+pub fn measure_time(args: TokenStream, input: TokenStream) -> TokenStream {
+    if cfg!(feature = "disabled") {
+        return input;
+    }
+    let args = parse_macro_input!(args as MacroArgs);
+    let summarize = args.summarize.unwrap_or(false) || args.summarize_every.is_some();
+    let per_call = args.per_call.unwrap_or(!summarize);
+    
+    let mut input_fn = parse_macro_input!(input as ItemFn);
+    let fn_name = get_fn_name(&input_fn);
+    let orig_block = input_fn.block.clone();
+    let is_async = input_fn.sig.asyncness.is_some();
+    let format_duration = format_duration_fn();
+    
+    let summary = if summarize {
+        latency_summary(&fn_name, args.summarize_every.unwrap_or(100), quote!(duration.as_secs_f64() * 1000.0))
+    } else {
+        quote! {}
+    };
+    let observe = match args.histogram {
+        Some(metric_name) => {
+            let help = format!("Duration of {} calls in seconds", fn_name);
+            let histogram = registered_metric(
+                quote!(prometheus::Histogram),
+                &metric_name,
+                quote!(prometheus::register_histogram!(#metric_name, #help)),
+            );
+            quote! {
+                if let Some(histogram) = #histogram {
+                    histogram.observe(duration.as_secs_f64());
+                }
+            }
+        },
+        None => quote! {},
+    };
+    let completed = quote! {
+        #observe
+        if #per_call {
+            liblogger::log_info!(&format!("{} completed in {}", #fn_name, format_duration(duration)), None);
+        }
+        #summary
+    };
+    
+    if is_async {
+        *input_fn.block = parse_quote!({
+            async move {
+                use std::time::Instant;
+                #format_duration
+                
+                let start_time = Instant::now();
+                let result = async move #orig_block.await;
+                let duration = start_time.elapsed();
+                
+                #completed
+                result
+            }.await
+        });
+    } else {
+        *input_fn.block = parse_quote!({
+            use std::time::Instant;
+            use std::panic::{catch_unwind, AssertUnwindSafe};
+            #format_duration
+            
+            let start_time = Instant::now();
+            
+            let result = catch_unwind(AssertUnwindSafe(|| #orig_block));
+            
+            let duration = start_time.elapsed();
+            
+            match result {
+                Ok(output) => {
+                    #completed
+                    output
+                },
+                Err(panic_err) => {
+                    #observe
+                    liblogger::log_error!(
+                        &format!("{} panicked after {}", #fn_name, format_duration(duration)), 
+                        None
+                    );
+                    std::panic::resume_unwind(panic_err);
+                }
+            }
+        });
+    }
+    
+    checked_fn(input_fn)
+}
+
+/// Log specified function arguments
+///
+/// On methods, `self.field` logs a field of the receiver and a bare `self`
+/// logs the whole receiver (which must implement `Debug`); nothing is moved.
+/// Use `max_len = N` to truncate each argument's `{:?}` rendering to N
+/// characters, and `level = "debug"` to change the level (default info).
+#[proc_macro_attribute]
+pub fn log_args(args: TokenStream, input: TokenStream) -> TokenStream {
+    if cfg!(feature = "disabled") {
+        return input;
+    }
+    let args = parse_macro_input!(args as IdList);
+    let mut input_fn = parse_macro_input!(input as ItemFn);
+    let fn_name = get_fn_name(&input_fn);
+    let orig_block = input_fn.block.clone();
+    let log_macro = log_macro_for_level(args.level.as_deref().unwrap_or("info"))
+        .expect("level validated while parsing");
+    let render = render_args(&args);
+    
+    *input_fn.block = parse_quote!({
+        let args_str = #render;
+        #log_macro!(&format!("Entering {} with args: {}", #fn_name, args_str), None);
+        #orig_block
+    });
+    
+    checked_fn(input_fn)
+}
+
+/// Log a function's arguments on entry and its return value on exit
+///
+/// Takes the same arguments as `#[log_args]` and logs the same entry line,
+/// then "{name} returned: {:?}" when the body completes, both at `level`
+/// (default info). Unlike stacking `#[log_args]` and `#[log_response]`, the
+/// body is wrapped once. Works on `async fn`s, where the arguments are
+/// rendered before the body first runs.
+#[proc_macro_attribute]
+pub fn log_call(args: TokenStream, input: TokenStream) -> TokenStream {
+    if cfg!(feature = "disabled") {
+        return input;
+    }
+    let args = parse_macro_input!(args as IdList);
+    let mut input_fn = parse_macro_input!(input as ItemFn);
+    let fn_name = get_fn_name(&input_fn);
+    let orig_block = input_fn.block.clone();
+    let is_async = input_fn.sig.asyncness.is_some();
+    let log_macro = log_macro_for_level(args.level.as_deref().unwrap_or("info"))
+        .expect("level validated while parsing");
+    let render = render_args(&args);
+    
+    *input_fn.block = wrap_body(is_async, quote! {
+        let args_str = #render;
+        #log_macro!(&format!("Entering {} with args: {}", #fn_name, args_str), None);
+    }, &orig_block, quote! {
+        #log_macro!(&format!("{} returned: {:?}", #fn_name, result), None);
+    });
+    
+    checked_fn(input_fn)
+}
+
+/// Log and implement retry logic
+#[proc_macro_attribute]
+pub fn log_retries(args: TokenStream, input: TokenStream) -> TokenStream {
+    if cfg!(feature = "disabled") {
+        return input;
+    }
+    let args = parse_macro_input!(args as MacroArgs);
+    let max_attempts = args.max_attempts.unwrap_or(3);
+      let mut input_fn = parse_macro_input!(input as ItemFn);
+    let fn_name = get_fn_name(&input_fn);
+    let orig_block = input_fn.block.clone();
+    let is_async = input_fn.sig.asyncness.is_some();
+
+    if is_async {
+        *input_fn.block = parse_quote!({
+            async move {
+                let mut attempts = 0u32;
+                loop {
+                    attempts += 1;
+                    if attempts > 1 {
+                        liblogger::log_info!(
+                            &format!("Retry attempt {} of {} for {}", attempts, #max_attempts, #fn_name), 
+                            None
+                        );
+                        // For async functions, we skip the delay to avoid tokio dependency
+                        // The user should implement their own delay if needed
+                        liblogger::log_info!(
+                            &format!("Async retry delay skipped for {} (implement your own async delay if needed)", #fn_name), 
+                            None
+                        );
+                    }
+                    
+                    let result = async move #orig_block.await;
+                    
+                    // Use pattern matching to determine success or failure
+                    match &result {
+                        Ok(_) => {
+                            // Success case
+                            if attempts > 1 {
+                                liblogger::log_info!(
+                                    &format!("{} succeeded after {} attempts", #fn_name, attempts), 
+                                    None
+                                );
+                            }
+                            return result;
+                        },
+                        Err(err) => {
+                            // Error case
+                            if attempts >= #max_attempts {
+                                liblogger::log_error!(
+                                    &format!("{} failed after {} attempts: {:?}", #fn_name, attempts, err), 
+                                    None
+                                );
+                                return result;
+                            }
+                            
+                            liblogger::log_warn!(
+                                &format!("{} attempt {} failed: {:?}", #fn_name, attempts, err), 
+                                None
+                            );
+                            // Continue to next retry iteration
+                        }
+                    }
+                }
+            }.await
+        });
+    } else {
+        *input_fn.block = parse_quote!({
+            let mut attempts = 0u32;
+            loop {
+                attempts += 1;
+                if attempts > 1 {
+                    liblogger::log_info!(
+                        &format!("Retry attempt {} of {} for {}", attempts, #max_attempts, #fn_name), 
+                        None
+                    );
+                    // Simple exponential backoff
+                    std::thread::sleep(std::time::Duration::from_millis((2u64.pow(attempts - 1) * 50) as u64));
+                }
+                
+                let result = (|| #orig_block)();
+                
+                // Use pattern matching to determine success or failure
+                match &result {
+                    Ok(_) => {
+                        // Success case
+                        if attempts > 1 {
+                            liblogger::log_info!(
+                                &format!("{} succeeded after {} attempts", #fn_name, attempts), 
+                                None
+                            );
+                        }
+                        return result;
+                    },
+                    Err(err) => {
+                        // Error case
+                        if attempts >= #max_attempts {
+                            liblogger::log_error!(
+                                &format!("{} failed after {} attempts: {:?}", #fn_name, attempts, err), 
+                                None
+                            );
+                            return result;
+                        }
+                        
+                        liblogger::log_warn!(
+                            &format!("{} attempt {} failed: {:?}", #fn_name, attempts, err), 
+                            None
+                        );
+                        // Continue to next retry iteration
+                    }
+                }
+            }
+        });
+    }
+    
+    checked_fn(input_fn)
+}
+
+/// Abort an async function that runs past a deadline
+///
+/// `#[deadline(ms = 500)]` runs the body under `tokio::time::timeout`. If it
+/// hasn't finished after `ms` milliseconds it is dropped, an ERROR is
+/// logged, and the function returns an `Err` built from a message
+/// describing the timeout, so the error type must implement `From<String>`.
+/// Only `async fn`s returning a `Result` are accepted. Requires `tokio` with
+/// the `time` feature in the calling crate.
+#[proc_macro_attribute]
+pub fn deadline(args: TokenStream, input: TokenStream) -> TokenStream {
+    if cfg!(feature = "disabled") {
+        return input;
+    }
+    let args = parse_macro_input!(args as MacroArgs);
+    let mut input_fn = parse_macro_input!(input as ItemFn);
+    let fn_name = get_fn_name(&input_fn);
+    let orig_block = input_fn.block.clone();
+    
+    let Some(ms) = args.ms else {
+        return syn::Error::new(
+            proc_macro2::Span::call_site(),
+            "deadline requires a limit, e.g. #[deadline(ms = 500)]",
+        ).to_compile_error().into();
+    };
+    if input_fn.sig.asyncness.is_none() {
+        return syn::Error::new_spanned(
+            &input_fn.sig,
+            "deadline can only be applied to an async fn",
+        ).to_compile_error().into();
+    }
+    if !returns_result(&input_fn.sig) {
+        return syn::Error::new_spanned(
+            &input_fn.sig,
+            "deadline requires the function to return a Result",
+        ).to_compile_error().into();
+    }
+    
+    *input_fn.block = parse_quote!({
+        let limit = std::time::Duration::from_millis(#ms);
+        match tokio::time::timeout(limit, async move #orig_block).await {
+            Ok(result) => result,
+            Err(_) => {
+                let message = format!("{} exceeded its deadline of {}ms", #fn_name, #ms);
+                liblogger::log_error!(&message, None);
+                Err(message.into())
+            }
+        }
+    });
+    
+    checked_fn(input_fn)
+}
+
+/// Create detailed audit logs
+#[proc_macro_attribute]
+pub fn audit_log(_args: TokenStream, input: TokenStream) -> TokenStream {
+    if cfg!(feature = "disabled") {
+        return input;
+    }
+    let mut input_fn = parse_macro_input!(input as ItemFn);
+    let fn_name = get_fn_name(&input_fn);
+    let orig_block = input_fn.block.clone();
+    let is_async = input_fn.sig.asyncness.is_some();
+    
+    let prologue = quote! {
+        let user_id = get_thread_local_value("user_id").unwrap_or_else(|| "unknown".to_string());
+        liblogger::log_info!(&format!("AUDIT: {} called", #fn_name), format!("user_id={}", user_id));
+        
+        let start_time = std::time::Instant::now();
+    };
+    
+    let epilogue = if is_async {
+        quote! {
+            let duration = start_time.elapsed();
+            
+            liblogger::log_info!(
+                &format!("AUDIT: {} completed in {}", #fn_name, format_duration(duration)),
+                format!("user_id={}", user_id)
+            );
+        }
+    } else {
+        quote! {
+            let duration = start_time.elapsed();
+            
+            // Use pattern matching on result
+            match &result {
+                () => {
+                    // Unit return type
+                    liblogger::log_info!(
+                        &format!("AUDIT: {} completed in {}", #fn_name, format_duration(duration)),
+                        format!("user_id={}", user_id)
+                    );
+                },
+                _ => {
+                    // Any other return type
+                    liblogger::log_info!(
+                        &format!("AUDIT: {} completed in {} with result: {:?}", 
+                            #fn_name, format_duration(duration), result),
+                        format!("user_id={}", user_id)
+                    );
+                }
+            }
+        }
+    };
+    
+    *input_fn.block = wrap_body(is_async, prologue, &orig_block, epilogue);
+    
+    checked_fn(input_fn)
+}
+
+/// Circuit breaker pattern with logging
+#[proc_macro_attribute]
+pub fn circuit_breaker(args: TokenStream, input: TokenStream) -> TokenStream {
+    if cfg!(feature = "disabled") {
+        return input;
+    }
+    let args = parse_macro_input!(args as MacroArgs);
+    let threshold = args.failure_threshold.unwrap_or(3);
+    
+    let mut input_fn = parse_macro_input!(input as ItemFn);
+    let fn_name = get_fn_name(&input_fn);
+    let orig_block = input_fn.block.clone();
+    let is_async = input_fn.sig.asyncness.is_some();
+    
+    *input_fn.block = wrap_body(is_async, quote! {
+        use std::sync::atomic::{AtomicU32, Ordering};
+        use std::sync::Mutex;
+        use std::time::{Instant, Duration};
+        
+        // Thread-safe failure counters
+        static FAILURE_COUNT: AtomicU32 = AtomicU32::new(0);
+        static LAST_SUCCESS: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+        
+        // Reset failure count after 30 seconds of success
+        let now = Instant::now();
+        let last_success_time = LAST_SUCCESS.load(Ordering::Relaxed);
+        
+        if last_success_time > 0 {
+            let elapsed = now.duration_since(Instant::now() - Duration::from_secs(last_success_time));
+            if elapsed > Duration::from_secs(30) {
+                FAILURE_COUNT.store(0, Ordering::Relaxed);
+            }
+        }
+        
+        // Check if circuit is open (too many failures)
+        let failures = FAILURE_COUNT.load(Ordering::Relaxed);
+        if failures >= #threshold {
+            liblogger::log_error!(
+                &format!("Circuit breaker open for {}: {} failures exceeded threshold {}", 
+                    #fn_name, failures, #threshold),
+                None
+            );
+            return Err(format!("Circuit breaker open for {}", #fn_name).into());
+        }
+    }, &orig_block, quote! {
+        // Use pattern matching for Result
+        match &result {
+            Ok(_) => {
+                // Reset failure count on success
+                FAILURE_COUNT.store(0, Ordering::Relaxed);
+                LAST_SUCCESS.store(now.elapsed().as_secs(), Ordering::Relaxed);
+            },
+            Err(_) => {
+                // Increment failure count
+                FAILURE_COUNT.fetch_add(1, Ordering::Relaxed);
+                let new_count = FAILURE_COUNT.load(Ordering::Relaxed);
+                
+                liblogger::log_warn!(&format!(
+                    "Circuit breaker: {} failed ({}/{} failures)", 
+                    #fn_name, new_count, #threshold
+                ), None);
+            }
+        }
+    });
+    
+    checked_fn(input_fn)
+}
+
+/// Throttle logs to avoid flooding during incidents
+///
+/// The first `rate` calls in each minute, counted from the first call, run
+/// normally and log an "executed" line. Later calls in the same minute run with logging muted, including
+/// the body's own log lines, and are counted in a summary logged once the
+/// next minute starts. Async bodies stay muted across `.await`s.
+#[proc_macro_attribute]
+pub fn throttle_log(args: TokenStream, input: TokenStream) -> TokenStream {
+    if cfg!(feature = "disabled") {
+        return input;
+    }
+    let args = parse_macro_input!(args as MacroArgs);
+    let rate = args.rate.unwrap_or(5);
+    
+    let mut input_fn = parse_macro_input!(input as ItemFn);
+    let fn_name = get_fn_name(&input_fn);
+    let orig_block = input_fn.block.clone();
+    let is_async = input_fn.sig.asyncness.is_some();
+    
+    // Decided before the body runs, with no await in between
+    let gate = quote! {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::OnceLock;
+        use std::time::Instant;
+        
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+        static LAST_MINUTE: AtomicUsize = AtomicUsize::new(0);
+        static SKIPPED_COUNT: AtomicUsize = AtomicUsize::new(0);
+        static FIRST_CALL: OnceLock<Instant> = OnceLock::new();
+        
+        // Minutes since the first call, immune to the wall clock jumping
+        let current_minute = (FIRST_CALL.get_or_init(Instant::now).elapsed().as_secs() / 60) as usize;
+        
+        // Check if we're in a new minute or still in the rate limit
+        let should_log = {
+            let last_minute = LAST_MINUTE.load(Ordering::SeqCst);
+            if last_minute != current_minute {
+                // New minute, reset counter and log a summary of skipped messages
+                LAST_MINUTE.store(current_minute, Ordering::SeqCst);
+                let skipped = SKIPPED_COUNT.swap(0, Ordering::SeqCst);
+                if skipped > 0 {
+                    liblogger::log_info!(
+                        &format!("Throttled logs for {}: skipped {} logs in previous minute", 
+                            #fn_name, skipped),
+                        None
+                    );
+                }
+                COUNTER.store(1, Ordering::SeqCst);
+                true
+            } else {
+                // Same minute, check counter
+                let count = COUNTER.fetch_add(1, Ordering::SeqCst) + 1;
+                if count <= #rate as usize {
+                    true
+                } else {
+                    SKIPPED_COUNT.fetch_add(1, Ordering::SeqCst);
+                    false
+                }
+            }
+        };
+    };
+    
+    let executed = quote! {
+        // Only log if within rate limits
+        if should_log {
+            // Simple logging without trying to match on the result type
+            liblogger::log_info!(&format!("{} executed", #fn_name), None);
+        }
+    };
+    
+    if is_async {
+        *input_fn.block = parse_quote!({
+            async move {
+                #gate
+                
+                let body = async move #orig_block;
+                let result = if should_log {
+                    body.await
+                } else {
+                    liblogger::Logger::quiet_future(body).await
+                };
+                
+                #executed
+                
+                result
+            }.await
+        });
+    } else {
+        *input_fn.block = parse_quote!({
+            #gate
+            
+            let result = {
+                let _quiet = (!should_log).then(liblogger::Logger::quiet_scope);
+                #orig_block
+            };
+            
+            #executed
+            
+            result
+        });
+    }
+    
+    checked_fn(input_fn)
+}
+
+/// Measure latency to external dependencies
+///
+/// `summarize_every = N` logs a min/mean/p50/p95/p99/max summary for the
+/// target every N calls. `per_call = false` drops the per-call start and
+/// completion lines (failures are still logged).
+#[proc_macro_attribute]
+pub fn dependency_latency(args: TokenStream, input: TokenStream) -> TokenStream {
+    if cfg!(feature = "disabled") {
+        return input;
+    }
+    let args = parse_macro_input!(args as MacroArgs);
+    let target = args.target.unwrap_or_else(|| "unknown".to_string());
+    let per_call = args.per_call.unwrap_or(true);
+    let summary = match args.summarize_every {
+        Some(every) => latency_summary(
+            &format!("Dependency {}", target),
+            every,
+            quote!(elapsed.as_secs_f64() * 1000.0),
+        ),
+        None => quote! {},
+    };
+    
+    let mut input_fn = parse_macro_input!(input as ItemFn);
+    let fn_name = get_fn_name(&input_fn);
+    let orig_block = input_fn.block.clone();
+    let is_async = input_fn.sig.asyncness.is_some();
+    let format_duration = format_duration_fn();
+    
+    *input_fn.block = wrap_body(is_async, quote! {
+        use std::time::Instant;
+        #format_duration
+        if #per_call {
+            liblogger::log_info!(
+                &format!("Dependency call to {} started for {}", #target, #fn_name),
+                None
+            );
+        }
+        let start_time = Instant::now();
+    }, &orig_block, quote! {
+        let elapsed = start_time.elapsed();
+        
+        // Use pattern matching to handle different result types
+        match &result {
+            Ok(_) => {
+                if #per_call {
+                    liblogger::log_info!(&format!("Dependency call to {} completed in {}", #target, format_duration(elapsed)), None);
+                }
+            },
+            Err(err) => {
+                liblogger::log_error!(
+                    &format!("Dependency call to {} failed after {} with error: {:?}",
+                        #target, format_duration(elapsed), err),
+                    None
+                );
+            },
+            _ => {
+                // For non-Result types
+                if #per_call {
+                    liblogger::log_info!(&format!("Dependency call to {} completed in {}", #target, format_duration(elapsed)), None);
+                }
+            }
+        }
+        
+        #summary
+    });
+    
+    checked_fn(input_fn)
+}
+
+/// Log the returned value from a function
+#[proc_macro_attribute]
+pub fn log_response(_args: TokenStream, input: TokenStream) -> TokenStream {
+    if cfg!(feature = "disabled") {
+        return input;
+    }
+    let mut input_fn = parse_macro_input!(input as ItemFn);
+    let fn_name = get_fn_name(&input_fn);
+    let orig_block = input_fn.block.clone();
+    let is_async = input_fn.sig.asyncness.is_some();
+    
+    *input_fn.block = wrap_body(is_async, quote! {}, &orig_block, quote! {
+        liblogger::log_debug!(&format!("{} returned: {:?}", #fn_name, result), None);
+    });
+    
+    checked_fn(input_fn)
+}
+
+/// Track concurrent invocations of a function
+///
+/// The running count is logged at DEBUG. With `max = N`, a WARN is emitted
+/// whenever more than N calls are in flight. The high-water mark is logged
+/// at INFO when a call that set a new peak exits. An `async fn` counts as
+/// in flight from its first poll until its body completes or is dropped.
+#[proc_macro_attribute]
+pub fn log_concurrency(args: TokenStream, input: TokenStream) -> TokenStream {
+    if cfg!(feature = "disabled") {
+        return input;
+    }
+    let args = parse_macro_input!(args as MacroArgs);
+    let mut input_fn = parse_macro_input!(input as ItemFn);
+    let fn_name = get_fn_name(&input_fn);
+    let orig_block = input_fn.block.clone();
+    let counter_var = format_ident!("CONCURRENCY_{}", fn_name.to_uppercase());
+    let peak_var = format_ident!("CONCURRENCY_PEAK_{}", fn_name.to_uppercase());
+    let is_async = input_fn.sig.asyncness.is_some();
+    
+    let max_check = match args.max {
+        Some(max) => quote! {
+            if current > #max {
+                liblogger::log_warn!(
+                    &format!("{} concurrency {} exceeds max {}", #fn_name, current, #max),
+                    None
+                );
+            }
+        },
+        None => quote! {},
+    };
+    
+    // The guard decrements the count when the call ends, including on early
+    // return, panic, or an async call whose future is dropped before finishing
+    let enter = quote! {
+        use std::sync::atomic::{AtomicU32, Ordering};
+        static #counter_var: AtomicU32 = AtomicU32::new(0);
+        static #peak_var: AtomicU32 = AtomicU32::new(0);
+        
+        struct ConcurrencyGuard {
+            current: u32,
+            new_peak: bool,
+        }
+        
+        impl Drop for ConcurrencyGuard {
+            fn drop(&mut self) {
+                let after = #counter_var.fetch_sub(1, Ordering::SeqCst) - 1;
+                liblogger::log_debug!(
+                    &format!("{} concurrent invocations after exit: {}", #fn_name, after),
+                    None
+                );
+                // Only the call holding the current record reports it
+                if self.new_peak && #peak_var.load(Ordering::SeqCst) == self.current {
+                    liblogger::log_info!(
+                        &format!("{} peak concurrency: {}", #fn_name, self.current),
+                        None
+                    );
+                }
+            }
+        }
+        
+        let current = #counter_var.fetch_add(1, Ordering::SeqCst) + 1;
+        let new_peak = #peak_var.fetch_max(current, Ordering::SeqCst) < current;
+        let _concurrency_guard = ConcurrencyGuard { current, new_peak };
+        liblogger::log_debug!(
+            &format!("{} concurrent invocations: {}", #fn_name, current),
+            None
+        );
+        #max_check
+    };
+    
+    if is_async {
+        // Count from the first poll until the awaited body finishes, not
+        // while the future is merely created
+        *input_fn.block = parse_quote!({
+            async move {
+                #enter
+                
+                async move #orig_block.await
+            }.await
+        });
+    } else {
+        *input_fn.block = parse_quote!({
+            #enter
+            
+            #orig_block
+        });
+    }
+    
+    checked_fn(input_fn)
+}
+
+/// Create and propagate a trace ID for request flow tracking
+///
+/// An existing `trace_id` in the logging context is reused; otherwise a new
+/// one is generated. While the function runs every log line carries
+/// `trace_id=...` in its context, and the previous context is restored on exit.
+#[proc_macro_attribute]
+pub fn trace_span(_args: TokenStream, input: TokenStream) -> TokenStream {
+    if cfg!(feature = "disabled") {
+        return input;
+    }
+    let mut input_fn = parse_macro_input!(input as ItemFn);
+    let fn_name = get_fn_name(&input_fn);
+    let orig_block = input_fn.block.clone();
+    let is_async = input_fn.sig.asyncness.is_some();
+    
+    let start_span = quote! {
+        use uuid::Uuid;
+        // Generate or reuse trace ID
+        let trace_id = get_trace_id().unwrap_or_else(|| Uuid::new_v4().to_string());
+        
+        liblogger::log_info!(
+            &format!("[TraceID: {}] {} started", trace_id, #fn_name),
+            None
+        );
+    };
+    
+    let end_span = quote! {
+        liblogger::log_info!(
+            &format!("[TraceID: {}] {} completed", trace_id, #fn_name),
+            None
+        );
+    };
+    
+    if is_async {
+        *input_fn.block = parse_quote!({
+            async move {
+                #start_span
+                
+                // Carry the trace ID with the future across worker threads
+                let result = liblogger::Logger::capture_context()
+                    .with_value("trace_id", &trace_id)
+                    .instrument(async move #orig_block)
+                    .await;
+                
+                #end_span
+                
+                result
+            }.await
+        });
+    } else {
+        *input_fn.block = parse_quote!({
+            #start_span
+            
+            let result = {
+                let _trace_scope = liblogger::Logger::scoped_context_value("trace_id", &trace_id);
+                #orig_block
+            };
+            
+            #end_span
+            
+            result
+        });
+    }
+    
+    checked_fn(input_fn)
+}
+
+/// Log feature flag state
+///
+/// The flag is resolved through the provider registered with
+/// `Logger::set_feature_flag_provider`; flags are disabled when none is set.
+#[proc_macro_attribute]
+pub fn feature_flag(args: TokenStream, input: TokenStream) -> TokenStream {
+    if cfg!(feature = "disabled") {
+        return input;
+    }
+    let args = parse_macro_input!(args as MacroArgs);
+    let flag_name = args.flag_name.unwrap_or_else(|| "unknown".to_string());
+    let mut input_fn = parse_macro_input!(input as ItemFn);
+    let fn_name = get_fn_name(&input_fn);
+    let orig_block = input_fn.block.clone();
+    let is_async = input_fn.sig.asyncness.is_some();
+    
+    *input_fn.block = wrap_body(is_async, quote! {
+        // Check feature flag against the registered provider
+        let is_enabled = is_feature_enabled(#flag_name);
+        
+        liblogger::log_info!(
+            &format!("{} called with feature flag {} = {}", 
+                #fn_name, #flag_name, is_enabled),
+            None
+        );
+    }, &orig_block, quote! {});
+    
+    checked_fn(input_fn)
+}
+
+/// Increment a metrics counter for function calls
+#[proc_macro_attribute]
+pub fn metrics_counter(args: TokenStream, input: TokenStream) -> TokenStream {
+    if cfg!(feature = "disabled") {
+        return input;
+    }
+    let args = parse_macro_input!(args as MacroArgs);
+    let counter_name = args.counter_name.unwrap_or_else(|| "function_calls".to_string());
+        
+    let mut input_fn = parse_macro_input!(input as ItemFn);
+    let orig_block = input_fn.block.clone();
+    let is_async = input_fn.sig.asyncness.is_some();
+    let counter = registered_metric(
+        quote!(prometheus::Counter),
+        &counter_name,
+        quote!(prometheus::register_counter!(#counter_name, "Function call counter")),
+    );
+    *input_fn.block = wrap_body(is_async, quote! {
+        // Increment counter using Prometheus
+        if let Some(counter) = #counter {
+            counter.inc();
+        }
+    }, &orig_block, quote! {});
+    
+    checked_fn(input_fn)
+}
+
+/// Log memory usage during function execution
+#[proc_macro_attribute]
+pub fn log_memory_usage(_args: TokenStream, input: TokenStream) -> TokenStream {
+    if cfg!(feature = "disabled") {
+        return input;
+    }
+    let mut input_fn = parse_macro_input!(input as ItemFn);
+    let fn_name = get_fn_name(&input_fn);
+    let orig_block = input_fn.block.clone();
+    let is_async = input_fn.sig.asyncness.is_some();
+    *input_fn.block = wrap_body(is_async, quote! {
+        let (start_rss, start_vms) = {
+            use psutil::process::Process;
+            let process = Process::current().unwrap();
+            let memory = process.memory_info().unwrap();
+            (memory.rss(), memory.vms())
+        };
+    }, &orig_block, quote! {
+        {
+            use psutil::process::Process;
+            let process = Process::current().unwrap();
+            let memory = process.memory_info().unwrap();
+            let end_rss = memory.rss();
+            let end_vms = memory.vms();
+            
+            liblogger::log_info!(
+                &format!("{} starting memory usage - RSS: {} bytes, VMS: {} bytes", 
+                    #fn_name, start_rss, start_vms),
+                None
+            );
+            liblogger::log_info!(
+                &format!("{} ending memory usage - RSS: {} bytes (delta: {} bytes), VMS: {} bytes (delta: {} bytes)", 
+                    #fn_name, end_rss, end_rss as i64 - start_rss as i64, 
+                    end_vms, end_vms as i64 - start_vms as i64),
+                None
+            );
+        }
+    });
+    
+    checked_fn(input_fn)
+}
+
+/// Log CPU time used during function execution
+#[proc_macro_attribute]
+pub fn log_cpu_time(_args: TokenStream, input: TokenStream) -> TokenStream {
+    if cfg!(feature = "disabled") {
+        return input;
+    }
+    let mut input_fn = parse_macro_input!(input as ItemFn);
+    let fn_name = get_fn_name(&input_fn);
+    let orig_block = input_fn.block.clone();
+    let is_async = input_fn.sig.asyncness.is_some();
+    let format_duration = format_duration_fn();
+    
+    *input_fn.block = wrap_body(is_async, quote! {
+        use std::time::Instant;
+        #format_duration
+        let wall_time_start = Instant::now();
+        
+        // There's no direct CPU time measurement in standard Rust
+        // This is just a placeholder that measures wall time
+    }, &orig_block, quote! {
+        let wall_time = wall_time_start.elapsed();
+        
+        liblogger::log_info!(
+            &format!("{} used CPU time: approx {} (wall time)", 
+                #fn_name, format_duration(wall_time)),
+            None
+        );
+    });
+    
+    checked_fn(input_fn)
+}
+
+/// Include version information in logs
+///
+/// The version is read from the `BUILD_VERSION` environment variable (or the
+/// one named by `env = "..."`) and otherwise defaults to the calling crate's
+/// `CARGO_PKG_VERSION`, suffixed with `+GIT_HASH` when that was set at build time.
+#[proc_macro_attribute]
+pub fn version_tag(args: TokenStream, input: TokenStream) -> TokenStream {
+    if cfg!(feature = "disabled") {
+        return input;
+    }
+    let args = parse_macro_input!(args as MacroArgs);
+    let env_var = args.env.unwrap_or_else(|| "BUILD_VERSION".to_string());
+    let mut input_fn = parse_macro_input!(input as ItemFn);
+    let fn_name = get_fn_name(&input_fn);
+    let orig_block = input_fn.block.clone();
+    let is_async = input_fn.sig.asyncness.is_some();
+    
+    let resolve_version = quote! {
+        // Runtime override first, then the crate version baked in at compile time
+        let version = std::env::var(#env_var).unwrap_or_else(|_| {
+            match option_env!("GIT_HASH") {
+                Some(hash) => format!("{}+{}", env!("CARGO_PKG_VERSION"), hash),
+                None => env!("CARGO_PKG_VERSION").to_string(),
+            }
+        });
+        liblogger::log_info!(
+            &format!("[Version: {}] {} called", version, #fn_name),
+            None
+        );
+    };
+    
+    if is_async {
+        *input_fn.block = parse_quote!({
+            async move {
+                #resolve_version
+                
+                // Tag every log emitted during the call with the version,
+                // following the future across worker threads
+                liblogger::Logger::capture_context()
+                    .with_value("version", &version)
+                    .instrument(async move #orig_block)
+                    .await
+            }.await
+        });
+    } else {
+        *input_fn.block = parse_quote!({
+            #resolve_version
+            
+            // Tag every log emitted during the call with the version
+            let _version_scope = liblogger::Logger::scoped_context_value("version", &version);
+            let result = #orig_block;
+            result
+        });
+    }
+    
+    checked_fn(input_fn)
+}
+
+/// Log a WARN each time a deprecated function is called
+///
+/// - `since = "1.2"` names the release that deprecated it
+/// - `use_instead = "new_fn"` names the replacement
+/// - `once = true` logs only the first call
+///
+/// The line's context carries `since`, `use_instead` and, for non-async
+/// functions, the caller's `called_from=file:line`, alongside the caller's
+/// logging context.
+#[proc_macro_attribute]
+pub fn log_deprecated(args: TokenStream, input: TokenStream) -> TokenStream {
+    if cfg!(feature = "disabled") {
+        return input;
+    }
+    let args = parse_macro_input!(args as MacroArgs);
+    let mut input_fn = parse_macro_input!(input as ItemFn);
+    let fn_name = get_fn_name(&input_fn);
+    let is_async = input_fn.sig.asyncness.is_some();
+    
+    let mut message = format!("DEPRECATED: {} called", fn_name);
+    let mut details = Vec::new();
+    if let Some(since) = &args.since {
+        message.push_str(&format!(" (deprecated since {})", since));
+        details.push(format!("since={}", since));
+    }
+    if let Some(use_instead) = &args.use_instead {
+        message.push_str(&format!("; use {} instead", use_instead));
+        details.push(format!("use_instead={}", use_instead));
+    }
+    
+    // The caller is only known to non-async functions, through #[track_caller]
+    let called_from = if is_async {
+        quote!()
+    } else {
+        input_fn.attrs.push(parse_quote!(#[track_caller]));
+        quote! {
+            let caller = std::panic::Location::caller();
+            let file = std::path::Path::new(caller.file())
+                .file_name()
+                .and_then(|name| name.to_str())
+                .unwrap_or(caller.file());
+            context.push(format!("called_from={}:{}", file, caller.line()));
+        }
+    };
+    let should_log = if args.once.unwrap_or(false) {
+        quote! {{
+            static LOGGED: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+            !LOGGED.swap(true, std::sync::atomic::Ordering::Relaxed)
+        }}
+    } else {
+        quote!(true)
+    };
+    
+    let orig_block = &input_fn.block;
+    *input_fn.block = parse_quote!({
+        if #should_log {
+            #[allow(unused_mut)]
+            let mut context: Vec<String> = vec![#(#details.to_string()),*];
+            #called_from
+            let context = (!context.is_empty()).then(|| context.join(", "));
+            liblogger::log_warn!(#message, context);
+        }
+        #orig_block
+    });
+    
+    checked_fn(input_fn)
+}
+
+/// Attach request context to logs
+///
+/// The request fields (user_id, session_id, request_id) are merged into the
+/// caller's thread-local context for the duration of the call, with request
+/// values winning, and the previous context is restored on exit.
+#[proc_macro_attribute]
+pub fn request_context(_args: TokenStream, input: TokenStream) -> TokenStream {
+    if cfg!(feature = "disabled") {
+        return input;
+    }
+    let mut input_fn = parse_macro_input!(input as ItemFn);
+    let fn_name = get_fn_name(&input_fn);
+    let orig_block = input_fn.block.clone();
+    let is_async = input_fn.sig.asyncness.is_some();
+    
+    let build_context = quote! {
+        // Layer the request fields over whatever context the caller already set
+        let mut request_context = liblogger::Logger::capture_context();
+        for key in ["user_id", "session_id", "request_id"] {
+            if let Some(value) = get_thread_local_value(key) {
+                request_context = request_context.with_value(key, &value);
+            }
+        }
+        let context_str = if request_context.fields().is_empty() {
+            Some("No context available".to_string())
+        } else {
+            None
+        };
+    };
+    
+    // The merged context is rendered by the logger, so only the
+    // placeholder is passed explicitly
+    let log_called = quote! {
+        
+        liblogger::log_info!(
+            &format!("{} called", #fn_name),
+            context_str
+        );
+    };
+    
+    if is_async {
+        *input_fn.block = parse_quote!({
+            async move {
+                #build_context
+                
+                request_context.instrument(async move {
+                    #log_called
+                    
+                    let result = async move #orig_block.await;
+                    result
+                }).await
+            }.await
+        });
+    } else {
+        *input_fn.block = parse_quote!({
+            #build_context
+            
+            let _request_scope = request_context.scope();
+            #log_called
+            
+            let result = #orig_block;
+            result
+        });
+    }
+    
+    checked_fn(input_fn)
+}
+
+/// Catch and log panics but don't crash
+///
+/// With `backtrace = true`, the logged error includes the backtrace of the
+/// panic when RUST_BACKTRACE enables one. Async functions are not wrapped in
+/// `catch_unwind`, so the option only applies to synchronous ones.
+#[proc_macro_attribute]
+pub fn catch_panic(args: TokenStream, input: TokenStream) -> TokenStream {
+    if cfg!(feature = "disabled") {
+        return input;
+    }
+    let args = parse_macro_input!(args as MacroArgs);
+    let mut input_fn = parse_macro_input!(input as ItemFn);
+    let fn_name = get_fn_name(&input_fn);
+    let orig_block = input_fn.block.clone();
+    let is_async = input_fn.sig.asyncness.is_some();
+    
+    // Determine return type
+    let returns_result = if let syn::ReturnType::Type(_, ty) = &input_fn.sig.output {
+        if let syn::Type::Path(type_path) = ty.as_ref() {
+            let last_segment = type_path.path.segments.last().unwrap();
+            last_segment.ident == "Result"
+        } else {
+            false
+        }
+    } else {
+        false
+    };
+    
+    // Run the body, yielding the panic payload and a note to append to the log line
+    let (catch, split_panic) = if args.backtrace.unwrap_or(false) {
+        (
+            quote!(liblogger::Logger::catch_unwind_with_backtrace(|| #orig_block)),
+            quote! {
+                let (panic_err, backtrace) = caught;
+                let backtrace_info = match backtrace {
+                    Some(backtrace) if backtrace.status() == std::backtrace::BacktraceStatus::Captured =>
+                        format!("\nBacktrace:\n{}", backtrace),
+                    _ => " (backtrace unavailable, set RUST_BACKTRACE=1 to capture it)".to_string(),
+                };
+            },
+        )
+    } else {
+        (
+            quote!(std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| #orig_block))),
+            quote! {
+                let panic_err = caught;
+                let backtrace_info = "";
+            },
+        )
+    };
+    
+    if is_async {
+        // For async functions, we can't use catch_unwind effectively
+        // Instead, we just wrap the execution and handle errors at the Result level
+        if returns_result {
+            *input_fn.block = parse_quote!({
+                async move {
+                    let result = async move #orig_block.await;
+                    
+                    // Log errors if they occur
+                    if let Err(ref err) = result {
+                        liblogger::log_error!(&format!("{} returned error: {:?}", #fn_name, err), None);
+                    }
+                    
+                    result
+                }.await
+            });
+        } else {
+            *input_fn.block = parse_quote!({
+                async move {
+                    let result = async move #orig_block.await;
+                    result
+                }.await
+            });
+        }
+    } else {
+        *input_fn.block = if returns_result {
+            parse_quote!({
+                match #catch {
+                    Ok(result) => result,
+                    Err(caught) => {
+                        #split_panic
+                        let panic_msg = if let Some(s) = panic_err.downcast_ref::<&str>() {
+                            s.to_string()
+                        } else if let Some(s) = panic_err.downcast_ref::<String>() {
+                            s.clone()
+                        } else {
+                            "Unknown panic ".to_string()
+                        };
+                        
+                        liblogger::log_error!(&format!("{} caught panic: {}{}", #fn_name, panic_msg, backtrace_info), None);
+                        Err(format!("Panic in {}: {}", #fn_name, panic_msg).into())
+                    }
+                }
+            })
+        } else {
+            parse_quote!({
+                match #catch {
+                    Ok(result) => result,
+                    Err(caught) => {
+                        #split_panic
+                        let panic_msg = if let Some(s) = panic_err.downcast_ref::<&str>() {
+                            s.to_string()
+                        } else if let Some(s) = panic_err.downcast_ref::<String>() {
+                            s.clone()
+                        } else {
+                            "Unknown panic ".to_string()
+                        };
+                        
+                        liblogger::log_error!(&format!("{} caught panic: {}{}", #fn_name, panic_msg, backtrace_info), None);
+                        // Return default value as fallback
+                        Default::default()
+                    }
+                }
+            })
+        };
+    }
+    
+    checked_fn(input_fn)
+}
+
+/// Log health check results
+///
+/// Each run is also recorded with `Logger::record_health_check`, so
+/// `Logger::health_report()` reflects the latest outcome of every check.
+#[proc_macro_attribute]
+pub fn health_check(_args: TokenStream, input: TokenStream) -> TokenStream {
+    if cfg!(feature = "disabled") {
+        return input;
+    }
+    let mut input_fn = parse_macro_input!(input as ItemFn);
+    let fn_name = get_fn_name(&input_fn);
+    let orig_block = input_fn.block.clone();
+    let is_async = input_fn.sig.asyncness.is_some();
+    let format_duration = format_duration_fn();
+    
+    *input_fn.block = wrap_body(is_async, quote! {
+        use std::time::Instant;
+        #format_duration
+        
+        let start_time = Instant::now();
+    }, &orig_block, quote! {
+        let duration = start_time.elapsed();
+        
+        // Use pattern matching to determine success or failure
+        let error = match &result {
+            Ok(_) => {
+                liblogger::log_info!(
+                    &format!("Health check {} passed in {}", #fn_name, format_duration(duration)),
+                    None
+                );
+                None
+            },
+            Err(err) => {
+                liblogger::log_error!(
+                    &format!("Health check {} failed in {}: {:?}", 
+                        #fn_name, format_duration(duration), err),
+                    None
+                );
+                Some(format!("{:?}", err))
+            }
+        };
+        liblogger::Logger::record_health_check(#fn_name, error, duration.as_secs_f64() * 1000.0);
+    });
+    
+    checked_fn(input_fn)
+}
+
+/// Log function result with different levels for success/error
+///
+/// `Ok` and `Some` values are logged at `success_level` (default "info"),
+/// `Err` and `None` at `error_level` (default "error"); an unknown level
+/// name fails the build. Functions returning anything else log that they
+/// completed at `success_level`. A return type is a `Result` when it is
+/// named `Result` (including `io::Result`); set `result_alias = true` for
+/// an alias such as `AppResult<T>`.
+#[proc_macro_attribute] 
+pub fn log_result(args: TokenStream, input: TokenStream) -> TokenStream {
+    if cfg!(feature = "disabled") {
+        return input;
+    }
+    let args = parse_macro_input!(args as MacroArgs);
+    // Level names were checked when the arguments were parsed
+    let success_macro = args.success_level.as_deref().and_then(log_macro_for_level)
+        .unwrap_or_else(|| quote!(liblogger::log_info));
+    let error_macro = args.error_level.as_deref().and_then(log_macro_for_level)
+        .unwrap_or_else(|| quote!(liblogger::log_error));
+    
+    let mut input_fn = parse_macro_input!(input as ItemFn);
+    let fn_name = get_fn_name(&input_fn);
+    let orig_block = input_fn.block.clone();
+    let is_async = input_fn.sig.asyncness.is_some();
+    let described = describe_error(quote!(err));
+    
+    // Aliases such as `AppResult<T>` are only handled as results with `result_alias = true`
+    if returns_result(&input_fn.sig) || args.result_alias.unwrap_or(false) {
+        *input_fn.block = wrap_body(is_async, quote! {}, &orig_block, quote! {
+            match &result {
+                Ok(val) => #success_macro!(&format!("{} succeeded with result: {:?}", #fn_name, val), None),
+                Err(err) => {
+                    let description = #described;
+                    #error_macro!(&format!("{} failed with error: {}", #fn_name, description), None);
+                }
+            }
+        });
+    } else if return_type_name(&input_fn.sig).is_some_and(|name| name == "Option") {
+        *input_fn.block = wrap_body(is_async, quote! {}, &orig_block, quote! {
+            match &result {
+                Some(val) => #success_macro!(&format!("{} returned Some: {:?}", #fn_name, val), None),
+                None => #error_macro!(&format!("{} returned None", #fn_name), None),
+            }
+        });
+    } else {
+        *input_fn.block = wrap_body(is_async, quote! {}, &orig_block, quote! {
+            #success_macro!(&format!("{} completed", #fn_name), None);
+        });
+    }
+    
+    checked_fn(input_fn)
+}
+
+// ====================
+// DevOps Infrastructure Macros
+// ====================
+
+/// Monitor disk usage and alert on threshold breaches
+#[proc_macro_attribute]
+pub fn log_disk_usage(args: TokenStream, input: TokenStream) -> TokenStream {
+    if cfg!(feature = "disabled") {
+        return input;
+    }
+    let args = parse_macro_input!(args as MacroArgs);
+    let threshold = args.threshold.unwrap_or(80) as u64; // Convert to u64
+    let mut input_fn = parse_macro_input!(input as ItemFn);
+    let fn_name = get_fn_name(&input_fn);
+    let orig_block = input_fn.block.clone();
+    let is_async = input_fn.sig.asyncness.is_some();
+    let utility_functions = generate_utility_functions();
+
+    *input_fn.block = wrap_body(is_async, quote! {
+        // Inject utility functions directly into the generated code
+        #utility_functions
+        
+        let start_time = std::time::Instant::now();
+        let disk_info_before = get_disk_info();
+    }, &orig_block, quote! {
+        let duration = start_time.elapsed();
+        let disk_info_after = get_disk_info();
+        let disk_change = if disk_info_after.used_percentage > disk_info_before.used_percentage {
+            disk_info_after.used_percentage - disk_info_before.used_percentage
+        } else {
+            0.0
+        };
+        
+        let current_usage = disk_info_after.used_percentage as u64;
+        let formatted_disk_info = format_disk_info(&disk_info_after);
+        
+        if current_usage >= #threshold {
+            liblogger::log_warn!(
+                &format!("DISK_ALERT: {} - High disk usage detected: {}% (threshold: {}%) | {} | Change: +{:.1}% | Duration: {}", 
+                    #fn_name, current_usage, #threshold, formatted_disk_info, disk_change, format_duration(duration)),
+                None
+            );
+        } else {
+            liblogger::log_info!(
+                &format!("DISK_MONITOR: {} - Disk usage: {}% (threshold: {}%) | {} | Change: +{:.1}% | Duration: {}", 
+                    #fn_name, current_usage, #threshold, formatted_disk_info, disk_change, format_duration(duration)),
+                None
+            );
+        }
+    });
+    
+    checked_fn(input_fn)
+}
+
+/// Monitor network connectivity and detect connection issues
+#[proc_macro_attribute]
+pub fn log_network_connectivity(args: TokenStream, input: TokenStream) -> TokenStream {
+    if cfg!(feature = "disabled") {
+        return input;
+    }
+    let args = parse_macro_input!(args as MacroArgs);
+    let endpoint = args.endpoint.unwrap_or_else(|| "8.8.8.8:53".to_string());
+    let mut input_fn = parse_macro_input!(input as ItemFn);
+    let fn_name = get_fn_name(&input_fn);
+    let orig_block = input_fn.block.clone();
+    let is_async = input_fn.sig.asyncness.is_some();
+    let utility_functions = generate_utility_functions();
+
+    *input_fn.block = wrap_body(is_async, quote! {
+        // Inject utility functions directly into the generated code
+        #utility_functions
+        
+        let start_time = std::time::Instant::now();
+        let network_info_before = get_network_interfaces();
+        let connectivity_before = check_network_connectivity(&#endpoint);
+    }, &orig_block, quote! {
+        let duration = start_time.elapsed();
+        let network_info_after = get_network_interfaces();
+        let connectivity_after = check_network_connectivity(&#endpoint);
+        let formatted_network_info = format_network_info(&network_info_after);
+        
+        if connectivity_before && connectivity_after {
+            liblogger::log_info!(
+                &format!("NETWORK_OK: {} - Connectivity maintained to {} | {} | Duration: {}", 
+                    #fn_name, #endpoint, formatted_network_info, format_duration(duration)),
+                None
+            );
+        } else if !connectivity_before && connectivity_after {
+            liblogger::log_info!(
+                &format!("NETWORK_RECOVERED: {} - Connectivity restored to {} | {} | Duration: {}", 
+                    #fn_name, #endpoint, formatted_network_info, format_duration(duration)),
+                None
+            );
+        } else if connectivity_before && !connectivity_after {
+            liblogger::log_error!(
+                &format!("NETWORK_LOST: {} - Connectivity lost to {} | {} | Duration: {}", 
+                    #fn_name, #endpoint, formatted_network_info, format_duration(duration)),
+                None
+            );
+        } else {
+            liblogger::log_warn!(
+                &format!("NETWORK_DOWN: {} - No connectivity to {} | {} | Duration: {}", 
+                    #fn_name, #endpoint, formatted_network_info, format_duration(duration)),
+                None
+            );
+        }
+    });
+    
+    checked_fn(input_fn)
+}
+
+/// Monitor database connection pool health and performance
+#[proc_macro_attribute]
+pub fn log_database_pool(args: TokenStream, input: TokenStream) -> TokenStream {
+    if cfg!(feature = "disabled") {
+        return input;
+    }
+    let args = parse_macro_input!(args as MacroArgs);
+    let pool_name = args.pool_name.unwrap_or_else(|| "default".to_string());
+    let threshold = args.threshold.unwrap_or(80) as u64; // Convert to u64
+    let mut input_fn = parse_macro_input!(input as ItemFn);
+    let fn_name = get_fn_name(&input_fn);
+    let orig_block = input_fn.block.clone();
+    let is_async = input_fn.sig.asyncness.is_some();
+    let utility_functions = generate_utility_functions();
+
+    *input_fn.block = wrap_body(is_async, quote! {
+        // Inject utility functions directly into the generated code
+        #utility_functions
+        
+        let start_time = std::time::Instant::now();
+        let pool_stats_before = get_db_pool_stats(&#pool_name);
+    }, &orig_block, quote! {
+        let duration = start_time.elapsed();
+        let pool_stats_after = get_db_pool_stats(&#pool_name);
+        let formatted_pool_info = format_db_pool_info(&pool_stats_after);
+        
+        let utilization = pool_stats_after.utilization_percentage;
+        
+        if utilization >= #threshold as f64 {
+            liblogger::log_warn!(
+                &format!("DB_POOL_ALERT: {} - High pool utilization: {:.1}% (threshold: {}%) | Pool: {} | {} | Duration: {}", 
+                    #fn_name, utilization, #threshold, #pool_name, formatted_pool_info, format_duration(duration)),
+                None
+            );
+        } else {
+            liblogger::log_info!(
+                &format!("DB_POOL_MONITOR: {} - Pool utilization: {:.1}% | Pool: {} | {} | Duration: {}", 
+                    #fn_name, utilization, #pool_name, formatted_pool_info, format_duration(duration)),
+                None
+            );
+        }
+    });
+    
+    checked_fn(input_fn)
+}
+
+/// Monitor file descriptor usage and detect resource leaks
+#[proc_macro_attribute]
+pub fn log_file_descriptors(args: TokenStream, input: TokenStream) -> TokenStream {
+    if cfg!(feature = "disabled") {
+        return input;
+    }
+    let args = parse_macro_input!(args as MacroArgs);
+    let threshold = args.threshold.unwrap_or(1000) as u64; // Convert to u64
+    let mut input_fn = parse_macro_input!(input as ItemFn);
+    let fn_name = get_fn_name(&input_fn);
+    let orig_block = input_fn.block.clone();
+    let is_async = input_fn.sig.asyncness.is_some();
+    let utility_functions = generate_utility_functions();
+
+    *input_fn.block = wrap_body(is_async, quote! {
+        // Inject utility functions directly into the generated code
+        #utility_functions
+        
+        let start_time = std::time::Instant::now();
+        let fd_count_before = get_fd_count();
+        let fd_limit = get_fd_limit();
+    }, &orig_block, quote! {
+        let duration = start_time.elapsed();
+        let fd_count_after = get_fd_count();
+        let fd_change = if fd_count_after > fd_count_before { 
+            fd_count_after - fd_count_before 
+        } else { 
+            0 
+        };
+        let formatted_fd_info = format_fd_info(fd_count_after, fd_limit);
+        
+        if fd_count_after >= #threshold {
+            liblogger::log_warn!(
+                &format!("FD_ALERT: {} - High file descriptor usage: {} (threshold: {}) | {} | Change: +{} | Duration: {}", 
+                    #fn_name, fd_count_after, #threshold, formatted_fd_info, fd_change, format_duration(duration)),
+                None
+            );
+        } else {
+            liblogger::log_info!(
+                &format!("FD_MONITOR: {} - File descriptors: {} | {} | Change: +{} | Duration: {}", 
+                    #fn_name, fd_count_after, formatted_fd_info, fd_change, format_duration(duration)),
+                None
+            );
+        }
+    });
+    
+    checked_fn(input_fn)
+}
+
+/// Monitor cache hit ratio and performance metrics
+#[proc_macro_attribute]
+pub fn log_cache_hit_ratio(args: TokenStream, input: TokenStream) -> TokenStream {
+    if cfg!(feature = "disabled") {
+        return input;
+    }
+    let args = parse_macro_input!(args as MacroArgs);
+    let threshold = args.threshold.unwrap_or(70);
+    let cache_name = args.cache_name.unwrap_or_else(|| "default".to_string());
+    let mut input_fn = parse_macro_input!(input as ItemFn);
+    let fn_name = get_fn_name(&input_fn);
+    let orig_block = input_fn.block.clone();
+    let is_async = input_fn.sig.asyncness.is_some();
+    let utility_functions = generate_utility_functions();
+
+    *input_fn.block = wrap_body(is_async, quote! {
+        #utility_functions
+        
+        let start_time = std::time::Instant::now();
+        let cache_stats_before = get_cache_stats(&#cache_name);
+    }, &orig_block, quote! {
+        let duration = start_time.elapsed();
+        let cache_stats_after = get_cache_stats(&#cache_name);
+        let formatted_cache_info = format_cache_info(&cache_stats_after);
+        
+        let hit_ratio = cache_stats_after.hit_ratio_percentage;
+        
+        if hit_ratio < #threshold as f64 {
+            liblogger::log_warn!(
+                &format!("CACHE_ALERT: {} - Low cache hit ratio: {:.1}% (threshold: {}%) | Cache: {} | {} | Duration: {}", 
+                    #fn_name, hit_ratio, #threshold, #cache_name, formatted_cache_info, format_duration(duration)),
+                None
+            );
+        } else {
+            liblogger::log_info!(
+                &format!("CACHE_MONITOR: {} - Cache hit ratio: {:.1}% | Cache: {} | {} | Duration: {}", 
+                    #fn_name, hit_ratio, #cache_name, formatted_cache_info, format_duration(duration)),
+                None
+            );
+        }
+    });
+    
+    checked_fn(input_fn)
+}
+
+/// Monitor queue depth and processing performance
+#[proc_macro_attribute]
+pub fn log_queue_depth(args: TokenStream, input: TokenStream) -> TokenStream {
+    if cfg!(feature = "disabled") {
+        return input;
+    }
+    let args = parse_macro_input!(args as MacroArgs);
+    let queue_name = args.queue_name.unwrap_or_else(|| "default".to_string());
+    let threshold = args.threshold.unwrap_or(1000) as u64; // Convert to u64
+    let mut input_fn = parse_macro_input!(input as ItemFn);
+    let fn_name = get_fn_name(&input_fn);
+    let orig_block = input_fn.block.clone();
+    let is_async = input_fn.sig.asyncness.is_some();
+    let utility_functions = generate_utility_functions();
+
+    *input_fn.block = wrap_body(is_async, quote! {
+        #utility_functions
+        
+        let start_time = std::time::Instant::now();
+        let queue_stats_before = get_queue_stats(&#queue_name);
+    }, &orig_block, quote! {
+        let duration = start_time.elapsed();
+        let queue_stats_after = get_queue_stats(&#queue_name);
+        let formatted_queue_info = format_queue_info(&queue_stats_after);
+        
+        let queue_depth = queue_stats_after.depth;
+        // Messages processed during the call; too short a call to time
+        // falls back to the rate reported by the provider
+        let processed = queue_stats_after.total_processed.saturating_sub(queue_stats_before.total_processed);
+        let elapsed_secs = duration.as_secs_f64();
+        let processing_rate = if elapsed_secs >= 0.001 {
+            processed as f64 / elapsed_secs
+        } else {
+            queue_stats_after.processing_rate
+        };
+        
+        if queue_depth >= #threshold {
+            liblogger::log_warn!(
+                &format!("QUEUE_ALERT: {} - High queue depth: {} (threshold: {}) | Queue: {} | {} | Processing: {:.1}/sec | Duration: {}", 
+                    #fn_name, queue_depth, #threshold, #queue_name, formatted_queue_info, processing_rate, format_duration(duration)),
+                None
+            );
+        } else {
+            liblogger::log_info!(
+                &format!("QUEUE_MONITOR: {} - Queue depth: {} | Queue: {} | {} | Processing: {:.1}/sec | Duration: {}", 
+                    #fn_name, queue_depth, #queue_name, formatted_queue_info, processing_rate, format_duration(duration)),
+                None
+            );
+        }
+    });
+    
+    checked_fn(input_fn)
+}
+
+/// Monitor garbage collection pressure and memory management
+#[proc_macro_attribute]
+pub fn log_gc_pressure(args: TokenStream, input: TokenStream) -> TokenStream {
+    if cfg!(feature = "disabled") {
+        return input;
+    }
+    let args = parse_macro_input!(args as MacroArgs);
+    let threshold = args.threshold.unwrap_or(100) as u64; // Convert to u64
+    let mut input_fn = parse_macro_input!(input as ItemFn);
+    let fn_name = get_fn_name(&input_fn);
+    let orig_block = input_fn.block.clone();
+    let is_async = input_fn.sig.asyncness.is_some();
+    let utility_functions = generate_utility_functions();
+
+    *input_fn.block = wrap_body(is_async, quote! {
+        #utility_functions
+        
+        let start_time = std::time::Instant::now();
+        let gc_stats_before = get_gc_stats();
+    }, &orig_block, quote! {
+        let duration = start_time.elapsed();
+        let gc_stats_after = get_gc_stats();
+        let formatted_gc_info = format_gc_info(&gc_stats_after);
+        
+        let gc_time_delta = gc_stats_after.total_gc_time_ms - gc_stats_before.total_gc_time_ms;
+        let gc_collections_delta = gc_stats_after.gc_collections - gc_stats_before.gc_collections;
+        
+        if gc_time_delta >= #threshold {
+            liblogger::log_warn!(
+                &format!("GC_PRESSURE_ALERT: {} - High GC activity: {}ms GC time (threshold: {}ms) | {} | Collections: +{} | Duration: {}", 
+                    #fn_name, gc_time_delta, #threshold, formatted_gc_info, gc_collections_delta, format_duration(duration)),
+                None
+            );
+        } else {
+            liblogger::log_info!(
+                &format!("GC_MONITOR: {} - GC time: {}ms | {} | Collections: +{} | Duration: {}", 
+                    #fn_name, gc_time_delta, formatted_gc_info, gc_collections_delta, format_duration(duration)),
+                None
+            );
+        }
+    });
+    
+    checked_fn(input_fn)
+}
+
+/// Implement anomaly detection for function behavior patterns
+///
+/// Each call's duration is compared with the running mean and standard
+/// deviation of the function's earlier calls. No call is flagged until
+/// `min_samples` calls (default 10) have built the baseline.
+#[proc_macro_attribute]
+pub fn log_anomaly_detection(args: TokenStream, input: TokenStream) -> TokenStream {
+    if cfg!(feature = "disabled") {
+        return input;
+    }
+    let args = parse_macro_input!(args as MacroArgs);
+    let service_name = args.service_name.unwrap_or_else(|| "default".to_string());
+    let max_utilization = args.max_utilization.unwrap_or(90) as f64; // Convert to f64
+    let min_samples = args.min_samples.unwrap_or(10) as u64;
+    let mut input_fn = parse_macro_input!(input as ItemFn);
+    let fn_name = get_fn_name(&input_fn);
+    let orig_block = input_fn.block.clone();
+    let is_async = input_fn.sig.asyncness.is_some();
+    let utility_functions = generate_utility_functions();
+
+    *input_fn.block = wrap_body(is_async, quote! {
+        #utility_functions
+        
+        let baseline_key = concat!(module_path!(), "::", #fn_name);
+        let baseline = liblogger::Logger::duration_baseline(baseline_key);
+        let start_time = std::time::Instant::now();
+    }, &orig_block, quote! {
+        let duration = start_time.elapsed();
+        let duration_ms = duration.as_secs_f64() * 1000.0;
+        liblogger::Logger::record_duration(baseline_key, duration_ms);
+        
+        let anomaly_context_after = get_anomaly_detection_context(&#service_name, &#fn_name);
+        let formatted_anomaly_info = format_anomaly_detection_info(&anomaly_context_after);
+        
+        let resource_utilization = anomaly_context_after.resource_utilization_percentage;
+        let pattern_deviation = anomaly_context_after.pattern_deviation_percentage;
+        
+        // Score against earlier calls only once enough of them have been seen
+        let established = baseline.count >= #min_samples;
+        let baseline_duration_ms = baseline.mean_ms;
+        let anomaly_score = if !established {
+            0.0
+        } else {
+            let z_score = baseline.z_score(duration_ms);
+            if z_score > 3.0 { 0.9 } else if z_score > 2.0 { 0.7 } else { 0.3 }
+        };
+        let duration_anomaly = if established && baseline_duration_ms > 0.0 {
+            ((duration_ms - baseline_duration_ms) / baseline_duration_ms) * 100.0
+        } else {
+            0.0
+        };
+        
+        if anomaly_score > 0.8 || resource_utilization > #max_utilization || duration_anomaly > 200.0 {
+            liblogger::log_warn!(
+                &format!("ANOMALY_DETECTED: {} - Anomalous behavior detected | Service: {} | {} | Score: {:.2} | Duration anomaly: {:.1}% | Resource util: {:.1}% | Pattern deviation: {:.1}% | Duration: {} (baseline: {:.0}ms)", 
+                    #fn_name, #service_name, formatted_anomaly_info, anomaly_score, duration_anomaly, resource_utilization, pattern_deviation, format_duration(duration), baseline_duration_ms),
+                None
+            );
+        } else if anomaly_score > 0.5 || resource_utilization > 70.0 {
+            liblogger::log_info!(
+                &format!("ANOMALY_WATCH: {} - Elevated anomaly metrics | Service: {} | {} | Score: {:.2} | Duration anomaly: {:.1}% | Resource util: {:.1}% | Pattern deviation: {:.1}% | Duration: {} (baseline: {:.0}ms)", 
+                    #fn_name, #service_name, formatted_anomaly_info, anomaly_score, duration_anomaly, resource_utilization, pattern_deviation, format_duration(duration), baseline_duration_ms),
+                None
+            );
+        } else {
+            liblogger::log_info!(
+                &format!("ANOMALY_BASELINE: {} - Normal behavior pattern | Service: {} | {} | Score: {:.2} | Resource util: {:.1}% | Samples: {} | Duration: {}", 
+                    #fn_name, #service_name, formatted_anomaly_info, anomaly_score, resource_utilization, baseline.count + 1, format_duration(duration)),
+                None
+            );
+        }
+    });
+    
+    checked_fn(input_fn)
+}
+
+/// Monitor API rate limits
+///
+/// With `extractor = "path"`, the quota is read from the returned value:
+/// the function is called as `extractor(&returned, header_name)` and returns
+/// the header's value as `Option<String>`. `X-RateLimit-Remaining` and
+/// `X-RateLimit-Limit` are looked up, and the call is logged at WARN when
+/// the remaining quota is below `threshold` percent of the limit.
+#[proc_macro_attribute]
+pub fn log_api_rate_limits(args: TokenStream, input: TokenStream) -> TokenStream {
+    if cfg!(feature = "disabled") {
+        return input;
+    }
+    let args = parse_macro_input!(args as MacroArgs);
+    let service_name = args.service_name.unwrap_or_else(|| "default".to_string());
+    let threshold = args.threshold.unwrap_or(10);
+    let mut input_fn = parse_macro_input!(input as ItemFn);
+    let fn_name = get_fn_name(&input_fn);
+    let orig_block = input_fn.block.clone();
+    let is_async = input_fn.sig.asyncness.is_some();
+    
+    let report = match args.extractor {
+        Some(extractor) => quote! {
+            let header = |name: &str| -> Option<u64> {
+                #extractor(&result, name).and_then(|value| value.trim().parse::<u64>().ok())
+            };
+            match (header("X-RateLimit-Remaining"), header("X-RateLimit-Limit")) {
+                (Some(remaining), Some(limit)) if limit > 0 => {
+                    let remaining_pct = remaining.min(limit) as f64 / limit as f64 * 100.0;
+                    let utilization = 100.0 - remaining_pct;
+                    if remaining_pct < #threshold as f64 {
+                        liblogger::log_warn!(
+                            &format!("API_RATE_LIMITS: {} - Quota running low | Service: {} | Remaining: {}/{} | Utilization: {:.1}% | Threshold: {}% remaining | Duration: {}", 
+                                #fn_name, #service_name, remaining, limit, utilization, #threshold, format_duration(duration)),
+                            None
+                        );
+                    } else {
+                        liblogger::log_info!(
+                            &format!("API_RATE_LIMITS: {} - Service: {} | Remaining: {}/{} | Utilization: {:.1}% | Threshold: {}% remaining | Duration: {}", 
+                                #fn_name, #service_name, remaining, limit, utilization, #threshold, format_duration(duration)),
+                            None
+                        );
+                    }
+                },
+                _ => {
+                    liblogger::log_info!(
+                        &format!("API_RATE_LIMITS: {} - Service: {} | Rate limit headers missing | Duration: {}", 
+                            #fn_name, #service_name, format_duration(duration)),
+                        None
+                    );
+                },
+            }
+        },
+        None => quote! {
+            liblogger::log_info!(
+                &format!("API_RATE_LIMITS: {} - Service: {} | Threshold: {}% remaining | Duration: {}", 
+                    #fn_name, #service_name, #threshold, format_duration(duration)),
+                None
+            );
+        },
+    };
+    
+    *input_fn.block = wrap_body(is_async, quote! {
+        let start_time = std::time::Instant::now();
+    }, &orig_block, quote! {
+        let duration = start_time.elapsed();
+        #report
+    });
+    
+    checked_fn(input_fn)
+}
+
+/// Monitor SSL certificate expiry
+#[proc_macro_attribute]
+pub fn log_ssl_certificate_expiry(args: TokenStream, input: TokenStream) -> TokenStream {
+    if cfg!(feature = "disabled") {
+        return input;
+    }
+    let args = parse_macro_input!(args as MacroArgs);
+    let domain = args.domain.unwrap_or_else(|| "example.com".to_string());
+    let days_warning = args.days_warning.unwrap_or(30);
+    let mut input_fn = parse_macro_input!(input as ItemFn);
+    let fn_name = get_fn_name(&input_fn);
+    let orig_block = input_fn.block.clone();
+    let is_async = input_fn.sig.asyncness.is_some();
+    
+    *input_fn.block = wrap_body(is_async, quote! {
+        let start_time = std::time::Instant::now();
+    }, &orig_block, quote! {
+        let duration = start_time.elapsed();
+        
+        liblogger::log_info!(
+            &format!("SSL_CERTIFICATE_EXPIRY: {} - Domain: {} | Warning threshold: {} days | Duration: {}", 
+                #fn_name, #domain, #days_warning, format_duration(duration)),
+            None
+        );
+    });
+    
+    checked_fn(input_fn)
+}
+
+/// Monitor service discovery
+#[proc_macro_attribute]
+pub fn log_service_discovery(args: TokenStream, input: TokenStream) -> TokenStream {
+    if cfg!(feature = "disabled") {
+        return input;
+    }
+    let args = parse_macro_input!(args as MacroArgs);
+    let service_name = args.service_name.unwrap_or_else(|| "default".to_string());
+    let mut input_fn = parse_macro_input!(input as ItemFn);
+    let fn_name = get_fn_name(&input_fn);
+    let orig_block = input_fn.block.clone();
+    let is_async = input_fn.sig.asyncness.is_some();
+    
+    *input_fn.block = wrap_body(is_async, quote! {
+        let start_time = std::time::Instant::now();
+    }, &orig_block, quote! {
+        let duration = start_time.elapsed();
+        
+        liblogger::log_info!(
+            &format!("SERVICE_DISCOVERY: {} - Service: {} | Duration: {}", 
+                #fn_name, #service_name, format_duration(duration)),
+            None
+        );
+    });
+    
+    checked_fn(input_fn)
+}
+
+/// Monitor load balancer health
+#[proc_macro_attribute]
+pub fn log_load_balancer_health(args: TokenStream, input: TokenStream) -> TokenStream {
+    if cfg!(feature = "disabled") {
+        return input;
+    }
+    let args = parse_macro_input!(args as MacroArgs);
+    let service_name = args.service_name.unwrap_or_else(|| "default".to_string());
+    let threshold = args.threshold.unwrap_or(3);
+    let mut input_fn = parse_macro_input!(input as ItemFn);
+    let fn_name = get_fn_name(&input_fn);
+    let orig_block = input_fn.block.clone();
+    let is_async = input_fn.sig.asyncness.is_some();
+    
+    *input_fn.block = wrap_body(is_async, quote! {
+        let start_time = std::time::Instant::now();
+    }, &orig_block, quote! {
+        let duration = start_time.elapsed();
+        
+        liblogger::log_info!(
+            &format!("LOAD_BALANCER_HEALTH: {} - Service: {} | Threshold: {} | Duration: {}", 
+                #fn_name, #service_name, #threshold, format_duration(duration)),
+            None
+        );
+    });
+    
+    checked_fn(input_fn)
+}
+
+/// Monitor security events
+#[proc_macro_attribute]
+pub fn log_security_event(args: TokenStream, input: TokenStream) -> TokenStream {
+    if cfg!(feature = "disabled") {
+        return input;
+    }
+    let args = parse_macro_input!(args as MacroArgs);
+    let warning_level = args.warning_level.unwrap_or_else(|| "medium".to_string());
+    // There is no FATAL level, so critical events log at ERROR
+    let log_macro = match warning_level.as_str() {
+        "low" => quote!(liblogger::log_info),
+        "medium" => quote!(liblogger::log_warn),
+        "high" | "critical" => quote!(liblogger::log_error),
+        other => {
+            return syn::Error::new(
+                proc_macro2::Span::call_site(),
+                format!("unknown warning_level \"{}\", expected low, medium, high or critical", other),
+            ).to_compile_error().into();
+        }
+    };
+    let mut input_fn = parse_macro_input!(input as ItemFn);
+    let fn_name = get_fn_name(&input_fn);
+    let orig_block = input_fn.block.clone();
+    let is_async = input_fn.sig.asyncness.is_some();
+    
+    *input_fn.block = wrap_body(is_async, quote! {
+        let start_time = std::time::Instant::now();
+    }, &orig_block, quote! {
+        let duration = start_time.elapsed();
+        
+        #log_macro!(
+            &format!("SECURITY_EVENT: {} - Warning level: {} | User: {} | Client IP: {} | Duration: {}", 
+                #fn_name, #warning_level,
+                get_current_user_context().unwrap_or_else(|| "unknown".to_string()),
+                get_client_ip().unwrap_or_else(|| "unknown".to_string()),
+                format_duration(duration)),
+            None
+        );
+    });
+    
+    checked_fn(input_fn)
+}
+
+/// Monitor compliance checks
+#[proc_macro_attribute]
+pub fn log_compliance_check(args: TokenStream, input: TokenStream) -> TokenStream {
+    if cfg!(feature = "disabled") {
+        return input;
+    }
+    let args = parse_macro_input!(args as MacroArgs);
+    let domain = args.domain.unwrap_or_else(|| "default".to_string());
+    let mut input_fn = parse_macro_input!(input as ItemFn);
+    let fn_name = get_fn_name(&input_fn);
+    let orig_block = input_fn.block.clone();
+    let is_async = input_fn.sig.asyncness.is_some();
+    
+    *input_fn.block = wrap_body(is_async, quote! {
+        let start_time = std::time::Instant::now();
+    }, &orig_block, quote! {
+        let duration = start_time.elapsed();
+        
+        liblogger::log_info!(
+            &format!("COMPLIANCE_CHECK: {} - Domain: {} | Duration: {}", 
+                #fn_name, #domain, format_duration(duration)),
+            None
+        );
+    });
+    
+    checked_fn(input_fn)
+}
+
+/// Monitor access control
+#[proc_macro_attribute]
+pub fn log_access_control(args: TokenStream, input: TokenStream) -> TokenStream {
+    if cfg!(feature = "disabled") {
+        return input;
+    }
+    let args = parse_macro_input!(args as MacroArgs);
+    let domain = args.domain.unwrap_or_else(|| "default".to_string());
+    let mut input_fn = parse_macro_input!(input as ItemFn);
+    let fn_name = get_fn_name(&input_fn);
+    let orig_block = input_fn.block.clone();
+    let is_async = input_fn.sig.asyncness.is_some();
+    
+    *input_fn.block = wrap_body(is_async, quote! {
+        let start_time = std::time::Instant::now();
+    }, &orig_block, quote! {
+        let duration = start_time.elapsed();
+        
+        liblogger::log_info!(
+            &format!("ACCESS_CONTROL: {} - Domain: {} | Duration: {}", 
+                #fn_name, #domain, format_duration(duration)),
+            None
+        );
+    });
+    
+    checked_fn(input_fn)
+}
+
+/// Monitor crypto operations
+#[proc_macro_attribute]
+pub fn log_crypto_operation(args: TokenStream, input: TokenStream) -> TokenStream {
+    if cfg!(feature = "disabled") {
+        return input;
+    }
+    let args = parse_macro_input!(args as MacroArgs);
+    let domain = args.domain.unwrap_or_else(|| "default".to_string());
+    let mut input_fn = parse_macro_input!(input as ItemFn);
+    let fn_name = get_fn_name(&input_fn);
+    let orig_block = input_fn.block.clone();
+    let is_async = input_fn.sig.asyncness.is_some();
+    
+    *input_fn.block = wrap_body(is_async, quote! {
+        let start_time = std::time::Instant::now();
+    }, &orig_block, quote! {
+        let duration = start_time.elapsed();
+        
+        liblogger::log_info!(
+            &format!("CRYPTO_OPERATION: {} - Domain: {} | Duration: {}", 
+                #fn_name, #domain, format_duration(duration)),
+            None
+        );
+    });
+    
+    checked_fn(input_fn)
+}
+
+/// Monitor config changes
+///
+/// With `before = "old", after = "new"` naming two parameters that hold
+/// config snapshots (anything iterating as key/value pairs, such as a
+/// `HashMap<String, String>`), the line lists every key whose value
+/// differs as `key: old -> new`. Values of keys listed in
+/// `redact = ["password"]` (matched case-insensitively) are masked.
+#[proc_macro_attribute]
+pub fn log_config_change(args: TokenStream, input: TokenStream) -> TokenStream {
+    if cfg!(feature = "disabled") {
+        return input;
+    }
+    let args = parse_macro_input!(args as MacroArgs);
+    let domain = args.domain.unwrap_or_else(|| "default".to_string());
+    let mut input_fn = parse_macro_input!(input as ItemFn);
+    let fn_name = get_fn_name(&input_fn);
+    let orig_block = input_fn.block.clone();
+    let is_async = input_fn.sig.asyncness.is_some();
+    
+    let param_names: Vec<String> = input_fn.sig.inputs.iter()
+        .filter_map(|arg| match arg {
+            syn::FnArg::Typed(pat_type) => match pat_type.pat.as_ref() {
+                syn::Pat::Ident(pat_ident) => Some(pat_ident.ident.to_string()),
+                _ => None,
+            },
+            syn::FnArg::Receiver(_) => None,
+        })
+        .collect();
+    let snapshots = match (&args.before, &args.after) {
+        (Some(before), Some(after)) => {
+            for name in [before, after] {
+                if !param_names.contains(name) {
+                    return syn::Error::new_spanned(
+                        &input_fn.sig,
+                        format!("log_config_change: \"{}\" is not a parameter of {}", name, fn_name),
+                    ).to_compile_error().into();
+                }
+            }
+            Some((format_ident!("{}", before), format_ident!("{}", after)))
+        },
+        (None, None) => None,
+        _ => {
+            return syn::Error::new(
+                proc_macro2::Span::call_site(),
+                "log_config_change: before and after must be given together",
+            ).to_compile_error().into();
+        }
+    };
+    
+    // Taken before the body runs, since it may consume the snapshots
+    let diff = match snapshots {
+        Some((before, after)) => {
+            let redact: Vec<String> = args.redact.unwrap_or_default().iter()
+                .map(|key| key.to_lowercase())
+                .collect();
+            quote! {
+                let changes: String = {
+                    let before: std::collections::BTreeMap<String, String> = (&#before).into_iter()
+                        .map(|(k, v)| (k.to_string(), v.to_string()))
+                        .collect();
+                    let after: std::collections::BTreeMap<String, String> = (&#after).into_iter()
+                        .map(|(k, v)| (k.to_string(), v.to_string()))
+                        .collect();
+                    let redact: &[&str] = &[#(#redact),*];
+                    let show = |key: &str, value: Option<&String>| match value {
+                        None => "(unset)".to_string(),
+                        Some(_) if redact.contains(&key.to_lowercase().as_str()) => "[REDACTED]".to_string(),
+                        Some(value) => value.clone(),
+                    };
+                    let keys: std::collections::BTreeSet<&String> = before.keys().chain(after.keys()).collect();
+                    let changed: Vec<String> = keys.into_iter()
+                        .filter(|key| before.get(*key) != after.get(*key))
+                        .map(|key| format!("{}: {} -> {}", key, show(key, before.get(key)), show(key, after.get(key))))
+                        .collect();
+                    if changed.is_empty() {
+                        " | Changes: none".to_string()
+                    } else {
+                        format!(" | Changes: {}", changed.join(", "))
+                    }
+                };
+            }
+        },
+        None => quote! { let changes = ""; },
+    };
+    
+    *input_fn.block = wrap_body(is_async, quote! {
+        #diff
+        let start_time = std::time::Instant::now();
+    }, &orig_block, quote! {
+        let duration = start_time.elapsed();
+        
+        liblogger::log_info!(
+            &format!("CONFIG_CHANGE: {} - Domain: {} | Duration: {}{}", 
+                #fn_name, #domain, format_duration(duration), changes),
+            None
+        );
+    });
+    
+    checked_fn(input_fn)
+}
+
+/// Monitor deployments
+#[proc_macro_attribute]
+pub fn log_deployment(args: TokenStream, input: TokenStream) -> TokenStream {
+    if cfg!(feature = "disabled") {
+        return input;
+    }
+    let args = parse_macro_input!(args as MacroArgs);
+    let service_name = args.service_name.unwrap_or_else(|| "default".to_string());
+    let mut input_fn = parse_macro_input!(input as ItemFn);
+    let fn_name = get_fn_name(&input_fn);
+    let orig_block = input_fn.block.clone();
+    let is_async = input_fn.sig.asyncness.is_some();
+    
+    *input_fn.block = wrap_body(is_async, quote! {
+        let start_time = std::time::Instant::now();
+    }, &orig_block, quote! {
+        let duration = start_time.elapsed();
+        
+        liblogger::log_info!(
+            &format!("DEPLOYMENT: {} - Service: {} | Duration: {}", 
+                #fn_name, #service_name, format_duration(duration)),
+            None
+        );
+    });
+    
+    checked_fn(input_fn)
+}
+
+/// Monitor environment validation
+#[proc_macro_attribute]
+pub fn log_environment_validation(args: TokenStream, input: TokenStream) -> TokenStream {
+    if cfg!(feature = "disabled") {
+        return input;
+    }
+    let args = parse_macro_input!(args as MacroArgs);
+    let service_name = args.service_name.unwrap_or_else(|| "default".to_string());
+    let mut input_fn = parse_macro_input!(input as ItemFn);
+    let fn_name = get_fn_name(&input_fn);
+    let orig_block = input_fn.block.clone();
+    let is_async = input_fn.sig.asyncness.is_some();
+    
+    *input_fn.block = wrap_body(is_async, quote! {
+        let start_time = std::time::Instant::now();
+    }, &orig_block, quote! {
+        let duration = start_time.elapsed();
+        
+        liblogger::log_info!(
+            &format!("ENVIRONMENT_VALIDATION: {} - Service: {} | Duration: {}", 
+                #fn_name, #service_name, format_duration(duration)),
+            None
+        );
+    });
+    
+    checked_fn(input_fn)
+}
+
+/// Monitor feature flag changes
+#[proc_macro_attribute]
+pub fn log_feature_flag_change(args: TokenStream, input: TokenStream) -> TokenStream {
+    if cfg!(feature = "disabled") {
+        return input;
+    }
+    let args = parse_macro_input!(args as MacroArgs);
+    let min_percentage = args.min_percentage.unwrap_or(0);
+    let max_percentage = args.max_percentage.unwrap_or(100);
+    let mut input_fn = parse_macro_input!(input as ItemFn);
+    let fn_name = get_fn_name(&input_fn);
+    let orig_block = input_fn.block.clone();
+    let is_async = input_fn.sig.asyncness.is_some();
+    
+    *input_fn.block = wrap_body(is_async, quote! {
+        let start_time = std::time::Instant::now();
+    }, &orig_block, quote! {
+        let duration = start_time.elapsed();
+        
+        liblogger::log_info!(
+            &format!("FEATURE_FLAG_CHANGE: {} - Min: {}% | Max: {}% | Duration: {}", 
+                #fn_name, #min_percentage, #max_percentage, format_duration(duration)),
+            None
+        );
+    });
+    
+    checked_fn(input_fn)
+}
+
+/// Monitor thread pool utilization and performance
+#[proc_macro_attribute]
+pub fn log_thread_pool_utilization(args: TokenStream, input: TokenStream) -> TokenStream {
+    if cfg!(feature = "disabled") {
+        return input;
+    }
+    let args = parse_macro_input!(args as MacroArgs);
+    let thread_pool_name = args.thread_pool_name.unwrap_or_else(|| "default".to_string());
+    let threshold = args.threshold.unwrap_or(90);
+    let mut input_fn = parse_macro_input!(input as ItemFn);
+    let fn_name = get_fn_name(&input_fn);
+    let orig_block = input_fn.block.clone();
+    let is_async = input_fn.sig.asyncness.is_some();
+    let utility_functions = generate_utility_functions();
+
+    *input_fn.block = wrap_body(is_async, quote! {
+        #utility_functions
+        
+        let start_time = std::time::Instant::now();
+        let pool_stats_before = get_thread_pool_stats(&#thread_pool_name);
+    }, &orig_block, quote! {
+        let duration = start_time.elapsed();
+        let pool_stats_after = get_thread_pool_stats(&#thread_pool_name);
+        let formatted_pool_info = format_thread_pool_info(&pool_stats_after);
+        
+        let utilization = pool_stats_after.utilization_percentage;
+        
+        if utilization >= #threshold as f64 {
+            liblogger::log_warn!(
+                &format!("THREAD_POOL_ALERT: {} - High utilization: {:.1}% (threshold: {}%) | Pool: {} | {} | Duration: {}", 
+                    #fn_name, utilization, #threshold, #thread_pool_name, formatted_pool_info, format_duration(duration)),
+                None
+            );
+        } else {
+            liblogger::log_info!(
+                &format!("THREAD_POOL_MONITOR: {} - Utilization: {:.1}% | Pool: {} | {} | Duration: {}", 
+                    #fn_name, utilization, #thread_pool_name, formatted_pool_info, format_duration(duration)),
+                None
+            );
+        }
+    });
+    
+    checked_fn(input_fn)
+}
+
+/// Monitor business rule execution and validation
+#[proc_macro_attribute]
+pub fn log_business_rule(args: TokenStream, input: TokenStream) -> TokenStream {
+    if cfg!(feature = "disabled") {
+        return input;
+    }
+    let args = parse_macro_input!(args as MacroArgs);
+    let domain = args.domain.unwrap_or_else(|| "default".to_string());
+    let mut input_fn = parse_macro_input!(input as ItemFn);
+    let fn_name = get_fn_name(&input_fn);
+    let orig_block = input_fn.block.clone();
+    let is_async = input_fn.sig.asyncness.is_some();
+    let utility_functions = generate_utility_functions();
+
+    *input_fn.block = wrap_body(is_async, quote! {
+        #utility_functions
+        
+        let start_time = std::time::Instant::now();
+        let rule_context = get_business_rule_context(&#domain, &#fn_name);
+    }, &orig_block, quote! {
+        let duration = start_time.elapsed();
+        let formatted_rule_info = format_business_rule_info(&rule_context);
+        
+        let rule_name = &rule_context.rule_name;
+        let rule_version = &rule_context.rule_version;
+        let execution_count = rule_context.execution_count;
+        
+        match &result {
+            Ok(_) => {
+                liblogger::log_info!(
+                    &format!("BUSINESS_RULE_PASS: {} - Business rule validation passed | Domain: {} | Rule: {} | {} | Version: {} | Executions: {} | Duration: {}", 
+                        #fn_name, #domain, rule_name, formatted_rule_info, rule_version, execution_count, format_duration(duration)),
+                    None
+                );
+            },
+            Err(_) => {
+                liblogger::log_warn!(
+                    &format!("BUSINESS_RULE_FAIL: {} - Business rule validation failed | Domain: {} | Rule: {} | {} | Version: {} | Executions: {} | Duration: {}", 
+                        #fn_name, #domain, rule_name, formatted_rule_info, rule_version, execution_count, format_duration(duration)),
+                    None
+                );
+            }
+        }
+    });
+    
+    checked_fn(input_fn)
+}
+
+/// Monitor data quality checks and validation processes
+///
+/// With `enforce = true` the function must return a `Result`: an `Ok` is
+/// replaced by an `Err` describing the failure when the quality score after
+/// the call is below `threshold`. By default low quality is only logged.
+///
+/// With `count_metric = "name"` every rule the provider reports as failed
+/// also increments a Prometheus counter of that name, labeled by `rule`.
+#[proc_macro_attribute]
+pub fn log_data_quality(args: TokenStream, input: TokenStream) -> TokenStream {
+    if cfg!(feature = "disabled") {
+        return input;
+    }
+    let args = parse_macro_input!(args as MacroArgs);
+    let domain = args.domain.unwrap_or_else(|| "default".to_string());
+    let threshold = args.threshold.unwrap_or(95);
+    let enforce = args.enforce.unwrap_or(false);
+    let mut input_fn = parse_macro_input!(input as ItemFn);
+    let fn_name = get_fn_name(&input_fn);
+    let orig_block = input_fn.block.clone();
+    let is_async = input_fn.sig.asyncness.is_some();
+    let utility_functions = generate_utility_functions();
+    
+    if enforce && !returns_result(&input_fn.sig) {
+        return syn::Error::new_spanned(
+            &input_fn.sig,
+            "log_data_quality(enforce = true) requires the function to return a Result",
+        ).to_compile_error().into();
+    }
+    
+    let count_failed_rules = match args.count_metric {
+        Some(metric_name) => {
+            let help = format!("Failed data quality rules in {}, by rule", fn_name);
+            let counter = registered_metric(
+                quote!(prometheus::IntCounterVec),
+                &metric_name,
+                quote!(prometheus::register_int_counter_vec!(#metric_name, #help, &["rule"])),
+            );
+            quote! {
+                if let Some(counter) = #counter {
+                    for rule in &quality_metrics_after.failed_rules {
+                        counter.with_label_values(&[rule.as_str()]).inc();
+                    }
+                }
+            }
+        },
+        None => quote!(),
+    };
+    
+    // Turn a successful result into an error when the score is too low
+    let enforcement = if enforce {
+        quote! {
+            let result = match result {
+                Ok(_) if quality_score < #threshold as f64 => Err(format!(
+                    "data quality check failed for {}: score {:.1}% is below threshold {}% in domain {}",
+                    #fn_name, quality_score, #threshold, #domain
+                ).into()),
+                other => other,
+            };
+        }
+    } else {
+        quote! {}
+    };
+
+    *input_fn.block = wrap_body(is_async, quote! {
+        #utility_functions
+        
+        let start_time = std::time::Instant::now();
+    }, &orig_block, quote! {
+        let duration = start_time.elapsed();
+        let quality_metrics_after = get_data_quality_metrics(&#domain);
+        let formatted_quality_info = format_data_quality_info(&quality_metrics_after);
+        
+        let quality_score = quality_metrics_after.quality_score_percentage;
+        let records_processed = quality_metrics_after.records_processed;
+        let validation_rules_passed = quality_metrics_after.validation_rules_passed();
+        let total_validation_rules = quality_metrics_after.total_validation_rules;
+        
+        if quality_score < #threshold as f64 {
+            liblogger::log_warn!(
+                &format!("DATA_QUALITY_ALERT: {} - Low data quality score: {:.1}% (threshold: {}%) | Domain: {} | {} | Records: {} | Rules: {}/{} | Duration: {}", 
+                    #fn_name, quality_score, #threshold, #domain, formatted_quality_info, records_processed, validation_rules_passed, total_validation_rules, format_duration(duration)),
+                None
+            );
+        } else {
+            liblogger::log_info!(
+                &format!("DATA_QUALITY_OK: {} - Data quality score: {:.1}% | Domain: {} | {} | Records: {} | Rules: {}/{} | Duration: {}", 
+                    #fn_name, quality_score, #domain, formatted_quality_info, records_processed, validation_rules_passed, total_validation_rules, format_duration(duration)),
+                None
+            );
+        }
+        #count_failed_rules
+        
+        #enforcement
+    });
+    
+    checked_fn(input_fn)
+}
+
+/// Monitor workflow and process execution steps
+///
+/// Steps of a workflow are numbered in the order they first run. A step that
+/// starts when a step at the same or a later position has already completed
+/// logs a WORKFLOW_STEP_OUT_OF_ORDER warning. The workflow is identified by
+/// the "workflow_id" context value, or by the domain when none is set.
+///
+/// - `domain = "..."` names the workflow when there is no workflow_id
+/// - `max_depth = N` warns when a step's position exceeds N (default 10)
+///
+#[proc_macro_attribute]
+pub fn log_workflow_step(args: TokenStream, input: TokenStream) -> TokenStream {
+    if cfg!(feature = "disabled") {
+        return input;
+    }
+    let args = parse_macro_input!(args as MacroArgs);
+    let domain = args.domain.unwrap_or_else(|| "default".to_string());
+    let max_depth = args.max_depth.unwrap_or(10);
+    let mut input_fn = parse_macro_input!(input as ItemFn);
+    let fn_name = get_fn_name(&input_fn);
+    let orig_block = input_fn.block.clone();
+    let is_async = input_fn.sig.asyncness.is_some();
+    let utility_functions = generate_utility_functions();
+
+    *input_fn.block = wrap_body(is_async, quote! {
+        #utility_functions
+        
+        // Steps of one workflow share an id; set "workflow_id" in the context to tell runs apart
+        let workflow_id = liblogger::Logger::get_context_value("workflow_id")
+            .unwrap_or_else(|| format!("wf_{}", #domain));
+        let mut step = liblogger::Logger::begin_workflow_step(&workflow_id, #fn_name);
+        
+        if step.is_out_of_order() {
+            liblogger::log_warn!(
+                &format!("WORKFLOW_STEP_OUT_OF_ORDER: {} - Step runs again or out of order | Domain: {} | Workflow: {} | Step: {} | Depth: {} | Last completed depth: {}", 
+                    #fn_name, #domain, workflow_id, #fn_name, step.index(), step.previous_index().unwrap_or_default()),
+                None
+            );
+        }
+        
+        let start_time = std::time::Instant::now();
+    }, &orig_block, quote! {
+        let duration = start_time.elapsed();
+        
+        if result.is_ok() {
+            step.complete();
+        }
+        let step_depth = step.index();
+        let total_steps = step.known_steps();
+        let completed_steps = step.completed_steps();
+        
+        if step_depth > #max_depth {
+            liblogger::log_warn!(
+                &format!("WORKFLOW_DEPTH_ALERT: {} - Workflow depth exceeded | Domain: {} | Workflow: {} | Step: {} | Depth: {} (max: {}) | Progress: {}/{} | Duration: {}", 
+                    #fn_name, #domain, workflow_id, #fn_name, step_depth, #max_depth, completed_steps, total_steps, format_duration(duration)),
+                None
+            );
+        } else {
+            match &result {
+                Ok(_) => {
+                    liblogger::log_info!(
+                        &format!("WORKFLOW_STEP_SUCCESS: {} - Workflow step completed | Domain: {} | Workflow: {} | Step: {} | Depth: {} | Progress: {}/{} | Duration: {}", 
+                            #fn_name, #domain, workflow_id, #fn_name, step_depth, completed_steps, total_steps, format_duration(duration)),
+                        None
+                    );
+                },
+                Err(_) => {
+                    liblogger::log_error!(
+                        &format!("WORKFLOW_STEP_FAILURE: {} - Workflow step failed | Domain: {} | Workflow: {} | Step: {} | Depth: {} | Progress: {}/{} | Duration: {}", 
+                            #fn_name, #domain, workflow_id, #fn_name, step_depth, completed_steps, total_steps, format_duration(duration)),
+                        None
+                    );
+                }
+            }
+        }
+    });
+    
+    checked_fn(input_fn)
+}
+
+/// Monitor transaction processing and state consistency
+///
+/// `Ok` results are logged as commits and `Err` results as rollbacks. Calls
+/// nested inside another annotated transaction on the same thread log their
+/// depth, at WARN instead of INFO with `warn_on_nested = true`.
+#[proc_macro_attribute]
+pub fn log_transaction(args: TokenStream, input: TokenStream) -> TokenStream {
+    if cfg!(feature = "disabled") {
+        return input;
+    }
+    let args = parse_macro_input!(args as MacroArgs);
+    let domain = args.domain.unwrap_or_else(|| "default".to_string());
+    let timeout_ms = args.timeout_ms.unwrap_or(5000);
+    let warn_on_nested = args.warn_on_nested.unwrap_or(false);
+    let mut input_fn = parse_macro_input!(input as ItemFn);
+    let fn_name = get_fn_name(&input_fn);
+    let orig_block = input_fn.block.clone();
+    let is_async = input_fn.sig.asyncness.is_some();
+    let utility_functions = generate_utility_functions();
+    
+    let nested_log = if warn_on_nested {
+        quote!(liblogger::log_warn!)
+    } else {
+        quote!(liblogger::log_info!)
+    };
+
+    *input_fn.block = wrap_body(is_async, quote! {
+        #utility_functions
+        
+        let start_time = std::time::Instant::now();
+        let tx_context = get_transaction_context(&#domain);
+        let tx_scope = liblogger::Logger::enter_transaction();
+        let depth = tx_scope.depth();
+        
+        if depth > 1 {
+            #nested_log(
+                &format!("TRANSACTION_NESTED: {} - nested transaction (depth {}) | Domain: {} | Tx ID: {}", 
+                    #fn_name, depth, #domain, tx_context.transaction_id),
+                None
+            );
+        }
+    }, &orig_block, quote! {
+        drop(tx_scope);
+        
+        let duration = start_time.elapsed();
+        let formatted_tx_info = format_transaction_info(&tx_context);
+        
+        let transaction_id = &tx_context.transaction_id;
+        let isolation_level = &tx_context.isolation_level;
+        let participant_count = tx_context.participant_count;
+        
+        if duration.as_millis() > #timeout_ms as u128 {
+            liblogger::log_warn!(
+                &format!("TRANSACTION_TIMEOUT_WARNING: {} - Transaction exceeded timeout | Domain: {} | Tx ID: {} | {} | Isolation: {} | Participants: {} | Depth: {} | Duration: {}", 
+                    #fn_name, #domain, transaction_id, formatted_tx_info, isolation_level, participant_count, depth, format_duration(duration)),
+                None
+            );
+        } else {
+            match &result {
+                Ok(_) => {
+                    liblogger::log_info!(
+                        &format!("TRANSACTION_COMMIT: {} - Transaction committed | Domain: {} | Tx ID: {} | {} | Isolation: {} | Participants: {} | Depth: {} | Duration: {}", 
+                            #fn_name, #domain, transaction_id, formatted_tx_info, isolation_level, participant_count, depth, format_duration(duration)),
+                        None
+                    );
+                },
+                Err(_) => {
+                    liblogger::log_error!(
+                        &format!("TRANSACTION_ROLLBACK: {} - Transaction rolled back | Domain: {} | Tx ID: {} | {} | Isolation: {} | Participants: {} | Depth: {} | Duration: {}", 
+                            #fn_name, #domain, transaction_id, formatted_tx_info, isolation_level, participant_count, depth, format_duration(duration)),
+                        None
+                    );
+                }
+            }
+        }
+    });
+    
+    checked_fn(input_fn)
+}
+
+/// Monitor inter-service communication and RPC calls
+///
+/// Every call is recorded in the circuit breaker named by `service_name`,
+/// shared with other functions calling the same service. The breaker opens
+/// after `failure_threshold` (default 5) failed calls in a row, and the
+/// logged state is the breaker's state after the call.
+#[proc_macro_attribute]
+pub fn log_service_communication(args: TokenStream, input: TokenStream) -> TokenStream {
+    if cfg!(feature = "disabled") {
+        return input;
+    }
+    let args = parse_macro_input!(args as MacroArgs);
+    let service_name = args.service_name.unwrap_or_else(|| "unknown".to_string());
+    let timeout_ms = args.timeout_ms.unwrap_or(5000);
+    let failure_threshold = args.failure_threshold.unwrap_or(5);
+    let mut input_fn = parse_macro_input!(input as ItemFn);
+    let fn_name = get_fn_name(&input_fn);
+    let orig_block = input_fn.block.clone();
+    let is_async = input_fn.sig.asyncness.is_some();
+    let utility_functions = generate_utility_functions();
+
+    *input_fn.block = wrap_body(is_async, quote! {
+        #utility_functions
+        
+        let start_time = std::time::Instant::now();
+        let comm_context = get_service_communication_context(&#service_name);
+    }, &orig_block, quote! {
+        let duration = start_time.elapsed();
+        let formatted_comm_info = format_service_communication_info(&comm_context);
+        
+        let target_service = &comm_context.target_service;
+        let protocol = &comm_context.protocol;
+        let circuit_breaker_state = liblogger::Logger::record_service_call(#service_name, result.is_ok(), #failure_threshold);
+        
+        if duration.as_millis() > #timeout_ms as u128 {
+            liblogger::log_warn!(
+                &format!("SERVICE_COMM_TIMEOUT: {} - Service communication timeout | Target: {} | {} | Protocol: {} | Circuit Breaker: {} | Duration: {} (timeout: {}ms)", 
+                    #fn_name, target_service, formatted_comm_info, protocol, circuit_breaker_state, format_duration(duration), #timeout_ms),
+                None
+            );
+        } else {
+            match &result {
+                Ok(_) => {
+                    liblogger::log_info!(
+                        &format!("SERVICE_COMM_SUCCESS: {} - Service communication successful | Target: {} | {} | Protocol: {} | Circuit Breaker: {} | Duration: {}", 
+                            #fn_name, target_service, formatted_comm_info, protocol, circuit_breaker_state, format_duration(duration)),
+                        None
+                    );
+                },
+                Err(_) => {
+                    liblogger::log_error!(
+                        &format!("SERVICE_COMM_FAILURE: {} - Service communication failed | Target: {} | {} | Protocol: {} | Circuit Breaker: {} | Duration: {}", 
+                            #fn_name, target_service, formatted_comm_info, protocol, circuit_breaker_state, format_duration(duration)),
+                        None
+                    );
+                }
+            }
+        }
+    });
+    
+    checked_fn(input_fn)
+}
+
+/// Monitor consensus algorithm operations and cluster decisions
+#[proc_macro_attribute]
+pub fn log_consensus_operation(args: TokenStream, input: TokenStream) -> TokenStream {
+    if cfg!(feature = "disabled") {
+        return input;
+    }
+    let args = parse_macro_input!(args as MacroArgs);
+    let domain = args.domain.unwrap_or_else(|| "default".to_string());
+    let timeout_ms = args.timeout_ms.unwrap_or(10000);
+    let mut input_fn = parse_macro_input!(input as ItemFn);
+    let fn_name = get_fn_name(&input_fn);
+    let orig_block = input_fn.block.clone();
+    let is_async = input_fn.sig.asyncness.is_some();
+    let utility_functions = generate_utility_functions();
+
+    *input_fn.block = wrap_body(is_async, quote! {
+        #utility_functions
+        
+        let start_time = std::time::Instant::now();
+        let consensus_context = get_consensus_context(&#domain);
+    }, &orig_block, quote! {
+        let duration = start_time.elapsed();
+        let formatted_consensus_info = format_consensus_info(&consensus_context);
+        
+        let term = consensus_context.term;
+        let leader_id = &consensus_context.leader_id;
+        let node_count = consensus_context.node_count;
+        let votes_received = consensus_context.votes_received;
+        
+        if duration.as_millis() > #timeout_ms as u128 {
+            liblogger::log_warn!(
+                &format!("CONSENSUS_TIMEOUT: {} - Consensus operation timeout | Domain: {} | {} | Term: {} | Leader: {} | Votes: {}/{} | Duration: {} (timeout: {}ms)", 
+                    #fn_name, #domain, formatted_consensus_info, term, leader_id, votes_received, node_count, format_duration(duration), #timeout_ms),
+                None
+            );
+        } else {
+            match &result {
+                Ok(_) => {
+                    liblogger::log_info!(
+                        &format!("CONSENSUS_SUCCESS: {} - Consensus achieved | Domain: {} | {} | Term: {} | Leader: {} | Votes: {}/{} | Duration: {}", 
+                            #fn_name, #domain, formatted_consensus_info, term, leader_id, votes_received, node_count, format_duration(duration)),
+                        None
+                    );
+                },
+                Err(_) => {
+                    liblogger::log_warn!(
+                        &format!("CONSENSUS_FAILURE: {} - Consensus failed | Domain: {} | {} | Term: {} | Leader: {} | Votes: {}/{} | Duration: {}", 
+                            #fn_name, #domain, formatted_consensus_info, term, leader_id, votes_received, node_count, format_duration(duration)),
+                        None
+                    );
+                }
+            }
+        }
+    });
+    
+    checked_fn(input_fn)
+}
+
+/// Monitor cluster health and node membership changes
+#[proc_macro_attribute]
+pub fn log_cluster_health(args: TokenStream, input: TokenStream) -> TokenStream {
+    if cfg!(feature = "disabled") {
+        return input;
+    }
+    let args = parse_macro_input!(args as MacroArgs);
+    let domain = args.domain.unwrap_or_else(|| "default".to_string());
+    let threshold = args.threshold.unwrap_or(70);
+    let mut input_fn = parse_macro_input!(input as ItemFn);
+    let fn_name = get_fn_name(&input_fn);
+    let orig_block = input_fn.block.clone();
+    let is_async = input_fn.sig.asyncness.is_some();
+    let utility_functions = generate_utility_functions();
+
+    *input_fn.block = wrap_body(is_async, quote! {
+        #utility_functions
+        
+        let start_time = std::time::Instant::now();
+        let cluster_health_before = get_cluster_health_stats(&#domain);
+    }, &orig_block, quote! {
+        let duration = start_time.elapsed();
+        let cluster_health_after = get_cluster_health_stats(&#domain);
+        let formatted_cluster_info = format_cluster_health_info(&cluster_health_after);
+        
+        let health_percentage = cluster_health_after.health_percentage;
+        let healthy_nodes = cluster_health_after.healthy_nodes;
+        let total_nodes = cluster_health_after.total_nodes;
+        let leader_node = &cluster_health_after.leader_node;
+        
+        if health_percentage < #threshold as f64 {
+            liblogger::log_error!(
+                &format!("CLUSTER_HEALTH_CRITICAL: {} - Cluster health critical: {:.1}% (threshold: {}%) | Domain: {} | {} | Healthy: {}/{} | Leader: {} | Duration: {}", 
+                    #fn_name, health_percentage, #threshold, #domain, formatted_cluster_info, healthy_nodes, total_nodes, leader_node, format_duration(duration)),
+                None
+            );
+        } else if health_percentage < 90.0 {
+            liblogger::log_warn!(
+                &format!("CLUSTER_HEALTH_DEGRADED: {} - Cluster health degraded: {:.1}% | Domain: {} | {} | Healthy: {}/{} | Leader: {} | Duration: {}", 
+                    #fn_name, health_percentage, #domain, formatted_cluster_info, healthy_nodes, total_nodes, leader_node, format_duration(duration)),
+                None
+            );
+        } else {
+            liblogger::log_info!(
+                &format!("CLUSTER_HEALTH_OK: {} - Cluster health good: {:.1}% | Domain: {} | {} | Healthy: {}/{} | Leader: {} | Duration: {}", 
+                    #fn_name, health_percentage, #domain, formatted_cluster_info, healthy_nodes, total_nodes, leader_node, format_duration(duration)),
+                None
+            );
+        }
+    });
+    
+    checked_fn(input_fn)
+}
+
+/// Monitor distributed lock operations and resource coordination
+///
+/// Each call is one hold of the lock "lock_{domain}_{fn}", from entry to
+/// return. Lines report the hold's wait queue (calls holding the same lock
+/// when it started) and whether the queue is growing or shrinking.
+///
+/// - `domain`: Lock namespace (default "default")
+/// - `timeout_ms`: Calls longer than this log DISTRIBUTED_LOCK_TIMEOUT (default 30000)
+/// - `warn_hold_ms`: Holds longer than this log a DISTRIBUTED_LOCK_LONG_HOLD
+///   warning with the hold time, a hint of a stuck holder or deadlock (default: off)
+#[proc_macro_attribute]
+pub fn log_distributed_lock(args: TokenStream, input: TokenStream) -> TokenStream {
+    if cfg!(feature = "disabled") {
+        return input;
+    }
+    let args = parse_macro_input!(args as MacroArgs);
+    let domain = args.domain.unwrap_or_else(|| "default".to_string());
+    let timeout_ms = args.timeout_ms.unwrap_or(30000);
+    let mut input_fn = parse_macro_input!(input as ItemFn);
+    let fn_name = get_fn_name(&input_fn);
+    let orig_block = input_fn.block.clone();
+    let is_async = input_fn.sig.asyncness.is_some();
+    let utility_functions = generate_utility_functions();
+    
+    let long_hold = match args.warn_hold_ms {
+        Some(warn_hold_ms) => quote! {
+            if hold_time.as_millis() > #warn_hold_ms as u128 {
+                liblogger::log_warn!(
+                    &format!("DISTRIBUTED_LOCK_LONG_HOLD: {} - Lock held for {}ms (warn_hold_ms: {}), possible deadlock | Domain: {} | Lock ID: {} | Holder: {} | Queue: {} ({}) | Longest hold: {}ms", 
+                        #fn_name, hold_time.as_millis(), #warn_hold_ms, #domain, lock_id, holder_node, wait_queue_size, queue_trend, lock_hold.longest_hold().as_millis()),
+                    None
+                );
+            }
+        },
+        None => quote! {},
+    };
+
+    *input_fn.block = wrap_body(is_async, quote! {
+        #utility_functions
+        
+        let start_time = std::time::Instant::now();
+        let lock_context = get_distributed_lock_context(&#domain, &#fn_name);
+        let mut lock_hold = liblogger::Logger::begin_lock_hold(&lock_context.lock_id);
+    }, &orig_block, quote! {
+        let hold_time = lock_hold.release();
+        let duration = start_time.elapsed();
+        let formatted_lock_info = format_distributed_lock_info(&lock_context);
+        
+        let lock_id = &lock_context.lock_id;
+        let holder_node = &lock_context.holder_node;
+        let lock_type = &lock_context.lock_type;
+        let wait_queue_size = lock_hold.queue_size();
+        let queue_trend = lock_hold.queue_trend();
+        
+        #long_hold
+        
+        if duration.as_millis() > #timeout_ms as u128 {
+            liblogger::log_warn!(
+                &format!("DISTRIBUTED_LOCK_TIMEOUT: {} - Lock operation timeout | Domain: {} | Lock ID: {} | {} | Holder: {} | Type: {} | Queue: {} ({}) | Duration: {} (timeout: {}ms)", 
+                    #fn_name, #domain, lock_id, formatted_lock_info, holder_node, lock_type, wait_queue_size, queue_trend, format_duration(duration), #timeout_ms),
+                None
+            );
+        } else {
+            match &result {
+                Ok(_) => {
+                    liblogger::log_info!(
+                        &format!("DISTRIBUTED_LOCK_SUCCESS: {} - Lock operation successful | Domain: {} | Lock ID: {} | {} | Holder: {} | Type: {} | Queue: {} ({}) | Duration: {}", 
+                            #fn_name, #domain, lock_id, formatted_lock_info, holder_node, lock_type, wait_queue_size, queue_trend, format_duration(duration)),
+                        None
+                    );
+                },
+                Err(_) => {
+                    liblogger::log_warn!(
+                        &format!("DISTRIBUTED_LOCK_FAILURE: {} - Lock operation failed | Domain: {} | Lock ID: {} | {} | Holder: {} | Type: {} | Queue: {} ({}) | Duration: {}", 
+                            #fn_name, #domain, lock_id, formatted_lock_info, holder_node, lock_type, wait_queue_size, queue_trend, format_duration(duration)),
+                        None
+                    );
+                }
+            }
+        }
+    });
+    
+    checked_fn(input_fn)
+}
+
+/// Implement distributed tracing with correlation IDs
+#[proc_macro_attribute]
+pub fn log_trace_correlation(args: TokenStream, input: TokenStream) -> TokenStream {
+    if cfg!(feature = "disabled") {
+        return input;
+    }
+    let args = parse_macro_input!(args as MacroArgs);
+    let service_name = args.service_name.unwrap_or_else(|| "unknown".to_string());
+    let mut input_fn = parse_macro_input!(input as ItemFn);
+    let fn_name = get_fn_name(&input_fn);
+    let orig_block = input_fn.block.clone();
+    let is_async = input_fn.sig.asyncness.is_some();
+    let utility_functions = generate_utility_functions();
+
+    *input_fn.block = wrap_body(is_async, quote! {
+        #utility_functions
+        
+        let start_time = std::time::Instant::now();
+        let trace_context = get_trace_context(&#service_name, &#fn_name);
+    }, &orig_block, quote! {
+        let duration = start_time.elapsed();
+        let formatted_trace_info = format_trace_info(&trace_context);
+        
+        let trace_id = &trace_context.trace_id;
+        let span_id = &trace_context.span_id;
+        let parent_span_id = &trace_context.parent_span_id;
+        let baggage = &trace_context.baggage;
+        
+        match &result {
+            Ok(_) => {
+                liblogger::log_info!(
+                    &format!("TRACE_SPAN_SUCCESS: {} - Span completed successfully | Service: {} | {} | Trace: {} | Span: {} | Parent: {} | Baggage: {} | Duration: {}", 
+                        #fn_name, #service_name, formatted_trace_info, trace_id, span_id, parent_span_id, baggage, format_duration(duration)),
+                    None
+                );
+            },
+            Err(_) => {
+                liblogger::log_error!(
+                    &format!("TRACE_SPAN_ERROR: {} - Span completed with error | Service: {} | {} | Trace: {} | Span: {} | Parent: {} | Baggage: {} | Duration: {}", 
+                        #fn_name, #service_name, formatted_trace_info, trace_id, span_id, parent_span_id, baggage, format_duration(duration)),
+                    None
+                );
+            }
+        }
+    });
+    
+    checked_fn(input_fn)
+}
+
+/// Collect custom metrics and dimensional data
+#[proc_macro_attribute]
+pub fn log_custom_metrics(args: TokenStream, input: TokenStream) -> TokenStream {
+    if cfg!(feature = "disabled") {
+        return input;
+    }
+    let args = parse_macro_input!(args as MacroArgs);
+    let metric_name = args.metric_name.unwrap_or_else(|| "custom_metric".to_string());
+    let mut input_fn = parse_macro_input!(input as ItemFn);
+    let fn_name = get_fn_name(&input_fn);
+    let orig_block = input_fn.block.clone();
+    let is_async = input_fn.sig.asyncness.is_some();
+    let utility_functions = generate_utility_functions();
+
+    *input_fn.block = wrap_body(is_async, quote! {
+        #utility_functions
+        
+        let start_time = std::time::Instant::now();
+        let metrics_context_before = get_custom_metrics_context(&#metric_name);
+    }, &orig_block, quote! {
+        let duration = start_time.elapsed();
+        let metrics_context_after = get_custom_metrics_context(&#metric_name);
+        let formatted_metrics_info = format_custom_metrics_info(&metrics_context_after);
+        
+        let metric_value = metrics_context_after.metric_value;
+        let dimensions = &metrics_context_after.dimensions;
+        let metric_type = &metrics_context_after.metric_type;
+        let tags = &metrics_context_after.tags;
+        
+        let value_delta = metric_value - metrics_context_before.metric_value;
+        
+        liblogger::log_info!(
+            &format!("CUSTOM_METRICS: {} - Metric collected | Metric: {} | {} | Value: {:.2} (Δ{:.2}) | Type: {} | Dimensions: {} | Tags: {} | Duration: {}", 
+                #fn_name, #metric_name, formatted_metrics_info, metric_value, value_delta, metric_type, dimensions, tags, format_duration(duration)),
+            None
+        );
+    });
+    
+    checked_fn(input_fn)
+}
+
+/// Monitor system health with multiple checkpoints
+#[proc_macro_attribute]
+pub fn log_health_check(args: TokenStream, input: TokenStream) -> TokenStream {
+    if cfg!(feature = "disabled") {
+        return input;
+    }
+    let args = parse_macro_input!(args as MacroArgs);
+    let service_name = args.service_name.unwrap_or_else(|| "default".to_string());
+    let threshold = args.threshold.unwrap_or(95);
+    let mut input_fn = parse_macro_input!(input as ItemFn);
+    let fn_name = get_fn_name(&input_fn);
+    let orig_block = input_fn.block.clone();
+    let is_async = input_fn.sig.asyncness.is_some();
+    let utility_functions = generate_utility_functions();
+
+    *input_fn.block = wrap_body(is_async, quote! {
+        #utility_functions
+        
+        let start_time = std::time::Instant::now();
+        let health_context = get_health_check_context(&#service_name);
+    }, &orig_block, quote! {
+        let duration = start_time.elapsed();
+        let formatted_health_info = format_health_check_info(&health_context);
+        
+        let overall_health = health_context.overall_health_percentage;
+        let checks_passed = health_context.checks_passed;
+        let total_checks = health_context.total_checks;
+        let failed_checks = &health_context.failed_checks;
+        
+        if overall_health < #threshold as f64 {
+            liblogger::log_error!(
+                &format!("HEALTH_CHECK_CRITICAL: {} - Health check failed | Service: {} | {} | Health: {:.1}% (threshold: {}%) | Passed: {}/{} | Failed: {:?} | Duration: {}", 
+                    #fn_name, #service_name, formatted_health_info, overall_health, #threshold, checks_passed, total_checks, failed_checks, format_duration(duration)),
+                None
+            );
+        } else if overall_health < 90.0 {
+            liblogger::log_warn!(
+                &format!("HEALTH_CHECK_DEGRADED: {} - Health check degraded | Service: {} | {} | Health: {:.1}% | Passed: {}/{} | Failed: {:?} | Duration: {}", 
+                    #fn_name, #service_name, formatted_health_info, overall_health, checks_passed, total_checks, failed_checks, format_duration(duration)),
+                None
+            );
+        } else {
+            liblogger::log_info!(
+                &format!("HEALTH_CHECK_OK: {} - Health check passed | Service: {} | {} | Health: {:.1}% | Passed: {}/{} | Duration: {}", 
+                    #fn_name, #service_name, formatted_health_info, overall_health, checks_passed, total_checks, format_duration(duration)),
+                None
+            );
+        }
+    });
+    
+    checked_fn(input_fn)
+}
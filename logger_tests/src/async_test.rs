@@ -1,64 +1,597 @@
-/**
- * Test module for the asynchronous logging capabilities
- * 
- * This test demonstrates and verifies that:
- * - Logger can handle large volumes of log messages sent rapidly
- * - Asynchronous processing works as expected without blocking the main thread
- * - Messages of different log levels are properly handled
- * - Graceful shutdown properly processes all pending log messages
- */
-use liblogger::{Logger, log_info, log_warn, log_error, shutdown_logger};
-use std::{thread, time::Duration};
-
-/**
- * Tests the asynchronous logging capabilities of the library
- * 
- * This function:
- * 1. Initializes the logger with settings from app_config.toml
- * 2. Sends 1000 log messages in rapid succession
- * 3. Includes messages at different severity levels (info, warn, error)
- * 4. Waits for processing to complete
- * 5. Demonstrates proper shutdown procedure
- * 
- * The test verifies that the async logger can handle high-volume message bursts
- * without message loss, and that the shutdown procedure waits for all
- * pending messages to be processed.
- */
-pub fn test_async_logger() {
-    println!("Starting async logger test");
-    
-    // Initialize the logger from the same config file as main.rs uses
-    match Logger::init_with_config_file("app_config.toml") {
-        Ok(_) => println!("Async logger initialized successfully"),
-        Err(e) => {
-            eprintln!("Failed to initialize async logger: {}", e);
-            return;
-        }
-    }
-    
-    // Generate a large number of log messages rapidly
-    println!("Generating 1000 log messages...");
-    for i in 0..1000 {
-        log_info!(&format!("Async test message {}", i));
-        
-        if i % 100 == 0 {
-            log_warn!(&format!("Warning message at {}", i));
-        }
-        
-        if i % 250 == 0 {
-            log_error!(&format!("Error message at {}", i));
-        }
-    }
-    
-    println!("Finished sending messages, waiting for processing...");
-    // Give the async logger time to process the messages
-    thread::sleep(Duration::from_secs(2));
-    
-    println!("Shutting down logger...");
-    match shutdown_logger() {
-        Ok(_) => println!("Logger shutdown successfully"),
-        Err(e) => eprintln!("Error shutting down logger: {}", e),
-    }
-    
-    println!("Async logger test completed");
-}
+/**
+ * Test module for the asynchronous logging capabilities
+ * 
+ * This test demonstrates and verifies that:
+ * - Logger can handle large volumes of log messages sent rapidly
+ * - Asynchronous processing works as expected without blocking the main thread
+ * - Messages of different log levels are properly handled
+ * - Graceful shutdown properly processes all pending log messages
+ */
+use liblogger::{Logger, log_info, log_warn, log_error, shutdown_logger};
+use liblogger_macros::*;
+use tokio::runtime::Runtime;
+use std::{thread, time::Duration};
+
+// Helper functions for the attribute macros used on async fns below
+initialize_logger_attributes!();
+
+/**
+ * Tests the asynchronous logging capabilities of the library
+ * 
+ * This function:
+ * 1. Initializes the logger with settings from app_config.toml
+ * 2. Sends 1000 log messages in rapid succession
+ * 3. Includes messages at different severity levels (info, warn, error)
+ * 4. Waits for processing to complete
+ * 5. Demonstrates proper shutdown procedure
+ * 
+ * The test verifies that the async logger can handle high-volume message bursts
+ * without message loss, and that the shutdown procedure waits for all
+ * pending messages to be processed.
+ */
+pub fn test_async_logger() {
+    println!("Starting async logger test");
+    
+    // Initialize the logger from the same config file as main.rs uses
+    match Logger::init_with_config_file("app_config.toml") {
+        Ok(_) => println!("Async logger initialized successfully"),
+        Err(e) => {
+            eprintln!("Failed to initialize async logger: {}", e);
+            return;
+        }
+    }
+    
+    // Generate a large number of log messages rapidly
+    println!("Generating 1000 log messages...");
+    for i in 0..1000 {
+        log_info!(&format!("Async test message {}", i));
+        
+        if i % 100 == 0 {
+            log_warn!(&format!("Warning message at {}", i));
+        }
+        
+        if i % 250 == 0 {
+            log_error!(&format!("Error message at {}", i));
+        }
+    }
+    
+    println!("Finished sending messages, waiting for processing...");
+    // Give the async logger time to process the messages
+    thread::sleep(Duration::from_secs(2));
+    
+    println!("Shutting down logger...");
+    match shutdown_logger() {
+        Ok(_) => println!("Logger shutdown successfully"),
+        Err(e) => eprintln!("Error shutting down logger: {}", e),
+    }
+    
+    println!("Async logger test completed");
+}
+
+/**
+ * Tests that the thread-local logging context follows spawned tasks
+ *
+ * A request_id set on the calling thread must be visible inside a task
+ * started with Logger::spawn_with_context, and inside a thread that
+ * re-installs a manually captured context.
+ */
+pub fn test_context_propagation() {
+    let runtime = Runtime::new().expect("Failed to create test runtime");
+    
+    Logger::set_context_value("request_id", "req-propagation-1");
+    
+    let seen = runtime.block_on(async {
+        Logger::spawn_with_context(async {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            log_info!("Logging from a spawned task");
+            Logger::get_context_value("request_id")
+        }).await.expect("Spawned task failed")
+    });
+    assert_eq!(seen.as_deref(), Some("req-propagation-1"));
+    
+    // Manual propagation to a plain thread
+    let captured = Logger::capture_context();
+    let seen = thread::spawn(move || {
+        let _scope = captured.scope();
+        log_info!("Logging from a thread with captured context");
+        Logger::get_context_value("request_id")
+    }).join().unwrap();
+    assert_eq!(seen.as_deref(), Some("req-propagation-1"));
+    
+    Logger::remove_context_value("request_id");
+    println!("Context propagation test completed");
+}
+
+#[log_entry_exit(level = "warn", timing = true)]
+async fn async_entry_exit() -> u32 {
+    tokio::time::sleep(Duration::from_millis(5)).await;
+    7
+}
+
+#[log_response]
+async fn async_log_response(value: u32) -> u32 {
+    tokio::time::sleep(Duration::from_millis(5)).await;
+    value * 2
+}
+
+#[log_concurrency]
+async fn async_log_concurrency(id: u32) -> u32 {
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    id
+}
+
+#[log_concurrency(max = 3)]
+async fn async_spawned_concurrency(barrier: std::sync::Arc<tokio::sync::Barrier>) {
+    // All four calls are in flight before any of them finishes
+    barrier.wait().await;
+    tokio::time::sleep(Duration::from_millis(10)).await;
+}
+
+#[log_concurrency]
+async fn async_cancelled_concurrency() {
+    tokio::time::sleep(Duration::from_secs(5)).await;
+}
+
+#[throttle_log(rate = 3)]
+async fn async_throttled(call: u32) -> u32 {
+    log_info!(&format!("throttle-async-body {} before await", call));
+    tokio::time::sleep(Duration::from_millis(2)).await;
+    log_info!(&format!("throttle-async-body {} after await", call));
+    call
+}
+
+#[log_call(order_id, level = "warn")]
+async fn async_log_call(order_id: u32) -> Result<u32, String> {
+    log_warn!("log-call-async-body");
+    tokio::time::sleep(Duration::from_millis(5)).await;
+    Ok(order_id * 2)
+}
+
+#[deadline(ms = 50)]
+async fn async_deadline(delay_ms: u64) -> Result<u64, String> {
+    tokio::time::sleep(Duration::from_millis(delay_ms)).await;
+    log_info!(&format!("deadline-body finished after {}ms", delay_ms));
+    Ok(delay_ms)
+}
+
+#[feature_flag(flag_name = "async_checkout")]
+async fn async_feature_flag(input: &str) -> Result<u32, String> {
+    tokio::time::sleep(Duration::from_millis(5)).await;
+    // `?` must return from the instrumented body, not skip the wrapper
+    let parsed: u32 = input.parse().map_err(|e| format!("bad input: {:?}", e))?;
+    Ok(parsed)
+}
+
+#[version_tag]
+async fn async_version_tag() -> Option<String> {
+    tokio::time::sleep(Duration::from_millis(5)).await;
+    log_info!("Async function with version tag");
+    Logger::get_context_value("version")
+}
+
+#[request_context]
+async fn async_request_context(early: bool) -> &'static str {
+    if early {
+        return "early";
+    }
+    tokio::time::sleep(Duration::from_millis(5)).await;
+    "completed"
+}
+
+// Each body leaves through `?` after an await when its input doesn't parse
+async fn parse_after_await(input: &str) -> Result<u32, String> {
+    tokio::time::sleep(Duration::from_millis(1)).await;
+    let value: u32 = input.parse().map_err(|_| format!("not a number: {}", input))?;
+    Ok(value)
+}
+
+#[dependency_latency(target = "inventory_api")]
+async fn async_dependency_latency(input: &str) -> Result<u32, String> {
+    let value = parse_after_await(input).await?;
+    Ok(value)
+}
+
+#[metrics_counter(counter_name = "async_macro_calls")]
+async fn async_metrics_counter(input: &str) -> Result<u32, String> {
+    let value = parse_after_await(input).await?;
+    Ok(value)
+}
+
+#[log_memory_usage]
+async fn async_log_memory_usage(input: &str) -> Result<u32, String> {
+    let value = parse_after_await(input).await?;
+    Ok(value)
+}
+
+#[log_cpu_time]
+async fn async_log_cpu_time(input: &str) -> Result<u32, String> {
+    let value = parse_after_await(input).await?;
+    Ok(value)
+}
+
+#[health_check]
+async fn async_health_check(input: &str) -> Result<u32, String> {
+    let value = parse_after_await(input).await?;
+    Ok(value)
+}
+
+#[log_result]
+async fn async_log_result(input: &str) -> Result<u32, String> {
+    let value = parse_after_await(input).await?;
+    Ok(value)
+}
+
+#[audit_log]
+async fn async_audit_log(input: &str) -> Result<u32, String> {
+    let value = parse_after_await(input).await?;
+    Ok(value)
+}
+
+#[circuit_breaker(failure_threshold = 10)]
+async fn async_circuit_breaker(input: &str) -> Result<u32, String> {
+    let value = parse_after_await(input).await?;
+    Ok(value)
+}
+
+#[log_disk_usage(threshold = 99)]
+async fn async_log_disk_usage(input: &str) -> Result<u32, String> {
+    let value = parse_after_await(input).await?;
+    Ok(value)
+}
+
+#[log_network_connectivity(endpoint = "127.0.0.1:9")]
+async fn async_log_network_connectivity(input: &str) -> Result<u32, String> {
+    let value = parse_after_await(input).await?;
+    Ok(value)
+}
+
+#[log_database_pool(pool_name = "async_db", threshold = 75)]
+async fn async_log_database_pool(input: &str) -> Result<u32, String> {
+    let value = parse_after_await(input).await?;
+    Ok(value)
+}
+
+#[log_file_descriptors(threshold = 100000)]
+async fn async_log_file_descriptors(input: &str) -> Result<u32, String> {
+    let value = parse_after_await(input).await?;
+    Ok(value)
+}
+
+#[log_cache_hit_ratio(cache_name = "async_cache", threshold = 80)]
+async fn async_log_cache_hit_ratio(input: &str) -> Result<u32, String> {
+    let value = parse_after_await(input).await?;
+    Ok(value)
+}
+
+#[log_queue_depth(queue_name = "async_queue", threshold = 500)]
+async fn async_log_queue_depth(input: &str) -> Result<u32, String> {
+    let value = parse_after_await(input).await?;
+    Ok(value)
+}
+
+#[log_gc_pressure(threshold = 150)]
+async fn async_log_gc_pressure(input: &str) -> Result<u32, String> {
+    let value = parse_after_await(input).await?;
+    Ok(value)
+}
+
+#[log_anomaly_detection(service_name = "async_service")]
+async fn async_log_anomaly_detection(input: &str) -> Result<u32, String> {
+    let value = parse_after_await(input).await?;
+    Ok(value)
+}
+
+#[log_api_rate_limits(service_name = "async_api", threshold = 90)]
+async fn async_log_api_rate_limits(input: &str) -> Result<u32, String> {
+    let value = parse_after_await(input).await?;
+    Ok(value)
+}
+
+#[log_ssl_certificate_expiry(domain = "example.com", days_warning = 60)]
+async fn async_log_ssl_certificate_expiry(input: &str) -> Result<u32, String> {
+    let value = parse_after_await(input).await?;
+    Ok(value)
+}
+
+#[log_service_discovery(service_name = "async_service")]
+async fn async_log_service_discovery(input: &str) -> Result<u32, String> {
+    let value = parse_after_await(input).await?;
+    Ok(value)
+}
+
+#[log_load_balancer_health(service_name = "async_lb", threshold = 3)]
+async fn async_log_load_balancer_health(input: &str) -> Result<u32, String> {
+    let value = parse_after_await(input).await?;
+    Ok(value)
+}
+
+#[log_security_event(warning_level = "high")]
+async fn async_log_security_event(input: &str) -> Result<u32, String> {
+    let value = parse_after_await(input).await?;
+    Ok(value)
+}
+
+#[log_compliance_check(domain = "gdpr")]
+async fn async_log_compliance_check(input: &str) -> Result<u32, String> {
+    let value = parse_after_await(input).await?;
+    Ok(value)
+}
+
+#[log_access_control(domain = "admin_panel")]
+async fn async_log_access_control(input: &str) -> Result<u32, String> {
+    let value = parse_after_await(input).await?;
+    Ok(value)
+}
+
+#[log_crypto_operation(domain = "encryption")]
+async fn async_log_crypto_operation(input: &str) -> Result<u32, String> {
+    let value = parse_after_await(input).await?;
+    Ok(value)
+}
+
+#[log_config_change(domain = "async_config")]
+async fn async_log_config_change(input: &str) -> Result<u32, String> {
+    let value = parse_after_await(input).await?;
+    Ok(value)
+}
+
+#[log_deployment(service_name = "async_service")]
+async fn async_log_deployment(input: &str) -> Result<u32, String> {
+    let value = parse_after_await(input).await?;
+    Ok(value)
+}
+
+#[log_environment_validation(service_name = "async_service")]
+async fn async_log_environment_validation(input: &str) -> Result<u32, String> {
+    let value = parse_after_await(input).await?;
+    Ok(value)
+}
+
+#[log_feature_flag_change(min_percentage = 10, max_percentage = 90)]
+async fn async_log_feature_flag_change(input: &str) -> Result<u32, String> {
+    let value = parse_after_await(input).await?;
+    Ok(value)
+}
+
+#[log_thread_pool_utilization(thread_pool_name = "async_pool", threshold = 85)]
+async fn async_log_thread_pool_utilization(input: &str) -> Result<u32, String> {
+    let value = parse_after_await(input).await?;
+    Ok(value)
+}
+
+#[log_business_rule(domain = "async_orders")]
+async fn async_log_business_rule(input: &str) -> Result<u32, String> {
+    let value = parse_after_await(input).await?;
+    Ok(value)
+}
+
+#[log_data_quality(domain = "async_data", threshold = 98)]
+async fn async_log_data_quality(input: &str) -> Result<u32, String> {
+    let value = parse_after_await(input).await?;
+    Ok(value)
+}
+
+#[log_workflow_step(domain = "async_flow")]
+async fn async_log_workflow_step(input: &str) -> Result<u32, String> {
+    let value = parse_after_await(input).await?;
+    Ok(value)
+}
+
+#[log_transaction(domain = "async_ledger")]
+async fn async_log_transaction(input: &str) -> Result<u32, String> {
+    let value = parse_after_await(input).await?;
+    Ok(value)
+}
+
+#[log_service_communication(service_name = "async_service", timeout_ms = 2000)]
+async fn async_log_service_communication(input: &str) -> Result<u32, String> {
+    let value = parse_after_await(input).await?;
+    Ok(value)
+}
+
+#[log_consensus_operation(domain = "async_cluster", timeout_ms = 5000)]
+async fn async_log_consensus_operation(input: &str) -> Result<u32, String> {
+    let value = parse_after_await(input).await?;
+    Ok(value)
+}
+
+#[log_cluster_health(domain = "async_cluster", threshold = 80)]
+async fn async_log_cluster_health(input: &str) -> Result<u32, String> {
+    let value = parse_after_await(input).await?;
+    Ok(value)
+}
+
+#[log_distributed_lock(domain = "async_lock", timeout_ms = 10000)]
+async fn async_log_distributed_lock(input: &str) -> Result<u32, String> {
+    let value = parse_after_await(input).await?;
+    Ok(value)
+}
+
+#[log_trace_correlation(service_name = "async_gateway")]
+async fn async_log_trace_correlation(input: &str) -> Result<u32, String> {
+    let value = parse_after_await(input).await?;
+    Ok(value)
+}
+
+#[log_custom_metrics(metric_name = "async_kpi")]
+async fn async_log_custom_metrics(input: &str) -> Result<u32, String> {
+    let value = parse_after_await(input).await?;
+    Ok(value)
+}
+
+#[log_health_check(service_name = "async_health", threshold = 99)]
+async fn async_log_health_check(input: &str) -> Result<u32, String> {
+    let value = parse_after_await(input).await?;
+    Ok(value)
+}
+
+/**
+ * Runs every macro that wraps its body with `wrap_body` on an async fn
+ *
+ * Each function is called once with a valid input and once with one that
+ * makes the body return early through `?`. The epilogue must run in both
+ * cases, so the failures show up in the lines logged after the body.
+ */
+pub fn test_async_wrapped_macros() {
+    let runtime = Runtime::new().expect("Failed to create test runtime");
+    
+    let start = crate::log_file_len();
+    runtime.block_on(async {
+        macro_rules! check {
+            ($function:ident) => {
+                assert_eq!($function("42").await, Ok(42), stringify!($function));
+                assert_eq!($function("x").await, Err("not a number: x".to_string()), stringify!($function));
+            };
+        }
+        check!(async_dependency_latency);
+        check!(async_metrics_counter);
+        check!(async_log_memory_usage);
+        check!(async_log_cpu_time);
+        check!(async_health_check);
+        check!(async_log_result);
+        check!(async_audit_log);
+        check!(async_circuit_breaker);
+        check!(async_log_disk_usage);
+        check!(async_log_network_connectivity);
+        check!(async_log_database_pool);
+        check!(async_log_file_descriptors);
+        check!(async_log_cache_hit_ratio);
+        check!(async_log_queue_depth);
+        check!(async_log_gc_pressure);
+        check!(async_log_anomaly_detection);
+        check!(async_log_api_rate_limits);
+        check!(async_log_ssl_certificate_expiry);
+        check!(async_log_service_discovery);
+        check!(async_log_load_balancer_health);
+        check!(async_log_security_event);
+        check!(async_log_compliance_check);
+        check!(async_log_access_control);
+        check!(async_log_crypto_operation);
+        check!(async_log_config_change);
+        check!(async_log_deployment);
+        check!(async_log_environment_validation);
+        check!(async_log_feature_flag_change);
+        check!(async_log_thread_pool_utilization);
+        check!(async_log_business_rule);
+        check!(async_log_data_quality);
+        check!(async_log_workflow_step);
+        check!(async_log_transaction);
+        check!(async_log_service_communication);
+        check!(async_log_consensus_operation);
+        check!(async_log_cluster_health);
+        check!(async_log_distributed_lock);
+        check!(async_log_trace_correlation);
+        check!(async_log_custom_metrics);
+        check!(async_log_health_check);
+    });
+    
+    assert!(crate::wait_for_log_line(start, "[ERROR]", "Dependency call to inventory_api failed after"));
+    assert!(crate::wait_for_log_line(start, "[ERROR]", "Health check async_health_check failed in"));
+    assert!(crate::wait_for_log_line(start, "[ERROR]", "async_log_result failed with error: \"not a number: x\""));
+    assert!(crate::wait_for_log_line(start, "[WARN]", "Circuit breaker: async_circuit_breaker failed (1/10 failures)"));
+    assert!(crate::wait_for_log_line(start, "[INFO]", "AUDIT: async_audit_log completed in"));
+    assert!(crate::wait_for_log_line(start, "[INFO]", "async_log_cpu_time used CPU time"));
+    assert!(crate::wait_for_log_line(start, "[INFO]", "CONFIG_CHANGE: async_log_config_change - Domain: async_config"));
+    assert!(crate::wait_for_log_line(start, "[INFO]", "DEPLOYMENT: async_log_deployment - Service: async_service"));
+    
+    // Both calls reach the epilogue of every macro that logs one
+    let written = std::fs::read_to_string(crate::TEST_LOG_FILE).expect("Failed to read log file");
+    let written = &written[start..];
+    for label in ["SECURITY_EVENT: async_log_security_event", "COMPLIANCE_CHECK: async_log_compliance_check",
+                  "API_RATE_LIMITS: async_log_api_rate_limits", "SERVICE_DISCOVERY: async_log_service_discovery"] {
+        assert_eq!(written.matches(label).count(), 2, "{}", label);
+    }
+    
+    println!("Async wrapped macro test completed");
+}
+
+/**
+ * Tests the attribute macros that wrap async fns
+ *
+ * The instrumentation must bracket the awaited body rather than the
+ * creation of the future: log_response sees the resolved value, `?` and
+ * `return` inside the body still work, and version_tag's context survives
+ * awaits on a multi-threaded runtime.
+ */
+pub fn test_async_macros() {
+    let runtime = Runtime::new().expect("Failed to create test runtime");
+    
+    let start = crate::log_file_len();
+    runtime.block_on(async {
+        assert_eq!(async_entry_exit().await, 7);
+        
+        assert_eq!(async_log_response(21).await, 42);
+        
+        assert_eq!(async_log_call(8).await, Ok(16));
+        
+        // A slow body is dropped at the deadline instead of running to completion
+        assert_eq!(async_deadline(5).await, Ok(5));
+        let started = std::time::Instant::now();
+        assert_eq!(async_deadline(2000).await, Err("async_deadline exceeded its deadline of 50ms".to_string()));
+        assert!(started.elapsed() < Duration::from_millis(1000), "{:?}", started.elapsed());
+        
+        let (a, b, c) = tokio::join!(
+            async_log_concurrency(1),
+            async_log_concurrency(2),
+            async_log_concurrency(3)
+        );
+        assert_eq!((a, b, c), (1, 2, 3));
+        
+        // Calls on other worker threads count while their futures are pending
+        let barrier = std::sync::Arc::new(tokio::sync::Barrier::new(4));
+        let tasks: Vec<_> = (0..4)
+            .map(|_| tokio::spawn(async_spawned_concurrency(barrier.clone())))
+            .collect();
+        for task in tasks {
+            task.await.expect("Concurrency task failed");
+        }
+        
+        // Calls over the rate are muted on both sides of their await
+        for call in 0..10 {
+            let handle = tokio::spawn(async_throttled(call));
+            assert_eq!(handle.await.expect("Throttled task failed"), call);
+        }
+        
+        // A future dropped mid-await leaves the count
+        let timed_out = tokio::time::timeout(Duration::from_millis(20), async_cancelled_concurrency()).await;
+        assert!(timed_out.is_err());
+        
+        assert_eq!(async_feature_flag("7").await, Ok(7));
+        assert!(async_feature_flag("seven").await.is_err());
+        
+        assert_eq!(async_request_context(true).await, "early");
+        assert_eq!(async_request_context(false).await, "completed");
+        
+        let version = tokio::spawn(async_version_tag()).await.expect("Version task failed");
+        assert_eq!(
+            version.as_deref().and_then(|v| v.split('+').next()),
+            Some(env!("CARGO_PKG_VERSION"))
+        );
+    });
+    assert_eq!(Logger::get_context_value("version"), None);
+    
+    assert!(crate::wait_for_log_line(start, "[WARN]", "ENTRY: async_entry_exit"));
+    assert!(crate::wait_for_log_line(start, "[WARN]", "EXIT: async_entry_exit ("));
+    assert!(crate::wait_for_log_line(start, "[WARN]", "async_spawned_concurrency concurrency 4 exceeds max 3"));
+    assert!(crate::wait_for_log_line(start, "[INFO]", "async_spawned_concurrency peak concurrency: 4"));
+    assert!(crate::wait_for_log_line(start, "[DEBUG]", "async_cancelled_concurrency concurrent invocations after exit: 0"));
+    
+    assert!(crate::wait_for_log_line(start, "[INFO]", "throttle-async-body 2 after await"));
+    let written = std::fs::read_to_string(crate::TEST_LOG_FILE).expect("Failed to read log file");
+    let written = &written[start..];
+    assert_eq!(written.matches("throttle-async-body").count(), 6, "{}", written);
+    assert_eq!(written.matches("async_throttled executed").count(), 3);
+    
+    assert!(crate::wait_for_log_line(start, "[ERROR]", "async_deadline exceeded its deadline of 50ms"));
+    assert!(written.contains("deadline-body finished after 5ms"));
+    assert!(!written.contains("deadline-body finished after 2000ms"));
+    
+    // One annotation logs the arguments, then the body, then the awaited result
+    assert!(crate::wait_for_log_line(start, "[WARN]", "Entering async_log_call with args: order_id = 8"));
+    let entry = written.find("Entering async_log_call with args: order_id = 8");
+    let body = written.find("log-call-async-body");
+    let exit = written.find("async_log_call returned: Ok(16)");
+    assert!(entry.is_some() && entry < body && body < exit, "{}", written);
+    
+    println!("Async macro test completed");
+}
@@ -13,6 +13,7 @@
  */
 
 use liblogger_macros::*;
+use std::collections::HashMap;
 
 // Initialize logger attributes for this module
 initialize_logger_attributes!();
@@ -92,6 +93,25 @@ fn test_api_rate_limit_monitoring() -> Result<String, String> {
     Ok("API rate limits checked".to_string())
 }
 
+// Stand-in for an HTTP client response
+struct FakeResponse {
+    headers: HashMap<String, String>,
+}
+
+fn rate_limit_header(response: &FakeResponse, name: &str) -> Option<String> {
+    response.headers.get(name).cloned()
+}
+
+#[log_api_rate_limits(service_name = "github", threshold = 10, extractor = "rate_limit_header")]
+fn test_api_rate_limit_headers(remaining: &str, limit: &str) -> FakeResponse {
+    let mut headers = HashMap::new();
+    if !remaining.is_empty() {
+        headers.insert("X-RateLimit-Remaining".to_string(), remaining.to_string());
+        headers.insert("X-RateLimit-Limit".to_string(), limit.to_string());
+    }
+    FakeResponse { headers }
+}
+
 #[log_ssl_certificate_expiry(domain = "example.com", days_warning = 60)]
 fn test_ssl_certificate_monitoring() -> Result<String, String> {
     // Simulate SSL certificate check
@@ -124,6 +144,16 @@ fn test_security_event_logging() -> Result<String, String> {
     Ok("Security event processed".to_string())
 }
 
+#[log_security_event(warning_level = "low")]
+fn test_security_event_low() -> Result<String, String> {
+    Ok("Password policy viewed".to_string())
+}
+
+#[log_security_event(warning_level = "critical")]
+fn test_security_event_critical() -> Result<String, String> {
+    Ok("Root credentials rotated".to_string())
+}
+
 #[log_compliance_check(domain = "gdpr")]
 fn test_compliance_monitoring() -> Result<String, String> {
     // Simulate compliance check
@@ -156,6 +186,12 @@ fn test_config_change_monitoring() -> Result<String, String> {
     Ok("Configuration change applied".to_string())
 }
 
+#[log_config_change(domain = "database", before = "old", after = "new", redact = ["Password"])]
+fn apply_database_config(old: HashMap<String, String>, new: HashMap<String, String>) -> usize {
+    drop(old);
+    new.len()
+}
+
 #[log_deployment(service_name = "web_service")]
 fn test_deployment_monitoring() -> Result<String, String> {
     // Simulate deployment process
@@ -195,6 +231,17 @@ fn test_data_quality_monitoring() -> Result<String, String> {
     Ok("Data quality check completed".to_string())
 }
 
+// The simulated quality score (96.5%) is below the 98% threshold
+#[log_data_quality(domain = "customer_data", threshold = 98, enforce = true)]
+fn test_data_quality_enforced() -> Result<String, String> {
+    Ok("Low quality data accepted".to_string())
+}
+
+#[log_data_quality(domain = "customer_data", threshold = 98, enforce = false)]
+fn test_data_quality_not_enforced() -> Result<String, String> {
+    Ok("Low quality data accepted".to_string())
+}
+
 #[log_workflow_step(domain = "payment_flow", max_depth = 5)]
 fn test_workflow_monitoring() -> Result<String, String> {
     // Simulate workflow step
@@ -202,6 +249,16 @@ fn test_workflow_monitoring() -> Result<String, String> {
     Ok("Workflow step completed".to_string())
 }
 
+#[log_workflow_step(domain = "checkout")]
+fn test_workflow_reserve_stock() -> Result<u32, String> {
+    Ok(1)
+}
+
+#[log_workflow_step(domain = "checkout")]
+fn test_workflow_charge_card() -> Result<u32, String> {
+    Ok(2)
+}
+
 #[log_transaction(domain = "payment", timeout_ms = 3000)]
 fn test_transaction_monitoring() -> Result<String, String> {
     // Simulate transaction processing
@@ -209,6 +266,27 @@ fn test_transaction_monitoring() -> Result<String, String> {
     Ok("Transaction completed".to_string())
 }
 
+#[log_transaction(domain = "ledger")]
+fn test_transaction_commit() -> Result<u32, String> {
+    Ok(1)
+}
+
+#[log_transaction(domain = "ledger")]
+fn test_transaction_rollback() -> Result<u32, String> {
+    Err("insufficient funds".to_string())
+}
+
+#[log_transaction(domain = "ledger", warn_on_nested = true)]
+fn test_transaction_inner() -> Result<u32, String> {
+    Ok(2)
+}
+
+#[log_transaction(domain = "ledger")]
+fn test_transaction_outer() -> Result<u32, String> {
+    let inner = test_transaction_inner()?;
+    Ok(inner + 1)
+}
+
 // ====================
 // Distributed Systems Macro Tests
 // ====================
@@ -220,6 +298,17 @@ fn test_service_communication_monitoring() -> Result<String, String> {
     Ok("Service communication completed".to_string())
 }
 
+#[log_service_communication(service_name = "inventory_service", failure_threshold = 3)]
+fn test_service_communication_failing() -> Result<String, String> {
+    Err("connection refused".to_string())
+}
+
+// Shares the inventory_service breaker with the function above
+#[log_service_communication(service_name = "inventory_service", failure_threshold = 3)]
+fn test_service_communication_recovering() -> Result<String, String> {
+    Ok("stock reserved".to_string())
+}
+
 #[log_consensus_operation(domain = "cluster", timeout_ms = 5000)]
 fn test_consensus_monitoring() -> Result<String, String> {
     // Simulate consensus operation
@@ -241,6 +330,13 @@ fn test_distributed_lock_monitoring() -> Result<String, String> {
     Ok("Distributed lock acquired".to_string())
 }
 
+#[log_distributed_lock(domain = "ledger_lock", warn_hold_ms = 50)]
+fn test_distributed_lock_long_hold() -> Result<String, String> {
+    // Simulate a holder that keeps the lock too long
+    std::thread::sleep(std::time::Duration::from_millis(120));
+    Ok("Ledger rebuilt".to_string())
+}
+
 // ====================
 // Observability & Correlation Macro Tests
 // ====================
@@ -273,6 +369,12 @@ fn test_anomaly_detection() -> Result<String, String> {
     Ok("Anomaly detection completed".to_string())
 }
 
+#[log_anomaly_detection(service_name = "anomaly_service", min_samples = 10)]
+fn timed_batch_job(delay_ms: u64) -> Result<u64, String> {
+    std::thread::sleep(std::time::Duration::from_millis(delay_ms));
+    Ok(delay_ms)
+}
+
 // ====================
 // Test Runner Functions
 // ====================
@@ -298,35 +400,115 @@ pub fn run_performance_tests() {
 pub fn run_external_deps_tests() {
     println!("=== Running External Dependencies Macro Tests ===");
     let _ = test_api_rate_limit_monitoring();
+    test_api_rate_limit_quota();
     let _ = test_ssl_certificate_monitoring();
     let _ = test_service_discovery_monitoring();
     let _ = test_load_balancer_monitoring();
     println!("External dependencies tests completed\n");
 }
 
+fn test_api_rate_limit_quota() {
+    // 4000 of 5000 left: 20% used, above the 10% remaining threshold
+    let start = crate::log_file_len();
+    test_api_rate_limit_headers("4000", "5000");
+    assert!(crate::wait_for_log_line(start, "[INFO]",
+        "API_RATE_LIMITS: test_api_rate_limit_headers - Service: github | Remaining: 4000/5000 | Utilization: 20.0% | Threshold: 10% remaining |"));
+    
+    // 250 of 5000 left is 5%, below the threshold
+    let start = crate::log_file_len();
+    test_api_rate_limit_headers("250", "5000");
+    assert!(crate::wait_for_log_line(start, "[WARN]",
+        "API_RATE_LIMITS: test_api_rate_limit_headers - Quota running low | Service: github | Remaining: 250/5000 | Utilization: 95.0% | Threshold: 10% remaining |"));
+    
+    // Responses without the headers say so instead of guessing
+    let start = crate::log_file_len();
+    test_api_rate_limit_headers("", "");
+    assert!(crate::wait_for_log_line(start, "[INFO]",
+        "API_RATE_LIMITS: test_api_rate_limit_headers - Service: github | Rate limit headers missing |"));
+}
+
 pub fn run_security_tests() {
     println!("=== Running Security & Compliance Macro Tests ===");
     let _ = test_security_event_logging();
+    test_security_event_levels();
     let _ = test_compliance_monitoring();
     let _ = test_access_control_monitoring();
     let _ = test_crypto_operation_monitoring();
     println!("Security & compliance tests completed\n");
 }
 
+fn test_security_event_levels() {
+    // The level follows warning_level, and user and client IP come from the context
+    let start = crate::log_file_len();
+    {
+        let _user = liblogger::Logger::scoped_context_value("user_id", "alice");
+        let _ip = liblogger::Logger::scoped_context_value("client_ip", "10.0.0.7");
+        assert!(test_security_event_low().is_ok());
+        assert!(test_security_event_critical().is_ok());
+    }
+    assert!(crate::wait_for_log_line(start, "[INFO]",
+        "SECURITY_EVENT: test_security_event_low - Warning level: low | User: alice | Client IP: 10.0.0.7 |"));
+    assert!(crate::wait_for_log_line(start, "[ERROR]",
+        "SECURITY_EVENT: test_security_event_critical - Warning level: critical | User: alice | Client IP: 10.0.0.7 |"));
+    
+    // Without them the event says so
+    let start = crate::log_file_len();
+    assert!(test_security_event_low().is_ok());
+    assert!(crate::wait_for_log_line(start, "[INFO]", "| User: unknown | Client IP: unknown |"));
+}
+
 pub fn run_config_deployment_tests() {
     println!("=== Running Configuration & Deployment Macro Tests ===");
     let _ = test_config_change_monitoring();
+    test_config_change_diff();
     let _ = test_deployment_monitoring();
     let _ = test_environment_validation();
     let _ = test_feature_flag_monitoring();
     println!("Configuration & deployment tests completed\n");
 }
 
+fn test_config_change_diff() {
+    // Changed keys are listed with old and new values, secrets masked
+    let old: HashMap<String, String> = [("host", "db-1"), ("pool_size", "10"), ("password", "hunter2"), ("port", "5432")]
+        .into_iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+    let mut new = old.clone();
+    new.insert("host".to_string(), "db-2".to_string());
+    new.insert("pool_size".to_string(), "20".to_string());
+    new.insert("password".to_string(), "correct-horse".to_string());
+    
+    let start = crate::log_file_len();
+    assert_eq!(apply_database_config(old, new), 4);
+    assert!(crate::wait_for_log_line(start, "[INFO]",
+        "| Changes: host: db-1 -> db-2, password: [REDACTED] -> [REDACTED], pool_size: 10 -> 20"));
+    let written = std::fs::read_to_string(crate::TEST_LOG_FILE).expect("Failed to read log file");
+    assert!(!written[start..].contains("hunter2") && !written[start..].contains("correct-horse"));
+    assert!(!written[start..].contains("port:"));
+}
+
 pub fn run_business_tests() {
     println!("=== Running Business Logic & Data Quality Macro Tests ===");
     let _ = test_business_rule_monitoring();
     let _ = test_data_quality_monitoring();
+    
+    let err = test_data_quality_enforced().unwrap_err();
+    assert!(err.contains("score 96.5% is below threshold 98%"), "{}", err);
+    assert_eq!(test_data_quality_not_enforced(), Ok("Low quality data accepted".to_string()));
     let _ = test_workflow_monitoring();
+    
+    // Steps 1 -> 2 -> 1: only the regression to step 1 is reported
+    let start = crate::log_file_len();
+    {
+        let _workflow = liblogger::Logger::scoped_context_value("workflow_id", "order-1106");
+        assert_eq!(test_workflow_reserve_stock(), Ok(1));
+        assert_eq!(test_workflow_charge_card(), Ok(2));
+        assert_eq!(test_workflow_reserve_stock(), Ok(1));
+    }
+    assert!(crate::wait_for_log_line(start, "[WARN]",
+        "WORKFLOW_STEP_OUT_OF_ORDER: test_workflow_reserve_stock - Step runs again or out of order | Domain: checkout | Workflow: order-1106 | Step: test_workflow_reserve_stock | Depth: 1 | Last completed depth: 2"));
+    let written = std::fs::read(crate::TEST_LOG_FILE).unwrap();
+    let written = String::from_utf8_lossy(&written[start..]);
+    assert_eq!(written.matches("WORKFLOW_STEP_OUT_OF_ORDER").count(), 1, "{}", written);
+    assert!(written.contains("WORKFLOW_STEP_SUCCESS: test_workflow_charge_card"));
     let _ = test_transaction_monitoring();
     println!("Business logic & data quality tests completed\n");
 }
@@ -337,6 +519,64 @@ pub fn run_distributed_tests() {
     let _ = test_consensus_monitoring();
     let _ = test_cluster_health_monitoring();
     let _ = test_distributed_lock_monitoring();
+    
+    let start = crate::log_file_len();
+    assert_eq!(test_transaction_commit(), Ok(1));
+    assert!(crate::wait_for_log_line(start, "[INFO]", "TRANSACTION_COMMIT: test_transaction_commit - Transaction committed"));
+    
+    let start = crate::log_file_len();
+    assert_eq!(test_transaction_outer(), Ok(3));
+    assert!(crate::wait_for_log_line(start, "[WARN]", "TRANSACTION_NESTED: test_transaction_inner - nested transaction (depth 2)"));
+    assert!(crate::wait_for_log_line(start, "[INFO]", "TRANSACTION_COMMIT: test_transaction_outer - Transaction committed"));
+    
+    // Holds past warn_hold_ms report how long the lock was held
+    let start = crate::log_file_len();
+    assert!(test_distributed_lock_long_hold().is_ok());
+    let line = crate::find_log_line(start, "[WARN]", "DISTRIBUTED_LOCK_LONG_HOLD: test_distributed_lock_long_hold - Lock held for ")
+        .expect("no long hold warning");
+    let held_ms: u64 = line.split("Lock held for ").nth(1).and_then(|rest| rest.split("ms").next())
+        .and_then(|ms| ms.parse().ok())
+        .expect("no hold time in warning");
+    assert!((120..1000).contains(&held_ms), "{}", line);
+    assert!(line.contains("Lock ID: lock_ledger_lock_test_distributed_lock_long_hold | Holder: node_1 | Queue: 0 (steady)"), "{}", line);
+    
+    // Holds still in flight make up the queue of the next one
+    let first = liblogger::Logger::begin_lock_hold("lock_queue_test");
+    let second = liblogger::Logger::begin_lock_hold("lock_queue_test");
+    assert_eq!((first.queue_size(), second.queue_size()), (0, 1));
+    assert_eq!(second.queue_trend(), "growing");
+    drop(first);
+    drop(second);
+    let mut third = liblogger::Logger::begin_lock_hold("lock_queue_test");
+    assert_eq!((third.queue_size(), third.queue_trend()), (0, "shrinking"));
+    let held = third.release();
+    assert_eq!(third.release(), held);
+    
+    // Repeated failures trip the breaker shared by every caller of the service
+    let start = crate::log_file_len();
+    for _ in 0..2 {
+        assert!(test_service_communication_failing().is_err());
+    }
+    assert_eq!(liblogger::Logger::circuit_breaker_state("inventory_service"), liblogger::BreakerState::Closed);
+    assert!(crate::wait_for_log_line(start, "[ERROR]", "SERVICE_COMM_FAILURE: test_service_communication_failing - Service communication failed | Target: inventory_service | Retries: 1 | Protocol: HTTP | Circuit Breaker: CLOSED"));
+    let start = crate::log_file_len();
+    assert!(test_service_communication_failing().is_err());
+    assert!(crate::wait_for_log_line(start, "[ERROR]", "| Target: inventory_service | Retries: 2 | Protocol: HTTP | Circuit Breaker: OPEN |"));
+    assert_eq!(liblogger::Logger::circuit_breaker_state("inventory_service"), liblogger::BreakerState::Open);
+    assert_eq!(liblogger::Logger::circuit_breaker_failures("inventory_service"), 3);
+    assert_eq!(liblogger::Logger::circuit_breaker_state("user_service"), liblogger::BreakerState::Closed);
+    
+    // A success closes it again
+    let start = crate::log_file_len();
+    assert!(test_service_communication_recovering().is_ok());
+    assert!(crate::wait_for_log_line(start, "[INFO]", "SERVICE_COMM_SUCCESS: test_service_communication_recovering - Service communication successful | Target: inventory_service | Retries: 3 | Protocol: HTTP | Circuit Breaker: CLOSED"));
+    assert_eq!(liblogger::Logger::circuit_breaker_failures("inventory_service"), 0);
+    
+    // Leaving the outer transaction resets the depth
+    let start = crate::log_file_len();
+    assert!(test_transaction_rollback().is_err());
+    assert!(crate::wait_for_log_line(start, "[ERROR]", "TRANSACTION_ROLLBACK: test_transaction_rollback - Transaction rolled back"));
+    assert!(crate::wait_for_log_line(start, "[ERROR]", "| Depth: 1 |"));
     println!("Distributed systems tests completed\n");
 }
 
@@ -346,6 +586,22 @@ pub fn run_observability_tests() {
     let _ = test_custom_metrics_collection();
     let _ = test_health_check_monitoring();
     let _ = test_anomaly_detection();
+    
+    // Ten consistent ~100ms calls build the baseline without flagging anything
+    let start = crate::log_file_len();
+    for _ in 0..10 {
+        timed_batch_job(100).unwrap();
+    }
+    assert!(crate::wait_for_log_line(start, "[INFO]", "ANOMALY_BASELINE: timed_batch_job"));
+    let baseline = liblogger::Logger::duration_baseline(concat!(module_path!(), "::timed_batch_job"));
+    assert_eq!(baseline.count, 10);
+    assert!((100.0..150.0).contains(&baseline.mean_ms), "{:?}", baseline);
+    
+    // A 1s call is far outside it
+    timed_batch_job(1000).unwrap();
+    assert!(crate::wait_for_log_line(start, "[WARN]", "ANOMALY_DETECTED: timed_batch_job"));
+    let written = std::fs::read_to_string(crate::TEST_LOG_FILE).expect("Failed to read log file");
+    assert_eq!(written[start..].matches("ANOMALY_DETECTED: timed_batch_job").count(), 1);
     println!("Observability & correlation tests completed\n");
 }
 
@@ -12,39 +12,146 @@
  * consistent testing across synchronous and asynchronous logging paths.
  */
 
-use liblogger::{Logger, log_info, log_warn, log_error, log_debug};
+use liblogger::{Logger, ColorMode, ConsoleStream, LineEnding, LogConfig, LogFormat, SCHEMA_VERSION, OutputSpec, OverflowPolicy, PoisonPolicy, shutdown_logger, LogLevel, LogType, log_info, log_info_every, log_progress, log_warn, log_error, log_debug};
+use liblogger::{LogOutput, ContextFormatter, CacheMetricsProvider, CacheStats, DataQualityProvider, DataQualityReport, DiskInfo, DiskMetricsProvider, QueueMetricsProvider, QueueStats};
 use liblogger_macros::*;
 use rand::Rng;
+use std::collections::{BTreeMap, HashMap};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::sync::{Arc, Barrier, Mutex};
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 // Initialize helper functions required by the procedural macros
 initialize_logger_attributes!();
 
 // Import the asynchronous logging test module
 mod async_test;
-use async_test::test_async_logger;
+use async_test::{test_async_logger, test_async_macros, test_async_wrapped_macros, test_context_propagation};
 
 // Import the DevOps macro tests module  
 mod devops_macro_tests;
 use devops_macro_tests::run_all_devops_tests;
 
 fn main() {
+    // Child processes used by tests that need a fresh global logger
+    let args: Vec<String> = std::env::args().collect();
+    if args.len() == 3 && args[1] == CHILD_ARG {
+        run_child_scenario(&args[2]);
+        return;
+    }
+    
     // Initialize the logger from default config file
     // Replace the default initialization with a custom one
     initialize_custom_logger();
     
     log_info!("Application started with enhanced logging macros");
     
+    test_config_builder();
+    
+    test_config_formats();
+    test_config_validation();
+    test_enum_string_conversions();
+    test_level_filtering_matrix();
+    
+    test_init_without_config_file();
+    test_runtime_fallback();
+    test_init_from_env();
+    #[cfg(unix)]
+    test_unix_socket();
+    
+    test_console_colors();
+    test_console_stderr();
+    
+    test_init_for_tests();
+    
+    test_logger_guard();
+    
+    test_composite_output_thresholds();
+    
+    test_metrics_providers();
+    
+    test_custom_output();
+    test_log_batch();
+    test_drop_logging();
+    test_poison_policy();
+    
+    test_log_raw();
+    test_buffered_file_output();
+    test_flush_on_error();
+    test_quiet_scope();
+    test_json_schema_version();
+    test_structured_context();
+    test_sync_only();
+    test_http_extra_fields();
+    test_auto_debug();
+    test_context_formatter();
+    test_pre_epoch_clock();
+    test_cef_format();
+    test_cloud_formats();
+    test_include_location();
+    test_json_field_map();
+    test_channel_overflow();
+    test_ring_buffer();
+    test_null_output();
+    test_catch_panic_backtrace();
+    test_module_truncate_segments();
+    test_sequence_numbers();
+    test_scoped_fields();
+    test_dump_config();
+    
+    test_text_template();
+    
+    test_global_fields();
+    
+    test_double_init();
+    
+    test_async_batching();
+    
+    test_disk_info();
+    
     // Test various logging macros
     test_log_entry_exit();
     
+    test_log_entry_exit_options();
+    
+    test_instrument_all();
+    
+    test_log_entry_exit_question_mark();
+    
     if let Err(err) = test_log_errors() {
         log_error!(&format!("Error test function returned: {:?}", err));
     }
     
+    test_log_errors_count_metric();
+    
+    test_log_errors_source_chain();
+    test_log_result_option();
+    
+    test_format_duration();
+    
     test_measure_time();
+    test_measure_time_summary();
+    test_measure_time_histogram();
     
     test_log_args(123, "test-session".to_string(), 42);
     
+    test_log_args_truncated((0..10_000).collect());
+    
+    test_log_args_method();
+    
+    test_log_call();
+    
+    test_max_message_bytes();
+    
+    test_macro_context_arities();
+    test_log_every();
+    test_log_progress();
+    
+    test_sanitize_messages();
+    
+    test_set_threshold();
+    
     if let Err(err) = test_log_retries() {
         log_warn!(&format!("Retry function ultimately failed: {:?}", err));
     }
@@ -69,35 +176,65 @@ fn main() {
     if let Err(err) = test_health_check() {
         log_warn!(&format!("Health check failed: {:?}", err));
     }
+    test_health_report();
     
     test_throttle_log();
+    test_throttle_log_mutes_body();
+    
+    test_thread_output();
+    
+    test_child_logger();
     
     // Handle Result from dependency_latency_test
     if let Err(err) = dependency_latency_test() {
         log_warn!(&format!("Dependency latency test failed: {:?}", err));
     }
     
+    test_dependency_latency_summary();
+    
     test_log_response();
     
     test_log_concurrency();
     
+    test_log_concurrency_max();
+    
     test_trace_span();
+    test_trace_id_from_header();
     
     feature_flag_test();
     
+    test_feature_flag_provider();
+    
     metrics_counter_test();
     
     test_log_memory_usage();
     
     test_log_cpu_time();
     
+    test_log_file_descriptors();
+    
     test_version_tag();
     
-    test_request_context();
+    test_log_deprecated();
+    
+    // A custom env var overrides the compile-time version
+    std::env::set_var("LOGGER_TESTS_RELEASE", "2024.06-rc1");
+    assert_eq!(test_version_tag_custom_env().as_deref(), Some("2024.06-rc1"));
+    std::env::remove_var("LOGGER_TESTS_RELEASE");
+    assert_eq!(Logger::get_context_value("version"), None);
+    
+    test_request_context_merging();
+    
+    test_panic_hook();
+    
+    test_async_macros();
+    test_async_wrapped_macros();
     
     // Test the async logger
     test_async_logger();
     
+    test_context_propagation();
+    
     // Test all DevOps monitoring macros
     run_all_devops_tests();
     
@@ -112,6 +249,94 @@ fn test_log_entry_exit() {
     std::thread::sleep(std::time::Duration::from_millis(50));
 }
 
+#[log_entry_exit(level = "debug", timing = true)]
+fn timed_entry_exit() {
+    std::thread::sleep(std::time::Duration::from_millis(5));
+}
+
+// ENTRY/EXIT at the chosen level, with the elapsed time on EXIT
+fn test_log_entry_exit_options() {
+    let start = log_file_len();
+    timed_entry_exit();
+    
+    assert!(wait_for_log_line(start, "[DEBUG]", "ENTRY: timed_entry_exit"));
+    assert!(wait_for_log_line(start, "[DEBUG]", "EXIT: timed_entry_exit ("));
+    let written = std::fs::read_to_string(TEST_LOG_FILE).expect("Failed to read log file");
+    let exit = written[start..]
+        .lines()
+        .find(|line| line.contains("EXIT: timed_entry_exit"))
+        .expect("EXIT line not found");
+    assert!(exit.contains("[DEBUG]") && exit.ends_with("ms)"), "{}", exit);
+}
+
+struct Inventory {
+    items: Vec<String>,
+}
+
+#[instrument_all(skip = (len))]
+impl Inventory {
+    fn new() -> Self {
+        Inventory { items: Vec::new() }
+    }
+    
+    fn add(&mut self, item: &str) -> usize {
+        self.items.push(item.to_string());
+        self.items.len()
+    }
+    
+    fn len(&self) -> usize {
+        self.items.len()
+    }
+}
+
+// Every method but the skipped one logs ENTRY and a timed EXIT
+fn test_instrument_all() {
+    let start = log_file_len();
+    let mut inventory = Inventory::new();
+    assert_eq!(inventory.add("widget"), 1);
+    assert_eq!(inventory.len(), 1);
+    log_info!("instrument-all-marker done");
+    
+    assert!(wait_for_log_line(start, "[INFO]", "instrument-all-marker done"));
+    let written = std::fs::read_to_string(TEST_LOG_FILE).expect("Failed to read log file");
+    let lines: Vec<&str> = written[start..].lines().filter(|line| line.contains("Inventory::")).collect();
+    assert_eq!(lines.len(), 4, "{:?}", lines);
+    assert!(lines[0].ends_with("ENTRY: Inventory::new"), "{}", lines[0]);
+    assert!(lines[1].contains("EXIT: Inventory::new ("), "{}", lines[1]);
+    assert!(lines[2].ends_with("ENTRY: Inventory::add"), "{}", lines[2]);
+    assert!(lines[3].contains("EXIT: Inventory::add ("), "{}", lines[3]);
+}
+
+// Durations are rendered in the unit that keeps them readable
+fn test_format_duration() {
+    use std::time::Duration;
+    assert_eq!(format_duration(Duration::from_micros(500)), "500µs");
+    assert_eq!(format_duration(Duration::from_millis(1500)), "1.50s");
+    assert_eq!(format_duration(Duration::from_secs(90)), "1m 30s");
+    assert_eq!(format_duration(Duration::from_micros(12_500)), "12.50ms");
+}
+
+#[log_entry_exit]
+fn parse_port(raw: &str) -> Result<u16, std::num::ParseIntError> {
+    let port = raw.trim().parse::<u16>()?;
+    Ok(port)
+}
+
+// `?` keeps working and the early return still logs EXIT
+fn test_log_entry_exit_question_mark() {
+    let start = log_file_len();
+    assert_eq!(parse_port(" 8080 "), Ok(8080));
+    assert!(parse_port("not-a-port").is_err());
+    
+    log_info!("parse-port-test-done");
+    
+    // Everything logged before the marker is on disk once the marker is
+    assert!(wait_for_log_line(start, "[INFO]", "parse-port-test-done"));
+    let written = std::fs::read_to_string(TEST_LOG_FILE).expect("Failed to read log file");
+    assert_eq!(written[start..].matches("ENTRY: parse_port").count(), 2);
+    assert_eq!(written[start..].matches("EXIT: parse_port").count(), 2);
+}
+
 #[log_errors]
 fn test_log_errors() -> Result<(), String> {
     if rand::random::<bool>() {
@@ -121,17 +346,284 @@ fn test_log_errors() -> Result<(), String> {
     }
 }
 
+#[derive(Debug)]
+#[allow(dead_code)]
+enum ImportError {
+    MissingColumn(String),
+    Corrupt { row: usize },
+}
+
+#[log_errors(count_metric = "logger_tests_import_errors_total")]
+fn import_row(row: usize) -> Result<usize, ImportError> {
+    match row % 3 {
+        0 => Ok(row),
+        1 => Err(ImportError::MissingColumn("email".to_string())),
+        _ => Err(ImportError::Corrupt { row }),
+    }
+}
+
+// Each error variant gets its own labeled series in the counter
+fn test_log_errors_count_metric() {
+    // Rows 1 and 4 are MissingColumn, 2, 5 and 8 are Corrupt
+    for row in [0, 1, 2, 3, 4, 5, 8] {
+        let _ = import_row(row);
+    }
+    
+    let families = prometheus::gather();
+    let family = families.iter()
+        .find(|f| f.get_name() == "logger_tests_import_errors_total")
+        .expect("Error counter was not registered");
+    let mut series: Vec<(String, f64)> = family.get_metric().iter()
+        .map(|m| (m.get_label()[0].get_value().to_string(), m.get_counter().get_value()))
+        .collect();
+    series.sort_by(|a, b| a.0.cmp(&b.0));
+    assert_eq!(series, vec![("Corrupt".to_string(), 3.0), ("MissingColumn".to_string(), 2.0)]);
+}
+
+#[derive(Debug)]
+struct DiskFull;
+
+impl std::fmt::Display for DiskFull {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "no space left on /var/spool")
+    }
+}
+
+impl std::error::Error for DiskFull {}
+
+#[derive(Debug)]
+struct SaveError {
+    source: DiskFull,
+}
+
+impl std::fmt::Display for SaveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "could not save report")
+    }
+}
+
+impl std::error::Error for SaveError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+#[log_errors]
+fn save_report() -> Result<(), SaveError> {
+    Err(SaveError { source: DiskFull })
+}
+
+#[log_result(error_level = "warn")]
+fn save_report_result() -> Result<(), SaveError> {
+    Err(SaveError { source: DiskFull })
+}
+
+// Errors implementing std::error::Error are logged with their causes
+fn test_log_errors_source_chain() {
+    let start = log_file_len();
+    assert!(save_report().is_err());
+    assert!(save_report_result().is_err());
+    
+    assert!(wait_for_log_line(start, "[ERROR]", "save_report returned error: SaveError { source: DiskFull }; caused by: no space left on /var/spool"));
+    assert!(wait_for_log_line(start, "[WARN]", "save_report_result failed with error: SaveError { source: DiskFull }; caused by: no space left on /var/spool"));
+}
+
+#[log_result]
+fn find_order(id: u32) -> Option<String> {
+    (id == 7).then(|| format!("order-{}", id))
+}
+
+#[log_result(success_level = "debug", error_level = "warn")]
+fn find_customer(name: &str) -> Option<u32> {
+    (name == "ada").then_some(1)
+}
+
+#[log_result]
+fn count_items(items: &[u32]) -> usize {
+    items.len()
+}
+
+// Not a Result, despite the name
+#[derive(Debug)]
+struct QueryResult {
+    rows: usize,
+}
+
+#[log_result]
+fn run_query() -> QueryResult {
+    QueryResult { rows: 2 }
+}
+
+type AppResult<T> = Result<T, String>;
+
+#[log_result(result_alias = true, success_level = "warning")]
+fn load_profile(id: u32) -> AppResult<u32> {
+    if id == 0 { Err("no such profile".to_string()) } else { Ok(id) }
+}
+
+#[log_result(success_level = "Warning")]
+fn parse_flag(raw: &str) -> Result<bool, String> {
+    raw.parse().map_err(|_| format!("not a flag: {}", raw))
+}
+
+// Option results log Some/None, other non-Result types log completion
+fn test_log_result_option() {
+    let start = log_file_len();
+    assert_eq!(find_order(7), Some("order-7".to_string()));
+    assert_eq!(find_order(8), None);
+    assert_eq!(find_customer("ada"), Some(1));
+    assert_eq!(find_customer("bob"), None);
+    assert_eq!(count_items(&[1, 2, 3]), 3);
+
+    assert!(wait_for_log_line(start, "[INFO]", "find_order returned Some: \"order-7\""));
+    assert!(wait_for_log_line(start, "[ERROR]", "find_order returned None"));
+    assert!(wait_for_log_line(start, "[DEBUG]", "find_customer returned Some: 1"));
+    assert!(wait_for_log_line(start, "[WARN]", "find_customer returned None"));
+    assert!(wait_for_log_line(start, "[INFO]", "count_items completed"));
+    
+    // Only `Result` itself, or an alias marked with result_alias, is matched as a result
+    assert_eq!(run_query().rows, 2);
+    assert_eq!(load_profile(3), Ok(3));
+    assert!(load_profile(0).is_err());
+    assert!(wait_for_log_line(start, "[INFO]", "run_query completed"));
+    assert!(wait_for_log_line(start, "[WARN]", "load_profile succeeded with result: 3"));
+    assert!(wait_for_log_line(start, "[ERROR]", "load_profile failed with error: \"no such profile\""));
+    // Level names are matched the same way for Ok as for Some
+    assert_eq!(parse_flag("true"), Ok(true));
+    assert!(wait_for_log_line(start, "[WARN]", "parse_flag succeeded with result: true"));
+}
+
 #[measure_time]
 fn test_measure_time() {
     log_info!("Testing time measurement");
     std::thread::sleep(std::time::Duration::from_millis(100));
 }
 
+#[measure_time(summarize = true, summarize_every = 20)]
+fn hot_path(delay_ms: u64) -> u64 {
+    std::thread::sleep(std::time::Duration::from_millis(delay_ms));
+    delay_ms
+}
+
+// A summarized function logs percentiles for each window instead of every call
+fn test_measure_time_summary() {
+    let start = log_file_len();
+    for i in 0..20u64 {
+        // Mostly fast calls with a slow tail
+        let delay_ms = if i % 10 == 9 { 25 } else { 1 + i % 4 };
+        assert_eq!(hot_path(delay_ms), delay_ms);
+    }
+    
+    assert!(wait_for_log_line(start, "[INFO]", "hot_path latency over 20 calls"));
+    let written = std::fs::read_to_string(TEST_LOG_FILE).expect("Failed to read log file");
+    let line = written[start..]
+        .lines()
+        .find(|line| line.contains("hot_path latency over 20 calls"))
+        .unwrap();
+    let stat = |name: &str| -> f64 {
+        let value = line.split(&format!(" {}=", name)).nth(1).unwrap();
+        value.split("ms").next().unwrap().parse().unwrap()
+    };
+    let (min, p50, p95, p99, max) = (stat("min"), stat("p50"), stat("p95"), stat("p99"), stat("max"));
+    assert!(min >= 1.0 && min <= p50 && p50 <= p95 && p95 <= p99 && p99 <= max, "{}", line);
+    assert!(p50 < 10.0 && p99 >= 25.0, "{}", line);
+    assert!(!written[start..].contains("hot_path completed in"));
+}
+
+#[measure_time(histogram = "logger_tests_checkout_seconds")]
+fn checkout(delay_ms: u64) {
+    std::thread::sleep(std::time::Duration::from_millis(delay_ms));
+}
+
+fn checkout_histogram() -> (u64, f64) {
+    prometheus::gather().iter()
+        .find(|f| f.get_name() == "logger_tests_checkout_seconds")
+        .map(|f| {
+            let histogram = f.get_metric()[0].get_histogram();
+            (histogram.get_sample_count(), histogram.get_sample_sum())
+        })
+        .unwrap_or((0, 0.0))
+}
+
+// Each call is observed into the histogram as well as logged
+fn test_measure_time_histogram() {
+    for call in 1..=3 {
+        checkout(10);
+        let (count, sum) = checkout_histogram();
+        assert_eq!(count, call);
+        // At least 10ms per call, and nowhere near a second
+        assert!(sum >= 0.010 * call as f64 && sum < call as f64, "sum {}", sum);
+    }
+}
+
 #[log_args(user_id, session_id)]
 fn test_log_args(user_id: i32, session_id: String, other: i32) {
     log_info!(&format!("Function with logged args called, other={}", other));
 }
 
+#[log_args(values, max_len = 64)]
+fn test_log_args_truncated(values: Vec<u32>) {
+    log_info!(&format!("Function with truncated args called, len={}", values.len()));
+}
+
+#[derive(Debug)]
+struct Account {
+    id: u32,
+    owner: String,
+}
+
+impl Account {
+    #[log_args(self.id, amount)]
+    fn deposit(&mut self, amount: u32) -> u32 {
+        self.id += amount;
+        self.id
+    }
+    
+    #[log_args(self)]
+    fn describe(&self) -> &str {
+        &self.owner
+    }
+}
+
+#[log_call(sku, quantity)]
+fn reserve_stock(sku: &str, quantity: u32) -> Result<u32, String> {
+    log_info!("log-call-body");
+    if quantity > 0 { Ok(100 - quantity) } else { Err(format!("nothing to reserve for {}", sku)) }
+}
+
+// log_call logs the arguments before the body and the result after it
+fn test_log_call() {
+    let start = log_file_len();
+    assert_eq!(reserve_stock("A-1", 3), Ok(97));
+    assert!(reserve_stock("B-2", 0).is_err());
+    
+    assert!(wait_for_log_line(start, "[INFO]", "reserve_stock returned: Err(\"nothing to reserve for B-2\")"));
+    let written = std::fs::read_to_string(TEST_LOG_FILE).expect("Failed to read log file");
+    let lines: Vec<&str> = written[start..].lines()
+        .filter(|line| line.contains("reserve_stock") || line.contains("log-call-body"))
+        .map(|line| line.split("] ").last().unwrap_or(line))
+        .collect();
+    assert_eq!(lines, [
+        "Entering reserve_stock with args: sku = \"A-1\", quantity = 3",
+        "log-call-body",
+        "reserve_stock returned: Ok(97)",
+        "Entering reserve_stock with args: sku = \"B-2\", quantity = 0",
+        "log-call-body",
+        "reserve_stock returned: Err(\"nothing to reserve for B-2\")",
+    ]);
+}
+
+// log_args on methods logs fields of self and the receiver itself without moving it
+fn test_log_args_method() {
+    let start = log_file_len();
+    let mut account = Account { id: 7, owner: "ada".to_string() };
+    assert_eq!(account.deposit(5), 12);
+    assert_eq!(account.describe(), "ada");
+    
+    assert!(wait_for_log_line(start, "[INFO]", "Entering deposit with args: self.id = 7, amount = 5"));
+    assert!(wait_for_log_line(start, "[INFO]", "Entering describe with args: self = Account { id: 12, owner: \"ada\" }"));
+}
+
 #[log_retries(max_attempts=3)]
 fn test_log_retries() -> Result<(), String> {
     // Simulate random failures
@@ -154,6 +646,33 @@ fn test_catch_panic() -> Result<(), String> {
     Ok(())
 }
 
+#[log_trace_correlation(service_name = "checkout")]
+fn correlated_request() -> Result<(), String> {
+    Ok(())
+}
+
+// An upstream request ID is adopted when sane, otherwise a UUID is generated
+fn test_trace_id_from_header() {
+    let start = log_file_len();
+    assert_eq!(Logger::set_trace_id_from(Some(" req-7f3a.42 ")), "req-7f3a.42");
+    assert_eq!(traced_function().as_deref(), Some("req-7f3a.42"));
+    assert!(correlated_request().is_ok());
+    assert!(wait_for_log_line(start, "[INFO]", "TRACE_SPAN_SUCCESS: correlated_request - Span completed successfully | Service: checkout | Operation: correlated_request | Trace: req-7f3a.42 |"));
+    
+    let traceparent = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+    assert_eq!(Logger::set_trace_id_from(Some(traceparent)), traceparent);
+    
+    // Missing, empty, oversized or unsafe values get a fresh UUID
+    let long = "a".repeat(129);
+    for header in [None, Some(""), Some(long.as_str()), Some("id\nFAKE LINE"), Some("id with spaces")] {
+        let generated = Logger::set_trace_id_from(header);
+        assert!(uuid::Uuid::parse_str(&generated).is_ok(), "{:?} -> {}", header, generated);
+        assert_eq!(Logger::get_context_value("trace_id"), Some(generated));
+    }
+    assert_ne!(Logger::set_trace_id_from(None), Logger::set_trace_id_from(None));
+    Logger::remove_context_value("trace_id");
+}
+
 // Rename to avoid the "expected identifier" errors
 #[log_result]
 fn log_result_test() -> Result<String, String> {
@@ -191,6 +710,53 @@ fn test_health_check() -> Result<(), String> {
     }
 }
 
+#[health_check]
+fn check_database() -> Result<(), String> {
+    Ok(())
+}
+
+#[health_check]
+fn check_cache() -> Result<(), String> {
+    Err("connection refused".to_string())
+}
+
+#[health_check]
+fn check_queue(backlog: u32) -> Result<(), String> {
+    if backlog > 100 {
+        Err(format!("backlog of {}", backlog))
+    } else {
+        Ok(())
+    }
+}
+
+// Fresh registry: only the checks run here are reported
+fn run_health_report() {
+    assert!(Logger::health_report().is_healthy());
+    
+    assert!(check_database().is_ok());
+    assert!(check_cache().is_err());
+    assert!(check_queue(500).is_err());
+    let report = Logger::health_report();
+    assert_eq!((report.passed, report.failed), (1, 2));
+    assert!(!report.is_healthy());
+    let failing: Vec<(&str, Option<&str>)> = report.failing().iter()
+        .map(|check| (check.name.as_str(), check.error.as_deref()))
+        .collect();
+    assert_eq!(failing, [("check_cache", Some("\"connection refused\"")), ("check_queue", Some("\"backlog of 500\""))]);
+    
+    // Only the latest run of a check counts
+    assert!(check_queue(3).is_ok());
+    let report = Logger::health_report();
+    assert_eq!((report.passed, report.failed), (2, 1));
+    assert_eq!(report.checks.iter().map(|check| check.name.as_str()).collect::<Vec<_>>(), ["check_cache", "check_database", "check_queue"]);
+    assert_eq!(report.failing()[0].name, "check_cache");
+}
+
+// Logger::health_report() aggregates the latest result of each #[health_check]
+fn test_health_report() {
+    run_child("health-report");
+}
+
 #[throttle_log(rate=5)]
 fn test_throttle_log() {
     // Call multiple times to test throttling
@@ -199,6 +765,91 @@ fn test_throttle_log() {
     }
 }
 
+#[throttle_log(rate = 2)]
+fn throttled_worker(call: u32) {
+    log_warn!(&format!("throttle-body {}", call));
+}
+
+// Calls over the rate don't log from their body either
+fn test_throttle_log_mutes_body() {
+    let start = log_file_len();
+    for call in 0..5 {
+        throttled_worker(call);
+    }
+    log_info!("throttle-body done");
+    
+    assert!(wait_for_log_line(start, "[INFO]", "throttle-body done"));
+    let written = std::fs::read_to_string(TEST_LOG_FILE).expect("Failed to read log file");
+    let bodies: Vec<&str> = written[start..].lines()
+        .filter_map(|line| line.split("throttle-body ").nth(1))
+        .collect();
+    assert_eq!(bodies, ["0", "1", "done"]);
+}
+
+// A child logger tags its own lines and leaves the global logger's alone
+fn test_child_logger() {
+    let start = log_file_len();
+    let billing = Logger::child("billing");
+    assert_eq!(billing.prefix(), "billing");
+    billing.info("child-logger-marker invoice sent");
+    billing.clone().error("child-logger-marker charge failed");
+    billing.debug("child-logger-marker debug");
+    log_info!("child-logger-marker global");
+    
+    assert!(wait_for_log_line(start, "[INFO]", "child-logger-marker global"));
+    let written = std::fs::read_to_string(TEST_LOG_FILE).expect("Failed to read log file");
+    let lines: Vec<&str> = written[start..].lines().filter(|line| line.contains("child-logger-marker")).collect();
+    assert_eq!(lines.len(), 4, "{:?}", lines);
+    assert!(lines[0].contains("[INFO]") && lines[0].ends_with("[billing] child-logger-marker invoice sent"), "{}", lines[0]);
+    assert!(lines[1].contains("[ERROR]") && lines[1].ends_with("[billing] child-logger-marker charge failed"), "{}", lines[1]);
+    assert!(lines[0].contains("[main.rs:"), "{}", lines[0]);
+    assert!(lines[2].contains("[DEBUG]") && lines[2].ends_with("[billing] child-logger-marker debug"), "{}", lines[2]);
+    assert!(!lines[3].contains("[billing]"), "{}", lines[3]);
+}
+
+// Collects the lines written to it
+struct ThreadLines {
+    lines: Arc<Mutex<Vec<String>>>,
+}
+
+impl LogOutput for ThreadLines {
+    fn write_log(&mut self, formatted_message: &str) -> Result<(), String> {
+        self.lines.lock().unwrap().push(formatted_message.to_string());
+        Ok(())
+    }
+}
+
+// Each thread's override receives only that thread's lines
+fn test_thread_output() {
+    let start = log_file_len();
+    let workers: Vec<_> = ["tenant-a", "tenant-b"].into_iter().map(|tenant| {
+        let lines = Arc::new(Mutex::new(Vec::new()));
+        let thread_lines = Arc::clone(&lines);
+        let handle = std::thread::spawn(move || {
+            Logger::set_thread_output(Box::new(ThreadLines { lines: thread_lines }));
+            for i in 0..3 {
+                log_info!(&format!("thread-output-marker {} {}", tenant, i));
+            }
+        });
+        (tenant, lines, handle)
+    }).collect();
+    
+    for (tenant, lines, handle) in workers {
+        handle.join().unwrap();
+        let lines = lines.lock().unwrap();
+        assert_eq!(lines.len(), 3, "{:?}", lines);
+        assert!(lines.iter().all(|line| line.contains("[INFO]") && line.contains(&format!("thread-output-marker {} ", tenant))), "{:?}", lines);
+    }
+    
+    // Threads without an override, and cleared threads, still use the global output
+    Logger::set_thread_output(Box::new(ThreadLines { lines: Arc::new(Mutex::new(Vec::new())) }));
+    Logger::clear_thread_output();
+    log_info!("thread-output-marker main");
+    assert!(wait_for_log_line(start, "[INFO]", "thread-output-marker main"));
+    let written = std::fs::read_to_string(TEST_LOG_FILE).expect("Failed to read log file");
+    assert!(!written[start..].contains("thread-output-marker tenant"));
+}
+
 // Rename to avoid the "expected identifier" errors
 #[dependency_latency]
 fn dependency_latency_test() -> Result<(), String> {
@@ -212,6 +863,36 @@ fn dependency_latency_test() -> Result<(), String> {
     }
 }
 
+#[dependency_latency(target = "inventory", summarize_every = 10, per_call = false)]
+fn inventory_lookup(delay_ms: u64) -> Result<u64, String> {
+    std::thread::sleep(std::time::Duration::from_millis(delay_ms));
+    Ok(delay_ms)
+}
+
+// Every 10 calls a percentile summary is logged for the target
+fn test_dependency_latency_summary() {
+    let start = log_file_len();
+    for delay_ms in [1, 8, 2, 6, 3, 12, 4, 5, 20, 7] {
+        inventory_lookup(delay_ms).expect("Inventory lookup failed");
+    }
+    
+    assert!(wait_for_log_line(start, "[INFO]", "Dependency inventory latency over 10 calls"));
+    let written = std::fs::read_to_string(TEST_LOG_FILE).expect("Failed to read log file");
+    let line = written[start..]
+        .lines()
+        .find(|line| line.contains("Dependency inventory latency over 10 calls"))
+        .unwrap();
+    let stat = |name: &str| -> f64 {
+        let value = line.split(&format!("{}=", name)).nth(1).unwrap();
+        value.split("ms").next().unwrap().parse().unwrap()
+    };
+    let (min, p50, p95, p99, max) = (stat("min"), stat("p50"), stat("p95"), stat("p99"), stat("max"));
+    assert!(min >= 1.0 && min <= p50 && p50 <= p95 && p95 <= p99 && p99 <= max && max >= 20.0);
+    
+    // per_call = false suppresses the individual call lines
+    assert!(!written[start..].contains("Dependency call to inventory"));
+}
+
 #[log_response]
 fn test_log_response() -> String {
     "This response will be logged".to_string()
@@ -223,17 +904,60 @@ fn test_log_concurrency() {
     std::thread::sleep(std::time::Duration::from_millis(50));
 }
 
+#[log_concurrency(max = 2)]
+fn bounded_concurrency_worker(barrier: Arc<Barrier>) {
+    // Hold every worker inside the function at the same time
+    barrier.wait();
+    std::thread::sleep(std::time::Duration::from_millis(20));
+}
+
+// Exceeding max must raise a WARN and the peak is reported on exit
+fn test_log_concurrency_max() {
+    let start = log_file_len();
+    let workers = 4;
+    let barrier = Arc::new(Barrier::new(workers));
+    
+    let handles: Vec<_> = (0..workers)
+        .map(|_| {
+            let barrier = Arc::clone(&barrier);
+            std::thread::spawn(move || bounded_concurrency_worker(barrier))
+        })
+        .collect();
+    for handle in handles {
+        handle.join().expect("Concurrency worker panicked");
+    }
+    
+    assert!(wait_for_log_line(start, "[WARN]", "bounded_concurrency_worker concurrency 3 exceeds max 2"));
+    assert!(wait_for_log_line(start, "[INFO]", "bounded_concurrency_worker peak concurrency: 4"));
+}
+
 #[trace_span]
-fn test_trace_span() {
+fn traced_function() -> Option<String> {
     log_info!("Function with trace ID");
     
     // Nested function call should use same trace ID
-    nested_trace_function();
+    let nested_id = nested_trace_function();
+    let trace_id = Logger::get_context_value("trace_id");
+    assert_eq!(nested_id, trace_id);
+    trace_id
 }
 
 #[trace_span]
-fn nested_trace_function() {
+fn nested_trace_function() -> Option<String> {
     log_info!("Nested function with same trace ID");
+    Logger::get_context_value("trace_id")
+}
+
+// Logs inside a span carry its trace ID, which is cleared on exit
+fn test_trace_span() {
+    let start = log_file_len();
+    let trace_id = traced_function().expect("No trace ID inside span");
+    assert_eq!(Logger::get_context_value("trace_id"), None);
+    
+    let outer = format!("Function with trace ID | trace_id={}", trace_id);
+    let nested = format!("Nested function with same trace ID | trace_id={}", trace_id);
+    assert!(wait_for_log_line(start, "[INFO]", &outer));
+    assert!(wait_for_log_line(start, "[INFO]", &nested));
 }
 
 // Rename to avoid the "expected identifier" errors
@@ -242,6 +966,23 @@ fn feature_flag_test() {
     log_info!("Function with feature flag");
 }
 
+#[feature_flag(flag_name = "new_checkout")]
+fn feature_flag_provider_test() -> bool {
+    Logger::is_feature_enabled("new_checkout")
+}
+
+// Flags resolve through the registered provider
+fn test_feature_flag_provider() {
+    let mut flags = HashMap::new();
+    flags.insert("new_checkout".to_string(), true);
+    flags.insert("dark_mode".to_string(), false);
+    Logger::set_feature_flag_provider(Box::new(flags));
+    
+    assert!(feature_flag_provider_test(), "new_checkout should be enabled");
+    assert!(!Logger::is_feature_enabled("dark_mode"));
+    assert!(!Logger::is_feature_enabled("unregistered_flag"));
+}
+
 // Rename to avoid the "expected identifier" errors
 #[metrics_counter]
 fn metrics_counter_test() {
@@ -270,17 +1011,2465 @@ fn test_log_cpu_time() {
     log_debug!(&format!("Sum: {}", sum));
 }
 
+// Keeps 50 files open past the end of the call so the macro sees them
+#[log_file_descriptors(threshold = 100000)]
+fn open_fifty_files(path: &std::path::Path) -> Vec<std::fs::File> {
+    (0..50).map(|_| std::fs::File::open(path).expect("Failed to open file")).collect()
+}
+
+// log_file_descriptors reports the real change in open descriptors
+fn test_log_file_descriptors() {
+    let before = Logger::open_file_descriptors().expect("fd count unsupported on this platform");
+    let start = log_file_len();
+    let files = open_fifty_files(std::path::Path::new("app_config.toml"));
+    let after = Logger::open_file_descriptors().unwrap();
+    assert!((45..=55).contains(&(after - before)), "{} -> {}", before, after);
+    assert!(Logger::file_descriptor_limit().is_none_or(|limit| limit >= after));
+    
+    assert!(wait_for_log_line(start, "[INFO]", "FD_MONITOR: open_fifty_files"));
+    let log = std::fs::read_to_string(TEST_LOG_FILE).unwrap();
+    let line = log[start..].lines().find(|line| line.contains("FD_MONITOR: open_fifty_files")).unwrap();
+    let change: u64 = line.split("Change: +").nth(1)
+        .and_then(|rest| rest.split(' ').next())
+        .and_then(|n| n.parse().ok())
+        .expect("No fd change in log line");
+    assert!((45..=55).contains(&change), "{}", line);
+    drop(files);
+}
+
 #[version_tag]
 fn test_version_tag() {
     log_info!("Function with version tag");
+    
+    // With no BUILD_VERSION set the tag falls back to the package version
+    let version = Logger::get_context_value("version").unwrap_or_default();
+    assert_eq!(version.split('+').next(), Some(env!("CARGO_PKG_VERSION")));
+}
+
+#[version_tag(env = "LOGGER_TESTS_RELEASE")]
+fn test_version_tag_custom_env() -> Option<String> {
+    Logger::get_context_value("version")
+}
+
+#[log_deprecated(since = "1.2", use_instead = "parse_order_v2")]
+fn parse_order_v1(raw: &str) -> usize {
+    raw.len()
+}
+
+#[log_deprecated(since = "0.9", once = true)]
+fn legacy_checksum(data: &[u8]) -> u32 {
+    data.iter().map(|&b| b as u32).sum()
+}
+
+// Every call to a deprecated function is logged with its replacement and caller
+fn test_log_deprecated() {
+    let start = log_file_len();
+    assert_eq!(parse_order_v1("order-1"), 7);
+    let caller_line = line!() - 1;
+    for _ in 0..3 {
+        assert_eq!(legacy_checksum(&[1, 2, 3]), 6);
+    }
+    
+    let message = "DEPRECATED: parse_order_v1 called (deprecated since 1.2); use parse_order_v2 instead";
+    assert!(wait_for_log_line(start, "[WARN]", message));
+    assert!(wait_for_log_line(start, "[WARN]", "DEPRECATED: legacy_checksum called (deprecated since 0.9)"));
+    let written = std::fs::read_to_string(TEST_LOG_FILE).expect("Failed to read log file");
+    let line = written[start..].lines().find(|line| line.contains(message)).expect("deprecation line missing");
+    assert!(line.contains(&format!("since=1.2, use_instead=parse_order_v2, called_from=main.rs:{}", caller_line)), "{}", line);
+    // once = true logs the first call only
+    assert_eq!(written[start..].matches("DEPRECATED: legacy_checksum").count(), 1);
 }
 
 #[request_context]
 fn test_request_context() {
     log_info!("Function with request context");
+    // Dropped again when the request scope ends
+    Logger::set_context_value("handler_state", "busy");
+}
+
+// Request fields are merged into the caller's context, not substituted for it
+fn test_request_context_merging() {
+    // Populate the thread-local context read by request_context
+    Logger::set_context_value("tenant", "acme");
+    Logger::set_context_value("user_id", "12345");
+    Logger::set_context_value("request_id", "req-789");
+    
+    let start = log_file_len();
+    test_request_context();
+    
+    assert!(wait_for_log_line(start, "[INFO]", "Function with request context"));
+    let written = std::fs::read_to_string(TEST_LOG_FILE).expect("Failed to read log file");
+    for line in written[start..].lines().filter(|l| l.contains("test_request_context called") || l.contains("Function with request context")) {
+        for field in ["tenant=acme", "user_id=12345", "request_id=req-789"] {
+            assert_eq!(line.matches(field).count(), 1, "{}", line);
+        }
+    }
+    
+    // The previous context is restored on exit
+    assert_eq!(Logger::get_context_value("handler_state"), None);
+    assert_eq!(Logger::get_context_value("tenant").as_deref(), Some("acme"));
+    
+    Logger::remove_context_value("tenant");
+    Logger::remove_context_value("user_id");
+    Logger::remove_context_value("request_id");
+}
+
+// Unhandled panics should be logged with their location by the global hook
+fn test_panic_hook() {
+    let stdout = run_child("panic-hook");
+    let panic_line = stdout.lines()
+        .find_map(|line| line.strip_prefix("panic-hook-line "))
+        .expect("child did not report the panic line");
+    let logged: Vec<&str> = stdout.lines()
+        .filter(|line| line.contains("PANIC: Test panic for the global hook"))
+        .collect();
+    // Installed twice, the hook still logs the panic once
+    assert_eq!(logged.len(), 1, "{}", stdout);
+    assert!(logged[0].contains("[ERROR]"), "{}", logged[0]);
+    assert!(logged[0].contains(&format!("[main.rs:{}]", panic_line)), "{}", logged[0]);
+}
+
+// A thread panics after the hook was installed twice
+fn run_panic_hook() {
+    Logger::init_with_config(LogConfig::builder().color(ColorMode::Never).async_logging(false).build())
+        .expect("Failed to init console logger");
+    Logger::install_panic_hook();
+    Logger::install_panic_hook();
+    
+    // The panic is raised on the line after this one
+    let panic_line = line!() + 1;
+    let handle = std::thread::Builder::new().name("panic-hook-test".to_string()).spawn(|| panic!("Test panic for the global hook"))
+        .unwrap();
+    assert!(handle.join().is_err(), "Spawned thread should have panicked");
+    println!("panic-hook-line {}", panic_line);
 }
 
 // Custom logger initialization to ensure all logs are displayed
+// Builder setters apply and unset fields keep the documented defaults
+fn test_config_builder() {
+    let config = LogConfig::builder()
+        .threshold(LogLevel::Debug)
+        .file("builder.log")
+        .log_folder("logs")
+        .force_flush(true)
+        .build();
+    assert_eq!(config.log_type, LogType::File);
+    assert_eq!(config.threshold, LogLevel::Debug);
+    assert_eq!(config.file_path.as_deref(), Some("builder.log"));
+    assert_eq!(config.log_folder.as_deref(), Some("logs"));
+    assert!(config.force_flush);
+    assert!(config.async_logging);
+    assert_eq!(config.max_file_size_mb, None);
+    
+    let config = LogConfig::builder()
+        .http_endpoint("https://logs.example.com")
+        .http_timeout_seconds(3)
+        .build();
+    assert_eq!(config.log_type, LogType::Http);
+    assert_eq!(config.http_endpoint.as_deref(), Some("https://logs.example.com"));
+    assert_eq!(config.http_timeout_seconds, Some(3));
+    assert_eq!(config.threshold, LogLevel::Info);
+    
+    let defaults = LogConfig::builder().build();
+    assert_eq!(defaults.log_type, LogType::Console);
+    assert_eq!(defaults.threshold, LogLevel::Info);
+    assert!(defaults.async_logging);
+    assert!(!defaults.force_flush);
+}
+
+// Messages over max_message_bytes (64 KiB in app_config.toml) are truncated
+// Context may be omitted or given as a String, Some(String) or None
+fn test_macro_context_arities() {
+    let start = log_file_len();
+    let order = 42;
+    log_info!("context-arity-marker bare");
+    log_info!("context-arity-marker string", format!("order={}", order));
+    log_warn!("context-arity-marker some", Some("order=43".to_string()));
+    log_error!("context-arity-marker none", None);
+    
+    assert!(wait_for_log_line(start, "[ERROR]", "context-arity-marker none"));
+    let written = std::fs::read_to_string(TEST_LOG_FILE).expect("Failed to read log file");
+    let line = |marker: &str| written[start..].lines()
+        .find(|line| line.contains(marker))
+        .unwrap_or_else(|| panic!("{} not logged", marker))
+        .to_string();
+    assert!(!line("context-arity-marker bare").contains(" | "));
+    assert!(line("context-arity-marker string").ends_with(" | order=42"));
+    assert!(line("context-arity-marker some").ends_with(" | order=43"));
+    assert!(!line("context-arity-marker none").contains(" | "));
+}
+
+// A site logging in a tight loop is let through about once per interval
+fn test_log_every() {
+    let start = log_file_len();
+    let started = std::time::Instant::now();
+    let mut calls = 0;
+    while started.elapsed() < std::time::Duration::from_secs(1) {
+        log_info_every!(100, "log-every-marker", format!("call={}", calls));
+        calls += 1;
+    }
+    log_info!("log-every-marker done");
+    
+    assert!(wait_for_log_line(start, "[INFO]", "log-every-marker done"));
+    let written = std::fs::read_to_string(TEST_LOG_FILE).expect("Failed to read log file");
+    let emitted = written[start..].lines().filter(|line| line.contains("log-every-marker | call=")).count();
+    assert!((9..=11).contains(&emitted), "{} lines from {} calls", emitted, calls);
+    assert!(written[start..].contains("log-every-marker | call=0"));
+}
+
+// One progress run over 1000 items, logging every 100
+fn log_progress_run() {
+    let items: Vec<u32> = (0..1000).collect();
+    for (i, _item) in items.iter().enumerate() {
+        std::thread::sleep(std::time::Duration::from_micros(50));
+        log_progress!(i + 1, items.len(), 100, "progress-marker");
+    }
+}
+
+// log_progress! reports every `every` items with a growing percentage and an ETA
+fn test_log_progress() {
+    for _run in 0..2 {
+        let start = log_file_len();
+        log_progress_run();
+        assert!(wait_for_log_line(start, "[INFO]", "progress-marker: 1000/1000 (100.0%), done in "));
+        let written = std::fs::read_to_string(TEST_LOG_FILE).expect("Failed to read log file");
+        let lines: Vec<&str> = written[start..].lines().filter(|line| line.contains("progress-marker: ")).collect();
+        assert_eq!(lines.len(), 10, "{:#?}", lines);
+        
+        let percentages: Vec<f64> = lines.iter()
+            .map(|line| line.split('(').nth(1).and_then(|rest| rest.split('%').next())
+                .and_then(|percent| percent.parse().ok())
+                .unwrap_or_else(|| panic!("No percentage in {}", line)))
+            .collect();
+        assert_eq!(percentages, (1..=10).map(|i| i as f64 * 10.0).collect::<Vec<_>>());
+        assert!(lines[0].contains("progress-marker: 100/1000 (10.0%), elapsed ") && lines[0].contains(", ETA "), "{}", lines[0]);
+    }
+}
+
+fn test_max_message_bytes() {
+    let start = log_file_len();
+    let huge = format!("oversized-message-marker {}", "x".repeat(5 * 1024 * 1024));
+    log_info!(&huge);
+    
+    assert!(wait_for_log_line(start, "[INFO]", "oversized-message-marker"));
+    let written = std::fs::read_to_string(TEST_LOG_FILE).expect("Failed to read log file");
+    let line = written[start..]
+        .lines()
+        .find(|line| line.contains("oversized-message-marker"))
+        .expect("Truncated line not found");
+    let removed = huge.len() - 65536;
+    assert!(line.ends_with(&format!("...[truncated {} bytes]", removed)));
+    assert!(line.len() < 65536 + 256);
+}
+
+// Newlines and escape sequences in user data can't forge extra log lines
+fn test_sanitize_messages() {
+    let start = log_file_len();
+    log_info!(
+        "injection-marker user=mallory\n2024-01-01T00:00:00Z [ERROR] forged line\r\x1b[31m",
+        Some("note=a\nb".to_string())
+    );
+    
+    assert!(wait_for_log_line(start, "[INFO]", "injection-marker"));
+    let written = std::fs::read_to_string(TEST_LOG_FILE).expect("Failed to read log file");
+    let written = &written[start..];
+    assert!(!written.lines().any(|line| line.starts_with("2024-01-01T00:00:00Z [ERROR] forged line")));
+    let line = written.lines().find(|line| line.contains("injection-marker")).unwrap();
+    assert!(line.contains("user=mallory\\n2024-01-01T00:00:00Z [ERROR] forged line\\r\\u{1b}[31m"), "{}", line);
+    assert!(line.contains("note=a\\nb"), "{}", line);
+}
+
+// Raising the threshold hides debug lines until it is lowered again
+fn test_set_threshold() {
+    let start = log_file_len();
+    
+    Logger::set_threshold(LogLevel::Info);
+    assert_eq!(Logger::threshold(), LogLevel::Info);
+    assert!(!Logger::is_enabled(&LogLevel::Debug));
+    log_debug!("threshold-marker hidden at info");
+    
+    Logger::set_threshold(LogLevel::Debug);
+    assert_eq!(Logger::threshold(), LogLevel::Debug);
+    log_debug!("threshold-marker visible at debug");
+    
+    assert!(wait_for_log_line(start, "[DEBUG]", "threshold-marker visible at debug"));
+    let written = std::fs::read_to_string(TEST_LOG_FILE).expect("Failed to read log file");
+    assert!(!written[start..].contains("threshold-marker hidden at info"));
+}
+
+// LogLevel and LogType print and parse for CLI flags and error messages
+fn test_enum_string_conversions() {
+    for level in [LogLevel::Debug, LogLevel::Info, LogLevel::Warn, LogLevel::Error] {
+        assert_eq!(level.to_string().parse::<LogLevel>(), Ok(level.clone()));
+        assert_eq!(level.to_string().to_lowercase().parse::<LogLevel>(), Ok(level));
+    }
+    assert_eq!(LogLevel::Warn.to_string(), "WARN");
+    assert_eq!("Warning".parse::<LogLevel>(), Ok(LogLevel::Warn));
+    let err = "loud".parse::<LogLevel>().unwrap_err();
+    assert_eq!(err, "Unknown log level 'loud', expected debug, info, warn or error");
+    
+    for log_type in [LogType::Console, LogType::File, LogType::Http, LogType::UnixSocket, LogType::Null] {
+        assert_eq!(log_type.to_string().parse::<LogType>(), Ok(log_type.clone()));
+        assert_eq!(log_type.to_string().to_uppercase().parse::<LogType>(), Ok(log_type));
+    }
+    assert_eq!(format!("{}", LogType::Http), "http");
+    let err = "syslog".parse::<LogType>().unwrap_err();
+    assert_eq!(err, "Unknown log type 'syslog', expected console, file, http, unix_socket or null");
+}
+
+// Every (level, threshold) pair filters as expected, through should_log and the logger
+fn test_level_filtering_matrix() {
+    let levels = [LogLevel::Debug, LogLevel::Info, LogLevel::Warn, LogLevel::Error];
+    // expected[level][threshold]
+    let expected = [
+        [true, false, false, false],
+        [true, true, false, false],
+        [true, true, true, false],
+        [true, true, true, true],
+    ];
+    
+    let previous = Logger::threshold();
+    for (threshold_index, threshold) in levels.iter().enumerate() {
+        Logger::set_threshold(threshold.clone());
+        for (level_index, level) in levels.iter().enumerate() {
+            let passes = expected[level_index][threshold_index];
+            assert_eq!(level.should_log(threshold), passes, "{} at threshold {}", level, threshold);
+            assert_eq!(level >= threshold, passes, "{} >= {}", level, threshold);
+            assert_eq!(Logger::is_enabled(level), passes, "{} enabled at threshold {}", level, threshold);
+        }
+    }
+    Logger::set_threshold(previous);
+    
+    let severities: Vec<u8> = levels.iter().map(LogLevel::severity).collect();
+    assert_eq!(severities, [0, 1, 2, 3]);
+}
+
+// The same settings load identically from TOML, JSON and YAML
+fn test_config_formats() {
+    let dir = std::env::temp_dir().join(format!("liblogger-config-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).expect("Failed to create temp dir");
+    
+    let files = [
+        ("config.toml", "[logging]\ntype = \"file\"\nthreshold = \"warn\"\nfile_path = \"app.log\"\nlog_folder = \"logs\"\nforce_flush = true\n"),
+        ("config.json", r#"{"logging": {"type": "file", "threshold": "warn", "file_path": "app.log", "log_folder": "logs", "force_flush": true}}"#),
+        ("config.yaml", "logging:\n  type: file\n  threshold: warn\n  file_path: app.log\n  log_folder: logs\n  force_flush: true\n"),
+        // The same settings without the logging section
+        ("flat.toml", "type = \"file\"\nthreshold = \"warn\"\nfile_path = \"app.log\"\nlog_folder = \"logs\"\nforce_flush = true\n"),
+        ("flat.json", r#"{"type": "file", "threshold": "warn", "file_path": "app.log", "log_folder": "logs", "force_flush": true}"#),
+        ("flat.yaml", "type: file\nthreshold: warn\nfile_path: app.log\nlog_folder: logs\nforce_flush: true\n"),
+    ];
+    
+    let expected = LogConfig::builder()
+        .threshold(LogLevel::Warn)
+        .file("app.log")
+        .log_folder("logs")
+        .force_flush(true)
+        .build();
+    for (name, contents) in files {
+        let path = dir.join(name);
+        std::fs::write(&path, contents).expect("Failed to write config");
+        let parsed = LogConfig::from_file(path.to_str().unwrap())
+            .unwrap_or_else(|e| panic!("Failed to load {}: {}", name, e));
+        assert_eq!(parsed, expected, "{} parsed differently", name);
+    }
+    
+    let err = LogConfig::from_file("settings.ini").unwrap_err();
+    assert!(err.contains("Unsupported config format '.ini'"), "{}", err);
+    
+    // Neither layout parses: the section error is reported
+    let invalid = [
+        ("invalid.toml", "[logging]\ntype = \"file\"\nthreshold = \"loud\"\n", "Failed to parse config file"),
+        ("invalid.json", r#"{"logging": {"type": "file"}}"#, "Failed to parse JSON config file"),
+        ("invalid.yaml", "level: debug\n", "Failed to parse YAML config file"),
+    ];
+    for (name, contents, expected) in invalid {
+        let path = dir.join(name);
+        std::fs::write(&path, contents).expect("Failed to write config");
+        let err = LogConfig::from_file(path.to_str().unwrap()).unwrap_err();
+        assert!(err.contains(expected), "{}: {}", name, err);
+    }
+    
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+// validate() rejects settings that would only fail once logging starts
+fn test_config_validation() {
+    let valid = [
+        LogConfig::default(),
+        LogConfig::builder().file("app.log").max_file_size_mb(10).build(),
+        LogConfig::builder().http_endpoint("https://logs.example.com/ingest").http_timeout_seconds(5).build(),
+        LogConfig::builder().text_template("{level} {message}").build(),
+        LogConfig::builder().output(OutputSpec::console()).output(OutputSpec::file("app.log")).build(),
+    ];
+    for config in valid {
+        assert_eq!(config.validate(), Ok(()), "{:?}", config);
+    }
+    
+    let invalid = [
+        (LogConfig { log_type: LogType::Http, ..LogConfig::default() }, "type = \"http\" requires an http_endpoint"),
+        (LogConfig::builder().http_endpoint("  ").build(), "type = \"http\" requires an http_endpoint"),
+        (LogConfig::builder().http_endpoint("logs.example.com").build(), "http_endpoint 'logs.example.com' is not a valid URL"),
+        (LogConfig::builder().http_endpoint("ftp://logs.example.com").build(), "must use http or https, not ftp"),
+        (LogConfig::builder().http_endpoint("https://logs.example.com").http_timeout_seconds(0).build(), "http_timeout_seconds must be at least 1"),
+        (LogConfig { log_type: LogType::File, ..LogConfig::default() }, "type = \"file\" requires a file_path"),
+        (LogConfig::builder().file("").build(), "type = \"file\" requires a file_path"),
+        (LogConfig::builder().file("app.log").max_file_size_mb(0).build(), "max_file_size_mb must be at least 1"),
+        (LogConfig::builder().text_template("{level} {when}").build(), "Unknown placeholder '{when}'"),
+        (LogConfig::builder().format(LogFormat::Json).text_template("{message}").build(), "cannot be combined with the json format"),
+        (LogConfig::builder().output(OutputSpec::console()).output(OutputSpec::http("")).build(), "outputs[1]: type = \"http\""),
+        (LogConfig::builder().auto_debug_on_error_rate(0).build(), "auto_debug_on_error_rate must be at least 1"),
+        (LogConfig::builder().async_channel_capacity(0).build(), "async_channel_capacity must be at least 1"),
+        (LogConfig::builder().json_field_name("msg", "message").build(), "json_field_map: unknown field 'msg'"),
+        (LogConfig::builder().json_field_name("message", " ").build(), "json_field_map: 'message' needs a non-empty name"),
+        (LogConfig::builder().json_field_name("message", "level").build(), "'level' and 'message' would both be written as 'level'"),
+        (LogConfig::builder().auto_debug_on_error_rate(10).escalation_secs(0).build(), "escalation_secs must be at least 1"),
+    ];
+    for (config, expected) in invalid {
+        let err = config.validate().unwrap_err();
+        assert!(err.contains(expected), "expected '{}', got '{}'", expected, err);
+    }
+    
+    // Init reports the same message without touching the running logger
+    let err = Logger::init_with_config(LogConfig::builder().file("app.log").max_file_size_mb(0).build()).unwrap_err();
+    assert!(err.starts_with("max_file_size_mb must be at least 1"), "{}", err);
+}
+
+const CHILD_ARG: &str = "--child";
+
+// Body of a child process started by run_child
+fn run_child_scenario(scenario: &str) {
+    match scenario {
+        // Runs in a directory without app_config.toml
+        "init-default" => {
+            Logger::init().expect("init should fall back to defaults");
+            log_debug!("init-default-marker debug");
+            log_info!("init-default-marker info");
+        },
+        "panic-hook" => run_panic_hook(),
+        "color-always" | "color-never" | "color-auto" => {
+            let color = match scenario {
+                "color-always" => ColorMode::Always,
+                "color-never" => ColorMode::Never,
+                _ => ColorMode::Auto,
+            };
+            Logger::init_with_config(LogConfig::builder().color(color).build())
+                .expect("Failed to init console logger");
+            log_warn!("color-marker");
+            if color == ColorMode::Always {
+                // Only the level of a text line is colored, whatever comes before or after it
+                shutdown_logger().expect("Shutdown failed");
+                let builders = [
+                    LogConfig::builder().include_sequence(true),
+                    LogConfig::builder().format(LogFormat::Json),
+                    LogConfig::builder().text_template("[{level}] {message}"),
+                ];
+                for builder in builders {
+                    Logger::init_with_config(builder.color(ColorMode::Always).async_logging(false).build())
+                        .expect("Failed to init console logger");
+                    log_warn!("color-layout-marker [ERROR]");
+                    shutdown_logger().expect("Shutdown failed");
+                }
+            }
+        },
+        "testing-capture" => run_testing_capture(),
+        "guard-flush" => {
+            run_guard_flush();
+            return;
+        },
+        "metrics-providers" => run_metrics_providers(),
+        "custom-output" => run_custom_output(),
+        "log-batch" => run_log_batch(),
+        "drop-logging" => run_drop_logging(),
+        "health-report" => run_health_report(),
+        "poison-recover" | "poison-warn" => run_poison_policy(scenario),
+        // The lock stays poisoned, so shutting down would panic too
+        "poison-panic" => {
+            run_poison_policy(scenario);
+            return;
+        },
+        "init-from-env" => run_init_from_env(),
+        #[cfg(unix)]
+        "unix-socket" => {
+            run_unix_socket();
+            return;
+        },
+        "runtime-fallback" => {
+            Logger::fail_runtime_creation_for_tests();
+            Logger::init_with_config(LogConfig::builder().async_logging(true).build())
+                .expect("init should fall back to synchronous logging");
+            log_info!("runtime-fallback-marker");
+        },
+        "batching" => {
+            run_batching();
+            return;
+        },
+        "template-minimal" => {
+            let err = Logger::init_with_config(LogConfig::builder().text_template("{level} {severity}").build())
+                .unwrap_err();
+            assert!(err.contains("Unknown placeholder '{severity}'"), "{}", err);
+            
+            Logger::init_with_config(LogConfig::builder().text_template("{level}: {message}").build())
+                .expect("Failed to init console logger");
+            log_warn!("template-minimal-marker");
+        },
+        "template-reordered" => {
+            Logger::init_with_config(LogConfig::builder()
+                .text_template("{message} <{level}> {module} @ {timestamp}{{{context}}}")
+                .build())
+                .expect("Failed to init console logger");
+            log_info!("template-reordered-marker", Some("order=42".to_string()));
+        },
+        "global-fields" => {
+            Logger::init_with_config(LogConfig::builder().color(ColorMode::Never).async_logging(false).build())
+                .expect("Failed to init console logger");
+            Logger::set_global_fields(HashMap::from([("service".to_string(), "api".to_string())]));
+            
+            log_info!("global-fields-marker plain");
+            log_warn!("global-fields-marker explicit", Some("order=7".to_string()));
+            std::thread::spawn(|| log_error!("global-fields-marker other thread")).join().unwrap();
+            let scope = Logger::scoped_context_value("service", "api-override");
+            log_info!("global-fields-marker overridden");
+            drop(scope);
+            shutdown_logger().expect("Shutdown failed");
+            
+            // JSON records get them as top-level keys, which scoped fields replace
+            Logger::init_with_config(LogConfig::builder()
+                .format(LogFormat::Json)
+                .file("global.json")
+                .log_folder("logs")
+                .async_logging(false)
+                .build())
+                .expect("Failed to init JSON logger");
+            log_info!("global-json plain");
+            {
+                let _fields = Logger::with_fields(&[("service", "checkout")]);
+                log_info!("global-json scoped");
+            }
+            shutdown_logger().expect("Shutdown failed");
+            let file = std::fs::read_to_string("logs/global.json").expect("Failed to read global.json");
+            let record = |marker: &str| -> serde_json::Value {
+                let line = file.lines().find(|line| line.contains(marker)).unwrap_or_else(|| panic!("no {} record", marker));
+                serde_json::from_str(line).expect("record is not JSON")
+            };
+            let plain = record("global-json plain");
+            assert_eq!(plain["service"], "api", "{}", plain);
+            assert_eq!(plain["pid"], std::process::id().to_string(), "{}", plain);
+            assert!(plain.get("context").is_none(), "{}", plain);
+            let scoped = record("global-json scoped");
+            assert_eq!(scoped["service"], "checkout", "{}", scoped);
+            assert_eq!(scoped["pid"], std::process::id().to_string(), "{}", scoped);
+        },
+        "console-stderr" | "console-stderr-async" => {
+            Logger::init_with_config(LogConfig::builder()
+                .console_stream(ConsoleStream::Stderr)
+                .color(ColorMode::Never)
+                .async_logging(scenario == "console-stderr-async")
+                .build())
+                .expect("Failed to init console logger");
+            log_info!("console-stderr-marker");
+        },
+        "console-stderr-file" => {
+            Logger::init_with_config(LogConfig::builder()
+                .console_stream(ConsoleStream::Stderr)
+                .color(ColorMode::Never)
+                .log_folder("logs")
+                .output(OutputSpec::console())
+                .output(OutputSpec::file("stderr-mode.log"))
+                .build())
+                .expect("Failed to init console and file logger");
+            log_info!("console-stderr-marker");
+            // Reading a config file that isn't there warns on the same stream
+            assert_eq!(LogConfig::from_file("missing.toml"), Ok(LogConfig::default()));
+        },
+        "double-init" => {
+            run_double_init();
+            return;
+        },
+        "flush-on-error" => {
+            run_flush_on_error();
+        },
+        "buffered-file" => {
+            run_buffered_file();
+            return;
+        },
+        "quiet-scope" => {
+            Logger::init_with_config(LogConfig::builder().color(ColorMode::Never).async_logging(false).build())
+                .expect("Failed to init console logger");
+            log_info!("quiet-marker before");
+            {
+                let _quiet = Logger::quiet_scope();
+                log_info!("quiet-marker muted info");
+                log_error!("quiet-marker muted error");
+                std::thread::spawn(|| log_info!("quiet-marker other thread")).join().unwrap();
+            }
+            {
+                let _quiet = Logger::quiet_scope_above(LogLevel::Warn);
+                log_info!("quiet-marker below floor");
+                log_warn!("quiet-marker at floor");
+            }
+            log_info!("quiet-marker after");
+        },
+        "log-raw" => {
+            Logger::init_with_config(LogConfig::builder().color(ColorMode::Never).build())
+                .expect("Failed to init console logger");
+            Logger::log_raw(LogLevel::Info, RAW_LINE);
+            Logger::log_raw(LogLevel::Debug, "raw-below-threshold");
+        },
+        "json-format" => {
+            run_json_format();
+            return;
+        },
+        "structured-context" => {
+            run_structured_context();
+            return;
+        },
+        "sync-only" => {
+            run_sync_only();
+            return;
+        },
+        "http-extra-fields" => {
+            run_http_extra_fields();
+            return;
+        },
+        "auto-debug" => {
+            run_auto_debug();
+            return;
+        },
+        "context-formatter" => run_context_formatter(),
+        "pre-epoch-clock" => run_pre_epoch_clock(),
+        "cef-format" => run_cef_format(),
+        "gcp-json" => run_gcp_json(),
+        "cloudwatch-emf" => run_cloudwatch_emf(),
+        "no-location" => run_no_location(),
+        "json-field-map" => run_json_field_map(),
+        "channel-overflow" => {
+            run_channel_overflow();
+            return;
+        },
+        "ring-buffer" => {
+            Logger::init_with_config(LogConfig::builder()
+                .threshold(LogLevel::Warn)
+                .ring_buffer_size(100)
+                .async_logging(false)
+                .build())
+                .expect("Failed to init console logger");
+            for i in 0..1000 {
+                log_debug!(&format!("ring-marker {}", i));
+            }
+            
+            let recent = Logger::recent_logs();
+            assert_eq!(recent.len(), 100);
+            for (line, i) in recent.iter().zip(900..) {
+                assert!(line.contains("[DEBUG]") && line.ends_with(&format!("] ring-marker {}", i)), "{}", line);
+            }
+        },
+        "null-output" => {
+            let config = LogConfig { log_type: LogType::Null, file_path: Some("null.log".to_string()), ..LogConfig::default() };
+            Logger::init_with_config(config).expect("Failed to init null logger");
+            for i in 0..100 {
+                log_warn!(&format!("null-marker {}", i));
+            }
+            shutdown_logger().expect("Shutdown failed");
+            
+            let created: Vec<_> = std::fs::read_dir(".").unwrap().collect();
+            assert!(created.is_empty(), "null output created {:?}", created);
+            return;
+        },
+        "panic-backtrace-on" | "panic-backtrace-off" => {
+            // Read once by the first Backtrace::capture in the process
+            std::env::set_var("RUST_BACKTRACE", if scenario == "panic-backtrace-on" { "1" } else { "0" });
+            Logger::init_with_config(LogConfig::builder().color(ColorMode::Never).async_logging(false).build())
+                .expect("Failed to init console logger");
+            assert_eq!(panicking_with_backtrace(0), Err("Panic in panicking_with_backtrace: backtrace-marker".to_string()));
+            assert_eq!(panicking_with_backtrace(1), Ok(1));
+        },
+        "module-segments" => {
+            for segments in [0, 2] {
+                Logger::reconfigure(LogConfig::builder()
+                    .color(ColorMode::Never)
+                    .async_logging(false)
+                    .module_truncate_segments(segments)
+                    .build())
+                    .expect("Failed to init console logger");
+                billing::invoices::log_invoice(segments);
+            }
+        },
+        "composite-thresholds" => {
+            run_composite_thresholds();
+            return;
+        },
+        "sequence" => {
+            run_sequence();
+            return;
+        },
+        "scoped-fields" => {
+            run_scoped_fields();
+            return;
+        },
+        "dump-config" => {
+            run_dump_config();
+            return;
+        },
+        other => panic!("Unknown child scenario: {}", other),
+    }
+    let _ = shutdown_logger();
+}
+
+// Re-run this binary in a scratch directory and capture its stdout
+fn run_child(scenario: &str) -> String {
+    run_child_streams(scenario).0
+}
+
+// Like run_child, but capture both stdout and stderr
+fn run_child_streams(scenario: &str) -> (String, String) {
+    let dir = std::env::temp_dir().join(format!("liblogger-{}-{}", scenario, std::process::id()));
+    std::fs::create_dir_all(&dir).expect("Failed to create temp dir");
+    
+    let output = std::process::Command::new(std::env::current_exe().expect("No current exe"))
+        .args([CHILD_ARG, scenario])
+        .current_dir(&dir)
+        .output()
+        .expect("Failed to run child process");
+    let _ = std::fs::remove_dir_all(&dir);
+    
+    assert!(output.status.success(), "child {} failed: {}", scenario, String::from_utf8_lossy(&output.stderr));
+    (String::from_utf8_lossy(&output.stdout).into_owned(), String::from_utf8_lossy(&output.stderr).into_owned())
+}
+
+// Two "tests" on separate threads, as the test harness would run them
+fn run_testing_capture() {
+    let barrier = Arc::new(Barrier::new(2));
+    let handles: Vec<_> = ["alpha", "beta"]
+        .into_iter()
+        .map(|name| {
+            let barrier = Arc::clone(&barrier);
+            std::thread::spawn(move || {
+                let captured = Logger::init_for_tests();
+                barrier.wait();
+                log_debug!(&format!("{} debug line", name));
+                log_info!(&format!("{} info line", name));
+                
+                let lines = captured.lock().unwrap().clone();
+                assert_eq!(lines.len(), 2, "{} captured {:?}", name, lines);
+                assert!(lines.iter().all(|line| line.contains(name)));
+                
+                Logger::reset_for_tests();
+                assert!(captured.lock().unwrap().is_empty());
+                log_warn!(&format!("{} after reset", name));
+                assert_eq!(captured.lock().unwrap().len(), 1);
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().expect("Capture test thread failed");
+    }
+}
+
+const GUARD_FLUSH_LINES: usize = 500;
+
+// Log a burst through the async file writer and drop the guard straight away
+fn run_guard_flush() {
+    std::fs::write("app_config.toml", "[logging]\ntype = \"file\"\nthreshold = \"info\"\nfile_path = \"guard.log\"\nlog_folder = \"logs\"\nasync_logging = true\n")
+        .expect("Failed to write child config");
+    
+    let guard = Logger::init_with_guard().expect("Failed to init logger with guard");
+    for i in 0..GUARD_FLUSH_LINES {
+        log_info!(&format!("guard-flush line {}", i));
+    }
+    drop(guard);
+    
+    let contents = std::fs::read_to_string("logs/guard.log").expect("Failed to read guard.log");
+    println!("guard-flush lines written: {}", contents.matches("guard-flush line ").count());
+}
+
+// Dropping the LoggerGuard writes every queued line before returning
+fn test_logger_guard() {
+    let stdout = run_child("guard-flush");
+    assert!(stdout.contains(&format!("guard-flush lines written: {}", GUARD_FLUSH_LINES)), "{}", stdout);
+}
+
+// Minimal HTTP endpoint that records request bodies and answers 200
+fn start_http_sink() -> (String, Arc<Mutex<Vec<String>>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("Failed to bind HTTP sink");
+    let url = format!("http://{}/logs", listener.local_addr().unwrap());
+    let bodies = Arc::new(Mutex::new(Vec::new()));
+    
+    let received = Arc::clone(&bodies);
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let received = Arc::clone(&received);
+            std::thread::spawn(move || {
+                let mut writer = stream.try_clone().expect("Failed to clone stream");
+                let mut reader = BufReader::new(stream);
+                // One connection may carry several keep-alive requests
+                loop {
+                    let mut content_length = 0;
+                    let mut line = String::new();
+                    if reader.read_line(&mut line).unwrap_or(0) == 0 {
+                        return;
+                    }
+                    loop {
+                        line.clear();
+                        reader.read_line(&mut line).expect("Failed to read header");
+                        if line.trim().is_empty() {
+                            break;
+                        }
+                        if let Some((name, value)) = line.split_once(':') {
+                            if name.eq_ignore_ascii_case("content-length") {
+                                content_length = value.trim().parse().unwrap_or(0);
+                            }
+                        }
+                    }
+                    let mut body = vec![0; content_length];
+                    reader.read_exact(&mut body).expect("Failed to read body");
+                    received.lock().unwrap().push(String::from_utf8_lossy(&body).into_owned());
+                    writer.write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n").expect("Failed to respond");
+                }
+            });
+        }
+    });
+    
+    (url, bodies)
+}
+
+// Everything to a file, only WARN and above to HTTP
+fn run_composite_thresholds() {
+    let (url, bodies) = start_http_sink();
+    let config = LogConfig::builder()
+        .threshold(LogLevel::Debug)
+        .output(OutputSpec::file("composite.log").log_folder("logs").threshold(LogLevel::Debug))
+        .output(OutputSpec::http(&url).threshold(LogLevel::Warn))
+        .build();
+    Logger::init_with_config(config).expect("Failed to init composite logger");
+    let guard = Logger::guard();
+    
+    log_debug!("composite debug line");
+    log_info!("composite info line");
+    log_warn!("composite warn line");
+    log_error!("composite error line");
+    drop(guard);
+    
+    let file = std::fs::read_to_string("logs/composite.log").expect("Failed to read composite.log");
+    for level in ["debug", "info", "warn", "error"] {
+        assert!(file.contains(&format!("composite {} line", level)), "file is missing {}: {}", level, file);
+    }
+    
+    let bodies = bodies.lock().unwrap();
+    let sent: Vec<&String> = bodies.iter().filter(|body| body.contains("composite ")).collect();
+    assert_eq!(sent.len(), 2, "HTTP sink received {:?}", bodies);
+    assert!(sent[0].contains("[WARN]") && sent[0].contains("composite warn line"));
+    assert!(sent[1].contains("[ERROR]") && sent[1].contains("composite error line"));
+}
+
+// JSON records to a file and an HTTP endpoint
+fn run_json_format() {
+    let (url, bodies) = start_http_sink();
+    let config = LogConfig::builder()
+        .format(LogFormat::Json)
+        .output(OutputSpec::file("json.log").log_folder("logs"))
+        .output(OutputSpec::http(&url))
+        .build();
+    Logger::init_with_config(config).expect("Failed to init JSON logger");
+    log_warn!("json-marker \"quoted\"", Some("order=7".to_string()));
+    shutdown_logger().expect("Shutdown failed");
+    
+    let file = std::fs::read_to_string("logs/json.log").expect("Failed to read json.log");
+    let bodies = bodies.lock().unwrap();
+    let http = bodies.iter().find(|body| body.contains("json-marker")).expect("HTTP sink got no record");
+    let line = file.lines().find(|line| line.contains("json-marker")).expect("json.log has no record");
+    for record in [line, http.as_str()] {
+        let record: serde_json::Value = serde_json::from_str(record).expect("record is not JSON");
+        assert_eq!(record["v"], SCHEMA_VERSION, "{}", record);
+        assert_eq!(record["level"], "WARN");
+        assert_eq!(record["message"], "json-marker \"quoted\"");
+        assert_eq!(record["context"], "order=7");
+        assert!(record.get("seq").is_none(), "{}", record);
+    }
+}
+
+// Both JSON file lines and HTTP bodies carry the schema version
+fn test_json_schema_version() {
+    run_child("json-format");
+}
+
+// With structured_context, key/value context becomes a JSON object
+fn run_structured_context() {
+    let (url, bodies) = start_http_sink();
+    let config = LogConfig::builder()
+        .format(LogFormat::Json)
+        .structured_context(true)
+        .ring_buffer_size(10)
+        .output(OutputSpec::file("structured.log").log_folder("logs"))
+        .output(OutputSpec::http(&url))
+        .build();
+    Logger::init_with_config(config).expect("Failed to init JSON logger");
+    Logger::set_context_value("session_id", "abc");
+    log_info!("structured-pairs", "user_id=123".to_string());
+    log_info!("structured-free", "retrying after timeout".to_string());
+    Logger::remove_context_value("session_id");
+    shutdown_logger().expect("Shutdown failed");
+    
+    let file = std::fs::read_to_string("logs/structured.log").expect("Failed to read structured.log");
+    let bodies = bodies.lock().unwrap();
+    for marker in ["structured-pairs", "structured-free"] {
+        let http = bodies.iter().find(|body| body.contains(marker)).expect("HTTP sink got no record");
+        let line = file.lines().find(|line| line.contains(marker)).expect("structured.log has no record");
+        for record in [line, http.as_str()] {
+            let record: serde_json::Value = serde_json::from_str(record).expect("record is not JSON");
+            let context = &record["context"];
+            if marker == "structured-pairs" {
+                assert_eq!(context["user_id"], "123", "{}", record);
+                assert_eq!(context["session_id"], "abc", "{}", record);
+            } else {
+                // Free text can't be split into pairs and stays a string
+                assert_eq!(context, "retrying after timeout, session_id=abc", "{}", record);
+            }
+        }
+    }
+    // Text lines still render the flat string
+    let recent = Logger::recent_logs();
+    assert!(recent.iter().any(|line| line.ends_with("structured-pairs | user_id=123, session_id=abc")), "{:?}", recent);
+}
+
+// JSON records carry a context built from key/value pairs as an object
+fn test_structured_context() {
+    run_child("structured-context");
+}
+
+// Names of this process's threads, from /proc
+#[cfg(target_os = "linux")]
+fn thread_names() -> Vec<String> {
+    std::fs::read_dir("/proc/self/task")
+        .expect("Failed to list threads")
+        .filter_map(|task| std::fs::read_to_string(task.ok()?.path().join("comm")).ok())
+        .map(|name| name.trim().to_string())
+        .collect()
+}
+
+// How many of this process's file descriptors have `file_name` open, from /proc
+#[cfg(target_os = "linux")]
+fn open_handles(file_name: &str) -> usize {
+    std::fs::read_dir("/proc/self/fd")
+        .expect("Failed to list file descriptors")
+        .filter_map(|fd| std::fs::read_link(fd.ok()?.path()).ok())
+        .filter(|target| target.file_name().is_some_and(|name| name == file_name))
+        .count()
+}
+
+// A short-lived process with async_logging off
+fn run_sync_only() {
+    Logger::init_with_config(LogConfig::builder()
+        .format(LogFormat::Json)
+        .output(OutputSpec::file("cli.log").log_folder("logs"))
+        .async_logging(false)
+        .build())
+        .expect("Failed to init sync logger");
+    log_info!("sync-only-marker", "step=1".to_string());
+    log_debug!("sync-only-below-threshold");
+    
+    // On disk before shutdown, with no background task to drain
+    let file = std::fs::read_to_string("logs/cli.log").expect("Failed to read cli.log");
+    assert!(file.contains("sync-only-marker"), "{}", file);
+    assert!(!file.contains("sync-only-below-threshold"), "{}", file);
+    #[cfg(target_os = "linux")]
+    {
+        let threads = thread_names();
+        assert!(!threads.iter().any(|name| name.starts_with("tokio")), "{:?}", threads);
+    }
+    shutdown_logger().expect("Shutdown failed");
+}
+
+// async_logging = false writes synchronously without starting a runtime
+fn test_sync_only() {
+    run_child("sync-only");
+}
+
+// Constant fields posted with every HTTP record
+fn run_http_extra_fields() {
+    let (url, bodies) = start_http_sink();
+    let config = LogConfig::builder()
+        .format(LogFormat::Json)
+        .http_extra_field("tenant", "acme")
+        .http_extra_field("environment", "staging")
+        .http_extra_field("level", "bogus")
+        .output(OutputSpec::file("extra.log").log_folder("logs"))
+        .output(OutputSpec::http(&url))
+        .build();
+    Logger::init_with_config(config).expect("Failed to init JSON logger");
+    let fields = Logger::with_fields(&[("environment", "canary")]);
+    log_warn!("extra-fields-marker", "order=7".to_string());
+    drop(fields);
+    shutdown_logger().expect("Shutdown failed");
+    
+    let bodies = bodies.lock().unwrap();
+    let http = bodies.iter().find(|body| body.contains("extra-fields-marker")).expect("HTTP sink got no record");
+    let record: serde_json::Value = serde_json::from_str(http).expect("record is not JSON");
+    assert_eq!(record["tenant"], "acme", "{}", record);
+    assert_eq!(record["v"], SCHEMA_VERSION, "{}", record);
+    assert_eq!(record["message"], "extra-fields-marker", "{}", record);
+    assert_eq!(record["file"], "main.rs", "{}", record);
+    // Built-in keys and the record's own fields take precedence
+    assert_eq!(record["level"], "WARN", "{}", record);
+    assert_eq!(record["environment"], "canary", "{}", record);
+    
+    // Only posted bodies get the extra fields; the collision is reported once
+    let file = std::fs::read_to_string("logs/extra.log").expect("Failed to read extra.log");
+    let line = file.lines().find(|line| line.contains("extra-fields-marker")).expect("extra.log has no record");
+    assert!(!line.contains("tenant"), "{}", line);
+    assert_eq!(file.matches("http_extra_fields level ignored").count(), 1, "{}", file);
+}
+
+// http_extra_fields appear in POST bodies next to the standard fields
+fn test_http_extra_fields() {
+    let path = std::env::temp_dir().join(format!("liblogger-extra-{}.toml", std::process::id()));
+    std::fs::write(&path, "[logging]\ntype = \"http\"\nthreshold = \"info\"\nhttp_endpoint = \"https://logs.example.com\"\n\n[logging.http_extra_fields]\ntenant = \"acme\"\nenvironment = \"prod\"\n")
+        .expect("Failed to write config");
+    let parsed = LogConfig::from_file(path.to_str().unwrap()).expect("Failed to load extra fields config");
+    let _ = std::fs::remove_file(&path);
+    assert_eq!(parsed, LogConfig::builder()
+        .http_endpoint("https://logs.example.com")
+        .http_extra_field("environment", "prod")
+        .http_extra_field("tenant", "acme")
+        .build());
+    assert!(parsed.to_toml().unwrap().contains("[logging.http_extra_fields]"));
+    
+    run_child("http-extra-fields");
+}
+
+// A burst of errors records DEBUG lines for escalation_secs
+fn run_auto_debug() {
+    Logger::init_with_config(LogConfig::builder()
+        .output(OutputSpec::file("escalation.log").log_folder("logs"))
+        .threshold(LogLevel::Info)
+        .auto_debug_on_error_rate(20)
+        .escalation_secs(1)
+        .async_logging(false)
+        .build())
+        .expect("Failed to init file logger");
+    log_debug!("auto-debug-marker before");
+    assert!(!Logger::is_enabled(&LogLevel::Debug));
+    
+    for i in 0..30 {
+        log_error!(&format!("auto-debug-marker error {}", i));
+    }
+    assert!(Logger::is_enabled(&LogLevel::Debug));
+    log_debug!("auto-debug-marker during");
+    
+    // The configured threshold applies again once the escalation ends
+    std::thread::sleep(std::time::Duration::from_millis(1100));
+    assert!(!Logger::is_enabled(&LogLevel::Debug));
+    log_debug!("auto-debug-marker after");
+    shutdown_logger().expect("Shutdown failed");
+    
+    let file = std::fs::read_to_string("logs/escalation.log").expect("Failed to read escalation.log");
+    assert!(!file.contains("auto-debug-marker before"), "{}", file);
+    assert!(file.contains("auto-debug-marker during"), "{}", file);
+    assert!(!file.contains("auto-debug-marker after"), "{}", file);
+    assert_eq!(file.matches("More than 20 errors per second; recording every level for 1s").count(), 1, "{}", file);
+}
+
+// auto_debug_on_error_rate lowers the threshold for a while after an error burst
+fn test_auto_debug() {
+    let path = std::env::temp_dir().join(format!("liblogger-auto-debug-{}.toml", std::process::id()));
+    std::fs::write(&path, "[logging]\ntype = \"console\"\nthreshold = \"info\"\nauto_debug_on_error_rate = 50\n")
+        .expect("Failed to write config");
+    let parsed = LogConfig::from_file(path.to_str().unwrap()).expect("Failed to load escalation config");
+    let _ = std::fs::remove_file(&path);
+    assert_eq!(parsed.auto_debug_on_error_rate, Some(50));
+    assert_eq!(parsed.escalation_secs, 60);
+    
+    run_child("auto-debug");
+}
+
+// Renders context as logfmt, quoting values that contain spaces
+struct LogfmtFormatter;
+
+impl ContextFormatter for LogfmtFormatter {
+    fn format(&self, fields: &BTreeMap<String, String>) -> String {
+        fields.iter()
+            .map(|(k, v)| if v.contains(' ') { format!("{}=\"{}\"", k, v) } else { format!("{}={}", k, v) })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+// Lines with and without a custom context formatter
+fn run_context_formatter() {
+    Logger::init_with_config(LogConfig::builder().color(ColorMode::Never).async_logging(false).build())
+        .expect("Failed to init console logger");
+    Logger::set_context_formatter(Box::new(LogfmtFormatter));
+    let _scope = Logger::scoped_context_value("request_id", "req-1");
+    log_info!("context-formatter-marker pairs", "user=jane doe, order=7".to_string());
+    log_info!("context-formatter-marker free-form", "retrying after timeout".to_string());
+    
+    Logger::clear_context_formatter();
+    log_info!("context-formatter-marker default", "order=8".to_string());
+}
+
+// set_context_formatter changes how key/value context is rendered
+fn test_context_formatter() {
+    let stdout = run_child("context-formatter");
+    let lines: Vec<&str> = stdout.lines().filter(|line| line.contains("context-formatter-marker")).collect();
+    assert_eq!(lines.len(), 3, "{}", stdout);
+    assert!(lines[0].contains("order=7 request_id=req-1 user=\"jane doe\""), "{}", lines[0]);
+    // A context that isn't all pairs is left alone
+    assert!(lines[1].contains("retrying after timeout, request_id=req-1"), "{}", lines[1]);
+    assert!(lines[2].contains("order=8, request_id=req-1"), "{}", lines[2]);
+}
+
+// A system clock set a day before the Unix epoch
+fn pre_epoch() -> std::time::SystemTime {
+    std::time::UNIX_EPOCH - std::time::Duration::from_secs(86_400)
+}
+
+#[log_transaction(domain = "clock")]
+fn clock_skew_transaction() -> Result<u32, String> {
+    Ok(1)
+}
+
+#[log_trace_correlation(service_name = "clock")]
+fn clock_skew_trace() -> Result<u32, String> {
+    Ok(2)
+}
+
+#[log_compliance_check(domain = "clock")]
+fn clock_skew_compliance() -> Result<u32, String> {
+    Ok(3)
+}
+
+// Macros that derive IDs from the wall clock, with the clock before the epoch
+fn run_pre_epoch_clock() {
+    Logger::set_clock_for_tests(pre_epoch);
+    Logger::init_with_config(LogConfig::builder().color(ColorMode::Never).async_logging(false).build())
+        .expect("Failed to init console logger");
+    assert_eq!(Logger::unix_time(), std::time::Duration::ZERO);
+    
+    assert_eq!(clock_skew_transaction(), Ok(1));
+    assert_eq!(clock_skew_trace(), Ok(2));
+    assert_eq!(clock_skew_compliance(), Ok(3));
+    for call in 0..3 {
+        throttled_worker(call);
+    }
+    log_info!("pre-epoch-clock-marker done");
+}
+
+// A clock set before the Unix epoch doesn't make the attribute macros panic
+fn test_pre_epoch_clock() {
+    let stdout = run_child("pre-epoch-clock");
+    assert!(stdout.contains("pre-epoch-clock-marker done"), "{}", stdout);
+}
+
+// Split a CEF line into its seven header fields and its extension key/values
+fn parse_cef(line: &str) -> (Vec<String>, HashMap<String, String>) {
+    let mut header = Vec::new();
+    let mut field = String::new();
+    let mut chars = line.chars();
+    while header.len() < 7 {
+        match chars.next().expect("CEF header has fewer than 7 fields") {
+            '\\' => field.push(chars.next().expect("dangling escape in CEF header")),
+            '|' => header.push(std::mem::take(&mut field)),
+            c => field.push(c),
+        }
+    }
+    
+    // A value runs until the space before the next unescaped key=
+    let mut extension = HashMap::new();
+    let rest: Vec<char> = chars.collect();
+    let mut key = String::new();
+    let mut value = String::new();
+    let mut i = 0;
+    let mut in_value = false;
+    while i < rest.len() {
+        let c = rest[i];
+        if !in_value {
+            if c == '=' {
+                in_value = true;
+            } else {
+                key.push(c);
+            }
+        } else if c == '\\' {
+            i += 1;
+            value.push(match rest[i] {
+                'n' => '\n',
+                'r' => '\r',
+                c => c,
+            });
+        } else if c == ' ' && rest[i + 1..].iter().take_while(|c| c.is_ascii_alphanumeric()).count() > 0
+            && rest[i + 1..].iter().find(|c| !c.is_ascii_alphanumeric()) == Some(&'=') {
+            extension.insert(std::mem::take(&mut key), std::mem::take(&mut value));
+            in_value = false;
+        } else {
+            value.push(c);
+        }
+        i += 1;
+    }
+    extension.insert(key, value);
+    (header, extension)
+}
+
+// Every level written as CEF to a file
+fn run_cef_format() {
+    Logger::init_with_config(LogConfig::builder()
+        .format(LogFormat::Cef)
+        .cef_device("Acme", "Payments", "2.1|beta")
+        .threshold(LogLevel::Debug)
+        .output(OutputSpec::file("siem.log").log_folder("logs"))
+        .async_logging(false)
+        .build())
+        .expect("Failed to init CEF logger");
+    log_debug!("cef-marker debug");
+    log_info!("cef-marker info a=b | c\\d", "user=alice, order=7".to_string());
+    log_warn!("cef-marker warn");
+    log_error!("cef-marker error");
+    shutdown_logger().expect("Shutdown failed");
+    
+    let file = std::fs::read_to_string("logs/siem.log").expect("Failed to read siem.log");
+    let lines: Vec<&str> = file.lines().filter(|line| line.contains("cef-marker")).collect();
+    assert_eq!(lines.len(), 4, "{}", file);
+    for (line, (level, severity)) in lines.iter().zip([("DEBUG", "1"), ("INFO", "3"), ("WARN", "6"), ("ERROR", "8")]) {
+        let (header, extension) = parse_cef(line);
+        assert_eq!(header[..4], ["CEF:0", "Acme", "Payments", "2.1|beta"], "{}", line);
+        assert_eq!(header[4], level, "{}", line);
+        assert_eq!(header[6], severity, "{}", line);
+        assert_eq!(extension["msg"], header[5], "{}", line);
+        assert_eq!(extension["fname"], "main.rs", "{}", line);
+        assert_eq!(extension["cs1Label"], "module", "{}", line);
+        assert!(extension["cn1"].parse::<u32>().is_ok(), "{}", line);
+        assert!(extension["cs3"].contains('T'), "{}", line);
+    }
+    
+    // Separators in the message and context survive escaping
+    let (header, extension) = parse_cef(lines[1]);
+    assert_eq!(header[5], "cef-marker info a=b | c\\d");
+    assert_eq!(extension["msg"], "cef-marker info a=b | c\\d");
+    assert!(extension["cs2"].starts_with("user=alice, order=7"), "{}", lines[1]);
+    assert_eq!(extension["cs2Label"], "context");
+}
+
+// format = "cef" writes Common Event Format lines with the configured device header
+fn test_cef_format() {
+    assert_eq!("CEF".parse::<LogFormat>(), Ok(LogFormat::Cef));
+    let err = Logger::init_with_config(LogConfig::builder().format(LogFormat::Cef).text_template("{message}").build()).unwrap_err();
+    assert!(err.contains("cannot be combined with the cef format"), "{}", err);
+    
+    run_child("cef-format");
+}
+
+// Records of a cloud format written to `logs/<file_name>`, one per level
+fn write_cloud_records(format: LogFormat, file_name: &str, namespace: Option<&str>) -> Vec<serde_json::Value> {
+    let mut builder = LogConfig::builder()
+        .format(format)
+        .threshold(LogLevel::Debug)
+        .output(OutputSpec::file(file_name).log_folder("logs"))
+        .async_logging(false);
+    if let Some(namespace) = namespace {
+        builder = builder.cloudwatch_namespace(namespace);
+    }
+    Logger::init_with_config(builder.build()).expect("Failed to init cloud format logger");
+    let fields = Logger::with_fields(&[("severity", "shadow"), ("order_id", "7")]);
+    log_debug!("cloud-marker debug");
+    log_info!("cloud-marker info", "user=alice, order=7".to_string());
+    log_warn!("cloud-marker warn", "free-form context".to_string());
+    log_error!("cloud-marker error");
+    drop(fields);
+    shutdown_logger().expect("Shutdown failed");
+    
+    let file = std::fs::read_to_string(format!("logs/{}", file_name)).expect("Failed to read cloud log");
+    let records: Vec<serde_json::Value> = file.lines()
+        .filter(|line| line.contains("cloud-marker"))
+        .map(|line| serde_json::from_str(line).expect("cloud record is not JSON"))
+        .collect();
+    assert_eq!(records.len(), 4, "{}", file);
+    records
+}
+
+// Every level written as Google Cloud Logging entries
+fn run_gcp_json() {
+    let records = write_cloud_records(LogFormat::GcpJson, "gcp.log", None);
+    for (record, severity) in records.iter().zip(["DEBUG", "INFO", "WARNING", "ERROR"]) {
+        assert_eq!(record["severity"], severity, "{}", record);
+        assert!(record["message"].as_str().unwrap().starts_with("cloud-marker"), "{}", record);
+        assert!(record["time"].as_str().unwrap().ends_with('Z'), "{}", record);
+        let location = &record["logging.googleapis.com/sourceLocation"];
+        assert_eq!(location["file"], "main.rs", "{}", record);
+        assert!(location["line"].as_str().unwrap().parse::<u32>().unwrap() > 0, "{}", record);
+        // A scoped field can't replace the severity
+        assert_eq!(record["order_id"], "7", "{}", record);
+        assert!(record.get("level").is_none(), "{}", record);
+    }
+    
+    // Key/value context becomes labels, anything else stays text
+    assert_eq!(records[1]["logging.googleapis.com/labels"]["user"], "alice", "{}", records[1]);
+    assert!(records[1].get("context").is_none(), "{}", records[1]);
+    assert!(records[2]["context"].as_str().unwrap().starts_with("free-form context"), "{}", records[2]);
+    assert!(records[2].get("logging.googleapis.com/labels").is_none(), "{}", records[2]);
+}
+
+// Every level written as CloudWatch EMF records
+fn run_cloudwatch_emf() {
+    let records = write_cloud_records(LogFormat::CloudWatchEmf, "emf.log", Some("Payments"));
+    for (record, level) in records.iter().zip(["DEBUG", "INFO", "WARN", "ERROR"]) {
+        let directive = &record["_aws"]["CloudWatchMetrics"][0];
+        assert_eq!(directive["Namespace"], "Payments", "{}", record);
+        assert_eq!(directive["Dimensions"], serde_json::json!([["level"]]), "{}", record);
+        assert_eq!(directive["Metrics"][0]["Name"], "LogLines", "{}", record);
+        assert!(record["_aws"]["Timestamp"].as_i64().unwrap() > 1_600_000_000_000, "{}", record);
+        assert_eq!(record["level"], level, "{}", record);
+        assert_eq!(record["LogLines"], 1, "{}", record);
+        assert_eq!(record["file"], "main.rs", "{}", record);
+        assert_eq!(record["severity"], "shadow", "{}", record);
+    }
+}
+
+// Lines written with include_location = false in each format
+fn run_no_location() {
+    for (format, file_name) in [(LogFormat::Text, "text.log"), (LogFormat::Json, "json.log"), (LogFormat::Cef, "cef.log")] {
+        Logger::init_with_config(LogConfig::builder()
+            .format(format)
+            .include_location(false)
+            .output(OutputSpec::file(file_name).log_folder("logs"))
+            .async_logging(false)
+            .build())
+            .expect("Failed to init logger without locations");
+        log_info!("no-location-marker info");
+        log_warn!("no-location-marker warn", "user=alice".to_string());
+        shutdown_logger().expect("Shutdown failed");
+        
+        let file = std::fs::read_to_string(format!("logs/{}", file_name)).expect("Failed to read log");
+        let lines: Vec<&str> = file.lines().filter(|line| line.contains("no-location-marker")).collect();
+        assert_eq!(lines.len(), 2, "{}", file);
+        for line in &lines {
+            assert!(!line.contains("main.rs"), "{}", line);
+        }
+        match format {
+            LogFormat::Text => {
+                assert!(lines[0].ends_with(" [INFO] [logger_tests] no-location-marker info"), "{}", lines[0]);
+                assert!(lines[1].contains(" [WARN] [logger_tests] no-location-marker warn | user=alice"), "{}", lines[1]);
+            },
+            LogFormat::Json => {
+                for line in &lines {
+                    let record: serde_json::Value = serde_json::from_str(line).expect("JSON record");
+                    assert!(record.get("file").is_none() && record.get("line").is_none(), "{}", line);
+                    assert_eq!(record["module"], "logger_tests", "{}", line);
+                }
+            },
+            _ => {
+                for line in &lines {
+                    let (_, extension) = parse_cef(line);
+                    assert!(!extension.contains_key("fname") && !extension.contains_key("cn1"), "{}", line);
+                }
+            },
+        }
+    }
+}
+
+// include_location = false leaves file and line out of every format
+fn test_include_location() {
+    assert!(LogConfig::default().include_location);
+    let start = log_file_len();
+    log_info!("location-marker");
+    assert!(wait_for_log_line(start, "[INFO]", "[main.rs:"));
+    
+    run_child("no-location");
+}
+
+// format = "gcp_json" and "cloudwatch_emf" write the shapes the cloud log agents parse
+fn test_cloud_formats() {
+    assert_eq!("GCP_JSON".parse::<LogFormat>(), Ok(LogFormat::GcpJson));
+    assert_eq!("cloudwatch_emf".parse::<LogFormat>(), Ok(LogFormat::CloudWatchEmf));
+    let err = Logger::init_with_config(LogConfig::builder().format(LogFormat::GcpJson).text_template("{message}").build()).unwrap_err();
+    assert!(err.contains("cannot be combined with the gcp_json format"), "{}", err);
+    
+    run_child("gcp-json");
+    run_child("cloudwatch-emf");
+}
+
+// JSON records with renamed keys, written to a file and posted over HTTP
+fn run_json_field_map() {
+    let (url, bodies) = start_http_sink();
+    Logger::init_with_config(LogConfig::builder()
+        .format(LogFormat::Json)
+        .json_field_name("timestamp", "@timestamp")
+        .json_field_name("message", "msg")
+        .json_field_name("level", "severity")
+        .http_extra_field("tenant", "acme")
+        .output(OutputSpec::file("renamed.log").log_folder("logs"))
+        .output(OutputSpec::http(&url))
+        .build())
+        .expect("Failed to init JSON logger");
+    // A scoped field can't take a renamed key either
+    let fields = Logger::with_fields(&[("msg", "shadow"), ("order_id", "7")]);
+    log_warn!("field-map-marker", "user=alice".to_string());
+    drop(fields);
+    shutdown_logger().expect("Shutdown failed");
+    
+    let file = std::fs::read_to_string("logs/renamed.log").expect("Failed to read renamed.log");
+    let line = file.lines().find(|line| line.contains("field-map-marker")).expect("renamed.log has no record");
+    let bodies = bodies.lock().unwrap();
+    let http = bodies.iter().find(|body| body.contains("field-map-marker")).expect("HTTP sink got no record");
+    for record in [line, http.as_str()] {
+        let record: serde_json::Value = serde_json::from_str(record).expect("record is not JSON");
+        assert_eq!(record["msg"], "field-map-marker", "{}", record);
+        assert_eq!(record["severity"], "WARN", "{}", record);
+        assert!(record["@timestamp"].as_str().is_some_and(|ts| ts.contains('T')), "{}", record);
+        for old in ["timestamp", "message", "level"] {
+            assert!(record.get(old).is_none(), "{} still present in {}", old, record);
+        }
+        assert_eq!(record["file"], "main.rs", "{}", record);
+        assert_eq!(record["order_id"], "7", "{}", record);
+    }
+    // Files keep the built-in field order
+    assert!(line.starts_with(&format!("{{\"v\":{},\"@timestamp\":", SCHEMA_VERSION)), "{}", line);
+    assert!(http.contains("\"tenant\":\"acme\""), "{}", http);
+}
+
+// json_field_map renames built-in JSON keys
+fn test_json_field_map() {
+    let path = std::env::temp_dir().join(format!("liblogger-field-map-{}.toml", std::process::id()));
+    std::fs::write(&path, "[logging]\ntype = \"console\"\nthreshold = \"info\"\nformat = \"json\"\n\n[logging.json_field_map]\ntimestamp = \"@timestamp\"\nmessage = \"msg\"\n")
+        .expect("Failed to write config");
+    let parsed = LogConfig::from_file(path.to_str().unwrap()).expect("Failed to load field map config");
+    let _ = std::fs::remove_file(&path);
+    assert_eq!(parsed, LogConfig::builder()
+        .format(LogFormat::Json)
+        .json_field_name("message", "msg")
+        .json_field_name("timestamp", "@timestamp")
+        .build());
+    
+    run_child("json-field-map");
+}
+
+// A flood through a tiny channel that drops what doesn't fit
+fn run_channel_overflow() {
+    Logger::init_with_config(LogConfig::builder()
+        .output(OutputSpec::file("flood.log").log_folder("logs"))
+        .async_channel_capacity(4)
+        .overflow_policy(OverflowPolicy::DropNewest)
+        .build())
+        .expect("Failed to init file logger");
+    const LINES: u64 = 20_000;
+    for i in 0..LINES {
+        log_info!(&format!("overflow-marker {}", i));
+    }
+    let dropped = Logger::dropped_lines();
+    shutdown_logger().expect("Shutdown failed");
+    
+    // Every line was either queued and written or dropped, never written by the caller
+    let file = std::fs::read_to_string("logs/flood.log").expect("Failed to read flood.log");
+    let written = file.lines().filter(|line| line.contains("overflow-marker")).count() as u64;
+    assert!(dropped > 0, "nothing dropped");
+    assert_eq!(written + dropped, LINES, "written {}, dropped {}", written, dropped);
+    assert!(file.contains("log messages were dropped due to backpressure"), "no drop warning");
+}
+
+// async_channel_capacity sizes the channel and overflow_policy decides what a full one does
+fn test_channel_overflow() {
+    let path = std::env::temp_dir().join(format!("liblogger-overflow-{}.toml", std::process::id()));
+    std::fs::write(&path, "[logging]\ntype = \"console\"\nthreshold = \"info\"\nasync_channel_capacity = 4096\noverflow_policy = \"drop_newest\"\n")
+        .expect("Failed to write config");
+    let parsed = LogConfig::from_file(path.to_str().unwrap()).expect("Failed to load overflow config");
+    let _ = std::fs::remove_file(&path);
+    assert_eq!(parsed, LogConfig::builder().async_channel_capacity(4096).overflow_policy(OverflowPolicy::DropNewest).build());
+    assert_eq!(LogConfig::default().async_channel_capacity, 100);
+    
+    run_child("channel-overflow");
+}
+
+// Each output of a composite configuration applies its own threshold
+fn test_composite_output_thresholds() {
+    let path = std::env::temp_dir().join(format!("liblogger-outputs-{}.toml", std::process::id()));
+    std::fs::write(&path, "[logging]\ntype = \"file\"\nthreshold = \"debug\"\n\n[[logging.outputs]]\ntype = \"file\"\nfile_path = \"app.log\"\n\n[[logging.outputs]]\ntype = \"http\"\nhttp_endpoint = \"https://logs.example.com\"\nthreshold = \"warn\"\n")
+        .expect("Failed to write config");
+    let parsed = LogConfig::from_file(path.to_str().unwrap()).expect("Failed to load outputs config");
+    let _ = std::fs::remove_file(&path);
+    assert_eq!(parsed.outputs, vec![
+        OutputSpec::file("app.log"),
+        OutputSpec::http("https://logs.example.com").threshold(LogLevel::Warn),
+    ]);
+    
+    run_child("composite-thresholds");
+}
+
+// Sequence numbers follow call order in text and JSON lines
+fn run_sequence() {
+    Logger::init_with_config(LogConfig::builder()
+        .file("sequence.log")
+        .log_folder("logs")
+        .include_sequence(true)
+        .build())
+        .expect("Failed to init sequenced logger");
+    for i in 0..1000 {
+        log_info!(&format!("seq-marker {}", i));
+    }
+    shutdown_logger().expect("Shutdown failed");
+    
+    // Lines that overflowed the channel may be written early, so match them by index
+    let file = std::fs::read_to_string("logs/sequence.log").expect("Failed to read sequence.log");
+    let mut numbered: Vec<(u64, u64)> = file.lines()
+        .filter_map(|line| {
+            let index = line.split("seq-marker ").nth(1)?.parse().ok()?;
+            let seq = line.split("[#").nth(1)?.split(']').next()?.parse().ok()?;
+            Some((index, seq))
+        })
+        .collect();
+    numbered.sort();
+    assert_eq!(numbered.len(), 1000, "{}", file);
+    let first = numbered[0].1;
+    for (index, seq) in numbered {
+        assert_eq!(seq, first + index, "line {} out of sequence", index);
+    }
+    
+    Logger::init_with_config(LogConfig::builder()
+        .format(LogFormat::Json)
+        .file("sequence.json")
+        .log_folder("logs")
+        .include_sequence(true)
+        .build())
+        .expect("Failed to init JSON logger");
+    log_info!("seq-json-marker");
+    log_warn!("seq-json-marker");
+    shutdown_logger().expect("Shutdown failed");
+    
+    let file = std::fs::read_to_string("logs/sequence.json").expect("Failed to read sequence.json");
+    let seqs: Vec<u64> = file.lines()
+        .filter(|line| line.contains("seq-json-marker"))
+        .map(|line| serde_json::from_str::<serde_json::Value>(line).expect("record is not JSON")["seq"].as_u64().expect("record has no seq"))
+        .collect();
+    assert_eq!(seqs, [first + 1000, first + 1001]);
+}
+
+// include_sequence numbers lines contiguously in the order they were logged
+fn test_sequence_numbers() {
+    run_child("sequence");
+}
+
+// Scoped fields become top-level keys of JSON records
+fn run_scoped_fields() {
+    Logger::init_with_config(LogConfig::builder()
+        .format(LogFormat::Json)
+        .file("fields.json")
+        .log_folder("logs")
+        .build())
+        .expect("Failed to init JSON logger");
+    {
+        let _order = Logger::with_fields(&[("order_id", "123"), ("region", "eu")]);
+        log_info!("fields-json outer");
+        {
+            let _item = Logger::with_fields(&[("item_id", "9"), ("region", "us"), ("message", "spoofed")]);
+            log_info!("fields-json inner");
+        }
+        std::thread::spawn(|| log_info!("fields-json other thread")).join().unwrap();
+    }
+    log_info!("fields-json none");
+    shutdown_logger().expect("Shutdown failed");
+    
+    let file = std::fs::read_to_string("logs/fields.json").expect("Failed to read fields.json");
+    let record = |marker: &str| -> serde_json::Value {
+        let line = file.lines().find(|line| line.contains(marker)).unwrap_or_else(|| panic!("no {} record", marker));
+        serde_json::from_str(line).expect("record is not JSON")
+    };
+    let outer = record("fields-json outer");
+    assert_eq!((outer["order_id"].as_str(), outer["region"].as_str()), (Some("123"), Some("eu")), "{}", outer);
+    assert!(outer.get("item_id").is_none(), "{}", outer);
+    
+    let inner = record("fields-json inner");
+    assert_eq!(inner["order_id"], "123", "{}", inner);
+    assert_eq!(inner["item_id"], "9", "{}", inner);
+    assert_eq!(inner["region"], "us", "{}", inner);
+    // A field can't replace the record's own keys
+    assert_eq!(inner["message"], "fields-json inner", "{}", inner);
+    // Written once, as keys, not repeated in the context
+    assert!(outer.get("context").is_none() && inner.get("context").is_none(), "{}", inner);
+    
+    for marker in ["fields-json other thread", "fields-json none"] {
+        let record = record(marker);
+        assert!(record.get("order_id").is_none(), "{}", record);
+    }
+}
+
+// Scoped fields reach text contexts, merge when nested and end with their guard
+fn test_scoped_fields() {
+    let start = log_file_len();
+    {
+        let _order = Logger::with_fields(&[("order_id", "123")]);
+        log_info!("fields-text outer");
+        {
+            let _item = Logger::with_fields(&[("item_id", "9")]);
+            log_warn!("fields-text inner", "step=pack".to_string());
+        }
+        log_info!("fields-text after inner");
+    }
+    log_info!("fields-text none");
+    
+    assert!(wait_for_log_line(start, "[INFO]", "fields-text outer | order_id=123"));
+    assert!(wait_for_log_line(start, "[WARN]", "fields-text inner | step=pack, item_id=9, order_id=123"));
+    assert!(wait_for_log_line(start, "[INFO]", "fields-text none"));
+    let written = std::fs::read_to_string(TEST_LOG_FILE).expect("Failed to read log file");
+    let after = written[start..].lines().find(|line| line.contains("fields-text after inner")).expect("missing line");
+    assert!(after.ends_with("fields-text after inner | order_id=123"), "{}", after);
+    let none = written[start..].lines().find(|line| line.contains("fields-text none")).expect("missing line");
+    assert!(!none.contains("order_id"), "{}", none);
+    
+    run_child("scoped-fields");
+}
+
+// The dumped config reflects runtime changes and loads back unchanged
+fn run_dump_config() {
+    std::fs::write("app.toml", "[logging]\ntype = \"file\"\nthreshold = \"debug\"\nfile_path = \"dump.log\"\nlog_folder = \"logs\"\nformat = \"json\"\nline_ending = \"crlf\"\ncolor = \"never\"\ninclude_sequence = true\n\n[[logging.outputs]]\ntype = \"file\"\n\n[[logging.outputs]]\ntype = \"console\"\nthreshold = \"warn\"\n")
+        .expect("Failed to write config");
+    let loaded = LogConfig::from_file("app.toml").expect("Failed to load config");
+    Logger::init_with_config(loaded.clone()).expect("Failed to init logger");
+    
+    // Overridden after loading
+    Logger::set_threshold(LogLevel::Error);
+    let dumped = Logger::dump_config();
+    shutdown_logger().expect("Shutdown failed");
+    assert!(dumped.starts_with("[logging]\n"), "{}", dumped);
+    assert!(dumped.contains("type = \"file\"\n"), "{}", dumped);
+    assert!(dumped.contains("threshold = \"error\"\n"), "{}", dumped);
+    
+    std::fs::write("dumped.toml", &dumped).expect("Failed to write dumped config");
+    let reloaded = LogConfig::from_file("dumped.toml").expect("Failed to reload dumped config");
+    assert_eq!(reloaded, LogConfig { threshold: LogLevel::Error, ..loaded }, "{}", dumped);
+}
+
+// Logger::dump_config writes the effective config as loadable TOML
+fn test_dump_config() {
+    run_child("dump-config");
+}
+
+// Metrics provider returning known values, in place of a real system probe
+struct FixedMetrics;
+
+impl DiskMetricsProvider for FixedMetrics {
+    fn disk_info(&self) -> DiskInfo {
+        DiskInfo {
+            total_space_gb: 1000.0,
+            used_space_gb: 935.0,
+            available_space_gb: 65.0,
+            used_percentage: 93.5,
+            filesystem: "xfs".to_string(),
+            mount_point: "/data".to_string(),
+        }
+    }
+}
+
+impl CacheMetricsProvider for FixedMetrics {
+    fn cache_stats(&self, cache_name: &str) -> CacheStats {
+        let hits = if cache_name == "sessions" { 42 } else { 99 };
+        CacheStats {
+            hits,
+            misses: 100 - hits,
+            hit_ratio_percentage: hits as f64,
+            total_entries: 500,
+            memory_usage_mb: 12.0,
+            evictions: 0,
+        }
+    }
+}
+
+impl QueueMetricsProvider for FixedMetrics {
+    fn queue_stats(&self, _queue_name: &str) -> QueueStats {
+        QueueStats {
+            depth: 7,
+            processing_rate: 3.0,
+            avg_processing_time_ms: 20.0,
+            total_processed: 1200,
+            failed_messages: 1,
+        }
+    }
+}
+
+impl DataQualityProvider for FixedMetrics {
+    fn data_quality(&self, _domain: &str) -> DataQualityReport {
+        DataQualityReport {
+            quality_score_percentage: 90.0,
+            records_processed: 4000,
+            total_validation_rules: 20,
+            failed_rules: vec!["order_total_positive".to_string(), "ship_date_after_order".to_string()],
+            data_completeness: 99.0,
+            data_accuracy: 91.0,
+        }
+    }
+}
+
+#[log_disk_usage(threshold = 90)]
+fn provider_disk_check() {}
+
+#[log_data_quality(domain = "orders", threshold = 95, count_metric = "logger_tests_failed_quality_rules_total")]
+fn provider_quality_check() {}
+
+#[log_cache_hit_ratio(cache_name = "sessions", threshold = 50)]
+fn provider_cache_check() {}
+
+#[log_queue_depth(queue_name = "jobs", threshold = 100)]
+fn provider_queue_check() {}
+
+// Queue whose processed count only grows while drain_queue runs
+static DRAINED: AtomicUsize = AtomicUsize::new(0);
+
+struct DrainingQueue;
+
+impl QueueMetricsProvider for DrainingQueue {
+    fn queue_stats(&self, _queue_name: &str) -> QueueStats {
+        QueueStats {
+            depth: 10,
+            processing_rate: 0.0,
+            avg_processing_time_ms: 4.0,
+            total_processed: DRAINED.load(Ordering::SeqCst) as u64,
+            failed_messages: 0,
+        }
+    }
+}
+
+// Processes 50 messages in ~200ms, about 250/sec
+#[log_queue_depth(queue_name = "drain", threshold = 100)]
+fn drain_queue() {
+    std::thread::sleep(std::time::Duration::from_millis(200));
+    DRAINED.fetch_add(50, Ordering::SeqCst);
+}
+
+fn run_metrics_providers() {
+    let captured = Logger::init_for_tests();
+    Logger::set_disk_provider(Box::new(FixedMetrics));
+    Logger::set_cache_provider(Box::new(FixedMetrics));
+    Logger::set_queue_provider(Box::new(FixedMetrics));
+    Logger::set_data_quality_provider(Box::new(FixedMetrics));
+    
+    provider_disk_check();
+    provider_cache_check();
+    provider_queue_check();
+    provider_quality_check();
+    provider_quality_check();
+    
+    let lines = captured.lock().unwrap().join("\n");
+    assert!(lines.contains("DISK_ALERT: provider_disk_check - High disk usage detected: 93%"), "{}", lines);
+    assert!(lines.contains("Total: 1000.0GB, Used: 935.0GB, Available: 65.0GB, FS: xfs"), "{}", lines);
+    assert!(lines.contains("CACHE_ALERT: provider_cache_check - Low cache hit ratio: 42.0%"), "{}", lines);
+    assert!(lines.contains("QUEUE_MONITOR: provider_queue_check - Queue depth: 7 | Queue: jobs"), "{}", lines);
+    assert!(lines.contains("DATA_QUALITY_ALERT: provider_quality_check - Low data quality score: 90.0% (threshold: 95%) | Domain: orders"), "{}", lines);
+    assert!(lines.contains("| Records: 4000 | Rules: 18/20 |"), "{}", lines);
+    
+    // Each failing rule is counted under its own label
+    let families = prometheus::gather();
+    let family = families.iter()
+        .find(|f| f.get_name() == "logger_tests_failed_quality_rules_total")
+        .expect("Failed rule counter was not registered");
+    let mut series: Vec<(String, f64)> = family.get_metric().iter()
+        .map(|m| (m.get_label()[0].get_value().to_string(), m.get_counter().get_value()))
+        .collect();
+    series.sort_by(|a, b| a.0.cmp(&b.0));
+    assert_eq!(series, vec![("order_total_positive".to_string(), 2.0), ("ship_date_after_order".to_string(), 2.0)]);
+    
+    // The logged rate comes from the processed count before and after the call
+    Logger::set_queue_provider(Box::new(DrainingQueue));
+    captured.lock().unwrap().clear();
+    drain_queue();
+    let lines = captured.lock().unwrap().join("\n");
+    let rate: f64 = lines.split("Processing: ").nth(1)
+        .and_then(|rest| rest.split("/sec").next())
+        .and_then(|rate| rate.parse().ok())
+        .unwrap_or_else(|| panic!("No processing rate in {}", lines));
+    assert!((200.0..=250.0).contains(&rate), "{}", lines);
+}
+
+// Without a provider, disk usage is measured for the log folder's filesystem
+fn test_disk_info() {
+    let info = Logger::disk_info();
+    assert!(info.total_space_gb > 0.0, "{:?}", info);
+    assert!(info.available_space_gb > 0.0, "{:?}", info);
+    assert!((0.0..=100.0).contains(&info.used_percentage), "{:?}", info);
+    assert!(info.used_space_gb <= info.total_space_gb, "{:?}", info);
+}
+
+// The DevOps macros report what the registered metrics providers return
+fn test_metrics_providers() {
+    run_child("metrics-providers");
+}
+
+const BATCH_LINES: usize = 10_000;
+
+// Write syscalls made by this process so far (Linux only)
+fn write_syscalls() -> Option<u64> {
+    let io = std::fs::read_to_string("/proc/self/io").ok()?;
+    io.lines().find_map(|line| line.strip_prefix("syscw:")?.trim().parse().ok())
+}
+
+// Log the same burst with per-line writes and with coalesced writes
+fn run_batching() {
+    let mut syscalls = Vec::new();
+    for (mode, flush_interval_ms) in [("per-line", 0), ("coalesced", 100)] {
+        let file_name = format!("batch-{}.log", mode);
+        let config = LogConfig::builder()
+            .file(&file_name)
+            .log_folder("logs")
+            .force_flush(true)
+            .flush_interval_ms(flush_interval_ms)
+            .build();
+        Logger::init_with_config(config).expect("Failed to init batching logger");
+        
+        let writes_before = write_syscalls();
+        let started = std::time::Instant::now();
+        for i in 0..BATCH_LINES {
+            log_info!(&format!("batch-line {}", i));
+            // Stay under the channel capacity so no line falls back to a sync write
+            if i % 64 == 63 {
+                std::thread::sleep(std::time::Duration::from_millis(1));
+            }
+        }
+        shutdown_logger().expect("Failed to shut down batching logger");
+        let elapsed = started.elapsed();
+        let writes = writes_before.zip(write_syscalls()).map(|(before, after)| after - before);
+        
+        let contents = std::fs::read_to_string(format!("logs/{}", file_name)).expect("Failed to read batch log");
+        let written: Vec<usize> = contents.lines()
+            .filter_map(|line| line.split("batch-line ").nth(1))
+            .map(|n| n.parse().expect("Bad line number"))
+            .collect();
+        assert_eq!(written, (0..BATCH_LINES).collect::<Vec<_>>(), "{} lines missing or out of order", mode);
+        println!("batching {}: {} lines in {:?}, {:?} write syscalls", mode, BATCH_LINES, elapsed, writes);
+        syscalls.push(writes);
+    }
+    
+    if let [Some(per_line), Some(coalesced)] = syscalls[..] {
+        assert!(coalesced < per_line, "coalescing did not reduce writes: {} vs {}", coalesced, per_line);
+    }
+}
+
+// Coalesced writes keep every line, in order, and are reported next to per-line writes
+fn test_async_batching() {
+    let stdout = run_child("batching");
+    for line in stdout.lines().filter(|line| line.starts_with("batching ")) {
+        println!("{}", line);
+    }
+    assert!(stdout.contains("batching per-line:") && stdout.contains("batching coalesced:"));
+}
+
+// Custom sink that only counts the lines it receives
+struct CountingOutput {
+    lines: Arc<AtomicUsize>,
+}
+
+impl LogOutput for CountingOutput {
+    fn write_log(&mut self, formatted_message: &str) -> Result<(), String> {
+        assert!(formatted_message.contains("custom-output-marker"), "{}", formatted_message);
+        self.lines.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+}
+
+fn run_custom_output() {
+    let lines = Arc::new(AtomicUsize::new(0));
+    Logger::init_with_output(Box::new(CountingOutput { lines: Arc::clone(&lines) }), LogLevel::Info);
+    
+    log_debug!("custom-output-marker below threshold");
+    for i in 0..3 {
+        log_info!(&format!("custom-output-marker {}", i));
+    }
+    log_warn!("custom-output-marker warn");
+    assert_eq!(lines.load(Ordering::SeqCst), 4);
+}
+
+// Logger::init_with_output installs an arbitrary LogOutput
+fn test_custom_output() {
+    run_child("custom-output");
+}
+
+fn run_log_batch() {
+    let lines = Arc::new(Mutex::new(Vec::new()));
+    Logger::init_with_output(Box::new(ThreadLines { lines: Arc::clone(&lines) }), LogLevel::Info);
+    
+    // Another thread logs as fast as it can while the batch is written
+    let done = Arc::new(std::sync::atomic::AtomicBool::new(false));
+    let noise = {
+        let done = Arc::clone(&done);
+        std::thread::spawn(move || {
+            while !done.load(Ordering::SeqCst) {
+                log_info!("batch-noise");
+            }
+        })
+    };
+    std::thread::sleep(std::time::Duration::from_millis(20));
+    
+    let messages: Vec<String> = (0..100).map(|i| format!("batch-line {}", i)).collect();
+    let messages: Vec<&str> = messages.iter().map(String::as_str).collect();
+    Logger::log_batch(LogLevel::Warn, &messages);
+    done.store(true, Ordering::SeqCst);
+    noise.join().unwrap();
+    
+    // One lock acquisition: the batch is contiguous, in order, with nothing in between
+    let lines = lines.lock().unwrap();
+    let first = lines.iter().position(|line| line.contains("batch-line 0")).expect("batch was not written");
+    for (i, line) in lines[first..first + 100].iter().enumerate() {
+        assert!(line.contains("[WARN]") && line.ends_with(&format!("batch-line {}", i)), "{}", line);
+        assert!(line.contains("[main.rs:") && line.contains("[main]"), "{}", line);
+    }
+    assert!(lines.iter().any(|line| line.contains("batch-noise")));
+}
+
+// A batch is written in order without other threads' lines interleaved
+fn test_log_batch() {
+    run_child("log-batch");
+}
+
+// Logs from its Drop, like a guard released inside a logging callback
+struct LogsOnDrop(&'static str);
+
+impl Drop for LogsOnDrop {
+    fn drop(&mut self) {
+        log_warn!(&format!("drop-logging-marker {}", self.0));
+    }
+}
+
+// Collects lines, runs a LogsOnDrop while writing the trigger line, and
+// logs again while writing a line marked to echo
+struct DropLoggingOutput {
+    lines: Arc<Mutex<Vec<String>>>,
+    _on_replace: Option<LogsOnDrop>,
+}
+
+impl LogOutput for DropLoggingOutput {
+    fn write_log(&mut self, formatted_message: &str) -> Result<(), String> {
+        self.lines.lock().unwrap().push(formatted_message.to_string());
+        if formatted_message.contains("drop-logging-marker trigger") {
+            drop(LogsOnDrop("from write"));
+        }
+        if formatted_message.contains("echo-marker") {
+            log_info!("echo-marker nested");
+        }
+        Ok(())
+    }
+}
+
+fn run_drop_logging() {
+    // A deadlock would hang the parent forever
+    std::thread::spawn(|| {
+        std::thread::sleep(std::time::Duration::from_secs(10));
+        eprintln!("drop-logging scenario hung");
+        std::process::exit(1);
+    });
+    
+    let first = Arc::new(Mutex::new(Vec::new()));
+    Logger::init_with_output(Box::new(DropLoggingOutput {
+        lines: Arc::clone(&first),
+        _on_replace: Some(LogsOnDrop("replaced output")),
+    }), LogLevel::Info);
+    
+    drop(LogsOnDrop("plain"));
+    log_info!("drop-logging-marker trigger");
+    let markers = |lines: &Mutex<Vec<String>>| -> Vec<String> {
+        lines.lock().unwrap().iter()
+            .map(|line| line.split("] ").last().unwrap().to_string())
+            .collect()
+    };
+    // The nested line is written once the outer write has finished
+    assert_eq!(markers(&first), ["drop-logging-marker plain", "drop-logging-marker trigger", "drop-logging-marker from write"]);
+    
+    // The old output is dropped under the lock; its line goes to the new one
+    let second = Arc::new(Mutex::new(Vec::new()));
+    Logger::init_with_output(Box::new(DropLoggingOutput { lines: Arc::clone(&second), _on_replace: None }), LogLevel::Info);
+    assert_eq!(markers(&second), ["drop-logging-marker replaced output"]);
+    
+    // A line logged while writing a deferred line goes to stderr instead of looping
+    log_info!("echo-marker outer");
+    assert_eq!(markers(&second)[1..], ["echo-marker outer", "echo-marker nested"]);
+}
+
+// Drop impls and outputs may log while the logger is busy without deadlocking
+fn test_drop_logging() {
+    let (_, stderr) = run_child_streams("drop-logging");
+    assert_eq!(stderr.lines().filter(|line| line.contains("[INFO]") && line.ends_with("echo-marker nested")).count(), 1, "{}", stderr);
+}
+
+// Panics while writing a marked line, poisoning the logger lock
+struct PanicsOnWrite;
+
+impl LogOutput for PanicsOnWrite {
+    fn write_log(&mut self, formatted_message: &str) -> Result<(), String> {
+        if formatted_message.contains("poison-marker") {
+            panic!("output failed");
+        }
+        Ok(())
+    }
+}
+
+// Poison the logger lock from a thread whose output panics
+fn poison_logger() {
+    let result = std::thread::spawn(|| {
+        Logger::set_thread_output(Box::new(PanicsOnWrite));
+        log_info!("poison-marker");
+    }).join();
+    assert!(result.is_err());
+}
+
+fn run_poison_policy(scenario: &str) {
+    let policy = match scenario {
+        "poison-recover" => PoisonPolicy::Recover,
+        "poison-warn" => PoisonPolicy::Warn,
+        _ => PoisonPolicy::Panic,
+    };
+    Logger::init_with_config(LogConfig::builder().on_poison(policy).async_logging(false).color(ColorMode::Never).build())
+        .expect("Failed to init console logger");
+    
+    if policy == PoisonPolicy::Panic {
+        // Every later line panics, since the lock is never recovered
+        poison_logger();
+        for _ in 0..2 {
+            let err = std::panic::catch_unwind(|| log_info!("poison-after")).unwrap_err();
+            let message = err.downcast_ref::<&str>().copied().unwrap_or_default();
+            assert!(message.contains("on_poison = \"panic\""), "{}", message);
+        }
+        assert_eq!(Logger::get_poison_recovery_count(), 0);
+        return;
+    }
+    
+    for (round, marker) in ["poison-after first", "poison-after second"].into_iter().enumerate() {
+        poison_logger();
+        log_info!(marker);
+        assert_eq!(Logger::get_poison_recovery_count(), round as u64 + 1);
+    }
+}
+
+// on_poison decides whether a poisoned logger lock is recovered quietly, with a WARN each time, or panics
+fn test_poison_policy() {
+    let recovered = |line: &str| line.contains("[WARN]") && line.contains("Recovered the logger lock");
+    for (scenario, warnings) in [("poison-recover", 1), ("poison-warn", 2)] {
+        let stdout = run_child(scenario);
+        assert_eq!(stdout.lines().filter(|line| recovered(line)).count(), warnings, "{}", stdout);
+        let lines: Vec<&str> = stdout.lines().filter(|line| recovered(line) || line.contains("poison-after")).collect();
+        assert!(recovered(lines[0]) && lines[1].ends_with("poison-after first"), "{}", stdout);
+        assert!(lines.last().unwrap().ends_with("poison-after second"), "{}", stdout);
+    }
+    
+    let stdout = run_child("poison-panic");
+    assert!(!stdout.contains("poison-after"), "{}", stdout);
+}
+
+// Parallel init_for_tests callers capture independently
+fn test_init_for_tests() {
+    run_child("testing-capture");
+}
+
+// Logger::init without a config file logs to the console at INFO
+fn test_init_without_config_file() {
+    let stdout = run_child("init-default");
+    assert!(stdout.contains("'app_config.toml' not found"));
+    assert!(stdout.contains("[INFO]") && stdout.contains("init-default-marker info"));
+    assert!(!stdout.contains("init-default-marker debug"));
+}
+
+// Async logging without a Tokio runtime still logs, synchronously
+fn test_runtime_fallback() {
+    let stdout = run_child("runtime-fallback");
+    assert_eq!(stdout.matches("Could not create the Tokio runtime").count(), 1, "{}", stdout);
+    assert!(stdout.lines().any(|line| line.contains("[WARN]") && line.contains("logging synchronously")), "{}", stdout);
+    assert!(stdout.lines().any(|line| line.contains("[INFO]") && line.contains("runtime-fallback-marker")), "{}", stdout);
+}
+
+// Builds the configuration from LIBLOGGER_* variables set in this child only
+fn run_init_from_env() {
+    assert_eq!(LogConfig::from_env(), Ok(LogConfig::default()));
+    
+    // An endpoint alone selects HTTP output, like the builder does
+    std::env::set_var("LIBLOGGER_HTTP_ENDPOINT", "https://logs.example.com/ingest");
+    assert_eq!(LogConfig::from_env().unwrap().log_type, LogType::Http);
+    std::env::remove_var("LIBLOGGER_HTTP_ENDPOINT");
+    std::env::set_var("LIBLOGGER_SOCKET_PATH", "/var/run/vector.sock");
+    let config = LogConfig::from_env().unwrap();
+    assert_eq!((config.log_type, config.socket_path.as_deref()), (LogType::UnixSocket, Some("/var/run/vector.sock")));
+    std::env::remove_var("LIBLOGGER_SOCKET_PATH");
+    
+    std::env::set_var("LIBLOGGER_THRESHOLD", "loud");
+    assert_eq!(LogConfig::from_env().unwrap_err(),
+        "LIBLOGGER_THRESHOLD: Unknown log level 'loud', expected debug, info, warn or error");
+    
+    std::env::set_var("LIBLOGGER_TYPE", "file");
+    std::env::set_var("LIBLOGGER_THRESHOLD", "Warn");
+    std::env::set_var("LIBLOGGER_FILE_PATH", "env.log");
+    std::env::set_var("LIBLOGGER_LOG_FOLDER", "logs");
+    std::env::set_var("LIBLOGGER_FORMAT", "json");
+    Logger::init_from_env().expect("Failed to init from env");
+    
+    let expected = LogConfig::builder()
+        .threshold(LogLevel::Warn)
+        .file("env.log")
+        .log_folder("logs")
+        .format(LogFormat::Json)
+        .build();
+    assert_eq!(Logger::dump_config(), expected.to_toml().unwrap());
+    
+    log_info!("init-from-env-marker hidden");
+    log_warn!("init-from-env-marker shown");
+    shutdown_logger().expect("Shutdown failed");
+    let written = std::fs::read_to_string("logs/env.log").expect("Failed to read env.log");
+    assert!(!written.contains("init-from-env-marker hidden"), "{}", written);
+    let line = written.lines().find(|line| line.contains("init-from-env-marker shown")).expect("marker missing");
+    let record: serde_json::Value = serde_json::from_str(line).expect("line is not JSON");
+    assert_eq!(record["level"], "WARN");
+}
+
+// init_from_env configures the logger from environment variables alone
+fn test_init_from_env() {
+    run_child("init-from-env");
+}
+
+// Collects lines from a Unix socket as (connection number, line); the first
+// connection is closed after one line to simulate a collector restart
+#[cfg(unix)]
+fn spawn_socket_collector(path: &str) -> std::sync::mpsc::Receiver<(usize, String)> {
+    let listener = std::os::unix::net::UnixListener::bind(path).expect("Failed to bind log socket");
+    let (tx, rx) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        for (connection, stream) in listener.incoming().enumerate() {
+            let tx = tx.clone();
+            let mut reader = BufReader::new(stream.expect("Failed to accept"));
+            std::thread::spawn(move || loop {
+                let mut line = String::new();
+                if reader.read_line(&mut line).unwrap_or(0) == 0 {
+                    return;
+                }
+                assert!(line.ends_with('\n'), "line not newline-terminated: {:?}", line);
+                if connection == 0 {
+                    drop(reader);
+                    let _ = tx.send((connection, line.trim_end().to_string()));
+                    return;
+                }
+                let _ = tx.send((connection, line.trim_end().to_string()));
+            });
+        }
+    });
+    rx
+}
+
+#[cfg(unix)]
+fn run_unix_socket() {
+    let timeout = std::time::Duration::from_secs(5);
+    let lines = spawn_socket_collector("collector.sock");
+    
+    Logger::init_with_config(LogConfig::builder().unix_socket("collector.sock").async_logging(false).build())
+        .expect("Failed to init socket logger");
+    log_info!("unix-socket-marker first");
+    let (connection, line) = lines.recv_timeout(timeout).expect("first line missing");
+    assert_eq!(connection, 0);
+    assert!(line.contains("[INFO]") && line.ends_with("unix-socket-marker first"), "{}", line);
+    
+    // The first connection is gone, so this write reconnects and resends
+    log_info!("unix-socket-marker second");
+    let (connection, line) = lines.recv_timeout(timeout).expect("line after reconnect missing");
+    assert_eq!(connection, 1);
+    assert!(line.ends_with("unix-socket-marker second"), "{}", line);
+    shutdown_logger().expect("Shutdown failed");
+    
+    // Async batches are framed the same way
+    Logger::init_with_config(LogConfig::builder().unix_socket("collector.sock").format(LogFormat::Json).build())
+        .expect("Failed to init async socket logger");
+    for i in 0..5 {
+        log_warn!(&format!("unix-socket-marker async {}", i));
+    }
+    shutdown_logger().expect("Shutdown failed");
+    for i in 0..5 {
+        let (_, line) = lines.recv_timeout(timeout).expect("async line missing");
+        let record: serde_json::Value = serde_json::from_str(&line).expect("line is not JSON");
+        assert_eq!(record["message"], format!("unix-socket-marker async {}", i));
+    }
+}
+
+// type = "unix_socket" streams newline-delimited lines to a collector and reconnects after it restarts
+#[cfg(unix)]
+fn test_unix_socket() {
+    run_child("unix-socket");
+}
+
+// console_stream = "stderr" keeps stdout free, for sync and async logging
+fn test_console_stderr() {
+    for scenario in ["console-stderr", "console-stderr-async", "console-stderr-file"] {
+        let (stdout, stderr) = run_child_streams(scenario);
+        assert_eq!(stdout, "", "{} wrote to stdout", scenario);
+        assert!(stderr.lines().any(|line| line.contains("[INFO]") && line.contains("console-stderr-marker")), "{}", stderr);
+    }
+}
+
+// Console levels are colorized only when asked to (a pipe is not a terminal)
+fn test_console_colors() {
+    let colored = run_child("color-always");
+    assert!(colored.lines().any(|line| line.contains("\x1b[33m[WARN]\x1b[0m") && line.contains("color-marker")));
+    let layouts: Vec<&str> = colored.lines().filter(|line| line.contains("color-layout-marker")).collect();
+    assert_eq!(layouts.len(), 3, "{}", colored);
+    assert!(layouts[0].contains("] \x1b[33m[WARN]\x1b[0m ") && layouts[0].contains("[#"), "{}", layouts[0]);
+    assert!(layouts[1..].iter().all(|line| !line.contains('\x1b')), "JSON or template line colored: {:?}", layouts);
+    
+    for scenario in ["color-never", "color-auto"] {
+        let plain = run_child(scenario);
+        assert!(plain.contains("[WARN]") && plain.contains("color-marker"));
+        assert!(!plain.contains('\x1b'), "{} output contains ANSI codes", scenario);
+    }
+}
+
+// text_template controls the layout and rejects unknown placeholders
+fn test_text_template() {
+    let stdout = run_child("template-minimal");
+    assert!(stdout.lines().any(|line| line == "WARN: template-minimal-marker"), "{}", stdout);
+    
+    let stdout = run_child("template-reordered");
+    let line = stdout.lines()
+        .find(|line| line.starts_with("template-reordered-marker"))
+        .unwrap_or_else(|| panic!("No templated line in {}", stdout));
+    assert!(line.starts_with("template-reordered-marker <INFO> logger_tests @ "), "{}", line);
+    assert!(line.ends_with("{order=42}"), "{}", line);
+    assert!(!line.contains("main.rs"), "{}", line);
+}
+
+// Global fields appear on every line, whatever thread or call site logs it
+fn test_global_fields() {
+    let stdout = run_child("global-fields");
+    let lines: Vec<&str> = stdout.lines().filter(|line| line.contains("global-fields-marker")).collect();
+    assert_eq!(lines.len(), 4, "{}", stdout);
+    for line in &lines {
+        assert!(line.contains("hostname=") && line.contains("pid="), "{}", line);
+        assert_eq!(line.matches("service=").count(), 1, "{}", line);
+    }
+    assert!(lines[..3].iter().all(|line| line.contains("service=api")));
+    assert!(lines[1].contains("order=7"));
+    // Thread-local values shadow global ones
+    assert!(lines[3].contains("service=api-override"));
+}
+
+// Init twice with the same config, then try a different one
+fn run_double_init() {
+    let config = LogConfig::builder().file("first.log").log_folder("logs").force_flush(true).build();
+    Logger::init_with_config(config.clone()).expect("First init failed");
+    log_info!("double-init before");
+    let fds = Logger::open_file_descriptors().expect("fd count unsupported on this platform");
+    
+    // Same config: no new background task, so no new file handles
+    Logger::init_with_config(config.clone()).expect("Repeated init failed");
+    assert_eq!(Logger::open_file_descriptors(), Some(fds));
+    
+    let other = LogConfig::builder().file("second.log").log_folder("logs").force_flush(true).build();
+    let err = Logger::init_with_config(other.clone()).unwrap_err();
+    assert!(err.contains("reconfigure"), "{}", err);
+    
+    Logger::reconfigure(other).expect("Reconfigure failed");
+    log_info!("double-init after");
+    shutdown_logger().expect("Shutdown failed");
+    
+    let first = std::fs::read_to_string("logs/first.log").expect("Failed to read first log");
+    let second = std::fs::read_to_string("logs/second.log").expect("Failed to read second log");
+    assert_eq!(first.matches("double-init before").count(), 1, "{}", first);
+    assert!(!first.contains("double-init after"), "{}", first);
+    assert_eq!(second.matches("double-init after").count(), 1, "{}", second);
+}
+
+// A second init with the same config is a no-op; a different one needs reconfigure
+fn test_double_init() {
+    run_child("double-init");
+}
+
+// Buffered CRLF file output, written synchronously and then by the background task
+fn run_buffered_file() {
+    let config = LogConfig::builder()
+        .file("buffered.log")
+        .log_folder("logs")
+        .async_logging(false)
+        .buffered(true)
+        .line_ending(LineEnding::CrLf)
+        .build();
+    Logger::init_with_config(config.clone()).expect("Failed to init buffered logger");
+    for i in 0..3 {
+        log_info!(&format!("buffered-marker {}", i));
+    }
+    
+    // Nothing reaches the file until the buffer is flushed
+    assert_eq!(std::fs::metadata("logs/buffered.log").unwrap().len(), 0);
+    // ...which happens within about a second, even with no further writes
+    let started = std::time::Instant::now();
+    while std::fs::metadata("logs/buffered.log").unwrap().len() == 0 {
+        assert!(started.elapsed() < std::time::Duration::from_secs(3), "buffered lines were never flushed");
+        std::thread::sleep(std::time::Duration::from_millis(50));
+    }
+    shutdown_logger().expect("Shutdown failed");
+    
+    let content = std::fs::read_to_string("logs/buffered.log").expect("Failed to read buffered log");
+    assert_eq!(content.matches("buffered-marker").count(), 3, "{}", content);
+    assert!(content.ends_with("\r\n"));
+    assert_eq!(content.matches("\r\n").count(), content.matches('\n').count(), "{:?}", content);
+    
+    // The background task flushes its buffer on shutdown too
+    Logger::init_with_config(LogConfig { file_path: Some("buffered-async.log".to_string()), async_logging: true, ..config })
+        .expect("Failed to init async buffered logger");
+    log_info!("buffered-marker async");
+    // Sync writes and the background task share one handle, so they can't reorder each other's lines
+    #[cfg(target_os = "linux")]
+    assert_eq!(open_handles("buffered-async.log"), 1);
+    shutdown_logger().expect("Shutdown failed");
+    
+    let content = std::fs::read_to_string("logs/buffered-async.log").expect("Failed to read buffered log");
+    assert!(content.contains("buffered-marker async\r\n"), "{:?}", content);
+}
+
+// Buffered file output holds lines until flushed and honours line_ending
+fn test_buffered_file_output() {
+    run_child("buffered-file");
+}
+
+// ERROR lines reach a buffered file at once, synchronously and through the background task
+fn run_flush_on_error() {
+    let config = LogConfig::builder()
+        .file("flush-on-error.log")
+        .log_folder("logs")
+        .async_logging(false)
+        .buffered(true)
+        .build();
+    Logger::init_with_config(config.clone()).expect("Failed to init buffered logger");
+    log_info!("flush-marker info");
+    assert_eq!(std::fs::metadata("logs/flush-on-error.log").unwrap().len(), 0);
+    log_error!("flush-marker error");
+    
+    // The buffered INFO line goes out with the ERROR, well before the buffer would be flushed
+    let content = std::fs::read_to_string("logs/flush-on-error.log").expect("Failed to read log");
+    assert_eq!(content.matches("flush-marker").count(), 2, "{}", content);
+    shutdown_logger().expect("Shutdown failed");
+    
+    let path = "logs/flush-on-error-async.log";
+    Logger::init_with_config(LogConfig { file_path: Some("flush-on-error-async.log".to_string()), async_logging: true, ..config.clone() })
+        .expect("Failed to init async buffered logger");
+    log_info!("flush-marker info");
+    log_error!("flush-marker error");
+    let started = std::time::Instant::now();
+    while !std::fs::read_to_string(path).unwrap_or_default().contains("flush-marker error") {
+        assert!(started.elapsed() < std::time::Duration::from_millis(500), "ERROR line was not flushed");
+        std::thread::sleep(std::time::Duration::from_millis(10));
+    }
+    shutdown_logger().expect("Shutdown failed");
+
+    // A file among several outputs gets the line's level too
+    let path = "logs/flush-on-error-composite.log";
+    Logger::init_with_config(LogConfig {
+        outputs: vec![OutputSpec::file("flush-on-error-composite.log").log_folder("logs"), OutputSpec::console()],
+        ..config.clone()
+    }).expect("Failed to init composite buffered logger");
+    log_info!("flush-marker info");
+    assert_eq!(std::fs::metadata(path).unwrap().len(), 0);
+    log_error!("flush-marker error");
+    let content = std::fs::read_to_string(path).expect("Failed to read log");
+    assert!(content.contains("flush-marker error"), "{}", content);
+    shutdown_logger().expect("Shutdown failed");
+
+    // Turned off, the ERROR line waits in the buffer like any other
+    Logger::init_with_config(LogConfig { file_path: Some("flush-on-error-off.log".to_string()), flush_on_error: false, ..config })
+        .expect("Failed to init buffered logger");
+    log_error!("flush-marker error");
+    assert_eq!(std::fs::metadata("logs/flush-on-error-off.log").unwrap().len(), 0);
+    shutdown_logger().expect("Shutdown failed");
+}
+
+// flush_on_error writes ERROR lines to a buffered file immediately
+fn test_flush_on_error() {
+    run_child("flush-on-error");
+}
+
+// A quiet scope mutes its own thread only, and the floor variant keeps WARN+
+fn test_quiet_scope() {
+    let stdout = run_child("quiet-scope");
+    let markers: Vec<&str> = stdout.lines()
+        .filter_map(|line| line.split("quiet-marker ").nth(1))
+        .collect();
+    assert_eq!(markers, ["before", "other thread", "at floor", "after"], "{}", stdout);
+}
+
+// The ring buffer keeps the last lines in order, even below the threshold
+fn test_ring_buffer() {
+    let stdout = run_child("ring-buffer");
+    assert!(!stdout.contains("ring-marker"), "filtered lines reached the output: {}", stdout);
+}
+
+// LogType::Null writes nothing anywhere
+fn test_null_output() {
+    let stdout = run_child("null-output");
+    assert!(!stdout.contains("null-marker"), "{}", stdout);
+    assert!(!stdout.contains("Logger shutdown initiated"), "{}", stdout);
+}
+
+#[catch_panic(backtrace = true)]
+fn panicking_with_backtrace(depth: u32) -> Result<u32, String> {
+    if depth == 0 {
+        panic!("backtrace-marker");
+    }
+    Ok(depth)
+}
+
+// backtrace = true logs the frames of the panic, or says why there are none
+fn test_catch_panic_backtrace() {
+    let stdout = run_child("panic-backtrace-on");
+    let line = stdout.lines()
+        .find(|line| line.contains("panicking_with_backtrace caught panic: backtrace-marker"))
+        .unwrap_or_else(|| panic!("no panic line in {}", stdout));
+    assert!(line.contains("Backtrace:"), "{}", line);
+    assert!(line.contains("logger_tests::panicking_with_backtrace"), "{}", line);
+    
+    let stdout = run_child("panic-backtrace-off");
+    assert!(stdout.lines().any(|line| line.ends_with(
+        "panicking_with_backtrace caught panic: backtrace-marker (backtrace unavailable, set RUST_BACKTRACE=1 to capture it)")), "{}", stdout);
+}
+
+// Nested module whose path the module-segments child shortens
+mod billing {
+    pub mod invoices {
+        use liblogger::{log_debug, log_info};
+        
+        pub fn log_invoice(segments: usize) {
+            log_info!(&format!("module-marker {}", segments));
+            log_debug!(&format!("module-marker {} debug", segments));
+        }
+    }
+}
+
+// module_truncate_segments shortens the displayed module only
+fn test_module_truncate_segments() {
+    let stdout = run_child("module-segments");
+    assert!(stdout.lines().any(|line| line.ends_with("[logger_tests::billing::invoices] module-marker 0")), "{}", stdout);
+    assert!(stdout.lines().any(|line| line.ends_with("] [billing::invoices] module-marker 2")), "{}", stdout);
+    // The INFO threshold still applies to the shortened module's DEBUG line
+    assert!(!stdout.contains("debug"), "{}", stdout);
+}
+
+// Pre-formatted line forwarded by the log-raw child
+const RAW_LINE: &str = "2024-01-01 00:00:00.000 worker[7] INFO forwarded | key=value";
+
+// log_raw writes the line exactly as given, subject to the threshold
+fn test_log_raw() {
+    let stdout = run_child("log-raw");
+    assert!(stdout.lines().any(|line| line == RAW_LINE), "{}", stdout);
+    assert!(!stdout.contains("raw-below-threshold"));
+}
+
+// Log file configured in app_config.toml, used by tests that assert on output
+const TEST_LOG_FILE: &str = "logs/workflow.log";
+
+// Current size of the log file, so a test only inspects lines it produced
+fn log_file_len() -> usize {
+    std::fs::metadata(TEST_LOG_FILE).map(|m| m.len() as usize).unwrap_or(0)
+}
+
+// Wait for the background writer to emit a line at `level` containing `needle`
+fn wait_for_log_line(since: usize, level: &str, needle: &str) -> bool {
+    find_log_line(since, level, needle).is_some()
+}
+
+// Like wait_for_log_line, but return the line
+fn find_log_line(since: usize, level: &str, needle: &str) -> Option<String> {
+    for _ in 0..100 {
+        if let Ok(bytes) = std::fs::read(TEST_LOG_FILE) {
+            let written = String::from_utf8_lossy(bytes.get(since..).unwrap_or_default());
+            if let Some(line) = written.lines().find(|line| line.contains(level) && line.contains(needle)) {
+                return Some(line.to_string());
+            }
+        }
+        std::thread::sleep(std::time::Duration::from_millis(20));
+    }
+    None
+}
+
 fn initialize_custom_logger() {
     // Initialize logger with debug threshold to ensure all logs are shown
     match Logger::init_with_config_file("app_config.toml") {
@@ -289,7 +3478,7 @@ fn initialize_custom_logger() {
             // Something went wrong with the config file
             println!("Error initializing logger from config: {}", e);
             // Fall back to console logging
-            Logger::init();
+            let _ = Logger::init_with_config(LogConfig::default());
             log_error!("Failed to initialize file logger, falling back to console");
         }
     }
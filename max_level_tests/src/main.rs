@@ -0,0 +1,35 @@
+/*
+ * Test application for the compile-time level ceiling
+ *
+ * Built with liblogger's `max_level_warn` feature, so log_debug! and
+ * log_info! must expand to nothing: their arguments are never evaluated.
+ * A Display impl that panics proves it.
+ *
+ * Run from this directory with `cargo run`.
+ */
+
+use liblogger::{Logger, LogConfig, LogLevel, STATIC_MAX_LEVEL, log_debug, log_info, log_info_every, log_warn, shutdown_logger};
+use std::fmt;
+
+struct PanicsWhenFormatted;
+
+impl fmt::Display for PanicsWhenFormatted {
+    fn fmt(&self, _f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        panic!("a compiled-out log message was formatted");
+    }
+}
+
+fn main() {
+    assert_eq!(STATIC_MAX_LEVEL, LogLevel::Warn);
+    
+    let config = LogConfig::builder().threshold(LogLevel::Debug).build();
+    Logger::init_with_config(config).expect("Failed to initialize logger");
+    
+    log_debug!(&format!("never formatted: {}", PanicsWhenFormatted));
+    log_info!(&format!("never formatted: {}", PanicsWhenFormatted));
+    log_info_every!(100, &format!("never formatted: {}", PanicsWhenFormatted));
+    log_warn!("Warnings are still compiled in");
+    
+    let _ = shutdown_logger();
+    println!("max_level_warn test completed");
+}